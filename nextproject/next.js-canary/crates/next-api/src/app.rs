@@ -25,6 +25,7 @@ use next_core::{
     next_manifests::{
         AppPathsManifest, BuildManifest, EdgeFunctionDefinition, MiddlewaresManifestV2,
         PagesManifest, ProxyMatcher, Regions, client_reference_manifest::ClientReferenceManifest,
+        subresource_integrity_manifest,
     },
     next_server::{
         ServerContextType, get_server_module_options_context, get_server_resolve_options_context,
@@ -1418,6 +1419,20 @@ impl AppEndpoint {
                 polyfill_files: vec![polyfill_output_asset],
             };
             server_assets.insert(ResolvedVc::upcast(build_manifest.resolved_cell()));
+
+            if let Some(subresource_integrity_manifest) = *subresource_integrity_manifest(
+                project.next_config(),
+                project.next_mode(),
+                node_root.join(&format!(
+                    "server/app{manifest_path_prefix}/subresource-integrity-manifest.json",
+                ))?,
+                client_relative_path.clone(),
+                *client_assets,
+            )
+            .await?
+            {
+                server_assets.insert(subresource_integrity_manifest);
+            }
         }
 
         if runtime == NextRuntime::Edge {
@@ -16,8 +16,9 @@ use turbopack_browser::{
 };
 use turbopack_core::{
     chunk::{
-        AssetSuffix, ChunkingConfig, ChunkingContext, MangleType, MinifyType, SourceMapSourceType,
-        SourceMapsType, UnusedReferences, chunk_id_strategy::ModuleIdStrategy,
+        AssetSuffix, ChunkingConfig, ChunkingContext, EcmascriptMinifyOptions, MangleType,
+        MinifyType, SourceMapSourceType, SourceMapsType, SubresourceIntegrityAlgorithm,
+        UnusedReferences, chunk_id_strategy::ModuleIdStrategy,
     },
     compile_time_info::{CompileTimeDefines, CompileTimeInfo, FreeVarReference, FreeVarReferences},
     environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
@@ -43,7 +44,7 @@ use crate::{
         runtime_entry::{RuntimeEntries, RuntimeEntry},
         transforms::get_next_client_transforms_rules,
     },
-    next_config::NextConfig,
+    next_config::{NextConfig, OptionEcmascriptMinifyOptions},
     next_font::local::NextFontLocalResolvePlugin,
     next_import_map::{
         get_next_client_fallback_import_map, get_next_client_import_map,
@@ -55,7 +56,7 @@ use crate::{
             get_invalid_server_only_resolve_plugin,
         },
         transforms::{
-            emotion::get_emotion_transform_rule,
+            emotion::get_emotion_transform_rule, graphql::get_graphql_transform_rule,
             react_remove_properties::get_react_remove_properties_transform_rule,
             relay::get_relay_transform_rule, remove_console::get_remove_console_transform_rule,
             styled_components::get_styled_components_transform_rule,
@@ -306,6 +307,7 @@ pub async fn get_client_module_options_context(
         get_swc_ecma_transform_plugin_rule(next_config, project_path.clone()).await?,
         get_relay_transform_rule(next_config, project_path.clone()).await?,
         get_emotion_transform_rule(next_config).await?,
+        get_graphql_transform_rule(next_config, project_path.clone()).await?,
         get_styled_components_transform_rule(next_config).await?,
         get_styled_jsx_transform_rule(next_config, target_browsers).await?,
         get_react_remove_properties_transform_rule(next_config).await?,
@@ -441,13 +443,14 @@ pub struct ClientChunkingContextOptions {
     pub module_id_strategy: Vc<ModuleIdStrategy>,
     pub export_usage: Vc<OptionBindingUsageInfo>,
     pub unused_references: Vc<UnusedReferences>,
-    pub minify: Vc<bool>,
+    pub minify: Vc<OptionEcmascriptMinifyOptions>,
     pub source_maps: Vc<SourceMapsType>,
     pub no_mangling: Vc<bool>,
     pub scope_hoisting: Vc<bool>,
     pub nested_async_chunking: Vc<bool>,
     pub debug_ids: Vc<bool>,
     pub should_use_absolute_url_references: Vc<bool>,
+    pub sri: Vc<OptionSubresourceIntegrityAlgorithm>,
 }
 
 #[turbo_tasks::function]
@@ -471,6 +474,7 @@ pub async fn get_client_chunking_context(
         nested_async_chunking,
         debug_ids,
         should_use_absolute_url_references,
+        sri,
     } = options;
 
     let next_mode = mode.await?;
@@ -487,12 +491,12 @@ pub async fn get_client_chunking_context(
     )
     .chunk_base_path(Some(asset_prefix.clone()))
     .asset_suffix(AssetSuffix::Inferred.resolved_cell())
-    .minify_type(if *minify.await? {
-        MinifyType::Minify {
+    .minify_type(match minify.owned().await? {
+        Some(options) => MinifyType::Minify(EcmascriptMinifyOptions {
             mangle: (!*no_mangling.await?).then_some(MangleType::OptimalSize),
-        }
-    } else {
-        MinifyType::NoMinify
+            ..options
+        }),
+        None => MinifyType::NoMinify,
     })
     .source_maps(*source_maps.await?)
     .asset_base_path(Some(asset_prefix))
@@ -530,6 +534,10 @@ pub async fn get_client_chunking_context(
             )
             .use_content_hashing(ContentHashing::Direct { length: 16 })
             .module_merging(*scope_hoisting.await?);
+
+        if let Some(algorithm) = *sri.await? {
+            builder = builder.subresource_integrity(algorithm);
+        }
     }
 
     Ok(Vc::upcast(builder.build()))
@@ -18,13 +18,16 @@ use turbopack::module_options::{
     WebpackRules, module_options_context::MdxTransformOptions,
 };
 use turbopack_core::{
-    chunk::SourceMapsType,
+    chunk::{
+        EcmaVersion, EcmascriptMinifyOptions, MinifyComments, OptionSubresourceIntegrityAlgorithm,
+        SourceMapsType, SubresourceIntegrityAlgorithm,
+    },
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
     resolve::ResolveAliasMap,
 };
 use turbopack_ecmascript::{OptionTreeShaking, TreeShakingMode};
 use turbopack_ecmascript_plugins::transform::{
-    emotion::EmotionTransformConfig, relay::RelayConfig,
+    emotion::EmotionTransformConfig, graphql::GraphqlConfig, relay::RelayConfig,
     styled_components::StyledComponentsTransformConfig,
 };
 use turbopack_node::transforms::webpack::{WebpackLoaderItem, WebpackLoaderItems};
@@ -911,6 +914,53 @@ pub enum ModuleIds {
 #[turbo_tasks::value(transparent)]
 pub struct OptionModuleIds(pub Option<ModuleIds>);
 
+/// Options of the minifier, `experimental.turbopackMinifyOptions`. Mangling is not configurable
+/// here, it is controlled by the `--no-mangling` flag.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    TraceRawVcs,
+    NonLocalValue,
+    OperationValue,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TurbopackMinifyOptions {
+    drop_console: bool,
+    pure_funcs: Vec<RcStr>,
+    passes: Option<usize>,
+    keep_classnames: bool,
+    keep_fnames: bool,
+    ecma: Option<EcmaVersion>,
+    comments: MinifyComments,
+    reserved: Vec<RcStr>,
+}
+
+impl TurbopackMinifyOptions {
+    fn to_minify_options(&self) -> EcmascriptMinifyOptions {
+        EcmascriptMinifyOptions {
+            drop_console: self.drop_console,
+            pure_funcs: self.pure_funcs.clone(),
+            passes: self.passes,
+            keep_classnames: self.keep_classnames,
+            keep_fnames: self.keep_fnames,
+            ecma: self.ecma,
+            comments: self.comments,
+            reserved: self.reserved.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The minifier options, or `None` if minification is disabled.
+#[turbo_tasks::value(transparent)]
+pub struct OptionEcmascriptMinifyOptions(Option<EcmascriptMinifyOptions>);
+
 #[derive(
     Clone, Debug, PartialEq, Deserialize, TraceRawVcs, NonLocalValue, OperationValue, Encode, Decode,
 )]
@@ -1071,6 +1121,7 @@ pub struct ExperimentalConfig {
     worker_threads: Option<bool>,
 
     turbopack_minify: Option<bool>,
+    turbopack_minify_options: Option<TurbopackMinifyOptions>,
     turbopack_module_ids: Option<ModuleIds>,
     turbopack_source_maps: Option<bool>,
     turbopack_input_source_maps: Option<bool>,
@@ -1263,6 +1314,7 @@ pub struct CompilerConfig {
     pub emotion: Option<EmotionTransformOptionsOrBoolean>,
     pub remove_console: Option<RemoveConsoleConfig>,
     pub styled_components: Option<StyledComponentsTransformOptionsOrBoolean>,
+    pub graphql: Option<GraphqlConfig>,
 }
 
 #[derive(
@@ -1739,11 +1791,26 @@ impl NextConfig {
         Vc::cell(self.experimental.swc_plugins.clone().unwrap_or_default())
     }
 
-    // TODO not implemented yet
-    // #[turbo_tasks::function]
-    // pub fn experimental_sri(&self) -> Vc<OptionSubResourceIntegrity> {
-    //     Vc::cell(self.experimental.sri.clone())
-    // }
+    /// The hash algorithm of `experimental.sri`, which enables Subresource Integrity for
+    /// production builds.
+    #[turbo_tasks::function]
+    pub fn experimental_sri_algorithm(&self) -> Result<Vc<OptionSubresourceIntegrityAlgorithm>> {
+        let Some(algorithm) = self
+            .experimental
+            .sri
+            .as_ref()
+            .and_then(|sri| sri.algorithm.as_ref())
+        else {
+            return Ok(Vc::cell(None));
+        };
+        let Some(algorithm) = SubresourceIntegrityAlgorithm::parse(algorithm) else {
+            bail!(
+                "Unsupported `experimental.sri.algorithm` {algorithm}, expected one of sha256, \
+                 sha384 or sha512"
+            );
+        };
+        Ok(Vc::cell(Some(algorithm)))
+    }
 
     #[turbo_tasks::function]
     pub fn experimental_turbopack_use_builtin_babel(&self) -> Vc<Option<bool>> {
@@ -1966,11 +2033,21 @@ impl NextConfig {
     }
 
     #[turbo_tasks::function]
-    pub async fn turbo_minify(&self, mode: Vc<NextMode>) -> Result<Vc<bool>> {
-        let minify = self.experimental.turbopack_minify;
-        Ok(Vc::cell(
-            minify.unwrap_or(matches!(*mode.await?, NextMode::Build)),
-        ))
+    pub async fn turbo_minify_options(
+        &self,
+        mode: Vc<NextMode>,
+    ) -> Result<Vc<OptionEcmascriptMinifyOptions>> {
+        let minify = self
+            .experimental
+            .turbopack_minify
+            .unwrap_or(matches!(*mode.await?, NextMode::Build));
+        Ok(Vc::cell(minify.then(|| {
+            self.experimental
+                .turbopack_minify_options
+                .as_ref()
+                .map(TurbopackMinifyOptions::to_minify_options)
+                .unwrap_or_default()
+        })))
     }
 
     #[turbo_tasks::function]
@@ -2218,4 +2295,55 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_serde_turbopack_minify_options() {
+        let config: ExperimentalConfig = serde_json::from_value(serde_json::json!({
+            "turbopackMinifyOptions": {
+                "keepClassnames": true,
+                "pureFuncs": ["console.info"],
+                "ecma": 2017,
+                "comments": "licenses",
+            }
+        }))
+        .unwrap();
+        let options = config.turbopack_minify_options.unwrap().to_minify_options();
+        assert!(options.keep_classnames);
+        assert!(!options.drop_console);
+        assert_eq!(options.pure_funcs, vec![rcstr!("console.info")]);
+        assert_eq!(options.ecma, Some(EcmaVersion::Es2017));
+        assert_eq!(options.comments, MinifyComments::Licenses);
+        assert_eq!(
+            options.mangle,
+            EcmascriptMinifyOptions::default().mangle,
+            "mangling is controlled by --no-mangling"
+        );
+
+        let error = serde_json::from_value::<ExperimentalConfig>(serde_json::json!({
+            "turbopackMinifyOptions": { "ecma": 2014 }
+        }))
+        .unwrap_err();
+        assert!(error.to_string().contains("unsupported ecma version 2014"));
+    }
+
+    #[test]
+    fn test_serde_compiler_graphql() {
+        use turbopack_ecmascript_plugins::transform::graphql::GraphqlOutput;
+
+        let config: CompilerConfig = serde_json::from_value(serde_json::json!({
+            "graphql": {
+                "schema": "schema.graphql",
+                "output": "persistedHash",
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            config.graphql,
+            Some(GraphqlConfig {
+                schema: Some("schema.graphql".to_string()),
+                tags: None,
+                output: GraphqlOutput::PersistedHash,
+            })
+        );
+    }
 }
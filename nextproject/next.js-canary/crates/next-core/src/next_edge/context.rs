@@ -6,8 +6,9 @@ use turbo_tasks_fs::FileSystemPath;
 use turbopack_browser::BrowserChunkingContext;
 use turbopack_core::{
     chunk::{
-        AssetSuffix, ChunkingConfig, ChunkingContext, MangleType, MinifyType, SourceMapsType,
-        UnusedReferences, UrlBehavior, chunk_id_strategy::ModuleIdStrategy,
+        AssetSuffix, ChunkingConfig, ChunkingContext, EcmascriptMinifyOptions, MangleType,
+        MinifyType, SourceMapsType, UnusedReferences, UrlBehavior,
+        chunk_id_strategy::ModuleIdStrategy,
     },
     compile_time_info::{CompileTimeDefines, CompileTimeInfo, FreeVarReference, FreeVarReferences},
     environment::{EdgeWorkerEnvironment, Environment, ExecutionEnvironment, NodeJsVersion},
@@ -23,7 +24,7 @@ use turbopack_resolve::resolve_options_context::{ResolveOptionsContext, TsConfig
 use crate::{
     app_structure::CollectedRootParams,
     mode::NextMode,
-    next_config::NextConfig,
+    next_config::{NextConfig, OptionEcmascriptMinifyOptions},
     next_font::local::NextFontLocalResolvePlugin,
     next_import_map::{get_next_edge_and_server_fallback_import_map, get_next_edge_import_map},
     next_server::context::ServerContextType,
@@ -216,7 +217,7 @@ pub struct EdgeChunkingContextOptions {
     pub module_id_strategy: Vc<ModuleIdStrategy>,
     pub export_usage: Vc<OptionBindingUsageInfo>,
     pub unused_references: Vc<UnusedReferences>,
-    pub turbo_minify: Vc<bool>,
+    pub turbo_minify: Vc<OptionEcmascriptMinifyOptions>,
     pub turbo_source_maps: Vc<SourceMapsType>,
     pub no_mangling: Vc<bool>,
     pub scope_hoisting: Vc<bool>,
@@ -260,13 +261,13 @@ pub async fn get_edge_chunking_context_with_client_assets(
         next_mode.runtime_type(),
     )
     .asset_base_path(Some(asset_prefix))
-    .minify_type(if *turbo_minify.await? {
-        MinifyType::Minify {
+    .minify_type(match turbo_minify.owned().await? {
+        Some(options) => MinifyType::Minify(EcmascriptMinifyOptions {
             // React needs deterministic function names to work correctly.
             mangle: (!*no_mangling.await?).then_some(MangleType::Deterministic),
-        }
-    } else {
-        MinifyType::NoMinify
+            ..options
+        }),
+        None => MinifyType::NoMinify,
     })
     .source_maps(*turbo_source_maps.await?)
     .module_id_strategy(module_id_strategy.to_resolved().await?)
@@ -345,12 +346,12 @@ pub async fn get_edge_chunking_context(
     // implementation in the edge sandbox. It will respond with the
     // asset from the output directory.
     .asset_base_path(Some(rcstr!("blob:server/edge/")))
-    .minify_type(if *turbo_minify.await? {
-        MinifyType::Minify {
+    .minify_type(match turbo_minify.owned().await? {
+        Some(options) => MinifyType::Minify(EcmascriptMinifyOptions {
             mangle: (!*no_mangling.await?).then_some(MangleType::OptimalSize),
-        }
-    } else {
-        MinifyType::NoMinify
+            ..options
+        }),
+        None => MinifyType::NoMinify,
     })
     .source_maps(*turbo_source_maps.await?)
     .module_id_strategy(module_id_strategy.to_resolved().await?)
@@ -14,10 +14,50 @@ use turbo_tasks::{
 use turbo_tasks_fs::{File, FileContent, FileSystemPath};
 use turbopack_core::{
     asset::{Asset, AssetContent},
-    output::{OutputAsset, OutputAssets, OutputAssetsReference, OutputAssetsWithReferenced},
+    chunk::SubresourceIntegrityManifest,
+    output::{
+        OptionOutputAsset, OutputAsset, OutputAssets, OutputAssetsReference,
+        OutputAssetsWithReferenced,
+    },
 };
 
-use crate::next_config::RouteHas;
+use crate::{
+    mode::NextMode,
+    next_config::{NextConfig, RouteHas},
+};
+
+/// The Subresource Integrity manifest of an app page when `experimental.sri` is enabled, which maps
+/// the paths of its client chunks, relative to the client root, to their integrity metadata.
+///
+/// Next.js merges the manifests of all pages into `server/subresource-integrity-manifest.json` and
+/// adds `integrity` attributes to the `<script>` and `<link>` tags of server-rendered HTML with it.
+#[turbo_tasks::function]
+pub async fn subresource_integrity_manifest(
+    next_config: Vc<NextConfig>,
+    next_mode: Vc<NextMode>,
+    output_path: FileSystemPath,
+    client_relative_path: FileSystemPath,
+    client_assets: Vc<OutputAssets>,
+) -> Result<Vc<OptionOutputAsset>> {
+    // Like the integrity metadata of chunks loaded at runtime, this is only emitted for production
+    // builds.
+    if !next_mode.await?.is_production() {
+        return Ok(Vc::cell(None));
+    }
+    let Some(algorithm) = *next_config.experimental_sri_algorithm().await? else {
+        return Ok(Vc::cell(None));
+    };
+    Ok(Vc::cell(Some(ResolvedVc::upcast(
+        SubresourceIntegrityManifest::new(
+            output_path,
+            client_relative_path,
+            client_assets,
+            algorithm,
+        )
+        .to_resolved()
+        .await?,
+    ))))
+}
 
 #[derive(Serialize, Default, Debug)]
 pub struct PagesManifest {
@@ -15,8 +15,9 @@ use turbopack::{
 };
 use turbopack_core::{
     chunk::{
-        AssetSuffix, ChunkingConfig, MangleType, MinifyType, SourceMapSourceType, SourceMapsType,
-        UnusedReferences, UrlBehavior, chunk_id_strategy::ModuleIdStrategy,
+        AssetSuffix, ChunkingConfig, EcmascriptMinifyOptions, MangleType, MinifyType,
+        SourceMapSourceType, SourceMapsType, UnusedReferences, UrlBehavior,
+        chunk_id_strategy::ModuleIdStrategy,
     },
     compile_time_defines,
     compile_time_info::{CompileTimeDefines, CompileTimeInfo, FreeVarReferences},
@@ -43,7 +44,7 @@ use crate::{
     app_structure::CollectedRootParams,
     mode::NextMode,
     next_build::get_postcss_package_mapping,
-    next_config::NextConfig,
+    next_config::{NextConfig, OptionEcmascriptMinifyOptions},
     next_font::local::NextFontLocalResolvePlugin,
     next_import_map::{get_next_edge_and_server_fallback_import_map, get_next_server_import_map},
     next_server::{
@@ -58,6 +59,7 @@ use crate::{
         },
         transforms::{
             EcmascriptTransformStage, emotion::get_emotion_transform_rule, get_ecma_transform_rule,
+            graphql::get_graphql_transform_rule,
             next_react_server_components::get_next_react_server_components_transform_rule,
             react_remove_properties::get_react_remove_properties_transform_rule,
             relay::get_relay_transform_rule, remove_console::get_remove_console_transform_rule,
@@ -570,6 +572,7 @@ pub async fn get_server_module_options_context(
         get_react_remove_properties_transform_rule(next_config).await?,
         get_emotion_transform_rule(next_config).await?,
         get_relay_transform_rule(next_config, project_path.clone()).await?,
+        get_graphql_transform_rule(next_config, project_path.clone()).await?,
         get_swc_ecma_transform_plugin_rule(next_config, project_path.clone()).await?,
     ]
     .into_iter()
@@ -1026,7 +1029,7 @@ pub struct ServerChunkingContextOptions {
     pub module_id_strategy: Vc<ModuleIdStrategy>,
     pub export_usage: Vc<OptionBindingUsageInfo>,
     pub unused_references: Vc<UnusedReferences>,
-    pub minify: Vc<bool>,
+    pub minify: Vc<OptionEcmascriptMinifyOptions>,
     pub source_maps: Vc<SourceMapsType>,
     pub no_mangling: Vc<bool>,
     pub scope_hoisting: Vc<bool>,
@@ -1081,13 +1084,13 @@ pub async fn get_server_chunking_context_with_client_assets(
             suffix: AssetSuffix::FromGlobal(rcstr!("NEXT_CLIENT_ASSET_SUFFIX")),
         },
     )
-    .minify_type(if *minify.await? {
-        MinifyType::Minify {
+    .minify_type(match minify.owned().await? {
+        Some(options) => MinifyType::Minify(EcmascriptMinifyOptions {
             // React needs deterministic function names to work correctly.
             mangle: (!*no_mangling.await?).then_some(MangleType::Deterministic),
-        }
-    } else {
-        MinifyType::NoMinify
+            ..options
+        }),
+        None => MinifyType::NoMinify,
     })
     .source_maps(*source_maps.await?)
     .module_id_strategy(module_id_strategy.to_resolved().await?)
@@ -1173,12 +1176,12 @@ pub async fn get_server_chunking_context(
             suffix: AssetSuffix::FromGlobal(rcstr!("NEXT_CLIENT_ASSET_SUFFIX")),
         },
     )
-    .minify_type(if *minify.await? {
-        MinifyType::Minify {
+    .minify_type(match minify.owned().await? {
+        Some(options) => MinifyType::Minify(EcmascriptMinifyOptions {
             mangle: (!*no_mangling.await?).then_some(MangleType::OptimalSize),
-        }
-    } else {
-        MinifyType::NoMinify
+            ..options
+        }),
+        None => MinifyType::NoMinify,
     })
     .source_maps(*source_maps.await?)
     .module_id_strategy(module_id_strategy.to_resolved().await?)
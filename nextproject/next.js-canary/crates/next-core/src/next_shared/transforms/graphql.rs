@@ -0,0 +1,28 @@
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbo_tasks_fs::FileSystemPath;
+use turbopack::module_options::ModuleRule;
+use turbopack_ecmascript_plugins::transform::graphql::GraphqlTransformer;
+
+use crate::{
+    next_config::NextConfig,
+    next_shared::transforms::{EcmascriptTransformStage, get_ecma_transform_rule},
+};
+
+/// Returns a transform rule which precompiles GraphQL tagged templates.
+pub async fn get_graphql_transform_rule(
+    next_config: Vc<NextConfig>,
+    project_path: FileSystemPath,
+) -> Result<Option<ModuleRule>> {
+    let enable_mdx_rs = next_config.mdx_rs().await?.is_some();
+    let module_rule = match &next_config.compiler().await?.graphql {
+        Some(config) => Some(get_ecma_transform_rule(
+            Box::new(GraphqlTransformer::new(config, &project_path)?),
+            enable_mdx_rs,
+            EcmascriptTransformStage::Main,
+        )),
+        None => None,
+    };
+
+    Ok(module_rule)
+}
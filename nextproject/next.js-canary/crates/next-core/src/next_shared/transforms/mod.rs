@@ -1,5 +1,6 @@
 pub(crate) mod debug_fn_name;
 pub(crate) mod emotion;
+pub(crate) mod graphql;
 pub(crate) mod modularize_imports;
 pub(crate) mod next_cjs_optimizer;
 pub(crate) mod next_disallow_re_export_all_in_page;
@@ -137,7 +137,11 @@ impl PageLoaderAsset {
             chunks = ResolvedVc::cell(rebased);
         };
 
-        Ok(ChunkData::from_assets(self.server_root.clone(), *chunks))
+        Ok(ChunkData::from_assets(
+            self.server_root.clone(),
+            *chunks,
+            None,
+        ))
     }
 }
 
@@ -1,4 +1,5 @@
-use mdxjs::{Options, compile};
+use markdown::message::Message;
+use mdxjs::Options;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
@@ -12,9 +13,7 @@ impl Task for MdxCompileTask {
     type JsValue = String;
 
     fn compute(&mut self) -> napi::Result<Self::Output> {
-        let options: Options = serde_json::from_slice(&self.option)?;
-
-        compile(&self.input, &options)
+        compile(&self.input, &self.option)?
             .map_err(|err| napi::Error::new(Status::GenericFailure, err.to_string()))
     }
 
@@ -38,8 +37,40 @@ pub fn mdx_compile(
 
 #[napi]
 pub fn mdx_compile_sync(value: String, option: Buffer) -> napi::Result<String> {
-    let option: Options = serde_json::from_slice(&option)?;
-
-    compile(value.as_str(), &option)
+    compile(value.as_str(), &option)?
         .map_err(|err| napi::Error::new(Status::GenericFailure, format!("{err:?}")))
 }
+
+/// The options of `mdxjs` with the extensions of `turbopack-mdx`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(serde::Deserialize)]
+struct CompileOptions {
+    #[serde(flatten)]
+    mdx: Options,
+    #[serde(flatten)]
+    extensions: turbopack_mdx::MdxExtensions,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn compile(value: &str, option: &[u8]) -> napi::Result<std::result::Result<String, Message>> {
+    let options: CompileOptions = serde_json::from_slice(option)?;
+    let compiled = turbopack_mdx::compile(value, &options.mdx, options.extensions)
+        .map_err(|err| napi::Error::from_reason(format!("{err:?}")))?;
+    if let Some(err) = compiled.frontmatter_error {
+        return Ok(Err(Message {
+            place: None,
+            reason: err.to_string(),
+            rule_id: Box::new("frontmatter".into()),
+            source: Box::new("turbopack-mdx".into()),
+        }));
+    }
+    Ok(compiled.code)
+}
+
+/// The `frontmatter`, `tableOfContents` and `highlightCode` options are not supported in the wasm
+/// build.
+#[cfg(target_arch = "wasm32")]
+fn compile(value: &str, option: &[u8]) -> napi::Result<std::result::Result<String, Message>> {
+    let options: Options = serde_json::from_slice(option)?;
+    Ok(mdxjs::compile(value, &options))
+}
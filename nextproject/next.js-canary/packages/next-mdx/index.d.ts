@@ -33,6 +33,24 @@ declare namespace nextMDX {
               )[]
             | Options[Key]
         : Options[Key]
+    } & {
+      /**
+       * Parse YAML (`---`) or TOML (`+++`) frontmatter into a `frontmatter`
+       * export. Requires `experimental.mdxRs`.
+       */
+      frontmatter?: boolean
+
+      /**
+       * Export the headings of the document as `tableOfContents` and add
+       * their ids to the rendered headings. Requires `experimental.mdxRs`.
+       */
+      tableOfContents?: boolean
+
+      /**
+       * Highlight fenced code blocks using Prism compatible class names.
+       * Requires `experimental.mdxRs`.
+       */
+      highlightCode?: boolean
     }
   }
 }
@@ -147,6 +147,9 @@ function createFormatAwareProcessors(bindings, compileOptions = {}) {
       pragma: compileOptions.pragma,
       pragmaFrag: compileOptions.pragmaFrag,
       pragmaImportSource: compileOptions.pragmaImportSource,
+      frontmatter: compileOptions.frontmatter,
+      tableOfContents: compileOptions.tableOfContents,
+      highlightCode: compileOptions.highlightCode,
       filepath: p,
     }
 
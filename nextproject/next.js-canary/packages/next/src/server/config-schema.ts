@@ -304,6 +304,9 @@ export const experimentalSchema = {
         jsxImportSource: z.string().optional(),
         providerImportSource: z.string().optional(),
         mdxType: z.enum(['gfm', 'commonmark']).optional(),
+        frontmatter: z.boolean().optional(),
+        tableOfContents: z.boolean().optional(),
+        highlightCode: z.boolean().optional(),
       }),
     ])
     .optional(),
@@ -314,6 +317,30 @@ export const experimentalSchema = {
   webpackMemoryOptimizations: z.boolean().optional(),
   turbopackMemoryLimit: z.number().optional(),
   turbopackMinify: z.boolean().optional(),
+  turbopackMinifyOptions: z
+    .strictObject({
+      dropConsole: z.boolean().optional(),
+      pureFuncs: z.array(z.string()).optional(),
+      passes: z.number().int().positive().optional(),
+      keepClassnames: z.boolean().optional(),
+      keepFnames: z.boolean().optional(),
+      ecma: z
+        .union([
+          z.literal(5),
+          z.literal(2015),
+          z.literal(2016),
+          z.literal(2017),
+          z.literal(2018),
+          z.literal(2019),
+          z.literal(2020),
+          z.literal(2021),
+          z.literal(2022),
+        ])
+        .optional(),
+      comments: z.enum(['none', 'licenses', 'extract-licenses']).optional(),
+      reserved: z.array(z.string()).optional(),
+    })
+    .optional(),
   turbopackFileSystemCacheForDev: z.boolean().optional(),
   turbopackFileSystemCacheForBuild: z.boolean().optional(),
   turbopackSourceMaps: z.boolean().optional(),
@@ -430,6 +457,13 @@ export const configSchema: zod.ZodType<NextConfig> = z.lazy(() =>
             }),
           ])
           .optional(),
+        graphql: z
+          .object({
+            schema: z.string().optional(),
+            tags: z.array(z.string()).optional(),
+            output: z.enum(['document', 'persistedHash']).optional(),
+          })
+          .optional(),
         reactRemoveProperties: z
           .union([
             z.boolean().optional(),
@@ -481,6 +481,32 @@ export interface ExperimentalConfig {
    */
   turbopackMinify?: boolean
 
+  /**
+   * Options of the minifier used by Turbopack. Mangling is controlled separately with
+   * `--no-mangling`.
+   */
+  turbopackMinifyOptions?: {
+    /** Removes `console.*` calls. */
+    dropConsole?: boolean
+    /** Calls of these functions are removed when their result is unused, e.g. `console.info`. */
+    pureFuncs?: string[]
+    /** The number of compress passes. Defaults to 2. */
+    passes?: number
+    /** Prevents discarding or mangling class names. */
+    keepClassnames?: boolean
+    /** Prevents discarding or mangling function names. */
+    keepFnames?: boolean
+    /** The ECMAScript version the minified output may use, `5` or a year between 2015 and 2022. */
+    ecma?: 5 | 2015 | 2016 | 2017 | 2018 | 2019 | 2020 | 2021 | 2022
+    /**
+     * Which comments are kept. `extract-licenses` writes license comments into `.LICENSE.txt`
+     * files next to the chunks.
+     */
+    comments?: 'none' | 'licenses' | 'extract-licenses'
+    /** Names which are never mangled. */
+    reserved?: string[]
+  }
+
   /**
    * Enable support for `with {type: "bytes"}` for ESM imports.
    */
@@ -588,6 +614,19 @@ export interface ExperimentalConfig {
         jsxImportSource?: string
         providerImportSource?: string
         mdxType?: 'gfm' | 'commonmark'
+        /**
+         * Parse YAML (`---`) or TOML (`+++`) frontmatter into a `frontmatter` export.
+         */
+        frontmatter?: boolean
+        /**
+         * Export the headings of the document as `tableOfContents` and add
+         * their ids to the rendered headings.
+         */
+        tableOfContents?: boolean
+        /**
+         * Highlight fenced code blocks using Prism compatible class names.
+         */
+        highlightCode?: boolean
       }
 
   /**
@@ -1315,6 +1354,25 @@ export interface NextConfig {
     styledComponents?: boolean | StyledComponentsConfig
     emotion?: boolean | EmotionConfig
 
+    /**
+     * Precompiles GraphQL tagged templates into document ASTs. Only supported by Turbopack.
+     */
+    graphql?: {
+      /**
+       * Path of the schema (SDL), relative to the project. Documents are validated against it.
+       */
+      schema?: string
+      /**
+       * The template tags which are precompiled. Defaults to `gql` and `graphql`.
+       */
+      tags?: string[]
+      /**
+       * `persistedHash` replaces the text of documents with the SHA-256 hash of their source
+       * text, including interpolated fragments, in `documentId`.
+       */
+      output?: 'document' | 'persistedHash'
+    }
+
     styledJsx?:
       | boolean
       | {
@@ -392,6 +392,7 @@ export async function handleRouteType({
       manifestLoader.loadAppPathsManifest(page)
       manifestLoader.loadActionManifest(page)
       manifestLoader.loadFontManifest(page, 'app')
+      manifestLoader.loadSubresourceIntegrityManifest(page)
 
       if (shouldCreateWebpackStats) {
         manifestLoader.loadWebpackStats(page, 'app')
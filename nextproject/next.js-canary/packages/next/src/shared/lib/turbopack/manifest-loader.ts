@@ -24,6 +24,7 @@ import {
   NEXT_FONT_MANIFEST,
   PAGES_MANIFEST,
   SERVER_REFERENCE_MANIFEST,
+  SUBRESOURCE_INTEGRITY_MANIFEST,
   TURBOPACK_CLIENT_BUILD_MANIFEST,
   TURBOPACK_CLIENT_MIDDLEWARE_MANIFEST,
   WEBPACK_STATS,
@@ -72,6 +73,7 @@ type ManifestName =
   | typeof APP_PATHS_MANIFEST
   | `${typeof SERVER_REFERENCE_MANIFEST}.json`
   | `${typeof NEXT_FONT_MANIFEST}.json`
+  | `${typeof SUBRESOURCE_INTEGRITY_MANIFEST}.json`
   | typeof REACT_LOADABLE_MANIFEST
   | typeof TURBOPACK_CLIENT_BUILD_MANIFEST
 
@@ -193,6 +195,10 @@ export class TurbopackManifestLoader {
   > = new ManifestsMap()
   private pagesManifests: ManifestsMap<string, PagesManifest> =
     new ManifestsMap()
+  private subresourceIntegrityManifests: ManifestsMap<
+    EntryKey,
+    Record<string, string>
+  > = new ManifestsMap()
   private webpackStats: ManifestsMap<EntryKey, WebpackStats> =
     new ManifestsMap()
   private encryptionKey: string
@@ -233,6 +239,7 @@ export class TurbopackManifestLoader {
     this.fontManifests.delete(key)
     this.middlewareManifests.delete(key)
     this.pagesManifests.delete(key)
+    this.subresourceIntegrityManifests.delete(key)
     this.webpackStats.delete(key)
   }
 
@@ -317,6 +324,58 @@ export class TurbopackManifestLoader {
     )
   }
 
+  /**
+   * Loads the Subresource Integrity manifest of an app page, which is only
+   * written when `experimental.sri` is enabled.
+   */
+  loadSubresourceIntegrityManifest(pageName: string): void {
+    const key = getEntryKey('app', 'server', pageName)
+    const manifestPath = getManifestPath(
+      pageName,
+      this.distDir,
+      `${SUBRESOURCE_INTEGRITY_MANIFEST}.json`,
+      'app',
+      true
+    )
+    if (!existsSync(manifestPath)) {
+      this.subresourceIntegrityManifests.delete(key)
+      return
+    }
+    this.subresourceIntegrityManifests.set(
+      key,
+      readFileSync(manifestPath, 'utf-8')
+    )
+  }
+
+  private writeSubresourceIntegrityManifest(): void {
+    if (!this.subresourceIntegrityManifests.takeChanged()) {
+      return
+    }
+    const manifests = [...this.subresourceIntegrityManifests.values()]
+    if (manifests.length === 0) {
+      return
+    }
+    const manifest: Record<string, string> = Object.assign({}, ...manifests)
+    const json = JSON.stringify(sortObjectByKey(manifest), null, 2)
+    const jsonPath = join(
+      this.distDir,
+      'server',
+      `${SUBRESOURCE_INTEGRITY_MANIFEST}.json`
+    )
+    const jsPath = join(
+      this.distDir,
+      'server',
+      `${SUBRESOURCE_INTEGRITY_MANIFEST}.js`
+    )
+    deleteCache(jsonPath)
+    deleteCache(jsPath)
+    writeFileAtomic(jsonPath, json)
+    writeFileAtomic(
+      jsPath,
+      `self.__SUBRESOURCE_INTEGRITY_MANIFEST=${JSON.stringify(json)}`
+    )
+  }
+
   loadAppPathsManifest(pageName: string): void {
     this.appPathsManifests.set(
       getEntryKey('app', 'server', pageName),
@@ -908,6 +967,7 @@ export class TurbopackManifestLoader {
     this.writeInterceptionRouteRewriteManifest(devRewrites, productionRewrites)
     this.writeNextFontManifest()
     this.writePagesManifest()
+    this.writeSubresourceIntegrityManifest()
 
     if (process.env.TURBOPACK_STATS != null) {
       this.writeWebpackStats()
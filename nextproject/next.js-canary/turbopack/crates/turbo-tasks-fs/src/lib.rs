@@ -31,6 +31,7 @@ pub mod source_context;
 pub mod util;
 pub(crate) mod virtual_fs;
 mod watcher;
+pub(crate) mod zip_fs;
 
 use std::{
     borrow::Cow,
@@ -85,7 +86,7 @@ use crate::{
     util::extract_disk_access,
     watcher::DiskWatcher,
 };
-pub use crate::{read_glob::ReadGlobResult, virtual_fs::VirtualFileSystem};
+pub use crate::{read_glob::ReadGlobResult, virtual_fs::VirtualFileSystem, zip_fs::ZipFileSystem};
 
 /// A (somewhat arbitrary) filename limit that we should try to keep output file names below.
 ///
@@ -0,0 +1,338 @@
+use std::{
+    io::{Cursor, Read},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, bail};
+use auto_hash_map::AutoMap;
+use bincode::{Decode, Encode};
+use rustc_hash::FxHashMap;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{NonLocalValue, ValueToString, Vc, trace::TraceRawVcs};
+use turbo_unix_path::{get_parent_path, normalize_path};
+use zip::ZipArchive;
+
+use crate::{
+    File, FileContent, FileMeta, FileSystem, FileSystemPath, LinkContent, RawDirectoryContent,
+    RawDirectoryEntry,
+};
+
+/// A read-only file system exposing the contents of a zip archive, e.g. a package in the cache of
+/// a Yarn Plug'n'Play install.
+#[turbo_tasks::value]
+pub struct ZipFileSystem {
+    /// The path of the zip archive.
+    pub archive: FileSystemPath,
+}
+
+#[turbo_tasks::value_impl]
+impl ZipFileSystem {
+    /// Creates a new [`Vc<ZipFileSystem>`] for the given archive.
+    ///
+    /// Unlike [`crate::VirtualFileSystem::new`], this is a `turbo_tasks::function`, so paths
+    /// inside the same archive are always created on the same file system.
+    #[turbo_tasks::function]
+    pub fn new(archive: FileSystemPath) -> Vc<Self> {
+        Self::cell(ZipFileSystem { archive })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+enum ZipEntry {
+    /// A file, by its index in the archive.
+    File(usize),
+    Directory(AutoMap<RcStr, RawDirectoryEntry>),
+}
+
+/// The entries of a zip archive, by their normalized path. Only the central directory of the
+/// archive is read here, the content of files is decompressed on demand by [zip_entry_content].
+#[turbo_tasks::value]
+#[derive(Debug)]
+struct ZipEntries {
+    entries: FxHashMap<RcStr, ZipEntry>,
+}
+
+impl ZipEntries {
+    fn add_directory(&mut self, path: &str) {
+        if self.entries.contains_key(path) {
+            return;
+        }
+        self.entries
+            .insert(path.into(), ZipEntry::Directory(AutoMap::new()));
+        if !path.is_empty() {
+            self.add_to_parent(path, RawDirectoryEntry::Directory);
+        }
+    }
+
+    fn add_to_parent(&mut self, path: &str, entry: RawDirectoryEntry) {
+        let parent = get_parent_path(path);
+        self.add_directory(parent);
+        let name = path
+            .strip_prefix(parent)
+            .unwrap_or(path)
+            .trim_start_matches('/');
+        if let Some(ZipEntry::Directory(entries)) = self.entries.get_mut(parent) {
+            entries.insert(name.into(), entry);
+        }
+    }
+}
+
+type ZipReader = ZipArchive<Cursor<Arc<[u8]>>>;
+
+/// A zip archive with its parsed central directory. Clones of the archive share the central
+/// directory, so reading a file doesn't parse the archive again.
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+struct ParsedZipArchive {
+    /// `None` if the archive doesn't exist.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    zip: Option<ZipReader>,
+}
+
+#[turbo_tasks::function]
+async fn zip_archive(archive: FileSystemPath) -> Result<Vc<ParsedZipArchive>> {
+    let FileContent::Content(file) = &*archive.read().await? else {
+        return Ok(ParsedZipArchive { zip: None }.cell());
+    };
+    let zip = ZipArchive::new(Cursor::new(Arc::<[u8]>::from(&*file.content().to_bytes())))
+        .with_context(|| format!("Unable to read zip archive {}", archive.path))?;
+    Ok(ParsedZipArchive { zip: Some(zip) }.cell())
+}
+
+#[turbo_tasks::function]
+async fn zip_entries(archive: FileSystemPath) -> Result<Vc<ZipEntries>> {
+    let mut zip_entries = ZipEntries {
+        entries: FxHashMap::default(),
+    };
+    let parsed = zip_archive(archive).await?;
+    let Some(zip) = &parsed.zip else {
+        return Ok(zip_entries.cell());
+    };
+
+    zip_entries.add_directory("");
+    for i in 0..zip.len() {
+        let Some(name) = zip.name_for_index(i) else {
+            continue;
+        };
+        // Entries which would leave the root of the archive are skipped.
+        let Some(path) = normalize_path(name) else {
+            continue;
+        };
+        if path.is_empty() {
+            continue;
+        }
+        if name.ends_with('/') {
+            zip_entries.add_directory(&path);
+        } else {
+            zip_entries.add_to_parent(&path, RawDirectoryEntry::File);
+            zip_entries.entries.insert(path.into(), ZipEntry::File(i));
+        }
+    }
+    Ok(zip_entries.cell())
+}
+
+/// Decompresses a single file of the archive, so that each file is stored in its own cell.
+#[turbo_tasks::function]
+async fn zip_entry_content(archive: FileSystemPath, index: usize) -> Result<Vc<FileContent>> {
+    let parsed = zip_archive(archive.clone()).await?;
+    let Some(zip) = &parsed.zip else {
+        return Ok(FileContent::NotFound.cell());
+    };
+    let mut zip = zip.clone();
+    let mut entry = zip.by_index(index).with_context(|| {
+        format!(
+            "Unable to read entry {index} of zip archive {}",
+            archive.path
+        )
+    })?;
+    let mut content = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut content)?;
+    Ok(FileContent::Content(File::from(content)).cell())
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for ZipFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPath) -> Result<Vc<FileContent>> {
+        let entries = zip_entries(self.archive.clone()).await?;
+        Ok(match entries.entries.get(&fs_path.path) {
+            Some(&ZipEntry::File(index)) => zip_entry_content(self.archive.clone(), index),
+            _ => FileContent::NotFound.cell(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    fn read_link(&self, _fs_path: FileSystemPath) -> Vc<LinkContent> {
+        LinkContent::NotFound.cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn raw_read_dir(&self, fs_path: FileSystemPath) -> Result<Vc<RawDirectoryContent>> {
+        let entries = zip_entries(self.archive.clone()).await?;
+        Ok(match entries.entries.get(&fs_path.path) {
+            Some(ZipEntry::Directory(entries)) => RawDirectoryContent::new(entries.clone()),
+            _ => RawDirectoryContent::not_found(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    fn write(&self, _fs_path: FileSystemPath, _content: Vc<FileContent>) -> Result<Vc<()>> {
+        bail!("Writing is not possible on the zip file system")
+    }
+
+    #[turbo_tasks::function]
+    fn write_link(&self, _fs_path: FileSystemPath, _target: Vc<LinkContent>) -> Result<Vc<()>> {
+        bail!("Writing is not possible on the zip file system")
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPath) -> Result<Vc<FileMeta>> {
+        let entries = zip_entries(self.archive.clone()).await?;
+        if !entries.entries.contains_key(&fs_path.path) {
+            bail!("path not found, can't read metadata");
+        }
+        Ok(FileMeta::default().cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ZipFileSystem {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!("zip {}", self.archive.value_to_string().await?).into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use super::ZipFileSystem;
+    use crate::{
+        DiskFileSystem, FileContent, FileSystem, FileSystemPath, RawDirectoryContent,
+        RawDirectoryEntry,
+    };
+
+    fn write_archive(path: &std::path::Path) {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.add_directory("package/", options).unwrap();
+        zip.start_file("package/index.js", options).unwrap();
+        zip.write_all(b"module.exports = 42").unwrap();
+        // Parent directories without an entry of their own are added implicitly.
+        zip.start_file("package/lib/util.js", options).unwrap();
+        zip.write_all(b"exports.util = true").unwrap();
+        // Entries leaving the root of the archive are skipped.
+        zip.start_file("../outside.js", options).unwrap();
+        zip.write_all(b"outside").unwrap();
+        std::fs::write(path, zip.finish().unwrap().into_inner()).unwrap();
+    }
+
+    async fn read_to_string(path: &FileSystemPath) -> anyhow::Result<Option<String>> {
+        Ok(match &*path.read().await? {
+            FileContent::Content(file) => Some(file.content().to_str()?.into_owned()),
+            FileContent::NotFound => None,
+        })
+    }
+
+    async fn read_dir_names(path: &FileSystemPath) -> anyhow::Result<Option<Vec<(RcStr, bool)>>> {
+        Ok(match &*path.raw_read_dir().await? {
+            RawDirectoryContent::Entries(entries) => {
+                let mut names = entries
+                    .iter()
+                    .map(|(name, entry)| (name.clone(), *entry == RawDirectoryEntry::Directory))
+                    .collect::<Vec<_>>();
+                names.sort();
+                Some(names)
+            }
+            RawDirectoryContent::NotFound => None,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn read_zip_archive() {
+        let scratch = tempfile::tempdir().unwrap();
+        write_archive(&scratch.path().join("package.zip"));
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        let path: RcStr = scratch.path().to_str().unwrap().into();
+        tt.run_once(async {
+            let disk_fs = DiskFileSystem::new(rcstr!("temp"), path);
+            let archive = disk_fs.root().await?.join("package.zip")?;
+            let root = ZipFileSystem::new(archive).root().owned().await?;
+
+            // Files
+            assert_eq!(
+                read_to_string(&root.join("package/index.js")?)
+                    .await?
+                    .as_deref(),
+                Some("module.exports = 42")
+            );
+            assert_eq!(
+                read_to_string(&root.join("package/lib/util.js")?)
+                    .await?
+                    .as_deref(),
+                Some("exports.util = true")
+            );
+
+            // Directories
+            assert_eq!(
+                read_dir_names(&root).await?,
+                Some(vec![(rcstr!("package"), true)])
+            );
+            assert_eq!(
+                read_dir_names(&root.join("package")?).await?,
+                Some(vec![(rcstr!("index.js"), false), (rcstr!("lib"), true)])
+            );
+            assert_eq!(
+                read_dir_names(&root.join("package/lib")?).await?,
+                Some(vec![(rcstr!("util.js"), false)])
+            );
+
+            // Missing entries
+            assert_eq!(
+                read_to_string(&root.join("package/missing.js")?).await?,
+                None
+            );
+            assert_eq!(read_to_string(&root.join("package")?).await?, None);
+            assert_eq!(read_to_string(&root.join("outside.js")?).await?, None);
+            assert_eq!(read_dir_names(&root.join("package/index.js")?).await?, None);
+            assert_eq!(read_dir_names(&root.join("missing")?).await?, None);
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn read_missing_zip_archive() {
+        let scratch = tempfile::tempdir().unwrap();
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        let path: RcStr = scratch.path().to_str().unwrap().into();
+        tt.run_once(async {
+            let disk_fs = DiskFileSystem::new(rcstr!("temp"), path);
+            let archive = disk_fs.root().await?.join("missing.zip")?;
+            let root = ZipFileSystem::new(archive).root().owned().await?;
+
+            assert_eq!(read_to_string(&root.join("package/index.js")?).await?, None);
+            assert_eq!(read_dir_names(&root).await?, None);
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -13,8 +13,8 @@ use turbopack_core::{
     chunk::{
         AssetSuffix, Chunk, ChunkGroupResult, ChunkItem, ChunkType, ChunkableModule,
         ChunkingConfig, ChunkingConfigs, ChunkingContext, EntryChunkGroupResult, EvaluatableAsset,
-        EvaluatableAssets, MinifyType, SourceMapSourceType, SourceMapsType, UnusedReferences,
-        UrlBehavior,
+        EvaluatableAssets, MinifyType, OptionSubresourceIntegrityAlgorithm, SourceMapSourceType,
+        SourceMapsType, SubresourceIntegrityAlgorithm, UnusedReferences, UrlBehavior,
         availability_info::AvailabilityInfo,
         chunk_group::{MakeChunkGroupResult, make_chunk_group},
         chunk_id_strategy::ModuleIdStrategy,
@@ -40,7 +40,7 @@ use crate::ecmascript::{
     chunk::EcmascriptBrowserChunk,
     evaluate::chunk::EcmascriptBrowserEvaluateChunk,
     list::asset::{EcmascriptDevChunkList, EcmascriptDevChunkListSource},
-    worker::EcmascriptBrowserWorkerEntrypoint,
+    worker::{EcmascriptBrowserStandaloneWorkerEntrypoint, EcmascriptBrowserWorkerEntrypoint},
 };
 
 #[turbo_tasks::value]
@@ -122,6 +122,11 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    pub fn module_federation(mut self, enable_module_federation: bool) -> Self {
+        self.chunking_context.enable_module_federation = enable_module_federation;
+        self
+    }
+
     pub fn asset_base_path(mut self, asset_base_path: Option<RcStr>) -> Self {
         self.chunking_context.asset_base_path = asset_base_path;
         self
@@ -243,6 +248,13 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    /// Computes Subresource Integrity metadata for chunks loaded by the runtime, which sets it on
+    /// the `<script>` and `<link>` tags it creates.
+    pub fn subresource_integrity(mut self, algorithm: SubresourceIntegrityAlgorithm) -> Self {
+        self.chunking_context.subresource_integrity = Some(algorithm);
+        self
+    }
+
     pub fn build(self) -> Vc<BrowserChunkingContext> {
         BrowserChunkingContext::cell(self.chunking_context)
     }
@@ -306,6 +318,8 @@ pub struct BrowserChunkingContext {
     enable_module_merging: bool,
     /// Enable dynamic chunk content loading.
     enable_dynamic_chunk_content_loading: bool,
+    /// Include the module federation runtime code.
+    enable_module_federation: bool,
     /// Enable debug IDs for chunks and source maps.
     debug_ids: bool,
     /// The environment chunks will be evaluated in.
@@ -337,6 +351,8 @@ pub struct BrowserChunkingContext {
     /// The global variable name used for chunk loading.
     /// Default: "TURBOPACK"
     chunk_loading_global: Option<RcStr>,
+    /// The algorithm for Subresource Integrity metadata of chunks loaded at runtime, if enabled.
+    subresource_integrity: Option<SubresourceIntegrityAlgorithm>,
 }
 
 impl BrowserChunkingContext {
@@ -373,6 +389,7 @@ impl BrowserChunkingContext {
                 enable_nested_async_availability: false,
                 enable_module_merging: false,
                 enable_dynamic_chunk_content_loading: false,
+                enable_module_federation: false,
                 debug_ids: false,
                 environment,
                 runtime_type,
@@ -388,6 +405,7 @@ impl BrowserChunkingContext {
                 should_use_absolute_url_references: false,
                 worker_forwarded_globals: vec![],
                 chunk_loading_global: Default::default(),
+                subresource_integrity: None,
             },
         }
     }
@@ -487,7 +505,7 @@ impl BrowserChunkingContext {
     /// Returns the minify type.
     #[turbo_tasks::function]
     pub fn minify_type(&self) -> Vc<MinifyType> {
-        self.minify_type.cell()
+        self.minify_type.clone().cell()
     }
 
     /// Returns the chunk path information.
@@ -719,9 +737,14 @@ impl ChunkingContext for BrowserChunkingContext {
         Vc::cell(self.enable_dynamic_chunk_content_loading)
     }
 
+    #[turbo_tasks::function]
+    fn is_module_federation_enabled(&self) -> Vc<bool> {
+        Vc::cell(self.enable_module_federation)
+    }
+
     #[turbo_tasks::function]
     pub fn minify_type(&self) -> Vc<MinifyType> {
-        self.minify_type.cell()
+        self.minify_type.clone().cell()
     }
 
     #[turbo_tasks::function]
@@ -729,6 +752,11 @@ impl ChunkingContext for BrowserChunkingContext {
         Vc::cell(self.should_use_absolute_url_references)
     }
 
+    #[turbo_tasks::function]
+    fn subresource_integrity(&self) -> Vc<OptionSubresourceIntegrityAlgorithm> {
+        Vc::cell(self.subresource_integrity)
+    }
+
     #[turbo_tasks::function]
     async fn chunk_group(
         self: ResolvedVc<Self>,
@@ -971,6 +999,17 @@ impl ChunkingContext for BrowserChunkingContext {
         let entrypoint = EcmascriptBrowserWorkerEntrypoint::new(*resolved, forwarded_globals);
         Ok(Vc::upcast(entrypoint))
     }
+
+    #[turbo_tasks::function]
+    fn standalone_worker_entrypoint(
+        self: Vc<Self>,
+        path: FileSystemPath,
+        chunks: Vc<OutputAssets>,
+    ) -> Vc<Box<dyn OutputAsset>> {
+        Vc::upcast(EcmascriptBrowserStandaloneWorkerEntrypoint::new(
+            self, path, chunks,
+        ))
+    }
 }
 
 #[turbo_tasks::value]
@@ -7,6 +7,7 @@ use turbopack_core::{
     chunk::{Chunk, ChunkingContext, OutputChunk, OutputChunkRuntimeInfo},
     ident::AssetIdent,
     introspect::{Introspectable, IntrospectableChildren},
+    license_comments::LicenseCommentsAsset,
     output::{OutputAsset, OutputAssetsReference, OutputAssetsWithReferenced},
     source_map::{GenerateSourceMap, SourceMapAsset},
     version::VersionedContent,
@@ -46,6 +47,16 @@ impl EcmascriptBrowserChunk {
             Vc::upcast(self),
         ))
     }
+
+    #[turbo_tasks::function]
+    pub(super) async fn license_comments(self: Vc<Self>) -> Result<Vc<LicenseCommentsAsset>> {
+        let this = self.await?;
+        Ok(LicenseCommentsAsset::new(
+            Vc::upcast(*this.chunking_context),
+            this.ident_for_path(),
+            self.own_content().code(),
+        ))
+    }
 }
 
 impl EcmascriptBrowserChunk {
@@ -115,6 +126,18 @@ impl OutputAssetsReference for EcmascriptBrowserChunk {
             assets.push(ResolvedVc::upcast(self.source_map().to_resolved().await?));
         }
 
+        if this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments()
+            && self.own_content().code().await?.has_license_comments()
+        {
+            assets.push(ResolvedVc::upcast(
+                self.license_comments().to_resolved().await?,
+            ));
+        }
+
         Ok(OutputAssetsWithReferenced {
             assets: ResolvedVc::cell(assets),
             referenced_assets: chunk_references.referenced_assets,
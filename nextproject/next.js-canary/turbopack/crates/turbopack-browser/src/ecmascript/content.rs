@@ -68,7 +68,7 @@ impl EcmascriptBrowserChunkContent {
     }
 
     #[turbo_tasks::function]
-    async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
+    pub(super) async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
         let this = self.await?;
         let source_maps = *this
             .chunking_context
@@ -127,8 +127,8 @@ impl EcmascriptBrowserChunkContent {
 
         let mut code = code.build();
 
-        if let MinifyType::Minify { mangle } = *this.chunking_context.minify_type().await? {
-            code = minify(code, source_maps, mangle)?;
+        if let MinifyType::Minify(options) = &*this.chunking_context.minify_type().await? {
+            code = minify(code, source_maps, options)?;
         }
 
         Ok(code.cell())
@@ -144,7 +144,10 @@ impl VersionedContent for EcmascriptBrowserChunkContent {
         Ok(AssetContent::file(
             FileContent::Content(File::from(
                 self.code()
-                    .to_rope_with_magic_comments(|| *this.source_map)
+                    .to_rope_with_magic_comments(
+                        || *this.source_map,
+                        Some(this.chunk.license_comments()),
+                    )
                     .await?,
             ))
             .cell(),
@@ -15,6 +15,7 @@ use turbopack_core::{
     },
     code_builder::{Code, CodeBuilder},
     ident::AssetIdent,
+    license_comments::LicenseCommentsAsset,
     module::Module,
     module_graph::ModuleGraph,
     output::{OutputAsset, OutputAssets, OutputAssetsReference, OutputAssetsWithReferenced},
@@ -72,6 +73,8 @@ impl EcmascriptBrowserEvaluateChunk {
         Ok(ChunkData::from_assets(
             self.chunking_context.output_root().owned().await?,
             *self.other_chunks,
+            // These chunks are loaded by the HTML page, not by the runtime.
+            None,
         ))
     }
 
@@ -181,6 +184,11 @@ impl EcmascriptBrowserEvaluateChunk {
                     output_root_to_root_path,
                     source_maps,
                     this.chunking_context.chunk_loading_global(),
+                    *this.chunking_context.is_module_federation_enabled().await?,
+                    this.chunking_context
+                        .subresource_integrity()
+                        .await?
+                        .is_some(),
                 );
                 code.push_code(&*runtime_code.await?);
             }
@@ -193,8 +201,8 @@ impl EcmascriptBrowserEvaluateChunk {
 
         let mut code = code.build();
 
-        if let MinifyType::Minify { mangle } = *this.chunking_context.minify_type().await? {
-            code = minify(code, source_maps, mangle)?;
+        if let MinifyType::Minify(options) = &*this.chunking_context.minify_type().await? {
+            code = minify(code, source_maps, options)?;
         }
 
         Ok(code.cell())
@@ -236,6 +244,16 @@ impl EcmascriptBrowserEvaluateChunk {
             Vc::upcast(self),
         ))
     }
+
+    #[turbo_tasks::function]
+    async fn license_comments(self: Vc<Self>) -> Result<Vc<LicenseCommentsAsset>> {
+        let this = self.await?;
+        Ok(LicenseCommentsAsset::new(
+            Vc::upcast(*this.chunking_context),
+            self.ident_for_path(),
+            self.code(),
+        ))
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -262,6 +280,18 @@ impl OutputAssetsReference for EcmascriptBrowserEvaluateChunk {
             references.push(ResolvedVc::upcast(self.source_map().to_resolved().await?));
         }
 
+        if this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments()
+            && self.code().await?.has_license_comments()
+        {
+            references.push(ResolvedVc::upcast(
+                self.license_comments().to_resolved().await?,
+            ));
+        }
+
         references.extend(this.other_chunks.await?.iter().copied());
 
         Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(
@@ -292,7 +322,10 @@ impl Asset for EcmascriptBrowserEvaluateChunk {
         Ok(AssetContent::file(
             FileContent::Content(File::from(
                 self.code()
-                    .to_rope_with_magic_comments(|| self.source_map())
+                    .to_rope_with_magic_comments(
+                        || self.source_map(),
+                        Some(self.license_comments()),
+                    )
                     .await?,
             ))
             .cell(),
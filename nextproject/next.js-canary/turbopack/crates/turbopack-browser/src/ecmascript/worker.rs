@@ -4,17 +4,20 @@ use anyhow::Result;
 use indoc::writedoc;
 use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{ResolvedVc, ValueToString, Vc};
-use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath, rope::Rope};
 use turbo_tasks_hash::hash_xxh3_hash64;
 use turbopack_core::{
     asset::{Asset, AssetContent},
-    chunk::{ChunkingContext, MinifyType},
+    chunk::{AssetSuffix, ChunkingContext, MinifyType},
     code_builder::{Code, CodeBuilder},
     ident::AssetIdent,
-    output::{OutputAsset, OutputAssetsReference, OutputAssetsWithReferenced},
+    license_comments::LicenseCommentsAsset,
+    output::{OutputAsset, OutputAssets, OutputAssetsReference, OutputAssetsWithReferenced},
     source_map::{GenerateSourceMap, SourceMapAsset},
 };
-use turbopack_ecmascript::minify::minify;
+use turbopack_ecmascript::{minify::minify, utils::StringifyJs};
+
+use crate::BrowserChunkingContext;
 
 /// A pre-compiled worker entrypoint that bootstraps workers by reading config from URL params.
 ///
@@ -55,8 +58,8 @@ impl EcmascriptBrowserWorkerEntrypoint {
         let forwarded_globals = this.forwarded_globals.await?;
         let mut code = generate_worker_bootstrap_code(&forwarded_globals)?;
 
-        if let MinifyType::Minify { mangle } = *this.chunking_context.minify_type().await? {
-            code = minify(code, source_maps, mangle)?;
+        if let MinifyType::Minify(options) = &*this.chunking_context.minify_type().await? {
+            code = minify(code, source_maps, options)?;
         }
 
         Ok(code.cell())
@@ -82,6 +85,16 @@ impl EcmascriptBrowserWorkerEntrypoint {
             Vc::upcast(self),
         ))
     }
+
+    #[turbo_tasks::function]
+    async fn license_comments(self: Vc<Self>) -> Result<Vc<LicenseCommentsAsset>> {
+        let this = self.await?;
+        Ok(LicenseCommentsAsset::new(
+            *this.chunking_context,
+            self.ident_for_path(),
+            self.code(),
+        ))
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -96,9 +109,22 @@ impl ValueToString for EcmascriptBrowserWorkerEntrypoint {
 impl OutputAssetsReference for EcmascriptBrowserWorkerEntrypoint {
     #[turbo_tasks::function]
     async fn references(self: Vc<Self>) -> Result<Vc<OutputAssetsWithReferenced>> {
-        Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(vec![
-            ResolvedVc::upcast(self.source_map().to_resolved().await?),
-        ])))
+        let this = self.await?;
+        let mut references = vec![ResolvedVc::upcast(self.source_map().to_resolved().await?)];
+        if this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments()
+            && self.code().await?.has_license_comments()
+        {
+            references.push(ResolvedVc::upcast(
+                self.license_comments().to_resolved().await?,
+            ));
+        }
+        Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(
+            references,
+        )))
     }
 }
 
@@ -124,7 +150,10 @@ impl Asset for EcmascriptBrowserWorkerEntrypoint {
         Ok(AssetContent::file(
             FileContent::Content(File::from(
                 self.code()
-                    .to_rope_with_magic_comments(|| self.source_map())
+                    .to_rope_with_magic_comments(
+                        || self.source_map(),
+                        Some(self.license_comments()),
+                    )
                     .await?,
             ))
             .cell(),
@@ -227,3 +256,266 @@ fn generate_worker_bootstrap_code(forwarded_globals: &[RcStr]) -> Result<Code> {
 
     Ok(code.build())
 }
+
+/// A single script containing all chunks of a service worker or worklet.
+///
+/// Service workers and worklets are loaded from a stable URL and can't load chunks at runtime
+/// (`importScripts` is not available in worklets, and only during installation in service
+/// workers), so the chunks are concatenated after a prelude which tells the runtime the URLs the
+/// chunks would have been loaded from. The source maps of the chunks are combined into the source
+/// map of the script.
+#[turbo_tasks::value(shared)]
+pub struct EcmascriptBrowserStandaloneWorkerEntrypoint {
+    chunking_context: ResolvedVc<BrowserChunkingContext>,
+    path: FileSystemPath,
+    chunks: ResolvedVc<OutputAssets>,
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    pub fn new(
+        chunking_context: ResolvedVc<BrowserChunkingContext>,
+        path: FileSystemPath,
+        chunks: ResolvedVc<OutputAssets>,
+    ) -> Vc<Self> {
+        EcmascriptBrowserStandaloneWorkerEntrypoint {
+            chunking_context,
+            path,
+            chunks,
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
+        let this = self.await?;
+        let source_maps = *this
+            .chunking_context
+            .reference_chunk_source_maps(Vc::upcast(self))
+            .await?;
+        let output_root = this.chunking_context.output_root().owned().await?;
+        let chunk_base_path = this.chunking_context.chunk_base_path().await?;
+        let chunk_base_path = chunk_base_path.as_deref().unwrap_or_default();
+        // Only a constant suffix is known at build time, the other kinds are resolved from the
+        // page, which the worker can't access.
+        let asset_suffix = this.chunking_context.asset_suffix().await?;
+        let asset_suffix = match &*asset_suffix {
+            AssetSuffix::Constant(suffix) => suffix.as_str(),
+            _ => "",
+        };
+
+        let extract_license_comments = this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments();
+
+        let mut chunk_urls = Vec::new();
+        let mut chunks = Vec::new();
+        let mut license_comments = Vec::new();
+        for &chunk in this.chunks.await?.iter() {
+            let path = chunk.path().await?;
+            if path.extension_ref() != Some("js") {
+                continue;
+            }
+            let Some(chunk_path) = output_root.get_path_to(&path) else {
+                continue;
+            };
+            let FileContent::Content(file) = &*chunk.content().file_content().await? else {
+                continue;
+            };
+            let source_map = if source_maps
+                && let Some(generate_source_map) =
+                    ResolvedVc::try_sidecast::<Box<dyn GenerateSourceMap>>(chunk)
+                && let FileContent::Content(source_map) =
+                    &*generate_source_map.generate_source_map().await?
+            {
+                Some(source_map.content().clone())
+            } else {
+                None
+            };
+            // The chunks point to their own `.LICENSE.txt` files, which aren't emitted next to
+            // the script, so their comments are moved into the license file of the script.
+            if extract_license_comments {
+                for &asset in chunk.references().await?.assets.await?.iter() {
+                    if let Some(chunk_license_comments) =
+                        ResolvedVc::try_downcast_type::<LicenseCommentsAsset>(asset)
+                    {
+                        license_comments.extend(
+                            chunk_license_comments
+                                .code()
+                                .await?
+                                .license_comments()
+                                .iter()
+                                .cloned(),
+                        );
+                    }
+                }
+            }
+            chunk_urls.push(chunk_url(chunk_base_path, chunk_path, asset_suffix));
+            chunks.push((
+                strip_magic_comments(&file.content().to_str()?).to_string(),
+                source_map,
+            ));
+        }
+        // The runtime pops the URLs as the chunks are registered.
+        chunk_urls.reverse();
+
+        let mut code = CodeBuilder::new(source_maps, false);
+        for comment in license_comments {
+            code.push_license_comment(comment);
+        }
+        writeln!(
+            code,
+            "globalThis.TURBOPACK_ASSET_SUFFIX = {};\nglobalThis.TURBOPACK_NEXT_CHUNK_URLS = {};",
+            StringifyJs(asset_suffix),
+            StringifyJs(&chunk_urls)
+        )?;
+        for (content, source_map) in chunks {
+            code.push_source(&Rope::from(content), source_map);
+            code += "\n";
+        }
+        Ok(code.build().cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn source_map(self: Vc<Self>) -> Result<Vc<SourceMapAsset>> {
+        Ok(SourceMapAsset::new_fixed(
+            self.await?.path.clone(),
+            Vc::upcast(self),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn license_comments(self: Vc<Self>) -> Result<Vc<LicenseCommentsAsset>> {
+        Ok(LicenseCommentsAsset::new_fixed(
+            self.await?.path.clone(),
+            self.code(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(rcstr!("Ecmascript Browser Standalone Worker Entrypoint"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<OutputAssetsWithReferenced>> {
+        let this = self.await?;
+        let mut references = vec![ResolvedVc::upcast(self.source_map().to_resolved().await?)];
+        if this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments()
+            && self.code().await?.has_license_comments()
+        {
+            references.push(ResolvedVc::upcast(
+                self.license_comments().to_resolved().await?,
+            ));
+        }
+        Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(
+            references,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    fn path(&self) -> Vc<FileSystemPath> {
+        self.path.clone().cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(
+                self.code()
+                    .to_rope_with_magic_comments(
+                        || self.source_map(),
+                        Some(self.license_comments()),
+                    )
+                    .await?,
+            ))
+            .cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GenerateSourceMap for EcmascriptBrowserStandaloneWorkerEntrypoint {
+    #[turbo_tasks::function]
+    fn generate_source_map(self: Vc<Self>) -> Vc<FileContent> {
+        self.code().generate_source_map()
+    }
+}
+
+/// Removes the trailing `sourceMappingURL`, `debugId` and license file comments of a chunk,
+/// which don't apply to the concatenated script. The lines before them are kept, so that the
+/// source map of the chunk stays valid.
+fn strip_magic_comments(content: &str) -> &str {
+    let mut content = content.trim_end();
+    while let Some((rest, last_line)) = content.rsplit_once('\n') {
+        if !last_line.starts_with("//# sourceMappingURL=")
+            && !last_line.starts_with("//# debugId=")
+            && !last_line.starts_with("/*! For license information please see ")
+        {
+            break;
+        }
+        content = rest.trim_end();
+    }
+    content
+}
+
+/// Returns the URL the runtime computes for a chunk, see `getChunkRelativeUrl`.
+fn chunk_url(chunk_base_path: &str, chunk_path: &str, asset_suffix: &str) -> String {
+    let chunk_path = chunk_path
+        .split('/')
+        .map(|segment| urlencoding::encode(segment))
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{chunk_base_path}{chunk_path}{asset_suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chunk_url, strip_magic_comments};
+
+    #[test]
+    fn strips_trailing_magic_comments() {
+        assert_eq!(
+            strip_magic_comments("a();\n//# debugId=1234\n\n//# sourceMappingURL=a.js.map\n"),
+            "a();"
+        );
+        assert_eq!(
+            strip_magic_comments(
+                "a();\n\n/*! For license information please see a.js.LICENSE.txt */\n//# \
+                 sourceMappingURL=a.js.map"
+            ),
+            "a();"
+        );
+        assert_eq!(
+            strip_magic_comments("a();\n//# sourceMappingURL=a.js.map\nb();\n"),
+            "a();\n//# sourceMappingURL=a.js.map\nb();"
+        );
+    }
+
+    #[test]
+    fn chunk_urls_are_encoded() {
+        assert_eq!(
+            chunk_url("/_next/", "static/chunks/[root] a.js", "?v=1"),
+            "/_next/static/chunks/%5Broot%5D%20a.js?v=1"
+        );
+    }
+}
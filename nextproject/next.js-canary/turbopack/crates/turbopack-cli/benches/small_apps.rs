@@ -77,6 +77,15 @@ fn bench_small_apps(c: &mut Criterion) {
                             no_minify: false,
                             force_memory_cleanup: true,
                             no_scope_hoist: false,
+                            license_report: false,
+                            binding_usage_hints: false,
+                            circular_dependencies: false,
+                            allow_circular_dependency: vec![],
+                            report_duplicate_packages: false,
+                            subresource_integrity: None,
+                            critical_css: None,
+                            url_imports: false,
+                            frozen: false,
                         })
                         .await
                     })
@@ -7,14 +7,21 @@ use std::{
 use anyhow::anyhow;
 use bincode::{Decode, Encode};
 use clap::{Args, Parser, ValueEnum};
+use turbo_rcstr::RcStr;
 use turbo_tasks::{NonLocalValue, TaskInput, trace::TraceRawVcs};
-use turbopack_core::issue::IssueSeverity;
+use turbopack_core::{
+    chunk::{EcmaVersion, EcmascriptMinifyOptions, MangleType, MinifyComments},
+    issue::IssueSeverity,
+};
+use turbopack_ecmascript_plugins::transform::graphql::{GraphqlConfig, GraphqlOutput};
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
 pub enum Arguments {
     Build(BuildArguments),
     Dev(DevArguments),
+    Resolve(ResolveArguments),
+    Duplicates(DuplicatesArguments),
 }
 
 impl Arguments {
@@ -23,6 +30,8 @@ impl Arguments {
         match self {
             Arguments::Build(args) => args.common.dir.as_deref(),
             Arguments::Dev(args) => args.common.dir.as_deref(),
+            Arguments::Resolve(args) => args.dir.as_deref(),
+            Arguments::Duplicates(args) => args.common.dir.as_deref(),
         }
     }
 
@@ -31,6 +40,8 @@ impl Arguments {
         match self {
             Arguments::Build(args) => args.common.worker_threads,
             Arguments::Dev(args) => args.common.worker_threads,
+            Arguments::Duplicates(args) => args.common.worker_threads,
+            Arguments::Resolve(_) => None,
         }
     }
 }
@@ -150,10 +161,63 @@ pub struct BuildArguments {
     #[clap(long)]
     pub no_minify: bool,
 
+    #[clap(flatten)]
+    pub minify: MinifyArguments,
+
     /// Don't perform scope hoisting.
     #[clap(long)]
     pub no_scope_hoist: bool,
 
+    /// Extract license comments into `.LICENSE.txt` files next to the chunks and write a report
+    /// of the licenses of all third-party packages to `third-party-licenses.json`.
+    #[clap(long)]
+    pub license_report: bool,
+
+    /// Report unused exports, modules imported without using any of their exports, and barrel
+    /// files which force the evaluation of unused modules. Shown with `--log-level hint`.
+    #[clap(long)]
+    pub binding_usage_hints: bool,
+
+    /// Report import cycles between first-party modules. Cycles containing async modules are
+    /// reported as errors.
+    #[clap(long)]
+    pub circular_dependencies: bool,
+
+    /// Don't report import cycles containing a module matching this glob. Can be repeated.
+    #[clap(long, value_name = "GLOB", requires = "circular_dependencies")]
+    pub allow_circular_dependency: Vec<String>,
+
+    /// Report packages of which more than one copy is bundled, like `turbopack duplicates` does.
+    #[clap(long)]
+    pub report_duplicate_packages: bool,
+
+    /// Add Subresource Integrity metadata to chunks loaded at runtime, and write the integrity of
+    /// all emitted files to `subresource-integrity-manifest.json`. Only used with `--target
+    /// browser`.
+    #[clap(long, value_name = "ALGORITHM", value_parser = ["sha256", "sha384", "sha512"])]
+    pub subresource_integrity: Option<String>,
+
+    /// Inline the CSS rules which are needed for the markup above the fold of this HTML file
+    /// (resolved relative to the project's directory) and defer-load the CSS chunks of the
+    /// entries. The page is written to the output directory together with a `.critical.json`
+    /// manifest. Only used with `--target browser`.
+    #[clap(long, value_name = "HTML")]
+    pub critical_css: Option<String>,
+
+    /// Resolve `https:` imports. Imported modules are stored in the `vendor` directory and their
+    /// integrity is recorded in `url-imports.lock`, so that the build works offline once all
+    /// imports are vendored.
+    #[clap(long)]
+    pub url_imports: bool,
+
+    /// Fail instead of fetching `https:` imports which aren't vendored, or aren't recorded in
+    /// `url-imports.lock`.
+    #[clap(long, requires = "url_imports")]
+    pub frozen: bool,
+
+    #[clap(flatten)]
+    pub graphql: GraphqlArguments,
+
     /// Drop the `TurboTasks` object upon exit. By default we intentionally leak this memory, as
     /// we're about to exit the process anyways, but that can cause issues with valgrind or other
     /// leak detectors.
@@ -161,6 +225,128 @@ pub struct BuildArguments {
     pub force_memory_cleanup: bool,
 }
 
+/// Options of the minifier, ignored with `--no-minify`.
+#[derive(Debug, Default, Args)]
+pub struct MinifyArguments {
+    /// Don't discard or mangle class names, e.g. for error reporting which relies on them.
+    #[clap(long)]
+    pub keep_classnames: bool,
+
+    /// Don't discard or mangle function names.
+    #[clap(long)]
+    pub keep_fnames: bool,
+
+    /// Remove `console.*` calls.
+    #[clap(long)]
+    pub drop_console: bool,
+
+    /// Remove calls of this function when their result is unused, e.g. `console.info`. Can be
+    /// repeated.
+    #[clap(long, value_name = "NAME")]
+    pub pure_func: Vec<String>,
+
+    /// The number of compress passes of the minifier. Defaults to 2.
+    #[clap(long, value_name = "PASSES")]
+    pub minify_passes: Option<usize>,
+
+    /// The ECMAScript version the minified output may use, `5` or a year between `2015` and
+    /// `2022`.
+    #[clap(long, value_name = "VERSION")]
+    pub ecma: Option<EcmaVersion>,
+
+    /// Never mangle this name. Can be repeated.
+    #[clap(long, value_name = "NAME")]
+    pub reserved: Vec<String>,
+}
+
+impl MinifyArguments {
+    pub fn minify_options(&self, comments: MinifyComments) -> EcmascriptMinifyOptions {
+        EcmascriptMinifyOptions {
+            mangle: Some(MangleType::OptimalSize),
+            drop_console: self.drop_console,
+            pure_funcs: self
+                .pure_func
+                .iter()
+                .map(|name| RcStr::from(name.as_str()))
+                .collect(),
+            passes: self.minify_passes,
+            keep_classnames: self.keep_classnames,
+            keep_fnames: self.keep_fnames,
+            ecma: self.ecma,
+            comments,
+            reserved: self
+                .reserved
+                .iter()
+                .map(|name| RcStr::from(name.as_str()))
+                .collect(),
+        }
+    }
+}
+
+/// Options of the GraphQL tagged template precompilation.
+#[derive(Debug, Default, Args)]
+pub struct GraphqlArguments {
+    /// Precompile `gql` and `graphql` tagged templates into GraphQL document ASTs.
+    #[clap(long)]
+    pub graphql: bool,
+
+    /// Validate GraphQL documents against this schema (SDL). Resolved relative to the project's
+    /// directory (`--dir`).
+    #[clap(long, value_name = "SCHEMA", requires = "graphql")]
+    pub graphql_schema: Option<String>,
+
+    /// Precompile templates with this tag instead of `gql` and `graphql`. Can be repeated.
+    #[clap(long, value_name = "TAG", requires = "graphql")]
+    pub graphql_tag: Vec<String>,
+
+    /// Replace the text of GraphQL documents with the SHA-256 hash of their source text, for
+    /// persisted queries.
+    #[clap(long, requires = "graphql")]
+    pub graphql_persisted_hash: bool,
+}
+
+impl GraphqlArguments {
+    pub fn graphql_config(&self) -> Option<GraphqlConfig> {
+        self.graphql.then(|| GraphqlConfig {
+            schema: self.graphql_schema.clone(),
+            tags: (!self.graphql_tag.is_empty()).then(|| self.graphql_tag.clone()),
+            output: if self.graphql_persisted_hash {
+                GraphqlOutput::PersistedHash
+            } else {
+                GraphqlOutput::Document
+            },
+        })
+    }
+}
+
+/// Explains how an import is resolved, similar to `node --trace-resolve`.
+#[derive(Debug, Args)]
+#[clap(author, version, about, long_about = None)]
+pub struct ResolveArguments {
+    /// The request to resolve, e.g. `react` or `./utils`.
+    #[clap(value_parser)]
+    pub specifier: String,
+
+    /// The file containing the import. Resolved relative to the project's directory (`--dir`).
+    #[clap(long, value_parser)]
+    pub from: String,
+
+    /// Resolve as `require()` instead of `import`.
+    #[clap(long)]
+    pub require: bool,
+
+    /// The directory of the application.
+    /// If no directory is provided, the current directory will be used.
+    #[clap(short, long, value_parser)]
+    pub dir: Option<PathBuf>,
+
+    /// The root directory of the project. Nothing outside of this directory can
+    /// be accessed. e. g. the monorepo root.
+    /// If no directory is provided, `dir` will be used.
+    #[clap(long, value_parser)]
+    pub root: Option<PathBuf>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct IssueSeverityCliOption(pub IssueSeverity);
 
@@ -191,3 +377,129 @@ impl FromStr for IssueSeverityCliOption {
         <IssueSeverityCliOption as clap::ValueEnum>::from_str(s, true).map_err(|s| anyhow!("{}", s))
     }
 }
+
+/// Lists the packages of which more than one copy is bundled, with the chunk groups containing
+/// each copy and the imports which pulled it in.
+#[derive(Debug, Args)]
+#[clap(author, version, about, long_about = None)]
+pub struct DuplicatesArguments {
+    /// The same options as `turbopack build`, so that the report covers the module graph of the
+    /// build. The module graph is the same for both `--target`s.
+    #[clap(flatten)]
+    pub common: CommonArguments,
+
+    /// Resolve `https:` imports, see `turbopack build --url-imports`.
+    #[clap(long)]
+    pub url_imports: bool,
+
+    /// Fail instead of fetching `https:` imports which aren't vendored, see `turbopack build
+    /// --frozen`.
+    #[clap(long, requires = "url_imports")]
+    pub frozen: bool,
+
+    #[clap(flatten)]
+    pub graphql: GraphqlArguments,
+
+    /// Print the report as JSON.
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use turbo_rcstr::rcstr;
+    use turbopack_core::chunk::{EcmaVersion, MinifyComments};
+    use turbopack_ecmascript_plugins::transform::graphql::GraphqlOutput;
+
+    use super::{Arguments, Target};
+
+    fn build_arguments(args: &[&str]) -> Result<super::BuildArguments, clap::Error> {
+        match Arguments::try_parse_from(["turbopack", "build"].iter().chain(args).copied())? {
+            Arguments::Build(args) => Ok(args),
+            _ => unreachable!(),
+        }
+    }
+
+    fn duplicates_arguments(args: &[&str]) -> Result<super::DuplicatesArguments, clap::Error> {
+        match Arguments::try_parse_from(["turbopack", "duplicates"].iter().chain(args).copied())? {
+            Arguments::Duplicates(args) => Ok(args),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn minify_options() {
+        let args = build_arguments(&[
+            "--keep-classnames",
+            "--drop-console",
+            "--pure-func",
+            "console.info",
+            "--minify-passes",
+            "3",
+            "--ecma",
+            "2017",
+            "--reserved",
+            "AppError",
+        ])
+        .unwrap();
+        let options = args.minify.minify_options(MinifyComments::Licenses);
+        assert!(options.keep_classnames);
+        assert!(!options.keep_fnames);
+        assert!(options.drop_console);
+        assert_eq!(options.pure_funcs, vec![rcstr!("console.info")]);
+        assert_eq!(options.passes, Some(3));
+        assert_eq!(options.ecma, Some(EcmaVersion::Es2017));
+        assert_eq!(options.reserved, vec![rcstr!("AppError")]);
+        assert_eq!(options.comments, MinifyComments::Licenses);
+    }
+
+    #[test]
+    fn unsupported_ecma_version() {
+        assert!(build_arguments(&["--ecma", "2014"]).is_err());
+    }
+    #[test]
+    fn graphql_config() {
+        assert_eq!(build_arguments(&[]).unwrap().graphql.graphql_config(), None);
+        assert!(build_arguments(&["--graphql-persisted-hash"]).is_err());
+
+        let config = build_arguments(&[
+            "--graphql",
+            "--graphql-schema",
+            "schema.graphql",
+            "--graphql-persisted-hash",
+        ])
+        .unwrap()
+        .graphql
+        .graphql_config()
+        .unwrap();
+        assert_eq!(config.schema.as_deref(), Some("schema.graphql"));
+        assert_eq!(config.tags, None);
+        assert_eq!(config.output, GraphqlOutput::PersistedHash);
+    }
+
+    #[test]
+    fn duplicates_accepts_build_options() {
+        let args = duplicates_arguments(&[
+            "src/index.js",
+            "--dir",
+            "app",
+            "--target",
+            "browser",
+            "--log-level",
+            "error",
+            "--url-imports",
+            "--frozen",
+            "--graphql",
+            "--json",
+        ])
+        .unwrap();
+        assert_eq!(args.common.entries, Some(vec!["src/index.js".to_string()]));
+        assert_eq!(args.common.target, Some(Target::Browser));
+        assert!(args.common.log_level.is_some());
+        assert!(args.url_imports && args.frozen && args.json);
+        assert!(args.graphql.graphql_config().is_some());
+
+        assert!(duplicates_arguments(&["--frozen"]).is_err());
+    }
+}
@@ -9,11 +9,13 @@ use anyhow::{Context, Result, bail};
 use rustc_hash::FxHashSet;
 use tracing::Instrument;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, TransientInstance, TryJoinIterExt, TurboTasks, Vc, apply_effects};
+use turbo_tasks::{
+    OperationVc, ResolvedVc, TransientInstance, TryJoinIterExt, TurboTasks, Vc, apply_effects,
+};
 use turbo_tasks_backend::{
     BackendOptions, NoopBackingStorage, TurboTasksBackend, noop_backing_storage,
 };
-use turbo_tasks_fs::FileSystem;
+use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbo_unix_path::join_path;
 use turbopack::global_module_ids::get_global_module_id_strategy;
 use turbopack_browser::{BrowserChunkingContext, ContentHashing, CurrentChunkMethod};
@@ -21,17 +23,25 @@ use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
 use turbopack_core::{
     asset::Asset,
     chunk::{
-        ChunkingConfig, ChunkingContext, ChunkingContextExt, EvaluatableAsset, EvaluatableAssets,
-        MangleType, MinifyType, SourceMapsType, availability_info::AvailabilityInfo,
+        ChunkingConfig, ChunkingContext, ChunkingContextExt, EcmascriptMinifyOptions,
+        EvaluatableAsset, EvaluatableAssets, MangleType, MinifyComments, MinifyType,
+        SourceMapsType, SubresourceIntegrityAlgorithm, SubresourceIntegrityManifest,
+        availability_info::AvailabilityInfo, chunking_context::UnusedReferences,
     },
+    context::AssetContext,
     environment::{BrowserEnvironment, Environment, ExecutionEnvironment, NodeJsEnvironment},
+    file_source::FileSource,
     ident::AssetIdent,
     issue::{IssueReporter, IssueSeverity, handle_issues},
     module::Module,
     module_graph::{
         ModuleGraph, SingleModuleGraph,
-        binding_usage_info::compute_binding_usage_info,
+        binding_usage_info::{BindingUsageInfo, compute_binding_usage_info},
         chunk_group_info::{ChunkGroup, ChunkGroupEntry},
+        circular_dependencies::report_circular_dependencies,
+        duplicate_packages::report_duplicate_package_versions,
+        license_report::{LicenseReportAsset, compute_license_report},
+        style_groups::StyleGroupsConfig,
     },
     output::{OutputAsset, OutputAssets, OutputAssetsWithReferenced},
     reference_type::{EntryReferenceSubType, ReferenceType},
@@ -40,12 +50,21 @@ use turbopack_core::{
         parse::Request,
     },
 };
-use turbopack_css::chunk::CssChunkType;
-use turbopack_ecmascript::chunk::EcmascriptChunkType;
+use turbopack_css::{
+    chunk::CssChunkType,
+    critical::{CriticalCssConfig, CriticalCssHtmlAsset},
+};
+use turbopack_ecmascript::{
+    binding_usage_hints::emit_binding_usage_hints, chunk::EcmascriptChunkType,
+};
+use turbopack_ecmascript_plugins::transform::graphql::GraphqlConfig;
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_env::dotenv::load_env;
 use turbopack_node::execution_context::ExecutionContext;
 use turbopack_nodejs::NodeJsChunkingContext;
+use turbopack_resolve::url_imports::{
+    UrlImportsOptions, update_url_imports_lockfile, vendor_url_imports,
+};
 
 use crate::{
     arguments::{BuildArguments, Target},
@@ -57,6 +76,13 @@ use crate::{
 
 type Backend = TurboTasksBackend<NoopBackingStorage>;
 
+/// The maximum size of shared CSS chunks in production builds for the browser. Critical CSS
+/// extraction needs to compute the style groups with the same size to find the same chunks.
+const CSS_MAX_MERGE_CHUNK_SIZE: usize = 100_000;
+
+pub(crate) const DEFAULT_BROWSERSLIST_QUERY: &str =
+    "last 1 Chrome versions, last 1 Firefox versions, last 1 Safari versions, last 1 Edge versions";
+
 pub struct TurbopackBuildBuilder {
     turbo_tasks: Arc<TurboTasks<Backend>>,
     project_dir: RcStr,
@@ -70,6 +96,15 @@ pub struct TurbopackBuildBuilder {
     minify_type: MinifyType,
     target: Target,
     scope_hoist: bool,
+    license_report: bool,
+    binding_usage_hints: bool,
+    circular_dependencies: Option<Vec<RcStr>>,
+    report_duplicate_packages: bool,
+    subresource_integrity: Option<SubresourceIntegrityAlgorithm>,
+    critical_css: Option<RcStr>,
+    url_imports: bool,
+    frozen: bool,
+    graphql: Option<GraphqlConfig>,
 }
 
 impl TurbopackBuildBuilder {
@@ -79,18 +114,26 @@ impl TurbopackBuildBuilder {
             project_dir,
             root_dir,
             entry_requests: vec![],
-            browserslist_query: "last 1 Chrome versions, last 1 Firefox versions, last 1 Safari \
-                                 versions, last 1 Edge versions"
-                .into(),
+            browserslist_query: DEFAULT_BROWSERSLIST_QUERY.into(),
             log_level: IssueSeverity::Warning,
             show_all: false,
             log_detail: false,
             source_maps_type: SourceMapsType::Full,
-            minify_type: MinifyType::Minify {
+            minify_type: MinifyType::Minify(EcmascriptMinifyOptions {
                 mangle: Some(MangleType::OptimalSize),
-            },
+                ..Default::default()
+            }),
             target: Target::Node,
             scope_hoist: true,
+            license_report: false,
+            binding_usage_hints: false,
+            circular_dependencies: None,
+            report_duplicate_packages: false,
+            subresource_integrity: None,
+            critical_css: None,
+            url_imports: false,
+            frozen: false,
+            graphql: None,
         }
     }
 
@@ -139,6 +182,62 @@ impl TurbopackBuildBuilder {
         self
     }
 
+    pub fn license_report(mut self, license_report: bool) -> Self {
+        self.license_report = license_report;
+        self
+    }
+
+    pub fn binding_usage_hints(mut self, binding_usage_hints: bool) -> Self {
+        self.binding_usage_hints = binding_usage_hints;
+        self
+    }
+
+    /// Reports import cycles, except for cycles containing a module matching one of the `allow`
+    /// globs.
+    pub fn circular_dependencies(mut self, allow: Vec<RcStr>) -> Self {
+        self.circular_dependencies = Some(allow);
+        self
+    }
+
+    pub fn report_duplicate_packages(mut self, report_duplicate_packages: bool) -> Self {
+        self.report_duplicate_packages = report_duplicate_packages;
+        self
+    }
+
+    pub fn subresource_integrity(
+        mut self,
+        subresource_integrity: Option<SubresourceIntegrityAlgorithm>,
+    ) -> Self {
+        self.subresource_integrity = subresource_integrity;
+        self
+    }
+
+    /// Inlines the critical CSS of the entries into the given HTML file, see
+    /// [CriticalCssHtmlAsset].
+    pub fn critical_css(mut self, html: Option<RcStr>) -> Self {
+        self.critical_css = html;
+        self
+    }
+
+    pub fn url_imports(mut self, url_imports: bool) -> Self {
+        self.url_imports = url_imports;
+        self
+    }
+
+    /// Fails on `https:` imports which aren't vendored or locked instead of fetching them.
+    pub fn frozen(mut self, frozen: bool) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// Precompiles GraphQL tagged templates, see [GraphqlTransformer].
+    ///
+    /// [GraphqlTransformer]: turbopack_ecmascript_plugins::transform::graphql::GraphqlTransformer
+    pub fn graphql(mut self, graphql: Option<GraphqlConfig>) -> Self {
+        self.graphql = graphql;
+        self
+    }
+
     pub async fn build(self) -> Result<()> {
         self.turbo_tasks
             .run_once(async move {
@@ -151,6 +250,15 @@ impl TurbopackBuildBuilder {
                     self.minify_type,
                     self.target,
                     self.scope_hoist,
+                    self.license_report,
+                    self.binding_usage_hints,
+                    self.circular_dependencies,
+                    self.report_duplicate_packages,
+                    self.subresource_integrity,
+                    self.critical_css,
+                    self.url_imports,
+                    self.frozen,
+                    self.graphql,
                 );
 
                 // Await the result to propagate any errors.
@@ -192,133 +300,60 @@ async fn build_internal(
     minify_type: MinifyType,
     target: Target,
     scope_hoist: bool,
+    license_report: bool,
+    binding_usage_hints: bool,
+    circular_dependencies: Option<Vec<RcStr>>,
+    report_duplicate_packages: bool,
+    subresource_integrity: Option<SubresourceIntegrityAlgorithm>,
+    critical_css: Option<RcStr>,
+    url_imports: bool,
+    frozen: bool,
+    graphql: Option<GraphqlConfig>,
 ) -> Result<Vc<()>> {
-    let output_fs = output_fs(project_dir.clone());
-    const OUTPUT_DIR: &str = "dist";
-    let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
-    let project_relative: RcStr = project_relative
-        .strip_prefix(MAIN_SEPARATOR)
-        .unwrap_or(project_relative)
-        .replace(MAIN_SEPARATOR, "/")
-        .into();
-    let project_fs = project_fs(
-        root_dir.clone(),
-        /* watch= */ false,
-        join_path(project_relative.as_str(), OUTPUT_DIR)
-            .unwrap()
-            .into(),
-    );
-    let root_path = project_fs.root().owned().await?;
-    let project_path = root_path.join(&project_relative)?;
-    let build_output_root = output_fs.root().await?.join(OUTPUT_DIR)?;
-
-    let node_env = NodeEnv::Production.cell();
-
-    let build_output_root_to_root_path = project_path
-        .join(OUTPUT_DIR)?
-        .get_relative_path_to(&root_path)
-        .context("Project path is in root path")?;
-
-    let runtime_type = match *node_env.await? {
-        NodeEnv::Development => RuntimeType::Development,
-        NodeEnv::Production => RuntimeType::Production,
-    };
-
-    let compile_time_info = get_client_compile_time_info(browserslist_query.clone(), node_env);
-    let execution_context = ExecutionContext::new(
-        root_path.clone(),
-        Vc::upcast(
-            NodeJsChunkingContext::builder(
-                project_path.clone(),
-                build_output_root.clone(),
-                build_output_root_to_root_path.clone(),
-                build_output_root.clone(),
-                build_output_root.clone(),
-                build_output_root.clone(),
-                Environment::new(ExecutionEnvironment::NodeJsLambda(
-                    NodeJsEnvironment::default().resolved_cell(),
-                ))
-                .to_resolved()
-                .await?,
-                runtime_type,
-            )
-            .build(),
-        ),
-        load_env(root_path.clone()),
-    );
-
-    let asset_context = get_client_asset_context(
-        project_path.clone(),
-        execution_context,
-        compile_time_info,
+    let BuildContext {
+        root_path,
+        project_path,
+        build_output_root,
+        build_output_root_to_root_path,
         node_env,
+        runtime_type,
+        url_imports,
+        asset_context,
+    } = build_context(
+        &project_dir,
+        root_dir,
+        browserslist_query.clone(),
         source_maps_type,
-    );
-
-    let entry_requests = (*entry_requests
-        .into_iter()
-        .map(|r| async move {
-            Ok(match r {
-                EntryRequest::Relative(p) => Request::relative(
-                    p.clone().into(),
-                    Default::default(),
-                    Default::default(),
-                    false,
-                ),
-                EntryRequest::Module(m, p) => Request::module(
-                    m.clone().into(),
-                    p.clone().into(),
-                    Default::default(),
-                    Default::default(),
-                ),
-            })
-        })
-        .try_join()
-        .await?)
-        .to_vec();
-
-    let origin = PlainResolveOrigin::new(asset_context, project_fs.root().await?.join("_")?);
-    let project_dir = &project_dir;
-    let entries = async move {
-        entry_requests
-            .into_iter()
-            .map(|request_vc| async move {
-                let ty = ReferenceType::Entry(EntryReferenceSubType::Undefined);
-                let request = request_vc.await?;
-                origin
-                    .resolve_asset(request_vc, origin.resolve_options(), ty)
-                    .await?
-                    .first_module()
-                    .await?
-                    .with_context(|| {
-                        format!(
-                            "Unable to resolve entry {} from directory {}.",
-                            request.request().unwrap(),
-                            project_dir
-                        )
-                    })
-            })
-            .try_join()
-            .await
-    }
-    .instrument(tracing::info_span!("resolve entries"))
+        url_imports,
+        frozen,
+        graphql,
+    )
     .await?;
 
-    let single_graph = SingleModuleGraph::new_with_entries(
-        ResolvedVc::cell(vec![ChunkGroupEntry::Entry(entries.clone())]),
-        false,
-        true,
-    );
-    let mut module_graph = ModuleGraph::from_single_graph(single_graph);
-    let binding_usage = compute_binding_usage_info(module_graph, true);
-    let unused_references = binding_usage
-        .connect()
-        .unused_references()
-        .to_resolved()
-        .await?;
-    module_graph =
-        ModuleGraph::from_single_graph_without_unused_references(single_graph, binding_usage);
-    let module_graph = module_graph.connect();
+    let BuildModuleGraph {
+        entries,
+        module_graph,
+        binding_usage,
+        unused_references,
+    } = build_module_graph(
+        asset_context,
+        root_path.clone(),
+        &project_dir,
+        entry_requests,
+        binding_usage_hints,
+    )
+    .await?;
+    if let Some(allow) = circular_dependencies {
+        report_circular_dependencies(module_graph, allow).await?;
+    }
+    if report_duplicate_packages {
+        report_duplicate_package_versions(module_graph, project_path.clone()).await?;
+    }
+    if let Some(url_imports) = url_imports
+        && !frozen
+    {
+        update_url_imports_lockfile(*url_imports, module_graph).await?;
+    }
     let module_id_strategy = get_global_module_id_strategy(module_graph)
         .to_resolved()
         .await?;
@@ -326,7 +361,7 @@ async fn build_internal(
     let chunking_context: Vc<Box<dyn ChunkingContext>> = match target {
         Target::Browser => {
             let mut builder = BrowserChunkingContext::builder(
-                project_path,
+                project_path.clone(),
                 build_output_root.clone(),
                 build_output_root_to_root_path,
                 build_output_root.clone(),
@@ -352,6 +387,10 @@ async fn build_internal(
             .current_chunk_method(CurrentChunkMethod::DocumentCurrentScript)
             .minify_type(minify_type);
 
+            if let Some(algorithm) = subresource_integrity {
+                builder = builder.subresource_integrity(algorithm);
+            }
+
             match *node_env.await? {
                 NodeEnv::Development => {}
                 NodeEnv::Production => {
@@ -368,7 +407,7 @@ async fn build_internal(
                         .chunking_config(
                             Vc::<CssChunkType>::default().to_resolved().await?,
                             ChunkingConfig {
-                                max_merge_chunk_size: 100_000,
+                                max_merge_chunk_size: CSS_MAX_MERGE_CHUNK_SIZE,
                                 ..Default::default()
                             },
                         )
@@ -382,7 +421,7 @@ async fn build_internal(
         }
         Target::Node => {
             let mut builder = NodeJsChunkingContext::builder(
-                project_path,
+                project_path.clone(),
                 build_output_root.clone(),
                 build_output_root_to_root_path,
                 build_output_root.clone(),
@@ -496,8 +535,60 @@ async fn build_internal(
 
     let all_assets = async move {
         let mut all_assets: FxHashSet<ResolvedVc<Box<dyn OutputAsset>>> = FxHashSet::default();
+        let mut entry_assets = Vec::new();
         for group in entry_chunk_groups {
             all_assets.extend(group.expand_all_assets().await?);
+            entry_assets.extend(group.await?.assets.await?.iter().copied());
+        }
+        if let Some(html) = critical_css
+            && target == Target::Browser
+        {
+            let html_path = project_path.join(&html)?;
+            let page = CriticalCssHtmlAsset::new(
+                build_output_root.join(html_path.file_name())?,
+                Vc::upcast(FileSource::new(html_path)),
+                Vc::cell(entry_assets),
+                module_graph.style_groups(
+                    chunking_context,
+                    StyleGroupsConfig {
+                        max_chunk_size: CSS_MAX_MERGE_CHUNK_SIZE,
+                    },
+                ),
+                CriticalCssConfig::default(),
+            )
+            .to_resolved()
+            .await?;
+            all_assets.insert(ResolvedVc::upcast(page));
+            all_assets.insert(ResolvedVc::upcast(page.manifest().to_resolved().await?));
+        }
+        if license_report {
+            all_assets.insert(ResolvedVc::upcast(
+                LicenseReportAsset::new(
+                    build_output_root.join("third-party-licenses.json")?,
+                    compute_license_report(module_graph),
+                )
+                .to_resolved()
+                .await?,
+            ));
+        }
+        if let Some(algorithm) = subresource_integrity
+            && target == Target::Browser
+        {
+            let assets = ResolvedVc::cell(all_assets.iter().copied().collect());
+            all_assets.insert(ResolvedVc::upcast(
+                SubresourceIntegrityManifest::new(
+                    build_output_root.join("subresource-integrity-manifest.json")?,
+                    build_output_root.clone(),
+                    *assets,
+                    algorithm,
+                )
+                .to_resolved()
+                .await?,
+            ));
+        }
+        if url_imports.is_some() {
+            // Fetched URL imports are stored in the vendor directory, next to the build output
+            all_assets.extend(vendor_url_imports(module_graph).await?.iter().copied());
         }
         anyhow::Ok(all_assets)
     }
@@ -513,6 +604,219 @@ async fn build_internal(
     Ok(Default::default())
 }
 
+/// The paths and the asset context of `turbopack build`, shared with the commands inspecting the
+/// build, like `turbopack resolve`.
+pub(crate) struct BuildContext {
+    pub root_path: FileSystemPath,
+    pub project_path: FileSystemPath,
+    pub build_output_root: FileSystemPath,
+    pub build_output_root_to_root_path: RcStr,
+    pub node_env: Vc<NodeEnv>,
+    pub runtime_type: RuntimeType,
+    pub url_imports: Option<ResolvedVc<UrlImportsOptions>>,
+    pub asset_context: Vc<Box<dyn AssetContext>>,
+}
+
+pub(crate) async fn build_context(
+    project_dir: &RcStr,
+    root_dir: RcStr,
+    browserslist_query: RcStr,
+    source_maps_type: SourceMapsType,
+    url_imports: bool,
+    frozen: bool,
+    graphql: Option<GraphqlConfig>,
+) -> Result<BuildContext> {
+    let output_fs = output_fs(project_dir.clone());
+    const OUTPUT_DIR: &str = "dist";
+    let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
+    let project_relative: RcStr = project_relative
+        .strip_prefix(MAIN_SEPARATOR)
+        .unwrap_or(project_relative)
+        .replace(MAIN_SEPARATOR, "/")
+        .into();
+    let project_fs = project_fs(
+        root_dir,
+        /* watch= */ false,
+        join_path(project_relative.as_str(), OUTPUT_DIR)
+            .unwrap()
+            .into(),
+    );
+    let root_path = project_fs.root().owned().await?;
+    let project_path = root_path.join(&project_relative)?;
+    let build_output_root = output_fs.root().await?.join(OUTPUT_DIR)?;
+
+    let node_env = NodeEnv::Production.cell();
+
+    let build_output_root_to_root_path = project_path
+        .join(OUTPUT_DIR)?
+        .get_relative_path_to(&root_path)
+        .context("Project path is in root path")?;
+
+    let runtime_type = match *node_env.await? {
+        NodeEnv::Development => RuntimeType::Development,
+        NodeEnv::Production => RuntimeType::Production,
+    };
+
+    let env = load_env(root_path.clone());
+    let compile_time_info =
+        get_client_compile_time_info(browserslist_query, node_env, root_path.clone(), env);
+    let execution_context = ExecutionContext::new(
+        root_path.clone(),
+        Vc::upcast(
+            NodeJsChunkingContext::builder(
+                project_path.clone(),
+                build_output_root.clone(),
+                build_output_root_to_root_path.clone(),
+                build_output_root.clone(),
+                build_output_root.clone(),
+                build_output_root.clone(),
+                Environment::new(ExecutionEnvironment::NodeJsLambda(
+                    NodeJsEnvironment::default().resolved_cell(),
+                ))
+                .to_resolved()
+                .await?,
+                runtime_type,
+            )
+            .build(),
+        ),
+        env,
+    );
+
+    let url_imports = if url_imports {
+        Some(
+            UrlImportsOptions {
+                vendor_dir: project_path.join("vendor")?,
+                lockfile: project_path.join("url-imports.lock")?,
+                frozen,
+            }
+            .resolved_cell(),
+        )
+    } else {
+        None
+    };
+
+    let asset_context = get_client_asset_context(
+        project_path.clone(),
+        execution_context,
+        compile_time_info,
+        node_env,
+        source_maps_type,
+        url_imports.map(|url_imports| *url_imports),
+        graphql,
+    );
+
+    Ok(BuildContext {
+        root_path,
+        project_path,
+        build_output_root,
+        build_output_root_to_root_path,
+        node_env,
+        runtime_type,
+        url_imports,
+        asset_context,
+    })
+}
+
+/// The module graph of `turbopack build`, shared with the commands inspecting the build, like
+/// `turbopack duplicates`.
+pub(crate) struct BuildModuleGraph {
+    pub entries: Vec<ResolvedVc<Box<dyn Module>>>,
+    /// The module graph without the references which are unused once unused exports are removed.
+    pub module_graph: Vc<ModuleGraph>,
+    pub binding_usage: OperationVc<BindingUsageInfo>,
+    pub unused_references: ResolvedVc<UnusedReferences>,
+}
+
+pub(crate) async fn build_module_graph(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    root_path: FileSystemPath,
+    project_dir: &RcStr,
+    entry_requests: Vec<EntryRequest>,
+    binding_usage_hints: bool,
+) -> Result<BuildModuleGraph> {
+    let entries = resolve_entries(asset_context, root_path, project_dir, entry_requests)
+        .instrument(tracing::info_span!("resolve entries"))
+        .await?;
+
+    let single_graph = SingleModuleGraph::new_with_entries(
+        ResolvedVc::cell(vec![ChunkGroupEntry::Entry(entries.clone())]),
+        false,
+        true,
+    );
+    let module_graph = ModuleGraph::from_single_graph(single_graph);
+    let binding_usage = compute_binding_usage_info(module_graph, true);
+    let unused_references = binding_usage
+        .connect()
+        .unused_references()
+        .to_resolved()
+        .await?;
+    if binding_usage_hints {
+        emit_binding_usage_hints(module_graph.connect(), binding_usage.connect()).await?;
+    }
+    let module_graph =
+        ModuleGraph::from_single_graph_without_unused_references(single_graph, binding_usage)
+            .connect();
+
+    Ok(BuildModuleGraph {
+        entries,
+        module_graph,
+        binding_usage,
+        unused_references,
+    })
+}
+
+/// Resolves the entry requests of the project to their modules.
+pub(crate) async fn resolve_entries(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    root_path: FileSystemPath,
+    project_dir: &RcStr,
+    entry_requests: Vec<EntryRequest>,
+) -> Result<Vec<ResolvedVc<Box<dyn Module>>>> {
+    let entry_requests = (*entry_requests
+        .into_iter()
+        .map(|r| async move {
+            Ok(match r {
+                EntryRequest::Relative(p) => Request::relative(
+                    p.clone().into(),
+                    Default::default(),
+                    Default::default(),
+                    false,
+                ),
+                EntryRequest::Module(m, p) => Request::module(
+                    m.clone().into(),
+                    p.clone().into(),
+                    Default::default(),
+                    Default::default(),
+                ),
+            })
+        })
+        .try_join()
+        .await?)
+        .to_vec();
+
+    let origin = PlainResolveOrigin::new(asset_context, root_path.join("_")?);
+    entry_requests
+        .into_iter()
+        .map(|request_vc| async move {
+            let ty = ReferenceType::Entry(EntryReferenceSubType::Undefined);
+            let request = request_vc.await?;
+            origin
+                .resolve_asset(request_vc, origin.resolve_options(), ty)
+                .await?
+                .first_module()
+                .await?
+                .with_context(|| {
+                    format!(
+                        "Unable to resolve entry {} from directory {}.",
+                        request.request().unwrap(),
+                        project_dir
+                    )
+                })
+        })
+        .try_join()
+        .await
+}
+
 pub async fn build(args: &BuildArguments) -> Result<()> {
     let NormalizedDirs {
         project_dir,
@@ -543,14 +847,37 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
         .minify_type(if args.no_minify {
             MinifyType::NoMinify
         } else {
-            MinifyType::Minify {
-                mangle: Some(MangleType::OptimalSize),
-            }
+            MinifyType::Minify(args.minify.minify_options(if args.license_report {
+                MinifyComments::ExtractLicenses
+            } else {
+                MinifyComments::None
+            }))
         })
         .scope_hoist(!args.no_scope_hoist)
+        .license_report(args.license_report)
+        .binding_usage_hints(args.binding_usage_hints)
+        .report_duplicate_packages(args.report_duplicate_packages)
+        .subresource_integrity(
+            args.subresource_integrity
+                .as_deref()
+                .and_then(SubresourceIntegrityAlgorithm::parse),
+        )
+        .critical_css(args.critical_css.as_deref().map(RcStr::from))
+        .url_imports(args.url_imports)
+        .frozen(args.frozen)
+        .graphql(args.graphql.graphql_config())
         .target(args.common.target.unwrap_or(Target::Node))
         .show_all(args.common.show_all);
 
+    if args.circular_dependencies {
+        builder = builder.circular_dependencies(
+            args.allow_circular_dependency
+                .iter()
+                .map(|glob| glob.as_str().into())
+                .collect(),
+        );
+    }
+
     for entry in normalize_entries(&args.common.entries) {
         builder = builder.entry_request(EntryRequest::Relative(entry));
     }
@@ -3,12 +3,13 @@ use std::fmt;
 use anyhow::Result;
 use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_env::ProcessEnv;
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack::{
     ModuleAssetContext,
     module_options::{
-        EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext,
-        TypescriptTransformOptions,
+        EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext, ModuleRule,
+        ModuleRuleEffect, RuleCondition, TypescriptTransformOptions,
     },
 };
 use turbopack_browser::react_refresh::assert_can_resolve_react_refresh;
@@ -23,11 +24,15 @@ use turbopack_core::{
     ident::Layer,
     resolve::options::{ImportMap, ImportMapping},
 };
-use turbopack_ecmascript::TreeShakingMode;
+use turbopack_ecmascript::{EcmascriptInputTransform, TreeShakingMode};
+use turbopack_ecmascript_plugins::transform::graphql::{GraphqlConfig, GraphqlTransformer};
+use turbopack_env::client_env_free_var_references;
 use turbopack_node::{
     execution_context::ExecutionContext, transforms::postcss::PostCssTransformOptions,
 };
-use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+use turbopack_resolve::{
+    resolve_options_context::ResolveOptionsContext, url_imports::UrlImportsOptions,
+};
 
 #[turbo_tasks::value(shared)]
 pub enum NodeEnv {
@@ -84,6 +89,8 @@ pub async fn get_client_resolve_options_context(
         .await?;
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().owned().await?),
+        check_locked_versions: true,
+        validate_package_exports: true,
         custom_conditions: vec![node_env.await?.to_string().into(), rcstr!("browser")],
         import_map: Some(next_client_import_map),
         browser: true,
@@ -109,6 +116,7 @@ async fn get_client_module_options_context(
     env: ResolvedVc<Environment>,
     node_env: Vc<NodeEnv>,
     source_maps_type: SourceMapsType,
+    graphql: Option<GraphqlConfig>,
 ) -> Result<Vc<ModuleOptionsContext>> {
     let is_dev = matches!(*node_env.await?, NodeEnv::Development);
     let module_options_context = ModuleOptionsContext {
@@ -116,6 +124,7 @@ async fn get_client_module_options_context(
         execution_context: Some(execution_context),
         tree_shaking_mode: Some(TreeShakingMode::ReexportsOnly),
         keep_last_successful_parse: is_dev,
+        enable_svg_optimization: true,
         ..Default::default()
     };
 
@@ -135,6 +144,27 @@ async fn get_client_module_options_context(
         .resolved_cell(),
     );
 
+    let module_rules = match graphql {
+        Some(graphql) => vec![ModuleRule::new(
+            RuleCondition::any(vec![
+                RuleCondition::ResourcePathEndsWith(".js".to_string()),
+                RuleCondition::ResourcePathEndsWith(".jsx".to_string()),
+                RuleCondition::ResourcePathEndsWith(".mjs".to_string()),
+                RuleCondition::ResourcePathEndsWith(".cjs".to_string()),
+                RuleCondition::ResourcePathEndsWith(".ts".to_string()),
+                RuleCondition::ResourcePathEndsWith(".tsx".to_string()),
+            ]),
+            vec![ModuleRuleEffect::ExtendEcmascriptTransforms {
+                preprocess: ResolvedVc::cell(vec![]),
+                main: ResolvedVc::cell(vec![EcmascriptInputTransform::Plugin(ResolvedVc::cell(
+                    Box::new(GraphqlTransformer::new(&graphql, &project_path)?) as _,
+                ))]),
+                postprocess: ResolvedVc::cell(vec![]),
+            }],
+        )],
+        None => vec![],
+    };
+
     let module_options_context = ModuleOptionsContext {
         ecmascript: EcmascriptOptionsContext {
             enable_jsx,
@@ -145,6 +175,8 @@ async fn get_client_module_options_context(
             ..module_options_context.ecmascript.clone()
         },
         enable_postcss_transform: Some(PostCssTransformOptions::default().resolved_cell()),
+        enable_svg_components: true,
+        module_rules,
         rules: vec![(
             foreign_code_context_condition(),
             module_options_context.clone().resolved_cell(),
@@ -163,15 +195,21 @@ pub fn get_client_asset_context(
     compile_time_info: Vc<CompileTimeInfo>,
     node_env: Vc<NodeEnv>,
     source_maps_type: SourceMapsType,
+    url_imports: Option<Vc<UrlImportsOptions>>,
+    graphql: Option<GraphqlConfig>,
 ) -> Vc<Box<dyn AssetContext>> {
-    let resolve_options_context =
+    let mut resolve_options_context =
         get_client_resolve_options_context(project_path.clone(), node_env);
+    if let Some(url_imports) = url_imports {
+        resolve_options_context = resolve_options_context.with_url_imports(url_imports);
+    }
     let module_options_context = get_client_module_options_context(
         project_path,
         execution_context,
         compile_time_info.environment(),
         node_env,
         source_maps_type,
+        graphql,
     );
 
     let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
@@ -197,8 +235,11 @@ fn client_defines(node_env: &NodeEnv) -> CompileTimeDefines {
 pub async fn get_client_compile_time_info(
     browserslist_query: RcStr,
     node_env: Vc<NodeEnv>,
+    project_path: FileSystemPath,
+    env: Vc<Box<dyn ProcessEnv>>,
 ) -> Result<Vc<CompileTimeInfo>> {
     let node_env = node_env.await?;
+    let env_free_var_references = client_env_free_var_references(project_path, env).await?;
     CompileTimeInfo::builder(
         Environment::new(ExecutionEnvironment::Browser(
             BrowserEnvironment {
@@ -214,7 +255,13 @@ pub async fn get_client_compile_time_info(
     )
     .defines(client_defines(&node_env).resolved_cell())
     .free_var_references(
-        free_var_references!(..client_defines(&node_env).into_iter()).resolved_cell(),
+        free_var_references!(
+            ..env_free_var_references
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+            ..client_defines(&node_env).into_iter()
+        )
+        .resolved_cell(),
     )
     .cell()
     .await
@@ -107,19 +107,22 @@ pub async fn create_web_entry_source(
     entry_requests: Vec<Vc<Request>>,
     server_root: FileSystemPath,
     server_root_to_root_path: RcStr,
-    _env: Vc<Box<dyn ProcessEnv>>,
+    env: Vc<Box<dyn ProcessEnv>>,
     eager_compile: bool,
     node_env: Vc<NodeEnv>,
     source_maps_type: SourceMapsType,
     browserslist_query: RcStr,
 ) -> Result<Vc<Box<dyn ContentSource>>> {
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
+    let compile_time_info =
+        get_client_compile_time_info(browserslist_query, node_env, root_path.clone(), env);
     let asset_context = get_client_asset_context(
         root_path.clone(),
         execution_context,
         compile_time_info,
         node_env,
         source_maps_type,
+        None,
+        None,
     );
     let chunking_context = get_client_chunking_context(
         root_path.clone(),
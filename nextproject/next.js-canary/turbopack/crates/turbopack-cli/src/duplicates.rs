@@ -0,0 +1,232 @@
+use std::{env::current_dir, path::PathBuf};
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TransientInstance, TurboTasks, Vc};
+use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
+use turbopack_core::{
+    chunk::SourceMapsType,
+    issue::{IssueReporter, IssueSeverity, handle_issues},
+    module_graph::duplicate_packages::{
+        DuplicatePackage, DuplicatePackagesReport, compute_duplicate_packages_report,
+    },
+};
+use turbopack_ecmascript_plugins::transform::graphql::GraphqlConfig;
+
+use crate::{
+    arguments::DuplicatesArguments,
+    build::{
+        BuildContext, BuildModuleGraph, DEFAULT_BROWSERSLIST_QUERY, build_context,
+        build_module_graph,
+    },
+    util::{EntryRequest, NormalizedDirs, normalize_dirs, normalize_entries},
+};
+
+/// Prints the packages of which more than one copy is included in the module graph of
+/// `turbopack build`.
+pub async fn duplicates(args: &DuplicatesArguments) -> Result<()> {
+    let NormalizedDirs {
+        project_dir,
+        root_dir,
+    } = normalize_dirs(&args.common.dir, &args.common.root)?;
+    let entry_requests = normalize_entries(&args.common.entries)
+        .into_iter()
+        .map(EntryRequest::Relative)
+        .collect::<Vec<_>>();
+    let url_imports = args.url_imports;
+    let frozen = args.frozen;
+    let graphql = args.graphql.graphql_config();
+    let log_options = LogOptions {
+        project_dir: PathBuf::from(&*project_dir),
+        current_dir: current_dir().unwrap(),
+        show_all: args.common.show_all,
+        log_detail: args.common.log_detail,
+        log_level: args
+            .common
+            .log_level
+            .map_or_else(|| IssueSeverity::Warning, |l| l.0),
+    };
+
+    let tt = TurboTasks::new(TurboTasksBackend::new(
+        BackendOptions {
+            dependency_tracking: false,
+            storage_mode: None,
+            ..Default::default()
+        },
+        noop_backing_storage(),
+    ));
+
+    let report = tt
+        .run_once(async move {
+            let report_op = duplicates_internal(
+                project_dir,
+                root_dir,
+                entry_requests,
+                url_imports,
+                frozen,
+                graphql,
+            );
+            let report = (*report_op.read_strongly_consistent().await?).clone();
+
+            let issue_reporter: Vc<Box<dyn IssueReporter>> =
+                Vc::upcast(ConsoleUi::new(TransientInstance::new(log_options)));
+            handle_issues(report_op, issue_reporter, IssueSeverity::Error, None, None).await?;
+
+            Ok(report)
+        })
+        .await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if report.is_empty() {
+        println!("No package is included more than once.");
+    } else {
+        for package in &report {
+            print_duplicate_package(package);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_duplicate_package(package: &DuplicatePackage) {
+    println!("{} ({} copies)", package.name, package.copies.len());
+    for copy in &package.copies {
+        println!(
+            "  {} {} ({} modules, {} bytes)",
+            copy.version.as_deref().unwrap_or("<no version>"),
+            copy.directory,
+            copy.modules,
+            copy.size
+        );
+        for chunk_group in &copy.chunk_groups {
+            println!("    in {chunk_group}");
+        }
+        if !copy.import_trace.is_empty() {
+            println!("    imported by {}", copy.import_trace.join("\n      -> "));
+        }
+    }
+    for chunk_group in &package.duplicated_in {
+        println!("  multiple copies in {chunk_group}");
+    }
+    println!();
+}
+
+#[turbo_tasks::function(operation)]
+async fn duplicates_internal(
+    project_dir: RcStr,
+    root_dir: RcStr,
+    entry_requests: Vec<EntryRequest>,
+    url_imports: bool,
+    frozen: bool,
+    graphql: Option<GraphqlConfig>,
+) -> Result<Vc<DuplicatePackagesReport>> {
+    let BuildContext {
+        root_path,
+        asset_context,
+        ..
+    } = build_context(
+        &project_dir,
+        root_dir,
+        DEFAULT_BROWSERSLIST_QUERY.into(),
+        SourceMapsType::None,
+        url_imports,
+        frozen,
+        graphql,
+    )
+    .await?;
+    let BuildModuleGraph { module_graph, .. } = build_module_graph(
+        asset_context,
+        root_path,
+        &project_dir,
+        entry_requests,
+        /* binding_usage_hints= */ false,
+    )
+    .await?;
+
+    Ok(compute_duplicate_packages_report(module_graph))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks::TurboTasks;
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+
+    use super::duplicates_internal;
+    use crate::util::EntryRequest;
+
+    fn write_package(dir: &std::path::Path, name: &str, version: &str, source: &str) {
+        create_dir_all(dir).unwrap();
+        write(
+            dir.join("package.json"),
+            format!(r#"{{ "name": "{name}", "version": "{version}" }}"#),
+        )
+        .unwrap();
+        write(dir.join("index.js"), source).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn reports_duplicate_packages() {
+        let scratch = tempfile::tempdir().unwrap();
+        let project = scratch.path().canonicalize().unwrap();
+        create_dir_all(project.join("src")).unwrap();
+        write(
+            project.join("src/index.js"),
+            "import { a } from 'a';\nimport { pkg } from 'pkg';\nconsole.log(a, pkg);\n",
+        )
+        .unwrap();
+        write_package(
+            &project.join("node_modules/pkg"),
+            "pkg",
+            "2.0.0",
+            "export const pkg = 2;",
+        );
+        write_package(
+            &project.join("node_modules/a"),
+            "a",
+            "1.0.0",
+            "import { pkg } from 'pkg';\nexport const a = pkg;",
+        );
+        write_package(
+            &project.join("node_modules/a/node_modules/pkg"),
+            "pkg",
+            "1.0.0",
+            "export const pkg = 1;",
+        );
+        let project_dir: RcStr = project.to_str().unwrap().into();
+
+        let tt = TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        let report = tt
+            .run_once(async move {
+                let report_op = duplicates_internal(
+                    project_dir.clone(),
+                    project_dir,
+                    vec![EntryRequest::Relative(rcstr!("src/index.js"))],
+                    false,
+                    false,
+                    None,
+                );
+                Ok((*report_op.read_strongly_consistent().await?).clone())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(report.len(), 1);
+        let package = &report[0];
+        assert_eq!(package.name, "pkg");
+        let versions = package
+            .copies
+            .iter()
+            .map(|copy| copy.version.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![Some("1.0.0"), Some("2.0.0")]);
+        assert!(package.copies.iter().all(|copy| copy.modules == 1));
+    }
+}
@@ -7,5 +7,7 @@ pub mod arguments;
 pub mod build;
 pub(crate) mod contexts;
 pub mod dev;
+pub mod duplicates;
 pub(crate) mod embed_js;
+pub mod resolve;
 pub(crate) mod util;
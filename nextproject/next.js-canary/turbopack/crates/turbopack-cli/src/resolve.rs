@@ -0,0 +1,94 @@
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TurboTasks, ValueToString, Vc};
+use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+use turbopack_core::{
+    chunk::SourceMapsType,
+    context::AssetContext,
+    reference_type::{CommonJsReferenceSubType, EcmaScriptModulesReferenceSubType, ReferenceType},
+    resolve::{explain::explain_resolve, parse::Request},
+};
+use turbopack_resolve::ecmascript::{apply_cjs_specific_options, apply_esm_specific_options};
+
+use crate::{
+    arguments::ResolveArguments,
+    build::{BuildContext, DEFAULT_BROWSERSLIST_QUERY, build_context},
+    util::{NormalizedDirs, normalize_dirs},
+};
+
+/// Prints how `args.specifier` is resolved from `args.from`, with the asset context of
+/// `turbopack build`.
+pub async fn resolve(args: &ResolveArguments) -> Result<()> {
+    let NormalizedDirs {
+        project_dir,
+        root_dir,
+    } = normalize_dirs(&args.dir, &args.root)?;
+
+    let tt = TurboTasks::new(TurboTasksBackend::new(
+        BackendOptions {
+            dependency_tracking: false,
+            storage_mode: None,
+            ..Default::default()
+        },
+        noop_backing_storage(),
+    ));
+
+    let specifier: RcStr = args.specifier.as_str().into();
+    let from: RcStr = args.from.as_str().into();
+    let require = args.require;
+    let explanation = tt
+        .run_once(async move {
+            let explanation_op = explain_internal(project_dir, root_dir, specifier, from, require);
+            Ok((*explanation_op.read_strongly_consistent().await?).clone())
+        })
+        .await?;
+    println!("{explanation}");
+
+    Ok(())
+}
+
+#[turbo_tasks::function(operation)]
+async fn explain_internal(
+    project_dir: RcStr,
+    root_dir: RcStr,
+    specifier: RcStr,
+    from: RcStr,
+    require: bool,
+) -> Result<Vc<RcStr>> {
+    let BuildContext {
+        project_path,
+        asset_context,
+        ..
+    } = build_context(
+        &project_dir,
+        root_dir,
+        DEFAULT_BROWSERSLIST_QUERY.into(),
+        SourceMapsType::None,
+        /* url_imports= */ false,
+        /* frozen= */ false,
+        /* graphql= */ None,
+    )
+    .await?;
+    let origin_path = project_path.join(&from)?;
+
+    let options = asset_context.resolve_options(origin_path.clone());
+    let (reference_type, options) = if require {
+        (
+            ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined),
+            apply_cjs_specific_options(options),
+        )
+    } else {
+        let reference_type =
+            ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::Import);
+        let options = apply_esm_specific_options(options, &reference_type);
+        (reference_type, options)
+    };
+
+    Ok(explain_resolve(
+        origin_path.parent(),
+        reference_type,
+        Request::parse_string(specifier),
+        options,
+    )
+    .to_string())
+}
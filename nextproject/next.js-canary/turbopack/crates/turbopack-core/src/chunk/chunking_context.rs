@@ -3,7 +3,9 @@ use bincode::{Decode, Encode};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
-use turbo_tasks::{NonLocalValue, ResolvedVc, TaskInput, Upcast, Vc, trace::TraceRawVcs};
+use turbo_tasks::{
+    NonLocalValue, OperationValue, ResolvedVc, TaskInput, Upcast, Vc, trace::TraceRawVcs,
+};
 use turbo_tasks_fs::FileSystemPath;
 use turbo_tasks_hash::DeterministicHash;
 
@@ -11,7 +13,8 @@ use crate::{
     asset::Asset,
     chunk::{
         ChunkItem, ChunkType, ChunkableModule, EvaluatableAssets,
-        availability_info::AvailabilityInfo, chunk_id_strategy::ModuleIdStrategy,
+        OptionSubresourceIntegrityAlgorithm, availability_info::AvailabilityInfo,
+        chunk_id_strategy::ModuleIdStrategy,
     },
     environment::Environment,
     ident::AssetIdent,
@@ -48,20 +51,176 @@ pub enum MangleType {
     Deterministic,
 }
 
+/// Which comments are kept in minified code.
+#[derive(
+    Debug,
+    Default,
+    TaskInput,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    DeterministicHash,
+    NonLocalValue,
+    OperationValue,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum MinifyComments {
+    /// Removes all comments.
+    #[default]
+    None,
+    /// Keeps license comments, i.e. `/*! ... */` and comments containing `@license`,
+    /// `@preserve` or `@cc_on`.
+    Licenses,
+    /// Removes license comments from the output and writes them into a `<chunk>.LICENSE.txt` file
+    /// next to the chunk instead. The chunk keeps a comment pointing to that file.
+    ExtractLicenses,
+}
+
+/// The ECMAScript version the minified output may use. It is written in the notation of terser,
+/// i.e. `5` or a year like `2015`, and unsupported versions are rejected when the options are
+/// deserialized.
+#[derive(
+    Debug,
+    TaskInput,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    DeterministicHash,
+    NonLocalValue,
+    OperationValue,
+    Encode,
+    Decode,
+)]
+#[serde(try_from = "u32")]
+pub enum EcmaVersion {
+    Es5,
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    Es2019,
+    Es2020,
+    Es2021,
+    Es2022,
+}
+
+impl TryFrom<u32> for EcmaVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(version: u32) -> Result<Self> {
+        Ok(match version {
+            5 => EcmaVersion::Es5,
+            6 | 2015 => EcmaVersion::Es2015,
+            2016 => EcmaVersion::Es2016,
+            2017 => EcmaVersion::Es2017,
+            2018 => EcmaVersion::Es2018,
+            2019 => EcmaVersion::Es2019,
+            2020 => EcmaVersion::Es2020,
+            2021 => EcmaVersion::Es2021,
+            2022 => EcmaVersion::Es2022,
+            _ => bail!(
+                "unsupported ecma version {version}, expected 5 or a year between 2015 and 2022"
+            ),
+        })
+    }
+}
+
+impl std::str::FromStr for EcmaVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<Self> {
+        version
+            .parse::<u32>()
+            .map_err(|_| anyhow::anyhow!("invalid ecma version {version:?}"))?
+            .try_into()
+    }
+}
+
+#[derive(
+    Debug,
+    TaskInput,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    DeterministicHash,
+    NonLocalValue,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EcmascriptMinifyOptions {
+    pub mangle: Option<MangleType>,
+    /// Removes `console.*` calls.
+    pub drop_console: bool,
+    /// Calls of these functions are removed when their result is unused, e.g. `console.info` or
+    /// `assert`.
+    pub pure_funcs: Vec<RcStr>,
+    /// The number of compress passes. Defaults to 2, which is a tradeoff between performance and
+    /// compression size.
+    pub passes: Option<usize>,
+    /// Prevents discarding or mangling class names.
+    pub keep_classnames: bool,
+    /// Prevents discarding or mangling function names.
+    pub keep_fnames: bool,
+    /// The ECMAScript version the compressed output may use.
+    pub ecma: Option<EcmaVersion>,
+    pub comments: MinifyComments,
+    /// Names which are never mangled.
+    pub reserved: Vec<RcStr>,
+}
+
+impl Default for EcmascriptMinifyOptions {
+    fn default() -> Self {
+        Self {
+            mangle: Some(MangleType::OptimalSize),
+            drop_console: false,
+            pure_funcs: Vec::new(),
+            passes: None,
+            keep_classnames: false,
+            keep_fnames: false,
+            ecma: None,
+            comments: MinifyComments::None,
+            reserved: Vec::new(),
+        }
+    }
+}
+
 #[turbo_tasks::value(shared)]
-#[derive(Debug, TaskInput, Clone, Copy, Hash, DeterministicHash, Deserialize)]
+#[derive(Debug, TaskInput, Clone, Hash, DeterministicHash, Deserialize)]
 pub enum MinifyType {
-    // TODO instead of adding a new property here,
-    // refactor that to Minify(MinifyOptions) to allow defaults on MinifyOptions
-    Minify { mangle: Option<MangleType> },
+    Minify(EcmascriptMinifyOptions),
     NoMinify,
 }
 
 impl Default for MinifyType {
     fn default() -> Self {
-        Self::Minify {
-            mangle: Some(MangleType::OptimalSize),
-        }
+        Self::Minify(EcmascriptMinifyOptions::default())
+    }
+}
+
+impl MinifyType {
+    /// Whether license comments are written into separate `.LICENSE.txt` files.
+    pub fn extracts_license_comments(&self) -> bool {
+        matches!(
+            self,
+            MinifyType::Minify(EcmascriptMinifyOptions {
+                comments: MinifyComments::ExtractLicenses,
+                ..
+            })
+        )
     }
 }
 
@@ -408,6 +567,13 @@ pub trait ChunkingContext {
         Vc::cell(false)
     }
 
+    /// Whether the runtime supports module federation, i.e. sharing modules with other bundles
+    /// and loading the modules exposed by their containers.
+    #[turbo_tasks::function]
+    fn is_module_federation_enabled(self: Vc<Self>) -> Vc<bool> {
+        Vc::cell(false)
+    }
+
     #[turbo_tasks::function]
     fn minify_type(self: Vc<Self>) -> Vc<MinifyType> {
         MinifyType::NoMinify.cell()
@@ -418,6 +584,13 @@ pub trait ChunkingContext {
         Vc::cell(false)
     }
 
+    /// The algorithm to compute Subresource Integrity metadata of chunks loaded at runtime with, or
+    /// `None` if it's disabled.
+    #[turbo_tasks::function]
+    fn subresource_integrity(self: Vc<Self>) -> Vc<OptionSubresourceIntegrityAlgorithm> {
+        Vc::cell(None)
+    }
+
     #[turbo_tasks::function]
     fn async_loader_chunk_item(
         &self,
@@ -494,6 +667,21 @@ pub trait ChunkingContext {
             name = self.name().await?
         );
     }
+
+    /// Returns a single script at `path` which contains the runtime and all `chunks` of a worker.
+    /// Used for service workers and worklets, which are loaded from a stable URL and can't load
+    /// chunks at runtime.
+    #[turbo_tasks::function]
+    async fn standalone_worker_entrypoint(
+        self: Vc<Self>,
+        _path: FileSystemPath,
+        _chunks: Vc<OutputAssets>,
+    ) -> Result<Vc<Box<dyn OutputAsset>>> {
+        bail!(
+            "Standalone worker entrypoints are not supported by {name}",
+            name = self.name().await?
+        );
+    }
 }
 pub trait ChunkingContextExt {
     fn root_chunk_group(
@@ -796,3 +984,29 @@ fn chunk_group_assets(
         .chunk_group(ident, chunk_group, module_graph, availability_info)
         .output_assets_with_referenced()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{EcmaVersion, EcmascriptMinifyOptions};
+
+    #[test]
+    fn ecma_version_is_validated_on_deserialize() {
+        let options: EcmascriptMinifyOptions =
+            serde_json::from_str(r#"{ "ecma": 2015, "keepClassnames": true }"#).unwrap();
+        assert_eq!(options.ecma, Some(EcmaVersion::Es2015));
+        assert!(options.keep_classnames);
+
+        let error = serde_json::from_str::<EcmascriptMinifyOptions>(r#"{ "ecma": 2014 }"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unsupported ecma version 2014"), "{error}");
+    }
+
+    #[test]
+    fn ecma_version_from_str() {
+        assert_eq!("5".parse::<EcmaVersion>().unwrap(), EcmaVersion::Es5);
+        assert_eq!("6".parse::<EcmaVersion>().unwrap(), EcmaVersion::Es2015);
+        assert!("3".parse::<EcmaVersion>().is_err());
+        assert!("es2020".parse::<EcmaVersion>().is_err());
+    }
+}
@@ -1,10 +1,14 @@
 use anyhow::Result;
+use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbo_tasks_hash::Xxh3Hash64Hasher;
 
 use crate::{
-    chunk::{ModuleId, OutputChunk, OutputChunkRuntimeInfo},
+    chunk::{
+        ModuleId, OutputChunk, OutputChunkRuntimeInfo, SubresourceIntegrityAlgorithm,
+        output_asset_integrity,
+    },
     output::{OutputAsset, OutputAssets},
 };
 
@@ -14,6 +18,8 @@ pub struct ChunkData {
     pub included: Vec<ModuleId>,
     pub excluded: Vec<ModuleId>,
     pub module_chunks: Vec<String>,
+    /// The Subresource Integrity metadata of the chunk, if enabled.
+    pub integrity: Option<RcStr>,
 }
 
 #[turbo_tasks::value(transparent)]
@@ -53,6 +59,9 @@ impl ChunkData {
         for module_chunk in &self.module_chunks {
             hasher.write_value(module_chunk.as_str());
         }
+        if let Some(integrity) = &self.integrity {
+            hasher.write_value(integrity.as_str());
+        }
 
         Ok(Vc::cell(hasher.finish()))
     }
@@ -61,6 +70,7 @@ impl ChunkData {
     pub async fn from_asset(
         output_root: FileSystemPath,
         chunk: Vc<Box<dyn OutputAsset>>,
+        integrity: Option<SubresourceIntegrityAlgorithm>,
     ) -> Result<Vc<ChunkDataOption>> {
         let path = chunk.path().await?;
         // The "path" in this case is the chunk's path, not the chunk item's path.
@@ -70,6 +80,11 @@ impl ChunkData {
             return Ok(Vc::cell(None));
         };
         let path = path.to_string();
+        let integrity = if let Some(algorithm) = integrity {
+            output_asset_integrity(chunk, algorithm).owned().await?
+        } else {
+            None
+        };
 
         let Some(output_chunk) = Vc::try_resolve_sidecast::<Box<dyn OutputChunk>>(chunk).await?
         else {
@@ -79,6 +94,7 @@ impl ChunkData {
                     included: Vec::new(),
                     excluded: Vec::new(),
                     module_chunks: Vec::new(),
+                    integrity,
                 }
                 .resolved_cell(),
             )));
@@ -130,6 +146,7 @@ impl ChunkData {
                 included,
                 excluded,
                 module_chunks,
+                integrity,
             }
             .resolved_cell(),
         )))
@@ -139,12 +156,13 @@ impl ChunkData {
     pub async fn from_assets(
         output_root: FileSystemPath,
         chunks: Vc<OutputAssets>,
+        integrity: Option<SubresourceIntegrityAlgorithm>,
     ) -> Result<Vc<ChunksData>> {
         Ok(Vc::cell(
             chunks
                 .await?
                 .iter()
-                .map(|&chunk| ChunkData::from_asset(output_root.clone(), *chunk))
+                .map(|&chunk| ChunkData::from_asset(output_root.clone(), *chunk, integrity))
                 .try_join()
                 .await?
                 .into_iter()
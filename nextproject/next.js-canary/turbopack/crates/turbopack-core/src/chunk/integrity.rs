@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use data_encoding::BASE64;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    NonLocalValue, ResolvedVc, TaskInput, TryFlatJoinIterExt, Vc, trace::TraceRawVcs,
+};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath, rope::Rope};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    output::{OutputAsset, OutputAssets, OutputAssetsReference},
+};
+
+/// The hash algorithm used for [Subresource Integrity] metadata.
+///
+/// [Subresource Integrity]: https://developer.mozilla.org/en-US/docs/Web/Security/Subresource_Integrity
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, TaskInput, TraceRawVcs, NonLocalValue, Encode, Decode,
+)]
+pub enum SubresourceIntegrityAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl SubresourceIntegrityAlgorithm {
+    /// Parses the algorithm name as used in integrity metadata, e.g. `sha384`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sha256" => Some(Self::Sha256),
+            "sha384" => Some(Self::Sha384),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Computes the integrity metadata for `content`, e.g. `sha384-<base64 digest>`.
+    pub fn integrity(&self, content: &Rope) -> String {
+        fn digest<D: Digest>(content: &Rope) -> Vec<u8> {
+            let mut hasher = D::new();
+            for bytes in content.read() {
+                hasher.update(bytes);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        let digest = match self {
+            Self::Sha256 => digest::<Sha256>(content),
+            Self::Sha384 => digest::<Sha384>(content),
+            Self::Sha512 => digest::<Sha512>(content),
+        };
+        format!("{}-{}", self.as_str(), BASE64.encode(&digest))
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionSubresourceIntegrityAlgorithm(Option<SubresourceIntegrityAlgorithm>);
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionSubresourceIntegrity(Option<RcStr>);
+
+/// Computes the integrity metadata of the content of an output asset. Returns `None` for assets
+/// which aren't files.
+#[turbo_tasks::function]
+pub async fn output_asset_integrity(
+    asset: Vc<Box<dyn OutputAsset>>,
+    algorithm: SubresourceIntegrityAlgorithm,
+) -> Result<Vc<OptionSubresourceIntegrity>> {
+    let AssetContent::File(content) = &*asset.content().await? else {
+        return Ok(Vc::cell(None));
+    };
+    let FileContent::Content(file) = &*content.await? else {
+        return Ok(Vc::cell(None));
+    };
+    Ok(Vc::cell(Some(algorithm.integrity(file.content()).into())))
+}
+
+/// A JSON manifest which maps the paths of output assets, relative to `output_root`, to their
+/// Subresource Integrity metadata, e.g. `{ "static/chunks/main.js": "sha384-..." }`.
+///
+/// Servers use it to add `integrity` attributes to the `<script>` and `<link>` tags of the chunks
+/// they render into the HTML. Chunks loaded by the runtime get their integrity from the chunk
+/// data instead, see [super::ChunkingContext::subresource_integrity].
+#[turbo_tasks::value]
+pub struct SubresourceIntegrityManifest {
+    path: FileSystemPath,
+    output_root: FileSystemPath,
+    assets: ResolvedVc<OutputAssets>,
+    algorithm: SubresourceIntegrityAlgorithm,
+}
+
+#[turbo_tasks::value_impl]
+impl SubresourceIntegrityManifest {
+    #[turbo_tasks::function]
+    pub fn new(
+        path: FileSystemPath,
+        output_root: FileSystemPath,
+        assets: ResolvedVc<OutputAssets>,
+        algorithm: SubresourceIntegrityAlgorithm,
+    ) -> Vc<Self> {
+        SubresourceIntegrityManifest {
+            path,
+            output_root,
+            assets,
+            algorithm,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for SubresourceIntegrityManifest {}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for SubresourceIntegrityManifest {
+    #[turbo_tasks::function]
+    fn path(&self) -> Vc<FileSystemPath> {
+        self.path.clone().cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for SubresourceIntegrityManifest {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let manifest = self
+            .assets
+            .await?
+            .iter()
+            .map(async |&asset| {
+                let path = asset.path().await?;
+                let Some(path) = self.output_root.get_path_to(&path) else {
+                    return Ok(None);
+                };
+                let path = path.to_string();
+                Ok(output_asset_integrity(*asset, self.algorithm)
+                    .owned()
+                    .await?
+                    .map(|integrity| (path, integrity)))
+            })
+            .try_flat_join()
+            .await?
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(serde_json::to_string_pretty(&manifest)?)).cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_rcstr::rcstr;
+    use turbo_tasks::{ResolvedVc, Vc};
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{
+        File, FileContent, FileSystem, FileSystemPath, VirtualFileSystem,
+        rope::{Rope, RopeBuilder},
+    };
+
+    use super::{SubresourceIntegrityAlgorithm, SubresourceIntegrityManifest};
+    use crate::{
+        asset::{Asset, AssetContent},
+        output::OutputAsset,
+        virtual_output::VirtualOutputAsset,
+    };
+
+    async fn file_asset(
+        path: FileSystemPath,
+        content: &'static str,
+    ) -> Result<ResolvedVc<Box<dyn OutputAsset>>> {
+        Ok(ResolvedVc::upcast(
+            VirtualOutputAsset::new(
+                path,
+                AssetContent::file(FileContent::Content(File::from(content)).cell()),
+            )
+            .to_resolved()
+            .await?,
+        ))
+    }
+
+    #[test]
+    fn integrity_of_chunked_content() {
+        let mut content = RopeBuilder::default();
+        content.push_static_bytes(b"console.log(");
+        content.push_static_bytes(b"\"hello\");\n");
+        let content = content.build();
+
+        assert_eq!(
+            SubresourceIntegrityAlgorithm::Sha256.integrity(&content),
+            "sha256-+URFENx0A+QQSd6xM/aJKqamPAVZGytZ5O5bI017vZk="
+        );
+        assert_eq!(
+            SubresourceIntegrityAlgorithm::Sha384.integrity(&content),
+            "sha384-M5mGpKxRozBpvsX+PXs0ssm1NdoBYDPN4gQsyCwq+RTqmuLt5T6LWQKklQd4sArc"
+        );
+        assert_eq!(
+            SubresourceIntegrityAlgorithm::Sha512.integrity(&content),
+            "sha512-LyYYwdNF8cBjCT12Taujev18TMbqWXIvdPRTAcJbUrE2jA3xIDtOpxFcP7H0IVXQn12RUeur1LgOKAAWs1bWYA=="
+        );
+        assert_eq!(
+            SubresourceIntegrityAlgorithm::Sha384
+                .integrity(&Rope::from("console.log(\"hello\");\n")),
+            SubresourceIntegrityAlgorithm::Sha384.integrity(&content)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn manifest_maps_paths_in_output_root_to_integrity() {
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(async move {
+            let fs = VirtualFileSystem::new_with_name(rcstr!("test"));
+            let root = fs.root().owned().await?;
+            let output_root = root.join(".next")?;
+
+            let assets = vec![
+                file_asset(
+                    output_root.join("static/chunks/main.js")?,
+                    "console.log(\"hello\");\n",
+                )
+                .await?,
+                file_asset(output_root.join("static/chunks/main.css")?, "body{}").await?,
+                // Assets outside of the output root aren't served, so they are left out.
+                file_asset(root.join("server.js")?, "").await?,
+            ];
+
+            let manifest = SubresourceIntegrityManifest::new(
+                output_root.join("subresource-integrity-manifest.json")?,
+                output_root.clone(),
+                Vc::cell(assets),
+                SubresourceIntegrityAlgorithm::Sha384,
+            );
+            let FileContent::Content(file) = &*manifest.content().file_content().await? else {
+                panic!("the manifest should be a file");
+            };
+            let manifest: serde_json::Value = serde_json::from_str(&file.content().to_str()?)?;
+            assert_eq!(
+                manifest,
+                serde_json::json!({
+                    "static/chunks/main.css": "sha384-myyg/hQ74aSgjBBvVME/QXAXEkT4Y9dHbVQ5C0lIyGpldvNLJV2IWc5ElXbqLi06",
+                    "static/chunks/main.js": "sha384-M5mGpKxRozBpvsX+PXs0ssm1NdoBYDPN4gQsyCwq+RTqmuLt5T6LWQKklQd4sArc",
+                })
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -7,6 +7,7 @@ pub mod chunking;
 pub(crate) mod chunking_context;
 pub(crate) mod data;
 pub(crate) mod evaluate;
+pub(crate) mod integrity;
 
 use std::fmt::Display;
 
@@ -28,11 +29,16 @@ pub use crate::chunk::{
     },
     chunking_context::{
         AssetSuffix, ChunkGroupResult, ChunkGroupType, ChunkingConfig, ChunkingConfigs,
-        ChunkingContext, ChunkingContextExt, EntryChunkGroupResult, MangleType, MinifyType,
-        SourceMapSourceType, SourceMapsType, UnusedReferences, UrlBehavior,
+        ChunkingContext, ChunkingContextExt, EcmaVersion, EcmascriptMinifyOptions,
+        EntryChunkGroupResult, MangleType, MinifyComments, MinifyType, SourceMapSourceType,
+        SourceMapsType, UnusedReferences, UrlBehavior,
     },
     data::{ChunkData, ChunkDataOption, ChunksData},
     evaluate::{EvaluatableAsset, EvaluatableAssetExt, EvaluatableAssets},
+    integrity::{
+        OptionSubresourceIntegrity, OptionSubresourceIntegrityAlgorithm,
+        SubresourceIntegrityAlgorithm, SubresourceIntegrityManifest, output_asset_integrity,
+    },
 };
 use crate::{
     asset::Asset,
@@ -7,7 +7,7 @@ use std::{
 use anyhow::Result;
 use tracing::instrument;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks::{FxIndexSet, ResolvedVc, Vc};
 use turbo_tasks_fs::{
     File, FileContent,
     rope::{Rope, RopeBuilder},
@@ -16,6 +16,7 @@ use turbo_tasks_hash::hash_xxh3_hash64;
 
 use crate::{
     debug_id::generate_debug_id,
+    license_comments::LicenseCommentsAsset,
     output::OutputAsset,
     source_map::{GenerateSourceMap, SourceMap, SourceMapAsset},
     source_pos::SourcePos,
@@ -31,6 +32,9 @@ pub struct Code {
     code: Rope,
     mappings: Vec<Mapping>,
     should_generate_debug_id: bool,
+    /// License comments which were extracted from the code, see
+    /// [`crate::chunk::MinifyComments::ExtractLicenses`].
+    license_comments: Vec<RcStr>,
 }
 
 impl Code {
@@ -47,19 +51,34 @@ impl Code {
         self.should_generate_debug_id
     }
 
+    /// The license comments which were extracted from the code.
+    pub fn license_comments(&self) -> &[RcStr] {
+        &self.license_comments
+    }
+
+    /// Tests if license comments were extracted from this Code.
+    pub fn has_license_comments(&self) -> bool {
+        !self.license_comments.is_empty()
+    }
+
     /// Take the source code out of the Code.
     pub fn into_source_code(self) -> Rope {
         self.code
     }
 
-    // Formats the code with the source map and debug id comments as
+    // Formats the code with the source map, license and debug id comments as
     pub async fn to_rope_with_magic_comments(
         self: Vc<Self>,
         source_map_path_fn: impl FnOnce() -> Vc<SourceMapAsset>,
+        license_comments: Option<Vc<LicenseCommentsAsset>>,
     ) -> Result<Rope> {
         let code = self.await?;
+        let license_comments = license_comments.filter(|_| code.has_license_comments());
         Ok(
-            if code.has_source_map() || code.should_generate_debug_id() {
+            if code.has_source_map()
+                || code.should_generate_debug_id()
+                || license_comments.is_some()
+            {
                 let mut rope_builder = RopeBuilder::default();
                 let debug_id = self.debug_id().await?;
                 // hand minified version of
@@ -87,6 +106,14 @@ impl Code {
 
                 rope_builder.concat(&code.code);
                 rope_builder.push_static_bytes(b"\n");
+                if let Some(license_comments) = license_comments {
+                    let license_comments_path = license_comments.path().await?;
+                    write!(
+                        rope_builder,
+                        "\n/*! For license information please see {} */",
+                        urlencoding::encode(license_comments_path.file_name())
+                    )?;
+                }
                 // Add debug ID comment if enabled
                 if let Some(debug_id) = &*debug_id {
                     write!(rope_builder, "\n//# debugId={}", debug_id)?;
@@ -113,6 +140,7 @@ pub struct CodeBuilder {
     code: RopeBuilder,
     mappings: Option<Vec<Mapping>>,
     should_generate_debug_id: bool,
+    license_comments: FxIndexSet<RcStr>,
 }
 
 impl Default for CodeBuilder {
@@ -121,6 +149,7 @@ impl Default for CodeBuilder {
             code: RopeBuilder::default(),
             mappings: Some(Vec::new()),
             should_generate_debug_id: false,
+            license_comments: FxIndexSet::default(),
         }
     }
 }
@@ -131,6 +160,7 @@ impl CodeBuilder {
             code: RopeBuilder::default(),
             mappings: collect_mappings.then(Vec::new),
             should_generate_debug_id,
+            license_comments: FxIndexSet::default(),
         }
     }
 
@@ -177,6 +207,14 @@ impl CodeBuilder {
         }
 
         self.code += &prebuilt.code;
+        for comment in &prebuilt.license_comments {
+            self.push_license_comment(comment.clone());
+        }
+    }
+
+    /// Records a license comment which was removed from the code. Duplicates are ignored.
+    pub fn push_license_comment(&mut self, comment: RcStr) {
+        self.license_comments.insert(comment);
     }
 
     /// Setting breakpoints on synthetic code can cause weird behaviors
@@ -212,6 +250,7 @@ impl CodeBuilder {
             code: self.code.build(),
             mappings: self.mappings.unwrap_or_default(),
             should_generate_debug_id: self.should_generate_debug_id,
+            license_comments: self.license_comments.into_iter().collect(),
         }
     }
 }
@@ -344,3 +383,94 @@ impl Code {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use turbo_rcstr::rcstr;
+    use turbo_tasks::Vc;
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{FileContent, FileSystem, VirtualFileSystem};
+
+    use super::CodeBuilder;
+    use crate::{
+        asset::Asset, license_comments::LicenseCommentsAsset, output::OutputAsset,
+        source_map::SourceMapAsset,
+    };
+
+    #[test]
+    fn license_comments_are_deduplicated_in_order() {
+        let mut inner = CodeBuilder::default();
+        inner.push_license_comment(rcstr!("/*! b */"));
+        inner.push_license_comment(rcstr!("/*! a */"));
+        let inner = inner.build();
+
+        let mut outer = CodeBuilder::default();
+        outer.push_license_comment(rcstr!("/*! a */"));
+        outer.push_code(&inner);
+        outer.push_license_comment(rcstr!("/*! b */"));
+        outer.push_license_comment(rcstr!("/*! c */"));
+        let code = outer.build();
+
+        assert_eq!(
+            code.license_comments(),
+            [rcstr!("/*! a */"), rcstr!("/*! b */"), rcstr!("/*! c */")]
+        );
+        assert!(code.has_license_comments());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn license_comments_are_referenced_from_the_code() {
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(async move {
+            let fs = VirtualFileSystem::new_with_name(rcstr!("test"));
+            let root = fs.root().await?;
+
+            let mut builder = CodeBuilder::default();
+            builder.push_source(&"f();".to_string().into(), None);
+            builder.push_license_comment(rcstr!("/*! a | MIT */"));
+            builder.push_license_comment(rcstr!("// @license ISC"));
+            let code = builder.build().cell();
+            let license_comments =
+                LicenseCommentsAsset::new_fixed(root.join("chunk.js")?, code.to_resolved().await?);
+
+            assert_eq!(
+                &*license_comments.path().await?,
+                &root.join("chunk.js.LICENSE.txt")?
+            );
+            let FileContent::Content(file) = &*license_comments.content().file_content().await?
+            else {
+                panic!("the license comments should have content");
+            };
+            assert_eq!(
+                file.content().to_str()?,
+                "/*! a | MIT */\n\n// @license ISC\n\n"
+            );
+
+            let source_map =
+                || -> Vc<SourceMapAsset> { unreachable!("the code has no source map") };
+            let rope = code
+                .to_rope_with_magic_comments(source_map, Some(license_comments))
+                .await?;
+            assert_eq!(
+                rope.to_str()?,
+                "f();\n\n/*! For license information please see chunk.js.LICENSE.txt */"
+            );
+
+            // Without extracted comments the code is left untouched.
+            let mut builder = CodeBuilder::default();
+            builder.push_source(&"f();".to_string().into(), None);
+            let code = builder.build().cell();
+            let rope = code
+                .to_rope_with_magic_comments(source_map, Some(license_comments))
+                .await?;
+            assert_eq!(rope.to_str()?, "f();");
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -23,6 +23,7 @@ pub mod file_source;
 pub mod ident;
 pub mod introspect;
 pub mod issue;
+pub mod license_comments;
 pub mod module;
 pub mod module_graph;
 pub mod node_addon_module;
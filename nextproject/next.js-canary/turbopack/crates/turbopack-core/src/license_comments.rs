@@ -0,0 +1,131 @@
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{
+    NonLocalValue, ResolvedVc, ValueToString, Vc, debug::ValueDebugFormat, trace::TraceRawVcs,
+};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath, rope::RopeBuilder};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    chunk::ChunkingContext,
+    code_builder::Code,
+    ident::AssetIdent,
+    introspect::Introspectable,
+    output::{OutputAsset, OutputAssetsReference},
+};
+
+#[derive(PartialEq, Eq, NonLocalValue, TraceRawVcs, ValueDebugFormat, Encode, Decode)]
+enum PathType {
+    Fixed {
+        path: FileSystemPath,
+    },
+    FromIdent {
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+        ident_for_path: ResolvedVc<AssetIdent>,
+    },
+}
+
+/// The license comments which were extracted from a chunk during minification, emitted as
+/// `<chunk>.LICENSE.txt` next to the chunk.
+#[turbo_tasks::value]
+pub struct LicenseCommentsAsset {
+    path_ty: PathType,
+    code: ResolvedVc<Code>,
+}
+
+#[turbo_tasks::value_impl]
+impl LicenseCommentsAsset {
+    #[turbo_tasks::function]
+    pub fn new(
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+        ident_for_path: ResolvedVc<AssetIdent>,
+        code: ResolvedVc<Code>,
+    ) -> Vc<Self> {
+        LicenseCommentsAsset {
+            path_ty: PathType::FromIdent {
+                chunking_context,
+                ident_for_path,
+            },
+            code,
+        }
+        .cell()
+    }
+
+    /// The license comments of the script at `path`, emitted as `<path>.LICENSE.txt`.
+    #[turbo_tasks::function]
+    pub fn new_fixed(path: FileSystemPath, code: ResolvedVc<Code>) -> Vc<Self> {
+        LicenseCommentsAsset {
+            path_ty: PathType::Fixed { path },
+            code,
+        }
+        .cell()
+    }
+
+    /// The code the license comments were extracted from.
+    #[turbo_tasks::function]
+    pub fn code(&self) -> Vc<Code> {
+        *self.code
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for LicenseCommentsAsset {}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for LicenseCommentsAsset {
+    #[turbo_tasks::function]
+    async fn path(self: Vc<Self>) -> Result<Vc<FileSystemPath>> {
+        let this = self.await?;
+        Ok(match &this.path_ty {
+            PathType::FromIdent {
+                chunking_context,
+                ident_for_path,
+            } => chunking_context
+                .chunk_path(
+                    Some(Vc::upcast(self)),
+                    **ident_for_path,
+                    None,
+                    rcstr!(".js"),
+                )
+                .await?
+                .append(".LICENSE.txt")?
+                .cell(),
+            PathType::Fixed { path } => path.append(".LICENSE.txt")?.cell(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for LicenseCommentsAsset {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let code = self.code.await?;
+        let mut rope_builder = RopeBuilder::default();
+        for comment in code.license_comments() {
+            rope_builder.push_bytes(comment.as_bytes());
+            rope_builder.push_static_bytes(b"\n\n");
+        }
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(rope_builder.build())).cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for LicenseCommentsAsset {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell(rcstr!("license comments"))
+    }
+
+    #[turbo_tasks::function]
+    fn title(self: Vc<Self>) -> Vc<RcStr> {
+        self.path().to_string()
+    }
+
+    #[turbo_tasks::function]
+    fn details(&self) -> Vc<RcStr> {
+        Vc::cell(rcstr!("license comments extracted from a chunk"))
+    }
+}
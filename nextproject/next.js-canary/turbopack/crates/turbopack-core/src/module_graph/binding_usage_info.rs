@@ -54,6 +54,15 @@ impl BindingUsageInfo {
         self.unused_references_edges.contains(edge)
     }
 
+    /// The computed export usage of a module, or `None` if the module isn't reachable via used
+    /// references.
+    pub fn export_usage(
+        &self,
+        module: ResolvedVc<Box<dyn Module>>,
+    ) -> Option<&ModuleExportUsageInfo> {
+        self.used_exports.get(&module)
+    }
+
     pub async fn used_exports(
         &self,
         module: ResolvedVc<Box<dyn Module>>,
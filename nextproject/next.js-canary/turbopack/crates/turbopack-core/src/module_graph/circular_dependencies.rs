@@ -0,0 +1,491 @@
+use std::{
+    collections::{VecDeque, hash_map::Entry},
+    hash::Hash,
+};
+
+use anyhow::Result;
+use rustc_hash::{FxHashMap, FxHashSet};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{
+    FileSystemPath,
+    glob::{Glob, GlobOptions},
+};
+
+use crate::{
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::Module,
+    module_graph::ModuleGraph,
+};
+
+/// Reports import cycles between first-party modules (i.e. modules outside of `node_modules`).
+///
+/// For every strongly connected component of the graph (following only parallel references),
+/// one of the shortest cycles through it is reported as a warning. Cycles containing async
+/// modules (using top-level await or depending on such a module) are reported as errors, as the
+/// evaluation order of these modules depends on timing.
+///
+/// Modules matching one of the `allow` globs are ignored like third-party modules, so cycles
+/// through them are not reported, but other cycles in the same component still are.
+#[turbo_tasks::function]
+pub async fn report_circular_dependencies(
+    graph: Vc<ModuleGraph>,
+    allow: Vec<RcStr>,
+) -> Result<Vc<()>> {
+    let allow = if allow.is_empty() {
+        None
+    } else {
+        Some(
+            Glob::alternatives(
+                allow
+                    .into_iter()
+                    .map(|glob| Glob::new(glob, GlobOptions::default()))
+                    .collect(),
+            )
+            .await?,
+        )
+    };
+    let async_modules = graph.async_module_info().await?;
+    let graph = graph.await?;
+
+    let mut components = Vec::new();
+    graph.traverse_cycles(
+        |e| e.chunking_type.is_parallel(),
+        |cycle| {
+            // Self-references are harmless.
+            if cycle.len() > 1 {
+                components.push(cycle.iter().map(|m| **m).collect::<Vec<_>>());
+            }
+            Ok(())
+        },
+    )?;
+    if components.is_empty() {
+        return Ok(Default::default());
+    }
+
+    let in_components = components
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<FxHashSet<_>>();
+    let mut edges = FxHashMap::<_, Vec<_>>::default();
+    graph.traverse_edges_unordered(|parent, target| {
+        if let Some((parent, ref_data)) = parent
+            && ref_data.chunking_type.is_parallel()
+            && in_components.contains(&parent)
+            && in_components.contains(&target)
+        {
+            let targets = edges.entry(parent).or_default();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        Ok(())
+    })?;
+
+    let paths = in_components
+        .iter()
+        .map(async |&module| Ok((module, module.ident().path().owned().await?)))
+        .try_join()
+        .await?
+        .into_iter()
+        .collect::<FxHashMap<_, _>>();
+
+    for component in components {
+        let candidates = component
+            .iter()
+            .copied()
+            .filter(|module| {
+                let path = &paths[module].path;
+                !path.split('/').any(|segment| segment == "node_modules")
+                    && !allow.as_ref().is_some_and(|allow| allow.matches(path))
+            })
+            .collect::<FxHashSet<_>>();
+        let Some(cycle) = component
+            .iter()
+            .filter(|module| candidates.contains(*module))
+            .find_map(|&start| {
+                shortest_cycle(start, |module| {
+                    edges
+                        .get(&module)
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .filter(|target| candidates.contains(target))
+                })
+            })
+        else {
+            continue;
+        };
+
+        let mut async_cycle_modules = Vec::new();
+        for &module in &cycle {
+            if async_modules.contains(&module) {
+                async_cycle_modules
+                    .push((paths[&module].path.clone(), *module.is_self_async().await?));
+            }
+        }
+
+        CircularDependencyIssue {
+            file_path: paths[&cycle[0]].clone(),
+            cycle: cycle
+                .iter()
+                .chain(std::iter::once(&cycle[0]))
+                .map(|module| paths[module].path.clone())
+                .collect(),
+            component_size: component.len(),
+            async_modules: async_cycle_modules,
+        }
+        .resolved_cell()
+        .emit();
+    }
+
+    Ok(Default::default())
+}
+
+/// Finds one of the shortest cycles starting and ending at `start` using a breadth-first search.
+/// The returned path starts with `start` and doesn't repeat it at the end.
+fn shortest_cycle<T, I>(start: T, mut successors: impl FnMut(T) -> I) -> Option<Vec<T>>
+where
+    T: Copy + Eq + Hash,
+    I: Iterator<Item = T>,
+{
+    let mut predecessors = FxHashMap::default();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for successor in successors(node) {
+            if successor == start {
+                let mut path = vec![node];
+                while let Some(&predecessor) = predecessors.get(path.last().unwrap()) {
+                    path.push(predecessor);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if let Entry::Vacant(e) = predecessors.entry(successor) {
+                e.insert(node);
+                queue.push_back(successor);
+            }
+        }
+    }
+    None
+}
+
+#[turbo_tasks::value(shared)]
+struct CircularDependencyIssue {
+    file_path: FileSystemPath,
+    /// The modules in the cycle, starting and ending with the same module.
+    cycle: Vec<RcStr>,
+    /// The number of modules which are (transitively) part of this cycle.
+    component_size: usize,
+    /// The async modules in the cycle, with whether they are async themselves (e.g. because of
+    /// top-level await) rather than because of an async dependency.
+    async_modules: Vec<(RcStr, bool)>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for CircularDependencyIssue {
+    fn severity(&self) -> IssueSeverity {
+        if self.async_modules.is_empty() {
+            IssueSeverity::Warning
+        } else {
+            IssueSeverity::Error
+        }
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Analysis.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            format!(
+                "Circular dependency between {} modules",
+                self.cycle.len() - 1
+            )
+            .into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut lines = vec![StyledString::Code(
+            self.cycle
+                .iter()
+                .map(|path| path.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+                .into(),
+        )];
+        if self.component_size + 1 > self.cycle.len() {
+            lines.push(StyledString::Text(
+                format!(
+                    "These modules are part of a group of {} modules which import each other.",
+                    self.component_size
+                )
+                .into(),
+            ));
+        }
+        if self.async_modules.is_empty() {
+            lines.push(StyledString::Text(
+                "Modules in a cycle can observe each other before they are initialized, which can \
+                 cause \"Cannot access before initialization\" errors."
+                    .into(),
+            ));
+        } else {
+            lines.push(StyledString::Text(
+                "The cycle contains async modules, so the order in which its modules are \
+                 evaluated depends on timing:"
+                    .into(),
+            ));
+            for (path, is_self_async) in &self.async_modules {
+                lines.push(StyledString::Text(
+                    if *is_self_async {
+                        format!("- {path} (uses top-level await)")
+                    } else {
+                        format!("- {path} (depends on an async module)")
+                    }
+                    .into(),
+                ));
+            }
+        }
+        Vc::cell(Some(StyledString::Stack(lines).resolved_cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rustc_hash::FxHashMap;
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks::{ResolvedVc, TryJoinIterExt, Vc};
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem};
+
+    use super::{report_circular_dependencies, shortest_cycle};
+    use crate::{
+        asset::{Asset, AssetContent},
+        ident::AssetIdent,
+        issue::{CollectibleIssuesExt, IssueFilter, IssueSeverity, StyledString},
+        module::{Module, ModuleSideEffects},
+        module_graph::{ModuleGraph, SingleModuleGraph, chunk_group_info::ChunkGroupEntry},
+        reference::{ModuleReference, ModuleReferences, SingleChunkableModuleReference},
+        resolve::ExportUsage,
+    };
+
+    #[test]
+    fn test_shortest_cycle() {
+        // 0 -> 1 -> 2 -> 3 -> 0 and 0 -> 4 -> 0
+        let edges: &[&[usize]] = &[&[1, 4], &[2], &[3], &[0], &[0]];
+        let successors = |node: usize| edges[node].iter().copied();
+
+        assert_eq!(shortest_cycle(0, successors), Some(vec![0, 4]));
+        assert_eq!(shortest_cycle(1, successors), Some(vec![1, 2, 3, 0]));
+    }
+
+    #[test]
+    fn test_shortest_cycle_none() {
+        let edges: &[&[usize]] = &[&[1], &[2], &[]];
+        let successors = |node: usize| edges[node].iter().copied();
+
+        assert_eq!(shortest_cycle(0, successors), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_report_cycle() {
+        let issues = report(
+            vec![
+                (rcstr!("index.js"), vec![rcstr!("a.js")]),
+                (rcstr!("a.js"), vec![rcstr!("b.js")]),
+                (rcstr!("b.js"), vec![rcstr!("a.js")]),
+            ],
+            vec![],
+        )
+        .await;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, IssueSeverity::Warning);
+        assert!(issues[0].1.contains("a.js") && issues[0].1.contains("b.js"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_report_cycle_allowlist() {
+        // a.js -> b.js -> a.js and a.js -> c.js -> a.js form a single component.
+        let deps = vec![
+            (rcstr!("index.js"), vec![rcstr!("a.js")]),
+            (rcstr!("a.js"), vec![rcstr!("b.js"), rcstr!("c.js")]),
+            (rcstr!("b.js"), vec![rcstr!("a.js")]),
+            (rcstr!("c.js"), vec![rcstr!("a.js")]),
+        ];
+
+        // Allowing b.js still reports the cycle through c.js.
+        let issues = report(deps.clone(), vec![rcstr!("b.js")]).await;
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].1.contains("c.js"), "{}", issues[0].1);
+        assert!(!issues[0].1.contains("b.js"), "{}", issues[0].1);
+
+        let issues = report(deps, vec![rcstr!("{b,c}.js")]).await;
+        assert!(issues.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_report_async_cycle() {
+        // tla.js uses top-level await, which makes a.js async as well.
+        let issues = report(
+            vec![
+                (rcstr!("index.js"), vec![rcstr!("a.js")]),
+                (rcstr!("a.js"), vec![rcstr!("tla.js")]),
+                (rcstr!("tla.js"), vec![rcstr!("a.js")]),
+            ],
+            vec![],
+        )
+        .await;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, IssueSeverity::Error);
+    }
+
+    /// Builds a graph from the adjacency lists in `deps`, starting at the first module, and
+    /// returns the severity and cycle of the reported issues.
+    async fn report(
+        deps: Vec<(RcStr, Vec<RcStr>)>,
+        allow: Vec<RcStr>,
+    ) -> Vec<(IssueSeverity, RcStr)> {
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(async move {
+            let op = report_operation(deps, allow);
+            op.read_strongly_consistent().await?;
+            let issues = op
+                .peek_issues()
+                .get_plain_issues(IssueFilter::everything())
+                .await?;
+            Ok(issues
+                .iter()
+                .map(|issue| {
+                    let Some(StyledString::Stack(lines)) = &issue.description else {
+                        panic!("unexpected description {:?}", issue.description);
+                    };
+                    let StyledString::Code(cycle) = &lines[0] else {
+                        panic!("unexpected description {lines:?}");
+                    };
+                    (issue.severity, cycle.clone())
+                })
+                .collect())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[turbo_tasks::function(operation)]
+    async fn report_operation(deps: Vec<(RcStr, Vec<RcStr>)>, allow: Vec<RcStr>) -> Result<()> {
+        let fs = VirtualFileSystem::new_with_name(rcstr!("test"));
+        let root = fs.root().await?;
+        let repo = TestRepo {
+            repo: deps
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        root.join(k).unwrap(),
+                        v.iter().map(|f| root.join(f).unwrap()).collect(),
+                    )
+                })
+                .collect(),
+        }
+        .cell();
+        let entry = Vc::upcast::<Box<dyn Module>>(MockModule::new(root.join(&deps[0].0)?, repo))
+            .to_resolved()
+            .await?;
+        let graph = ModuleGraph::from_single_graph(SingleModuleGraph::new_with_entries(
+            ResolvedVc::cell(vec![ChunkGroupEntry::Entry(vec![entry])]),
+            false,
+            false,
+        ))
+        .connect();
+        report_circular_dependencies(graph, allow).await?;
+        Ok(())
+    }
+
+    #[turbo_tasks::value(shared)]
+    struct TestRepo {
+        repo: FxHashMap<FileSystemPath, Vec<FileSystemPath>>,
+    }
+
+    #[turbo_tasks::value]
+    struct MockModule {
+        path: FileSystemPath,
+        repo: ResolvedVc<TestRepo>,
+    }
+
+    #[turbo_tasks::value_impl]
+    impl MockModule {
+        #[turbo_tasks::function]
+        fn new(path: FileSystemPath, repo: ResolvedVc<TestRepo>) -> Vc<Self> {
+            Self { path, repo }.cell()
+        }
+    }
+
+    #[turbo_tasks::value_impl]
+    impl Asset for MockModule {
+        #[turbo_tasks::function]
+        fn content(&self) -> Vc<AssetContent> {
+            panic!("MockModule::content shouldn't be called")
+        }
+    }
+
+    #[turbo_tasks::value_impl]
+    impl Module for MockModule {
+        #[turbo_tasks::function]
+        fn ident(&self) -> Vc<AssetIdent> {
+            AssetIdent::from_path(self.path.clone())
+        }
+
+        #[turbo_tasks::function]
+        fn source(&self) -> Vc<crate::source::OptionSource> {
+            Vc::cell(None)
+        }
+
+        #[turbo_tasks::function]
+        async fn references(&self) -> Result<Vc<ModuleReferences>> {
+            let repo = self.repo.await?;
+            let references = match repo.repo.get(&self.path) {
+                Some(deps) => {
+                    deps.iter()
+                        .map(|p| {
+                            Vc::upcast::<Box<dyn ModuleReference>>(
+                                SingleChunkableModuleReference::new(
+                                    Vc::upcast(MockModule::new(p.clone(), *self.repo)),
+                                    rcstr!("normal-dep"),
+                                    ExportUsage::all(),
+                                ),
+                            )
+                            .to_resolved()
+                        })
+                        .try_join()
+                        .await?
+                }
+                None => vec![],
+            };
+
+            Ok(Vc::cell(references))
+        }
+
+        #[turbo_tasks::function]
+        fn is_self_async(&self) -> Vc<bool> {
+            Vc::cell(self.path.path == "tla.js")
+        }
+
+        #[turbo_tasks::function]
+        fn side_effects(self: Vc<Self>) -> Vc<ModuleSideEffects> {
+            ModuleSideEffects::SideEffectful.cell()
+        }
+    }
+}
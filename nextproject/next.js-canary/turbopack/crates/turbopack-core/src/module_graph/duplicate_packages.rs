@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, BTreeSet, hash_map::Entry};
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{
+    NonLocalValue, ResolvedVc, TryJoinIterExt, Vc, debug::ValueDebugFormat, trace::TraceRawVcs,
+};
+use turbo_tasks_fs::FileSystemPath;
+
+use crate::{
+    asset::Asset,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::Module,
+    module_graph::{
+        GraphTraversalAction, ModuleGraph, chunk_group_info::ChunkGroup,
+        license_report::third_party_package,
+    },
+    resolve::lockfile::{LockfileKind, find_locked_versions},
+};
+
+/// Reports packages from `node_modules` of which more than one copy contributes modules to the
+/// module graph, see [compute_duplicate_packages_report]. Each of these copies is bundled, even
+/// when a single one would satisfy all dependents. The lockfile of `project_dir` is used to suggest
+/// the dedupe command of the package manager.
+#[turbo_tasks::function]
+pub async fn report_duplicate_package_versions(
+    graph: Vc<ModuleGraph>,
+    project_dir: FileSystemPath,
+) -> Result<Vc<()>> {
+    let report = compute_duplicate_packages_report(graph).await?;
+    if report.is_empty() {
+        return Ok(Vc::cell(()));
+    }
+    let locked_versions = match *find_locked_versions(project_dir.clone()).await? {
+        Some(locked_versions) => Some(locked_versions.await?),
+        None => None,
+    };
+    let file_path = match &locked_versions {
+        Some(locked_versions) => locked_versions.lockfile.clone(),
+        None => project_dir.join("package.json")?,
+    };
+
+    for package in report.iter() {
+        DuplicatePackageVersionsIssue {
+            file_path: file_path.clone(),
+            kind: locked_versions
+                .as_ref()
+                .map(|locked_versions| locked_versions.kind),
+            package: package.clone(),
+        }
+        .resolved_cell()
+        .emit();
+    }
+
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::value(shared)]
+struct DuplicatePackageVersionsIssue {
+    /// The lockfile, or the `package.json` of the project when there is none.
+    file_path: FileSystemPath,
+    kind: Option<LockfileKind>,
+    package: DuplicatePackage,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for DuplicatePackageVersionsIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Warning
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Analysis.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text(format!("{} copies of ", self.package.copies.len()).into()),
+            StyledString::Code(self.package.name.clone()),
+            StyledString::Text(rcstr!(" are bundled")),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut lines = self
+            .package
+            .copies
+            .iter()
+            .map(|copy| {
+                StyledString::Text(
+                    format!(
+                        "{}: {}",
+                        copy.version.as_deref().unwrap_or("unknown version"),
+                        copy.directory
+                    )
+                    .into(),
+                )
+            })
+            .collect::<Vec<_>>();
+        if !self.package.duplicated_in.is_empty() {
+            lines.push(StyledString::Text(
+                format!(
+                    "More than one copy is loaded by: {}",
+                    self.package.duplicated_in.join(", ")
+                )
+                .into(),
+            ));
+        }
+        let dedupe = match self.kind {
+            Some(kind) => StyledString::Code(kind.dedupe_command().into()),
+            None => StyledString::Text(rcstr!("the dedupe command of your package manager")),
+        };
+        lines.push(StyledString::Line(vec![
+            StyledString::Text(rcstr!(
+                "If the dependents of these copies accept a common version, run "
+            )),
+            dedupe,
+            StyledString::Text(rcstr!(" to install only one of them.")),
+        ]));
+        Vc::cell(Some(StyledString::Stack(lines).resolved_cell()))
+    }
+}
+
+/// A package of which more than one copy contributes modules to the module graph.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    NonLocalValue,
+    TraceRawVcs,
+    ValueDebugFormat,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePackage {
+    pub name: RcStr,
+    /// The copies of the package, sorted by version and directory.
+    pub copies: Vec<PackageCopy>,
+    /// The chunk groups which contain modules of more than one copy, i.e. which load the package
+    /// multiple times.
+    pub duplicated_in: Vec<RcStr>,
+}
+
+/// A package directory in `node_modules` which contributes modules to the module graph.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    NonLocalValue,
+    TraceRawVcs,
+    ValueDebugFormat,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCopy {
+    pub version: Option<RcStr>,
+    /// The directory containing the `package.json`.
+    pub directory: RcStr,
+    /// The number of source files of this copy in the module graph.
+    pub modules: usize,
+    /// The total size of these source files in bytes.
+    pub size: u64,
+    /// The chunk groups containing modules of this copy.
+    pub chunk_groups: Vec<RcStr>,
+    /// The shortest chain of imports from an entry to a module of this copy, starting with the
+    /// entry.
+    pub import_trace: Vec<RcStr>,
+}
+
+/// The packages from `node_modules` which are included more than once in a module graph, sorted
+/// by name.
+#[turbo_tasks::value(transparent)]
+pub struct DuplicatePackagesReport(Vec<DuplicatePackage>);
+
+/// Groups the modules of the module graph which are located in a `node_modules` directory by the
+/// package they belong to, and lists every package with more than one copy (by directory, so
+/// multiple installations of the same version are included as well).
+#[turbo_tasks::function]
+pub async fn compute_duplicate_packages_report(
+    graph: Vc<ModuleGraph>,
+) -> Result<Vc<DuplicatePackagesReport>> {
+    let chunk_group_info = graph.chunk_group_info().await?;
+    let module_chunk_groups = chunk_group_info.module_chunk_groups.await?;
+    let graph_ref = graph.await?;
+
+    let modules = graph_ref
+        .iter_nodes()
+        .map(async |module| {
+            let path = module.ident().path().owned().await?;
+            if !path
+                .path
+                .split('/')
+                .any(|segment| segment == "node_modules")
+            {
+                return Ok(None);
+            }
+            let Some(package) = third_party_package(path.parent()).owned().await? else {
+                return Ok(None);
+            };
+            Ok(Some((module, path.path, package)))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut copies_by_name = BTreeMap::<RcStr, BTreeMap<RcStr, CopyModules>>::new();
+    for (module, path, package) in modules {
+        let copy = copies_by_name
+            .entry(package.name)
+            .or_default()
+            .entry(package.directory)
+            .or_insert_with(|| CopyModules {
+                version: package.version,
+                ..Default::default()
+            });
+        copy.modules.push(module);
+        // The same file can be included as multiple modules, e.g. in different layers
+        copy.paths.insert(path, module);
+    }
+    copies_by_name.retain(|_, copies| copies.len() > 1);
+    if copies_by_name.is_empty() {
+        return Ok(Vc::cell(Vec::new()));
+    }
+
+    // The first edge which reaches a module in breadth-first order is on a shortest path from an
+    // entry.
+    let mut parents = FxHashMap::<ResolvedVc<Box<dyn Module>>, Option<_>>::default();
+    graph_ref.traverse_edges_bfs(
+        graph_ref
+            .entries()
+            .flat_map(|entry| entry.entries().collect::<Vec<_>>()),
+        |parent, target| {
+            Ok(match parents.entry(target) {
+                Entry::Occupied(_) => GraphTraversalAction::Skip,
+                Entry::Vacant(entry) => {
+                    entry.insert(parent.map(|(parent, _)| parent));
+                    GraphTraversalAction::Continue
+                }
+            })
+        },
+    )?;
+    let trace_to = |module| {
+        let mut trace = vec![module];
+        while let Some(Some(parent)) = parents.get(trace.last().unwrap()) {
+            trace.push(*parent);
+        }
+        trace.reverse();
+        trace
+    };
+
+    let chunk_group_names = chunk_group_info
+        .chunk_groups
+        .iter()
+        .map(chunk_group_name)
+        .try_join()
+        .await?;
+
+    let mut report = Vec::new();
+    for (name, copies) in copies_by_name {
+        let mut copies_per_chunk_group = BTreeMap::<u32, usize>::new();
+        let mut package_copies = Vec::new();
+        for (directory, copy) in copies {
+            let chunk_groups = copy
+                .modules
+                .iter()
+                .filter_map(|module| module_chunk_groups.get(module))
+                .fold(BTreeSet::new(), |mut chunk_groups, bitmap| {
+                    chunk_groups.extend(bitmap.iter());
+                    chunk_groups
+                });
+            for idx in &chunk_groups {
+                *copies_per_chunk_group.entry(*idx).or_default() += 1;
+            }
+
+            let size = copy
+                .paths
+                .values()
+                .map(async |module| {
+                    Ok(match *module.source().await? {
+                        Some(source) => (*source.content().len().await?).unwrap_or_default(),
+                        None => 0,
+                    })
+                })
+                .try_join()
+                .await?
+                .into_iter()
+                .sum();
+
+            let import_trace = copy
+                .modules
+                .iter()
+                .map(|module| trace_to(*module))
+                .min_by_key(|trace| trace.len())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|module| module.ident_string().owned())
+                .try_join()
+                .await?;
+
+            package_copies.push(PackageCopy {
+                version: copy.version,
+                directory,
+                modules: copy.paths.len(),
+                size,
+                chunk_groups: chunk_groups
+                    .into_iter()
+                    .map(|idx| chunk_group_names[idx as usize].clone())
+                    .collect(),
+                import_trace,
+            });
+        }
+        package_copies.sort_by(|a, b| (&a.version, &a.directory).cmp(&(&b.version, &b.directory)));
+
+        report.push(DuplicatePackage {
+            name,
+            copies: package_copies,
+            duplicated_in: copies_per_chunk_group
+                .into_iter()
+                .filter(|(_, copies)| *copies > 1)
+                .map(|(idx, _)| chunk_group_names[idx as usize].clone())
+                .collect(),
+        });
+    }
+
+    Ok(Vc::cell(report))
+}
+
+/// The modules of a single package directory.
+#[derive(Default)]
+struct CopyModules {
+    version: Option<RcStr>,
+    modules: Vec<ResolvedVc<Box<dyn Module>>>,
+    /// One module per file path.
+    paths: BTreeMap<RcStr, ResolvedVc<Box<dyn Module>>>,
+}
+
+/// A human-readable name for a chunk group, made of its kind and its entry modules.
+async fn chunk_group_name(chunk_group: &ChunkGroup) -> Result<RcStr> {
+    let kind = match chunk_group {
+        ChunkGroup::Entry(_) => "entry",
+        ChunkGroup::Async(_) => "async",
+        ChunkGroup::Isolated(_) | ChunkGroup::IsolatedMerged { .. } => "isolated",
+        ChunkGroup::Shared(_) | ChunkGroup::SharedMerged { .. } => "shared",
+    };
+    let entries = chunk_group
+        .entries()
+        .map(|module| module.ident_string().owned())
+        .try_join()
+        .await?;
+    Ok(format!("{kind} {}", entries.join(", ")).into())
+}
@@ -0,0 +1,325 @@
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{
+    FxIndexSet, NonLocalValue, ResolvedVc, TryJoinIterExt, ValueToString, Vc,
+    debug::ValueDebugFormat, trace::TraceRawVcs,
+};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    file_source::FileSource,
+    module_graph::ModuleGraph,
+    output::{OutputAsset, OutputAssetsReference},
+    package_json::read_package_json,
+    resolve::{FindContextFileResult, find_context_file, package_json},
+};
+
+/// A package from `node_modules` which contributes at least one module to the module graph.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    NonLocalValue,
+    TraceRawVcs,
+    ValueDebugFormat,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ThirdPartyPackage {
+    pub name: RcStr,
+    pub version: Option<RcStr>,
+    /// The license as declared in the `license` field of the `package.json`, or the legacy
+    /// `licenses` array joined with `OR`. `None` when the package doesn't declare a license.
+    pub license: Option<RcStr>,
+    /// The directory containing the `package.json`.
+    pub directory: RcStr,
+}
+
+/// An inventory of the third-party packages used by a module graph, sorted by name and version.
+#[turbo_tasks::value(transparent)]
+pub struct LicenseReport(Vec<ThirdPartyPackage>);
+
+#[turbo_tasks::value(transparent)]
+pub(crate) struct OptionThirdPartyPackage(Option<ThirdPartyPackage>);
+
+/// Computes the license report for all modules in the module graph which are located in a
+/// `node_modules` directory.
+#[turbo_tasks::function]
+pub async fn compute_license_report(graph: Vc<ModuleGraph>) -> Result<Vc<LicenseReport>> {
+    let graph = graph.await?;
+    let package_dirs = graph
+        .iter_nodes()
+        .map(async |module| {
+            let path = module.ident().path().owned().await?;
+            Ok(path
+                .path
+                .split('/')
+                .any(|segment| segment == "node_modules")
+                .then(|| path.parent()))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect::<FxIndexSet<_>>();
+
+    let mut seen = FxHashSet::default();
+    let mut packages = package_dirs
+        .into_iter()
+        .map(|dir| third_party_package(dir).owned())
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .filter(|package| seen.insert((package.name.clone(), package.version.clone())))
+        .collect::<Vec<_>>();
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    Ok(Vc::cell(packages))
+}
+
+/// Finds the package which contains the given directory. Nested `package.json` files without a
+/// `name` (e.g. `{"type": "module"}` in an `esm` folder) are skipped.
+#[turbo_tasks::function]
+pub(crate) async fn third_party_package(
+    lookup_path: FileSystemPath,
+) -> Result<Vc<OptionThirdPartyPackage>> {
+    let FindContextFileResult::Found(package_json_path, _) =
+        &*find_context_file(lookup_path, package_json(), false).await?
+    else {
+        return Ok(Vc::cell(None));
+    };
+    let package_dir = package_json_path.parent();
+    let read = read_package_json(Vc::upcast(FileSource::new(package_json_path.clone()))).await?;
+    if let Some(package_json) = &*read
+        && let Some(name) = package_json["name"].as_str()
+    {
+        return Ok(Vc::cell(Some(ThirdPartyPackage {
+            name: name.into(),
+            version: package_json["version"].as_str().map(RcStr::from),
+            license: license_from_package_json(package_json),
+            directory: package_dir.path.clone(),
+        })));
+    }
+
+    if package_dir.is_root() {
+        Ok(Vc::cell(None))
+    } else {
+        Ok(third_party_package(package_dir.parent()))
+    }
+}
+
+fn license_from_package_json(package_json: &JsonValue) -> Option<RcStr> {
+    fn license_name(license: &JsonValue) -> Option<&str> {
+        match license {
+            JsonValue::String(license) => Some(license),
+            // Deprecated form: `{ "type": "MIT", "url": "..." }`
+            JsonValue::Object(license) => license.get("type").and_then(JsonValue::as_str),
+            _ => None,
+        }
+    }
+
+    if let Some(license) = package_json.get("license").and_then(license_name) {
+        return Some(license.into());
+    }
+    // Deprecated form: `"licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }]`
+    let licenses = package_json
+        .get("licenses")?
+        .as_array()?
+        .iter()
+        .filter_map(license_name)
+        .collect::<Vec<_>>();
+    (!licenses.is_empty()).then(|| licenses.join(" OR ").into())
+}
+
+/// Writes a [`LicenseReport`] as JSON.
+#[turbo_tasks::value]
+pub struct LicenseReportAsset {
+    path: FileSystemPath,
+    report: ResolvedVc<LicenseReport>,
+}
+
+#[turbo_tasks::value_impl]
+impl LicenseReportAsset {
+    #[turbo_tasks::function]
+    pub fn new(path: FileSystemPath, report: ResolvedVc<LicenseReport>) -> Vc<Self> {
+        LicenseReportAsset { path, report }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for LicenseReportAsset {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(rcstr!("license report"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for LicenseReportAsset {}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for LicenseReportAsset {
+    #[turbo_tasks::function]
+    fn path(&self) -> Vc<FileSystemPath> {
+        self.path.clone().cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for LicenseReportAsset {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let report = self.report.await?;
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(serde_json::to_string_pretty(&*report)?)).cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use serde_json::json;
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks::{ResolvedVc, Vc};
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+
+    use super::{ThirdPartyPackage, compute_license_report, license_from_package_json};
+    use crate::{
+        module::Module,
+        module_graph::{
+            ModuleGraph, SingleModuleGraph,
+            chunk_group_info::ChunkGroupEntry,
+            tests::{MockModule, TestRepo},
+        },
+    };
+
+    #[test]
+    fn test_license_from_package_json() {
+        assert_eq!(
+            license_from_package_json(&json!({ "license": "MIT" })),
+            Some(rcstr!("MIT"))
+        );
+        assert_eq!(
+            license_from_package_json(&json!({ "license": "(MIT OR Apache-2.0)" })),
+            Some(rcstr!("(MIT OR Apache-2.0)"))
+        );
+        assert_eq!(
+            license_from_package_json(
+                &json!({ "license": { "type": "ISC", "url": "https://example.com" } })
+            ),
+            Some(rcstr!("ISC"))
+        );
+        assert_eq!(
+            license_from_package_json(
+                &json!({ "licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }] })
+            ),
+            Some(rcstr!("MIT OR Apache-2.0"))
+        );
+        assert_eq!(license_from_package_json(&json!({ "licenses": [] })), None);
+        assert_eq!(license_from_package_json(&json!({ "name": "a" })), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_compute_license_report() {
+        let scratch = tempfile::tempdir().unwrap();
+        let node_modules = scratch.path().join("node_modules");
+        create_dir_all(node_modules.join("b/esm")).unwrap();
+        create_dir_all(node_modules.join("a")).unwrap();
+        write(
+            node_modules.join("b/package.json"),
+            r#"{ "name": "b", "version": "2.0.0", "license": "MIT" }"#,
+        )
+        .unwrap();
+        // A nested package.json without a name belongs to the package around it.
+        write(
+            node_modules.join("b/esm/package.json"),
+            r#"{ "type": "module" }"#,
+        )
+        .unwrap();
+        write(
+            node_modules.join("a/package.json"),
+            r#"{ "name": "a", "version": "1.0.0" }"#,
+        )
+        .unwrap();
+        let path: RcStr = scratch.path().to_str().unwrap().into();
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+
+        tt.run_once(async move {
+            let fs = Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new(rcstr!("temp"), path));
+            let root = fs.root().owned().await?;
+
+            let repo = TestRepo {
+                repo: [
+                    (
+                        "index.js",
+                        vec!["node_modules/b/index.js", "node_modules/a/index.js"],
+                    ),
+                    (
+                        "node_modules/b/index.js",
+                        vec!["node_modules/b/esm/index.js"],
+                    ),
+                ]
+                .into_iter()
+                .map(|(module, deps)| {
+                    (
+                        root.join(module).unwrap(),
+                        deps.into_iter()
+                            .map(|dep| root.join(dep).unwrap())
+                            .collect(),
+                    )
+                })
+                .collect(),
+            }
+            .cell();
+            let entry =
+                Vc::upcast::<Box<dyn Module>>(MockModule::new(root.join("index.js")?, repo))
+                    .to_resolved()
+                    .await?;
+            let graph = ModuleGraph::from_single_graph(SingleModuleGraph::new_with_entries(
+                ResolvedVc::cell(vec![ChunkGroupEntry::Entry(vec![entry])]),
+                false,
+                false,
+            ))
+            .connect();
+
+            // First-party modules are left out, packages are listed once and sorted by name.
+            assert_eq!(
+                *compute_license_report(graph).await?,
+                vec![
+                    ThirdPartyPackage {
+                        name: rcstr!("a"),
+                        version: Some(rcstr!("1.0.0")),
+                        license: None,
+                        directory: rcstr!("node_modules/a"),
+                    },
+                    ThirdPartyPackage {
+                        name: rcstr!("b"),
+                        version: Some(rcstr!("2.0.0")),
+                        license: Some(rcstr!("MIT")),
+                        directory: rcstr!("node_modules/b"),
+                    },
+                ]
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -45,11 +45,14 @@ use crate::{
 pub mod async_module_info;
 pub mod binding_usage_info;
 pub mod chunk_group_info;
+pub mod circular_dependencies;
+pub mod duplicate_packages;
+pub mod license_report;
 pub mod merged_modules;
 pub mod module_batch;
 pub(crate) mod module_batches;
 mod side_effect_module_info;
-pub(crate) mod style_groups;
+pub mod style_groups;
 mod traced_di_graph;
 
 pub use self::module_batches::BatchingConfig;
@@ -2169,18 +2172,18 @@ pub mod tests {
     }
 
     #[turbo_tasks::value(shared)]
-    struct TestRepo {
-        repo: FxHashMap<FileSystemPath, Vec<FileSystemPath>>,
+    pub(crate) struct TestRepo {
+        pub(crate) repo: FxHashMap<FileSystemPath, Vec<FileSystemPath>>,
     }
     #[turbo_tasks::value]
-    struct MockModule {
+    pub(crate) struct MockModule {
         path: FileSystemPath,
         repo: ResolvedVc<TestRepo>,
     }
     #[turbo_tasks::value_impl]
     impl MockModule {
         #[turbo_tasks::function]
-        fn new(path: FileSystemPath, repo: ResolvedVc<TestRepo>) -> Vc<Self> {
+        pub(crate) fn new(path: FileSystemPath, repo: ResolvedVc<TestRepo>) -> Vc<Self> {
             Self { path, repo }.cell()
         }
     }
@@ -261,6 +261,7 @@ pub enum WorkerReferenceSubType {
     WebWorker,
     SharedWorker,
     ServiceWorker,
+    Worklet,
     NodeWorker,
     Custom(u8),
     Undefined,
@@ -0,0 +1,464 @@
+//! Explains how a request is resolved, similar to `node --trace-resolve`.
+//!
+//! The resolver records its decisions as [ResolveTrace] collectibles when
+//! [ResolveOptions::explain] is set, so the explanation always matches what [resolve] does.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    CollectiblesSource, NonLocalValue, ResolvedVc, TryJoinIterExt, ValueToString, Vc,
+    trace::TraceRawVcs,
+};
+use turbo_tasks_fs::FileSystemPath;
+
+use crate::{
+    reference_type::ReferenceType,
+    resolve::{
+        ResolveResult,
+        options::{ConditionValue, ImportMapResult, ResolveOptions},
+        origin::ResolveOrigin,
+        parse::Request,
+        resolve,
+    },
+};
+
+/// A step taken while resolving a request, see [`explain_resolve`].
+#[derive(Clone, Debug, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub enum ResolveTraceStep {
+    /// A `before_resolve` plugin whose condition matched the request. When it `handled` the
+    /// request, its result is used and no other resolving takes place.
+    BeforeResolvePlugin { index: usize, handled: bool },
+    /// An alias of the import map matched the request. Aliases which don't resolve are skipped.
+    ImportMapAlias { mapping: RcStr, resolved: bool },
+    /// A modules directory, e.g. `node_modules`, which was searched for the package of a module
+    /// request.
+    ModulesDirectory {
+        directory: FileSystemPath,
+        found: bool,
+    },
+    /// The Yarn Plug'n'Play manifest which was consulted for the package.
+    PnpManifest { manifest: FileSystemPath },
+    /// A package directory or file which matched the module request.
+    PackageFound { name: RcStr, path: FileSystemPath },
+    /// The package can't be used, e.g. because of the dependency rules of Yarn Plug'n'Play.
+    PackageError { message: RcStr },
+    /// The conditions used for the `exports` or `imports` field of a package.json.
+    PackageConditions {
+        package_json: FileSystemPath,
+        conditions: Vec<(RcStr, ConditionValue)>,
+        unspecified_conditions: ConditionValue,
+    },
+    /// An `after_resolve` plugin whose condition matched a resolved file.
+    AfterResolvePlugin {
+        index: usize,
+        path: FileSystemPath,
+        replaced: bool,
+    },
+    /// The conditions of the `exports` or `imports` field which led to a result.
+    MatchedConditions {
+        request: Option<RcStr>,
+        conditions: Vec<(RcStr, bool)>,
+    },
+}
+
+impl ResolveTraceStep {
+    /// Orders the steps like the resolver takes them. The collectibles are unordered, and a
+    /// request can be resolved concurrently in multiple places.
+    fn order(&self) -> (u8, usize) {
+        match self {
+            ResolveTraceStep::BeforeResolvePlugin { index, .. } => (0, *index),
+            ResolveTraceStep::ImportMapAlias { .. } => (1, 0),
+            // Nested modules directories are searched from the lookup path upwards.
+            ResolveTraceStep::ModulesDirectory { directory, .. } => {
+                (2, usize::MAX - directory.path.len())
+            }
+            ResolveTraceStep::PnpManifest { .. } => (2, 0),
+            ResolveTraceStep::PackageFound { .. } | ResolveTraceStep::PackageError { .. } => (3, 0),
+            ResolveTraceStep::PackageConditions { .. } => (4, 0),
+            ResolveTraceStep::AfterResolvePlugin { index, .. } => (5, *index),
+            ResolveTraceStep::MatchedConditions { .. } => (6, 0),
+        }
+    }
+}
+
+/// A [ResolveTraceStep] together with the request it was taken for. Nested requests, e.g. the
+/// target of an alias, are recorded with their own request.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct ResolveTraceRecord {
+    pub request: RcStr,
+    pub step: ResolveTraceStep,
+}
+
+/// A collectible emitted by the resolver for each of its decisions when
+/// [ResolveOptions::explain] is set.
+#[turbo_tasks::value_trait]
+pub trait ResolveTrace {
+    #[turbo_tasks::function]
+    fn record(self: Vc<Self>) -> Vc<ResolveTraceRecord>;
+}
+
+#[turbo_tasks::value_impl]
+impl ResolveTrace for ResolveTraceRecord {
+    #[turbo_tasks::function]
+    fn record(self: Vc<Self>) -> Vc<ResolveTraceRecord> {
+        self
+    }
+}
+
+/// Records a step of the resolver. Callers only do this when [ResolveOptions::explain] is set.
+pub(super) fn emit_resolve_trace_step(request: RcStr, step: ResolveTraceStep) {
+    turbo_tasks::emit(ResolvedVc::upcast::<Box<dyn ResolveTrace>>(
+        ResolveTraceRecord { request, step }.resolved_cell(),
+    ));
+}
+
+/// A structured trace of how a request was resolved, see [`resolve_explain`].
+#[turbo_tasks::value(shared)]
+pub struct ResolveExplanation {
+    pub lookup_path: FileSystemPath,
+    pub request: ResolvedVc<Request>,
+    pub steps: Vec<ResolveTraceRecord>,
+    /// The result of [`resolve`], including the changes of the plugins.
+    pub result: ResolvedVc<ResolveResult>,
+}
+
+/// Explains how `request` is resolved from `origin` with the given `options`.
+#[turbo_tasks::function]
+pub async fn resolve_explain(
+    origin: Vc<Box<dyn ResolveOrigin>>,
+    request: Vc<Request>,
+    options: Vc<ResolveOptions>,
+) -> Result<Vc<ResolveExplanation>> {
+    Ok(explain_resolve(
+        origin.origin_path().await?.parent(),
+        ReferenceType::Undefined,
+        request,
+        options,
+    ))
+}
+
+/// Explains how `request` is resolved from `lookup_path`, by resolving it with
+/// [ResolveOptions::explain] set and collecting the steps recorded by the resolver.
+#[turbo_tasks::function]
+pub async fn explain_resolve(
+    lookup_path: FileSystemPath,
+    reference_type: ReferenceType,
+    request: ResolvedVc<Request>,
+    options: Vc<ResolveOptions>,
+) -> Result<Vc<ResolveExplanation>> {
+    let options = explain_options(options).to_resolved().await?;
+    let result_op = resolve_operation(lookup_path.clone(), reference_type, request, options);
+    let result = result_op.resolve_strongly_consistent().await?;
+    let traces = result_op.peek_collectibles::<Box<dyn ResolveTrace>>();
+    result_op.drop_collectibles::<Box<dyn ResolveTrace>>();
+
+    let mut steps = traces
+        .iter()
+        .map(|trace| trace.record().owned())
+        .try_join()
+        .await?;
+    let request_str = request.to_string().owned().await?;
+    steps.sort_by_cached_key(|record| {
+        (
+            record.step.order(),
+            record.request != request_str,
+            record.request.clone(),
+            format!("{:?}", record.step),
+        )
+    });
+    for (key, _) in result.await?.primary.iter() {
+        if !key.conditions.is_empty() {
+            steps.push(ResolveTraceRecord {
+                request: request_str.clone(),
+                step: ResolveTraceStep::MatchedConditions {
+                    request: key.request.clone(),
+                    conditions: key
+                        .conditions
+                        .iter()
+                        .map(|(condition, value)| (condition.clone(), *value))
+                        .collect(),
+                },
+            });
+        }
+    }
+
+    Ok(ResolveExplanation {
+        lookup_path,
+        request,
+        steps,
+        result,
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+async fn explain_options(options: Vc<ResolveOptions>) -> Result<Vc<ResolveOptions>> {
+    let mut options = options.owned().await?;
+    options.explain = true;
+    Ok(options.cell())
+}
+
+#[turbo_tasks::function(operation)]
+fn resolve_operation(
+    lookup_path: FileSystemPath,
+    reference_type: ReferenceType,
+    request: ResolvedVc<Request>,
+    options: ResolvedVc<ResolveOptions>,
+) -> Vc<ResolveResult> {
+    resolve(lookup_path, reference_type, *request, *options)
+}
+
+pub(super) async fn describe_import_map_result(result: &ImportMapResult) -> Result<String> {
+    Ok(match result {
+        ImportMapResult::Result(result) => result.to_string().owned().await?.to_string(),
+        ImportMapResult::External { name, ty, .. } => format!("external {name} ({ty})"),
+        ImportMapResult::AliasExternal { name, ty, .. } => {
+            format!("external {name} ({ty}), if resolvable")
+        }
+        ImportMapResult::Alias(request, lookup_path) => {
+            let mut description = format!("alias {}", request.to_string().await?);
+            if let Some(lookup_path) = lookup_path {
+                write!(description, " in {}", lookup_path.value_to_string().await?)?;
+            }
+            description
+        }
+        ImportMapResult::Alternatives(alternatives) => {
+            let mut descriptions = Vec::with_capacity(alternatives.len());
+            for alternative in alternatives {
+                descriptions.push(Box::pin(describe_import_map_result(alternative)).await?);
+            }
+            descriptions.join(" | ")
+        }
+        ImportMapResult::NoEntry => "no entry".to_string(),
+    })
+}
+
+fn condition_value_str(value: ConditionValue) -> &'static str {
+    match value {
+        ConditionValue::Set => "set",
+        ConditionValue::Unset => "unset",
+        ConditionValue::Unknown => "unknown",
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ResolveExplanation {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        let request = self.request.to_string().await?;
+        let mut out = format!(
+            "resolving {request} from {}\n",
+            self.lookup_path.value_to_string().await?
+        );
+        for ResolveTraceRecord {
+            request: step_request,
+            step,
+        } in &self.steps
+        {
+            match step {
+                ResolveTraceStep::BeforeResolvePlugin { index, handled } => {
+                    write!(
+                        out,
+                        "  before_resolve plugin #{index}: {}",
+                        if *handled { "handled" } else { "skipped" }
+                    )?;
+                }
+                ResolveTraceStep::ImportMapAlias { mapping, resolved } => {
+                    write!(
+                        out,
+                        "  import map: {mapping}{}",
+                        if *resolved { "" } else { " (unresolvable)" }
+                    )?;
+                }
+                ResolveTraceStep::ModulesDirectory { directory, found } => {
+                    write!(
+                        out,
+                        "  looking in {}: {}",
+                        directory.value_to_string().await?,
+                        if *found { "found" } else { "not found" }
+                    )?;
+                }
+                ResolveTraceStep::PnpManifest { manifest } => {
+                    write!(
+                        out,
+                        "  looking up in Plug'n'Play manifest {}",
+                        manifest.value_to_string().await?
+                    )?;
+                }
+                ResolveTraceStep::PackageFound { name, path } => {
+                    write!(out, "  package {name}: {}", path.value_to_string().await?)?;
+                }
+                ResolveTraceStep::PackageError { message } => {
+                    write!(out, "  package error: {message}")?;
+                }
+                ResolveTraceStep::PackageConditions {
+                    package_json,
+                    conditions,
+                    unspecified_conditions,
+                } => {
+                    let conditions = conditions
+                        .iter()
+                        .map(|(condition, value)| {
+                            format!("{condition}={}", condition_value_str(*value))
+                        })
+                        .collect::<Vec<_>>();
+                    write!(
+                        out,
+                        "  conditions of {}: {} (others {})",
+                        package_json.value_to_string().await?,
+                        conditions.join(", "),
+                        condition_value_str(*unspecified_conditions)
+                    )?;
+                }
+                ResolveTraceStep::AfterResolvePlugin {
+                    index,
+                    path,
+                    replaced,
+                } => {
+                    write!(
+                        out,
+                        "  after_resolve plugin #{index} on {}: {}",
+                        path.value_to_string().await?,
+                        if *replaced { "replaced" } else { "kept" }
+                    )?;
+                }
+                ResolveTraceStep::MatchedConditions {
+                    request,
+                    conditions,
+                } => {
+                    let conditions = conditions
+                        .iter()
+                        .map(|(condition, value)| {
+                            if *value {
+                                condition.to_string()
+                            } else {
+                                format!("!{condition}")
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    write!(
+                        out,
+                        "  matched conditions for {}: {}",
+                        request.as_deref().unwrap_or("<request>"),
+                        conditions.join(", ")
+                    )?;
+                }
+            }
+            if *step_request != *request {
+                write!(out, " (for {step_request})")?;
+            }
+            out.push('\n');
+        }
+        write!(out, "result: {}", self.result.to_string().await?)?;
+        Ok(Vc::cell(out.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks::Vc;
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+
+    use super::{ResolveTraceStep, explain_resolve};
+    use crate::{
+        reference_type::{CommonJsReferenceSubType, ReferenceType},
+        resolve::{node::node_cjs_resolve_options, parse::Request},
+        source::Source,
+    };
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_explain_resolve() {
+        let scratch = tempfile::tempdir().unwrap();
+        let project = scratch.path();
+        create_dir_all(project.join("app/src")).unwrap();
+        create_dir_all(project.join("app/node_modules")).unwrap();
+        create_dir_all(project.join("node_modules/pkg")).unwrap();
+        write(
+            project.join("node_modules/pkg/package.json"),
+            r#"{ "name": "pkg", "exports": { "custom": "./custom.js", "default": "./index.js" } }"#,
+        )
+        .unwrap();
+        write(project.join("node_modules/pkg/custom.js"), "").unwrap();
+        write(project.join("node_modules/pkg/index.js"), "").unwrap();
+        let path: RcStr = project.to_str().unwrap().into();
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+
+        tt.run_once(async move {
+            let fs = Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new(rcstr!("temp"), path));
+            let root = fs.root().owned().await?;
+
+            let explanation = explain_resolve(
+                root.join("app/src")?,
+                ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined),
+                Request::parse_string(rcstr!("pkg")),
+                node_cjs_resolve_options(root.clone()),
+            )
+            .await?;
+
+            // The existing modules directories are searched from the lookup path upwards, and
+            // each of them is listed once.
+            let directories = explanation
+                .steps
+                .iter()
+                .filter_map(|record| match &record.step {
+                    ResolveTraceStep::ModulesDirectory { directory, found } => {
+                        Some((directory.path.clone(), *found))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                directories,
+                [
+                    (rcstr!("app/node_modules"), false),
+                    (rcstr!("node_modules"), true)
+                ]
+            );
+            let packages = explanation
+                .steps
+                .iter()
+                .filter_map(|record| match &record.step {
+                    ResolveTraceStep::PackageFound { name, path } => {
+                        Some((name.clone(), path.path.clone()))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(packages, [(rcstr!("pkg"), rcstr!("node_modules/pkg"))]);
+            assert_eq!(
+                explanation
+                    .steps
+                    .iter()
+                    .filter(|record| matches!(
+                        record.step,
+                        ResolveTraceStep::PackageConditions { .. }
+                    ))
+                    .count(),
+                1
+            );
+
+            // The explanation describes the result of the real resolver.
+            let source = (*explanation.result.first_source().await?).unwrap();
+            assert_eq!(
+                source.ident().path().await?.path,
+                rcstr!("node_modules/pkg/index.js")
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,674 @@
+//! Reading the versions locked by `pnpm-lock.yaml`, `package-lock.json` or `yarn.lock`, to detect
+//! when the installed `node_modules` have drifted from the lockfile.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{NonLocalValue, ResolvedVc, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{FileContent, FileSystemEntryType, FileSystemPath};
+
+use crate::{
+    file_source::FileSource,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    package_json::read_package_json,
+};
+
+/// The package manager which wrote a lockfile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub enum LockfileKind {
+    Npm,
+    Pnpm,
+    YarnClassic,
+    YarnBerry,
+}
+
+impl LockfileKind {
+    /// The command which installs exactly the locked versions.
+    pub fn install_command(&self) -> &'static str {
+        match self {
+            LockfileKind::Npm => "npm ci",
+            LockfileKind::Pnpm => "pnpm install --frozen-lockfile",
+            LockfileKind::YarnClassic => "yarn install --frozen-lockfile",
+            LockfileKind::YarnBerry => "yarn install --immutable",
+        }
+    }
+
+    /// The command which removes duplicate versions from the lockfile, where possible.
+    pub fn dedupe_command(&self) -> &'static str {
+        match self {
+            LockfileKind::Npm => "npm dedupe",
+            LockfileKind::Pnpm => "pnpm dedupe",
+            LockfileKind::YarnClassic => "npx yarn-deduplicate",
+            LockfileKind::YarnBerry => "yarn dedupe",
+        }
+    }
+}
+
+/// A package version recorded in a lockfile.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub struct LockedPackage {
+    pub version: RcStr,
+    /// The integrity of the package tarball, e.g. `sha512-...`. For Yarn Berry, this is the
+    /// checksum of the package in the Yarn cache.
+    pub integrity: Option<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionLockedPackage(Option<LockedPackage>);
+
+/// The package versions locked by the lockfile of a project.
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct LockedVersions {
+    /// The lockfile the versions have been read from.
+    pub lockfile: FileSystemPath,
+    pub kind: LockfileKind,
+    /// The locked packages by their install path relative to the directory of the lockfile, e.g.
+    /// `node_modules/foo/node_modules/bar`. Only lockfiles which record the layout of
+    /// `node_modules` (i.e. `package-lock.json`) provide these.
+    packages_by_path: FxHashMap<RcStr, LockedPackage>,
+    /// All locked versions of each package, by the name of the package.
+    packages_by_name: FxHashMap<RcStr, Vec<LockedPackage>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionLockedVersions(Option<ResolvedVc<LockedVersions>>);
+
+/// The lockfiles which are looked up, in order.
+const LOCKFILES: [(&str, LockfileKind); 3] = [
+    ("pnpm-lock.yaml", LockfileKind::Pnpm),
+    ("package-lock.json", LockfileKind::Npm),
+    ("yarn.lock", LockfileKind::YarnClassic),
+];
+
+/// Reads the lockfile in `project_dir` or in the closest ancestor directory which has one, e.g. at
+/// the root of a monorepo. Returns `None` when there is no lockfile or it can't be parsed.
+#[turbo_tasks::function]
+pub async fn find_locked_versions(project_dir: FileSystemPath) -> Result<Vc<OptionLockedVersions>> {
+    for (name, kind) in LOCKFILES {
+        let lockfile = project_dir.join(name)?;
+        if *lockfile.get_type().await? == FileSystemEntryType::File {
+            return Ok(locked_versions(lockfile, kind));
+        }
+    }
+    if project_dir.is_root() {
+        return Ok(Vc::cell(None));
+    }
+    Ok(find_locked_versions(project_dir.parent()))
+}
+
+/// Parses a lockfile. Lockfiles which can't be parsed are reported as an issue and ignored, so they
+/// don't break resolving.
+#[turbo_tasks::function]
+async fn locked_versions(
+    lockfile: FileSystemPath,
+    kind: LockfileKind,
+) -> Result<Vc<OptionLockedVersions>> {
+    let FileContent::Content(file) = &*lockfile.read().await? else {
+        return Ok(Vc::cell(None));
+    };
+    let content = file.content().to_str()?;
+    let mut versions = LockedVersions::empty(lockfile.clone(), kind);
+    let result = match kind {
+        LockfileKind::Npm => versions.add_npm_lockfile(&content),
+        LockfileKind::Pnpm => versions.add_pnpm_lockfile(&content),
+        LockfileKind::YarnClassic | LockfileKind::YarnBerry => {
+            versions.add_yarn_lockfile(&content);
+            Ok(())
+        }
+    };
+    if let Err(err) = result {
+        LockfileParseIssue {
+            lockfile,
+            error_message: format!("{err:#}").into(),
+        }
+        .resolved_cell()
+        .emit();
+        return Ok(Vc::cell(None));
+    }
+    Ok(Vc::cell(Some(versions.resolved_cell())))
+}
+
+#[turbo_tasks::value(shared)]
+struct LockfileParseIssue {
+    lockfile: FileSystemPath,
+    error_message: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for LockfileParseIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Warning
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Parse.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.lockfile.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text(rcstr!("Unable to parse the lockfile ")),
+            StyledString::Code(self.lockfile.file_name().into()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Stack(vec![
+                StyledString::Text(self.error_message.clone()),
+                StyledString::Text(rcstr!(
+                    "The installed versions of packages aren't checked against the lockfile."
+                )),
+            ])
+            .resolved_cell(),
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmLockfile {
+    /// The packages by install path, in lockfile version 2 and 3.
+    #[serde(default)]
+    packages: BTreeMap<RcStr, NpmLockfilePackage>,
+    /// The nested dependencies, in lockfile version 1.
+    #[serde(default)]
+    dependencies: BTreeMap<RcStr, NpmLockfileDependency>,
+}
+
+#[derive(Deserialize)]
+struct NpmLockfilePackage {
+    version: Option<RcStr>,
+    integrity: Option<RcStr>,
+    /// Links to workspace packages, which aren't locked.
+    #[serde(default)]
+    link: bool,
+}
+
+#[derive(Deserialize)]
+struct NpmLockfileDependency {
+    version: RcStr,
+    integrity: Option<RcStr>,
+    #[serde(default)]
+    dependencies: BTreeMap<RcStr, NpmLockfileDependency>,
+}
+
+impl LockedVersions {
+    fn empty(lockfile: FileSystemPath, kind: LockfileKind) -> Self {
+        LockedVersions {
+            lockfile,
+            kind,
+            packages_by_path: Default::default(),
+            packages_by_name: Default::default(),
+        }
+    }
+
+    fn add(&mut self, name: &str, package: LockedPackage) {
+        let versions = self.packages_by_name.entry(name.into()).or_default();
+        if !versions.contains(&package) {
+            versions.push(package);
+        }
+    }
+
+    fn add_npm_lockfile(&mut self, content: &str) -> Result<()> {
+        fn add_dependencies(
+            locked_versions: &mut LockedVersions,
+            path: &str,
+            dependencies: BTreeMap<RcStr, NpmLockfileDependency>,
+        ) {
+            for (name, dependency) in dependencies {
+                let path = format!("{path}node_modules/{name}");
+                let package = LockedPackage {
+                    version: dependency.version,
+                    integrity: dependency.integrity,
+                };
+                locked_versions.add(&name, package.clone());
+                locked_versions
+                    .packages_by_path
+                    .insert(path.as_str().into(), package);
+                add_dependencies(
+                    locked_versions,
+                    &format!("{path}/"),
+                    dependency.dependencies,
+                );
+            }
+        }
+
+        let lockfile: NpmLockfile = serde_json::from_str(content)?;
+        if lockfile.packages.is_empty() {
+            add_dependencies(self, "", lockfile.dependencies);
+            return Ok(());
+        }
+        for (path, package) in lockfile.packages {
+            // The root package has an empty path
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            let Some(version) = package.version.filter(|_| !package.link) else {
+                continue;
+            };
+            let package = LockedPackage {
+                version,
+                integrity: package.integrity,
+            };
+            self.add(name, package.clone());
+            self.packages_by_path.insert(path.clone(), package);
+        }
+        Ok(())
+    }
+
+    fn add_pnpm_lockfile(&mut self, content: &str) -> Result<()> {
+        let lockfile: serde_yaml_ng::Value = serde_yaml_ng::from_str(content)?;
+        let lockfile_version = match &lockfile["lockfileVersion"] {
+            serde_yaml_ng::Value::Number(version) => version.as_f64(),
+            serde_yaml_ng::Value::String(version) => version.parse().ok(),
+            _ => None,
+        }
+        .unwrap_or_default();
+        let Some(packages) = lockfile["packages"].as_mapping() else {
+            return Ok(());
+        };
+        for (key, package) in packages {
+            let Some((name, version)) = key
+                .as_str()
+                .and_then(|key| parse_pnpm_package_key(key, lockfile_version < 6.0))
+            else {
+                continue;
+            };
+            // Packages which aren't from a registry have their name and version listed
+            // explicitly.
+            let name = package["name"].as_str().unwrap_or(name);
+            let version = package["version"].as_str().unwrap_or(version);
+            self.add(
+                name,
+                LockedPackage {
+                    version: version.into(),
+                    integrity: package["resolution"]["integrity"].as_str().map(RcStr::from),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn add_yarn_lockfile(&mut self, content: &str) {
+        if content.lines().any(|line| line == "__metadata:") {
+            self.kind = LockfileKind::YarnBerry;
+        }
+
+        // The name, version and integrity of the current entry
+        let mut entry: Option<(&str, Option<&str>, Option<&str>)> = None;
+        for line in content.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if !line.starts_with(' ') {
+                self.add_yarn_entry(entry.take());
+                entry = line
+                    .trim_end()
+                    .strip_suffix(':')
+                    .and_then(parse_yarn_entry_header)
+                    .map(|name| (name, None, None));
+                continue;
+            }
+            // Only the fields of the entry itself, not nested ones like `dependencies`.
+            let Some(field) = line.strip_prefix("  ").filter(|f| !f.starts_with(' ')) else {
+                continue;
+            };
+            let Some((key, value)) = field.split_once(' ') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+            if let Some((_, version, integrity)) = &mut entry {
+                match key.trim_end_matches(':') {
+                    "version" => *version = Some(value),
+                    "integrity" | "checksum" => *integrity = Some(value),
+                    _ => {}
+                }
+            }
+        }
+        self.add_yarn_entry(entry);
+    }
+
+    fn add_yarn_entry(&mut self, entry: Option<(&str, Option<&str>, Option<&str>)>) {
+        if let Some((name, Some(version), integrity)) = entry {
+            self.add(
+                name,
+                LockedPackage {
+                    version: version.into(),
+                    integrity: integrity.map(RcStr::from),
+                },
+            );
+        }
+    }
+
+    /// Returns the locked versions of the package `name` installed at `path` relative to the
+    /// directory of the lockfile. For lockfiles recording the layout of `node_modules`, this is the
+    /// single version locked for that path, otherwise all locked versions of the package.
+    pub fn locked_packages(&self, path: &str, name: &str) -> &[LockedPackage] {
+        if !self.packages_by_path.is_empty() {
+            return self
+                .packages_by_path
+                .get(path)
+                .map(std::slice::from_ref)
+                .unwrap_or_default();
+        }
+        self.packages_by_name
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Parses the name and version from the key of a package in `pnpm-lock.yaml`, e.g.
+/// `/@scope/name@1.0.0(react@18.2.0)` or `name@1.0.0`. Before lockfile version 6, the keys have the
+/// form of `/@scope/name/1.0.0_react@18.2.0`.
+fn parse_pnpm_package_key(key: &str, legacy: bool) -> Option<(&str, &str)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    if legacy {
+        let scoped = key.starts_with('@');
+        let mut segments = key.splitn(if scoped { 3 } else { 2 }, '/');
+        let name_len = segments
+            .by_ref()
+            .take(if scoped { 2 } else { 1 })
+            .map(|segment| segment.len() + 1)
+            .sum::<usize>()
+            - 1;
+        let version = segments.next()?;
+        let version = version
+            .split_once('_')
+            .map_or(version, |(version, _)| version);
+        return Some((&key[..name_len], version));
+    }
+    let key = key.split_once('(').map_or(key, |(key, _)| key);
+    let index = key.get(1..)?.find('@')? + 1;
+    Some((&key[..index], &key[index + 1..]))
+}
+
+/// Parses the package name from the header of a `yarn.lock` entry, e.g. `"foo@^1.0.0",
+/// "foo@^1.1.0"` or `"@scope/foo@npm:^1.0.0"`. Returns `None` for metadata and workspace entries.
+fn parse_yarn_entry_header(header: &str) -> Option<&str> {
+    if header.contains("@workspace:") {
+        return None;
+    }
+    let descriptor = header.split(',').next()?.trim().trim_matches('"');
+    let index = descriptor.get(1..)?.find('@')? + 1;
+    Some(&descriptor[..index])
+}
+
+/// Returns the version of the package `name` in `package_dir` which is locked by the lockfile,
+/// i.e. the installed version, with the integrity recorded in the lockfile. Returns `None` when
+/// the installed version isn't locked.
+#[turbo_tasks::function]
+pub async fn locked_package(
+    locked_versions: Vc<LockedVersions>,
+    package_dir: FileSystemPath,
+    name: RcStr,
+) -> Result<Vc<OptionLockedPackage>> {
+    let locked_versions = locked_versions.await?;
+    let Some(path) = locked_versions.lockfile.parent().get_path_to(&package_dir) else {
+        return Ok(Vc::cell(None));
+    };
+    let Some((name, installed_version)) = installed_package(&package_dir, name).await? else {
+        return Ok(Vc::cell(None));
+    };
+    Ok(Vc::cell(
+        locked_versions
+            .locked_packages(path, &name)
+            .iter()
+            .find(|package| package.version == installed_version)
+            .cloned(),
+    ))
+}
+
+/// Reads the name and version of the package installed in `package_dir`. The name is the one
+/// from the `package.json`, which differs from the requested `name` for aliased dependencies.
+async fn installed_package(
+    package_dir: &FileSystemPath,
+    name: RcStr,
+) -> Result<Option<(RcStr, RcStr)>> {
+    let package_json = read_package_json(Vc::upcast(FileSource::new(
+        package_dir.join("package.json")?,
+    )))
+    .await?;
+    let Some(package_json) = &*package_json else {
+        return Ok(None);
+    };
+    let Some(version) = package_json["version"].as_str() else {
+        return Ok(None);
+    };
+    let name = package_json["name"]
+        .as_str()
+        .map(RcStr::from)
+        .unwrap_or(name);
+    Ok(Some((name, version.into())))
+}
+
+/// Warns when the version of the package `name` installed in `package_dir` isn't the one locked
+/// by the lockfile. Only packages in `node_modules` next to the lockfile are checked.
+#[turbo_tasks::function]
+pub(super) async fn check_locked_version(
+    locked_versions: Vc<LockedVersions>,
+    package_dir: FileSystemPath,
+    name: RcStr,
+) -> Result<Vc<()>> {
+    let locked_versions = locked_versions.await?;
+    let Some(path) = locked_versions.lockfile.parent().get_path_to(&package_dir) else {
+        return Ok(Vc::cell(()));
+    };
+    if !package_dir.is_in_node_modules() {
+        return Ok(Vc::cell(()));
+    }
+    let Some((name, installed_version)) = installed_package(&package_dir, name).await? else {
+        return Ok(Vc::cell(()));
+    };
+    let locked = locked_versions.locked_packages(path, &name);
+    if locked
+        .iter()
+        .any(|package| package.version == installed_version)
+    {
+        return Ok(Vc::cell(()));
+    }
+
+    LockedVersionMismatchIssue {
+        file_path: package_dir.join("package.json")?,
+        lockfile: locked_versions.lockfile.clone(),
+        kind: locked_versions.kind,
+        name,
+        installed_version,
+        locked_versions: locked
+            .iter()
+            .map(|package| package.version.clone())
+            .collect(),
+    }
+    .resolved_cell()
+    .emit();
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::value(shared)]
+struct LockedVersionMismatchIssue {
+    file_path: FileSystemPath,
+    lockfile: FileSystemPath,
+    kind: LockfileKind,
+    name: RcStr,
+    installed_version: RcStr,
+    locked_versions: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for LockedVersionMismatchIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Warning
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Resolve.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text(rcstr!("The installed version of ")),
+            StyledString::Code(self.name.clone()),
+            StyledString::Text(rcstr!(" doesn't match the lockfile")),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let locked = if self.locked_versions.is_empty() {
+            "doesn't lock any version of it".to_string()
+        } else {
+            format!("locks {}", self.locked_versions.join(", "))
+        };
+        Vc::cell(Some(
+            StyledString::Stack(vec![
+                StyledString::Text(
+                    format!(
+                        "Version {} of {} is installed, but {} {locked}.",
+                        self.installed_version,
+                        self.name,
+                        self.lockfile.file_name(),
+                    )
+                    .into(),
+                ),
+                StyledString::Line(vec![
+                    StyledString::Text(rcstr!(
+                        "The installed dependencies are out of date, so this build can differ \
+                         from builds of a fresh install, e.g. on CI. Run "
+                    )),
+                    StyledString::Code(self.kind.install_command().into()),
+                    StyledString::Text(rcstr!(" to install the locked versions.")),
+                ]),
+            ])
+            .resolved_cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use turbo_rcstr::{RcStr, rcstr};
+    use turbo_tasks::Vc;
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+
+    use super::{
+        LockfileKind, find_locked_versions, parse_pnpm_package_key, parse_yarn_entry_header,
+    };
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_find_locked_versions() {
+        let scratch = tempfile::tempdir().unwrap();
+        let monorepo = scratch.path().join("monorepo");
+        create_dir_all(monorepo.join("apps/web")).unwrap();
+        write(
+            monorepo.join("package-lock.json"),
+            r#"{
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "name": "monorepo" },
+                    "node_modules/react": { "version": "18.2.0" },
+                    "apps/web/node_modules/react": { "version": "19.0.0" }
+                }
+            }"#,
+        )
+        .unwrap();
+        let broken = scratch.path().join("broken");
+        create_dir_all(&broken).unwrap();
+        write(broken.join("package-lock.json"), "{ not json").unwrap();
+        let path: RcStr = scratch.path().to_str().unwrap().into();
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+
+        tt.run_once(async move {
+            let fs = Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new(rcstr!("temp"), path));
+            let root = fs.root().owned().await?;
+
+            // The lockfile at the root of the monorepo is found from a workspace package.
+            let locked_versions = find_locked_versions(root.join("monorepo/apps/web")?)
+                .await?
+                .expect("the lockfile of the monorepo should be found");
+            let locked_versions = locked_versions.await?;
+            assert_eq!(
+                locked_versions.lockfile,
+                root.join("monorepo/package-lock.json")?
+            );
+            assert_eq!(locked_versions.kind, LockfileKind::Npm);
+            assert_eq!(
+                locked_versions
+                    .locked_packages("apps/web/node_modules/react", "react")
+                    .iter()
+                    .map(|package| package.version.as_str())
+                    .collect::<Vec<_>>(),
+                ["19.0.0"]
+            );
+
+            // A lockfile which can't be parsed is ignored instead of failing.
+            assert!(find_locked_versions(root.join("broken")?).await?.is_none());
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_parse_pnpm_package_key() {
+        assert_eq!(
+            parse_pnpm_package_key("/react@18.2.0", false),
+            Some(("react", "18.2.0"))
+        );
+        assert_eq!(
+            parse_pnpm_package_key("@babel/core@7.24.0(supports-color@8.1.1)", false),
+            Some(("@babel/core", "7.24.0"))
+        );
+        assert_eq!(
+            parse_pnpm_package_key("/react-dom/18.2.0_react@18.2.0", true),
+            Some(("react-dom", "18.2.0"))
+        );
+        assert_eq!(
+            parse_pnpm_package_key("/@babel/core/7.24.0", true),
+            Some(("@babel/core", "7.24.0"))
+        );
+    }
+
+    #[test]
+    fn test_parse_yarn_entry_header() {
+        assert_eq!(
+            parse_yarn_entry_header(r#""@babel/core@^7.0.0", "@babel/core@^7.12.3""#),
+            Some("@babel/core")
+        );
+        assert_eq!(
+            parse_yarn_entry_header("lodash@npm:^4.17.21"),
+            Some("lodash")
+        );
+        assert_eq!(parse_yarn_entry_header(r#""app@workspace:.""#), None);
+        assert_eq!(parse_yarn_entry_header("__metadata"), None);
+    }
+}
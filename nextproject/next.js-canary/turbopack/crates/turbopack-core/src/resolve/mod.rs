@@ -37,27 +37,35 @@ use crate::{
     reference_type::ReferenceType,
     resolve::{
         alias_map::AliasKey,
+        explain::{ResolveTraceStep, describe_import_map_result, emit_resolve_trace_step},
+        lockfile::check_locked_version,
         node::{node_cjs_resolve_options, node_esm_resolve_options},
         options::{
             ConditionValue, ImportMapResult, ResolveInPackage, ResolveIntoPackage, ResolveModules,
             ResolveModulesOptions, ResolveOptions, resolve_modules_options,
         },
         origin::ResolveOrigin,
+        package_exports_validation::validate_package_exports,
         parse::{Request, stringify_data_uri},
         pattern::{Pattern, PatternMatch, read_matches},
         plugin::{AfterResolvePlugin, AfterResolvePluginCondition, BeforeResolvePlugin},
+        pnp::{PnpResolution, pnp_issuer_path, pnp_location_path, pnp_manifest},
         remap::{ExportsField, ImportsField, ReplacedSubpathValueResult},
     },
     source::{OptionSource, Source, Sources},
 };
 
 mod alias_map;
+pub mod explain;
+pub mod lockfile;
 pub mod node;
 pub mod options;
 pub mod origin;
+pub mod package_exports_validation;
 pub mod parse;
 pub mod pattern;
 pub mod plugin;
+pub mod pnp;
 pub(crate) mod remap;
 
 pub use alias_map::{
@@ -1333,6 +1341,11 @@ struct FindPackageResult {
     packages: Vec<FindPackageItem>,
     // Only populated if collect_affecting_sources is true
     affecting_sources: Vec<ResolvedVc<Box<dyn Source>>>,
+    /// Explanations why a package can't be accessed, e.g. because of the dependency rules of Yarn
+    /// Plug'n'Play.
+    errors: Vec<RcStr>,
+    /// The locations which were searched, only populated if explain is true.
+    trace: Vec<ResolveTraceStep>,
 }
 
 #[turbo_tasks::function]
@@ -1341,9 +1354,12 @@ async fn find_package(
     package_name: Pattern,
     options: Vc<ResolveModulesOptions>,
     collect_affecting_sources: bool,
+    explain: bool,
 ) -> Result<Vc<FindPackageResult>> {
     let mut packages = vec![];
     let mut affecting_sources = vec![];
+    let mut errors = vec![];
+    let mut trace = vec![];
     let options = options.await?;
     let package_name_cell = Pattern::new(package_name.clone());
 
@@ -1372,6 +1388,14 @@ async fn find_package(
                             let matches =
                                 read_matches(fs_path.clone(), rcstr!(""), true, package_name_cell)
                                     .await?;
+                            if explain {
+                                trace.push(ResolveTraceStep::ModulesDirectory {
+                                    directory: fs_path.clone(),
+                                    found: matches
+                                        .iter()
+                                        .any(|m| matches!(m, PatternMatch::Directory(..))),
+                                });
+                            }
                             for m in &*matches {
                                 if let PatternMatch::Directory(_, package_dir) = m {
                                     packages.push(FindPackageItem::PackageDirectory {
@@ -1399,6 +1423,7 @@ async fn find_package(
                 dir,
                 excluded_extensions,
             } => {
+                let packages_before = packages.len();
                 let matches =
                     read_matches(dir.clone(), rcstr!(""), true, package_name_cell).await?;
                 for m in &*matches {
@@ -1453,12 +1478,50 @@ async fn find_package(
                         });
                     }
                 }
+                if explain {
+                    trace.push(ResolveTraceStep::ModulesDirectory {
+                        directory: dir.clone(),
+                        found: packages.len() > packages_before,
+                    });
+                }
+            }
+            ResolveModules::PnP { manifest } => {
+                let Some(issuer_path) = pnp_issuer_path(&lookup_path, manifest.fs).await? else {
+                    continue;
+                };
+                if explain {
+                    trace.push(ResolveTraceStep::PnpManifest {
+                        manifest: manifest.clone(),
+                    });
+                }
+                if collect_affecting_sources {
+                    affecting_sources.push(ResolvedVc::upcast(
+                        FileSource::new(manifest.clone()).to_resolved().await?,
+                    ));
+                }
+                match pnp_manifest(manifest.clone())
+                    .await?
+                    .resolve(&issuer_path, &package_name)
+                {
+                    PnpResolution::Unhandled => {}
+                    PnpResolution::Resolved(resolved) => {
+                        for (name, location) in resolved {
+                            packages.push(FindPackageItem::PackageDirectory {
+                                name,
+                                dir: pnp_location_path(&location, manifest.fs).await?,
+                            });
+                        }
+                    }
+                    PnpResolution::Error(error) => errors.push(error.into()),
+                }
             }
         }
     }
     Ok(FindPackageResult::cell(FindPackageResult {
         packages,
         affecting_sources,
+        errors,
+        trace,
     }))
 }
 
@@ -1738,11 +1801,25 @@ async fn handle_before_resolve_plugins(
     request: Vc<Request>,
     options: Vc<ResolveOptions>,
 ) -> Result<Option<Vc<ResolveResult>>> {
+    let options_value = options.await?;
     for plugin in get_matching_before_resolve_plugins(options, request).await? {
-        if let Some(result) = *plugin
+        let result = *plugin
             .before_resolve(lookup_path.clone(), reference_type.clone(), request)
-            .await?
-        {
+            .await?;
+        if options_value.explain {
+            emit_resolve_trace_step(
+                request.to_string().owned().await?,
+                ResolveTraceStep::BeforeResolvePlugin {
+                    index: options_value
+                        .before_resolve_plugins
+                        .iter()
+                        .position(|p| p == plugin)
+                        .unwrap_or_default(),
+                    handled: result.is_some(),
+                },
+            );
+        }
+        if let Some(result) = result {
             return Ok(Some(*result));
         }
     }
@@ -1774,18 +1851,32 @@ async fn handle_after_resolve_plugins(
         reference_type: ReferenceType,
         request: Vc<Request>,
         plugins_with_conditions: &[AfterResolvePluginWithCondition],
+        explain: bool,
     ) -> Result<Option<Vc<ResolveResult>>> {
-        for (plugin, after_resolve_condition) in plugins_with_conditions {
-            if *after_resolve_condition.matches(path.clone()).await?
-                && let Some(result) = *plugin
-                    .after_resolve(
-                        path.clone(),
-                        lookup_path.clone(),
-                        reference_type.clone(),
-                        request,
-                    )
-                    .await?
-            {
+        for (index, (plugin, after_resolve_condition)) in plugins_with_conditions.iter().enumerate()
+        {
+            if !*after_resolve_condition.matches(path.clone()).await? {
+                continue;
+            }
+            let result = *plugin
+                .after_resolve(
+                    path.clone(),
+                    lookup_path.clone(),
+                    reference_type.clone(),
+                    request,
+                )
+                .await?;
+            if explain {
+                emit_resolve_trace_step(
+                    request.to_string().owned().await?,
+                    ResolveTraceStep::AfterResolvePlugin {
+                        index,
+                        path: path.clone(),
+                        replaced: result.is_some(),
+                    },
+                );
+            }
+            if let Some(result) = result {
                 return Ok(Some(*result));
             }
         }
@@ -1807,6 +1898,7 @@ async fn handle_after_resolve_plugins(
                 reference_type.clone(),
                 request,
                 &resolved_conditions,
+                options_value.explain,
             )
             .await?
             {
@@ -1893,6 +1985,19 @@ async fn resolve_internal_inline(
                         request.query().owned().await?,
                     )
                     .await?;
+                    if options_value.explain {
+                        let resolved = match resolved_result {
+                            Some(result) => !*result.is_unresolvable().await?,
+                            None => false,
+                        };
+                        emit_resolve_trace_step(
+                            request.to_string().owned().await?,
+                            ResolveTraceStep::ImportMapAlias {
+                                mapping: describe_import_map_result(&result).await?.into(),
+                                resolved,
+                            },
+                        );
+                    }
                     // We might have matched an alias in the import map, but there is no guarantee
                     // the alias actually resolves to something. For instance, a tsconfig.json
                     // `compilerOptions.paths` option might alias "@*" to "./*", which
@@ -1970,7 +2075,7 @@ async fn resolve_internal_inline(
                 force_in_lookup_dir,
                 fragment,
             } => {
-                resolve_relative_request(
+                let result = resolve_relative_request(
                     lookup_path.clone(),
                     request,
                     options,
@@ -1980,7 +2085,22 @@ async fn resolve_internal_inline(
                     *force_in_lookup_dir,
                     fragment.clone(),
                 )
-                .await?
+                .await?;
+                if !options_value.root_dirs.is_empty() && *result.is_unresolvable().await? {
+                    resolve_relative_request_in_root_dirs(
+                        lookup_path.clone(),
+                        request,
+                        options,
+                        options_value,
+                        path,
+                        query.clone(),
+                        fragment.clone(),
+                    )
+                    .await?
+                    .unwrap_or(result)
+                } else {
+                    result
+                }
             }
             Request::Module {
                 module,
@@ -2583,6 +2703,55 @@ async fn resolve_relative_request(
     Ok(merge_results(results))
 }
 
+/// Resolves a relative request, which couldn't be resolved in `lookup_path`, at the same location
+/// in the other [ResolveOptions::root_dirs], as if these directories were merged into one.
+async fn resolve_relative_request_in_root_dirs(
+    lookup_path: FileSystemPath,
+    request: Vc<Request>,
+    options: Vc<ResolveOptions>,
+    options_value: &ResolveOptions,
+    path_pattern: &Pattern,
+    query: RcStr,
+    fragment: RcStr,
+) -> Result<Option<Vc<ResolveResult>>> {
+    // Nested root dirs are allowed, the innermost one containing the lookup path applies.
+    let Some((root_dir, sub_path)) = options_value
+        .root_dirs
+        .iter()
+        .filter_map(|root_dir| {
+            if *root_dir == lookup_path {
+                Some((root_dir, ""))
+            } else {
+                Some((root_dir, root_dir.get_path_to(&lookup_path)?))
+            }
+        })
+        .max_by_key(|(root_dir, _)| root_dir.path.len())
+    else {
+        return Ok(None);
+    };
+
+    for other_root_dir in &options_value.root_dirs {
+        if other_root_dir == root_dir {
+            continue;
+        }
+        let result = resolve_relative_request(
+            other_root_dir.join(sub_path)?,
+            request,
+            options,
+            options_value,
+            path_pattern,
+            query.clone(),
+            false,
+            fragment.clone(),
+        )
+        .await?;
+        if !*result.is_unresolvable().await? {
+            return Ok(Some(result));
+        }
+    }
+    Ok(None)
+}
+
 #[tracing::instrument(level = Level::TRACE, skip_all)]
 async fn apply_in_package(
     lookup_path: FileSystemPath,
@@ -2777,10 +2946,52 @@ async fn resolve_module_request(
         module.clone(),
         resolve_modules_options(options).resolve().await?,
         options_value.collect_affecting_sources,
+        options_value.explain,
     )
     .await?;
 
+    if options_value.explain {
+        let request_str = request.to_string().owned().await?;
+        let steps = result.trace.iter().cloned().chain(
+            result
+                .packages
+                .iter()
+                .map(|package| {
+                    let (FindPackageItem::PackageDirectory { name, dir: path }
+                    | FindPackageItem::PackageFile { name, file: path }) = package;
+                    ResolveTraceStep::PackageFound {
+                        name: name.clone(),
+                        path: path.clone(),
+                    }
+                })
+                .chain(
+                    result
+                        .errors
+                        .iter()
+                        .map(|error| ResolveTraceStep::PackageError {
+                            message: error.clone(),
+                        }),
+                ),
+        );
+        for step in steps {
+            emit_resolve_trace_step(request_str.clone(), step);
+        }
+    }
+
     if result.packages.is_empty() {
+        for error in &result.errors {
+            ResolvingIssue {
+                severity: error_severity(options).await?,
+                file_path: lookup_path.clone(),
+                request_type: "Yarn Plug'n'Play request".to_string(),
+                request: request.to_resolved().await?,
+                resolve_options: options.to_resolved().await?,
+                error_message: Some(error.to_string()),
+                source: None,
+            }
+            .resolved_cell()
+            .emit();
+        }
         return Ok(*ResolveResult::unresolvable_with_affecting_sources(
             result.affecting_sources.clone(),
         ));
@@ -2794,6 +3005,9 @@ async fn resolve_module_request(
     for item in &result.packages {
         match item {
             FindPackageItem::PackageDirectory { name, dir } => {
+                if let Some(locked_versions) = options_value.locked_versions {
+                    check_locked_version(*locked_versions, dir.clone(), name.clone()).await?;
+                }
                 results.push(
                     resolve_into_package(
                         path.clone(),
@@ -2879,6 +3093,9 @@ async fn resolve_into_package(
                 else {
                     continue;
                 };
+                if options_value.validate_package_exports {
+                    validate_package_exports(package_json_path.clone()).await?;
+                }
 
                 results.push(
                     handle_exports_imports_field(
@@ -3120,6 +3337,20 @@ async fn handle_exports_imports_field(
     }
     let req = path;
 
+    if options.await?.explain {
+        emit_resolve_trace_step(
+            req.describe_as_string().into(),
+            ResolveTraceStep::PackageConditions {
+                package_json: package_json_path.clone(),
+                conditions: conditions
+                    .iter()
+                    .map(|(condition, value)| (condition.clone(), *value))
+                    .collect(),
+                unspecified_conditions: *unspecified_conditions,
+            },
+        );
+    }
+
     let values = exports_imports_field.lookup(&req);
     for value in values {
         let value = value?;
@@ -3242,6 +3473,9 @@ async fn resolve_package_internal_with_imports_field(
         ImportsFieldResult::Some(i, p) => (i, p.clone()),
         ImportsFieldResult::None => return Ok(*ResolveResult::unresolvable()),
     };
+    if resolve_options.await?.validate_package_exports {
+        validate_package_exports(package_json_path.clone()).await?;
+    }
 
     handle_exports_imports_field(
         package_json_path.parent(),
@@ -3520,6 +3754,7 @@ mod tests {
     use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
 
     use crate::{
+        reference_type::ReferenceType,
         resolve::{
             ResolveResult, ResolveResultItem, node::node_esm_resolve_options, parse::Request,
             pattern::Pattern,
@@ -3813,4 +4048,65 @@ mod tests {
             r => panic!("request should be relative, got {r:?}"),
         }
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_root_dirs() {
+        let scratch = tempfile::tempdir().unwrap();
+        for file_name in ["src/views/main.ts", "generated/views/template.ts"] {
+            let file_path = scratch.path().join(file_name);
+            create_dir_all(file_path.parent().unwrap()).unwrap();
+            File::create_new(&file_path).unwrap();
+        }
+        let path: RcStr = scratch.path().to_str().unwrap().into();
+
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+
+        tt.run_once(async move {
+            let fs = Vc::upcast::<Box<dyn FileSystem>>(DiskFileSystem::new(rcstr!("temp"), path));
+            let root = fs.root().owned().await?;
+
+            let result = resolve_root_dirs_helper(root).await?;
+            let results = result
+                .primary
+                .iter()
+                .map(async |(k, v)| {
+                    let ResolveResultItem::Source(source) = v else {
+                        unreachable!()
+                    };
+                    Ok((k.to_string(), source.ident().await?.path.path.to_string()))
+                })
+                .try_join()
+                .await?;
+
+            assert_eq!(
+                results,
+                vec![(
+                    "./template".to_string(),
+                    "generated/views/template.ts".to_string()
+                )]
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[turbo_tasks::function]
+    async fn resolve_root_dirs_helper(root: FileSystemPath) -> anyhow::Result<Vc<ResolveResult>> {
+        let mut options_value = node_esm_resolve_options(root.clone())
+            .with_extensions(vec![rcstr!(".ts"), rcstr!(".js")])
+            .owned()
+            .await?;
+        options_value.root_dirs = vec![root.join("src")?, root.join("generated")?];
+        Ok(super::resolve(
+            root.join("src/views")?,
+            ReferenceType::Undefined,
+            Request::parse_string(rcstr!("./template")),
+            options_value.cell(),
+        ))
+    }
 }
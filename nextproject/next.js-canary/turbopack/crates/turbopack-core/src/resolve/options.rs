@@ -12,15 +12,12 @@ use turbo_tasks_fs::{FileSystemPath, glob::Glob};
 use crate::resolve::{
     AliasPattern, ExternalTraced, ExternalType, ResolveResult, ResolveResultItem,
     alias_map::{AliasMap, AliasTemplate},
+    lockfile::LockedVersions,
     parse::Request,
     pattern::Pattern,
     plugin::{AfterResolvePlugin, BeforeResolvePlugin},
 };
 
-#[turbo_tasks::value(shared)]
-#[derive(Hash, Debug)]
-pub struct LockedVersions {}
-
 #[turbo_tasks::value(transparent)]
 #[derive(Debug)]
 pub struct ExcludedExtensions(#[bincode(with = "turbo_bincode::indexset")] pub FxIndexSet<RcStr>);
@@ -38,6 +35,9 @@ pub enum ResolveModules {
         dir: FileSystemPath,
         excluded_extensions: ResolvedVc<ExcludedExtensions>,
     },
+    /// resolve packages with the Yarn Plug'n'Play manifest (`.pnp.data.json` or `.pnp.cjs`) at
+    /// that path, see [super::pnp]
+    PnP { manifest: FileSystemPath },
 }
 
 #[derive(TraceRawVcs, Hash, PartialEq, Eq, Clone, Copy, Debug, NonLocalValue, Encode, Decode)]
@@ -637,12 +637,28 @@ pub struct ResolveOptions {
     pub after_resolve_plugins: Vec<ResolvedVc<Box<dyn AfterResolvePlugin>>>,
     /// Support resolving *.js requests to *.ts files
     pub enable_typescript_with_output_extension: bool,
+    /// Directories whose contents are merged into one virtual directory for relative requests,
+    /// like `compilerOptions.rootDirs` in a tsconfig.json. A relative request which can't be
+    /// resolved in one of these directories is resolved at the same location in the others.
+    pub root_dirs: Vec<FileSystemPath>,
     /// Warn instead of error for resolve errors
     pub loose_errors: bool,
     /// Collect affecting sources for each resolve result.  Useful for tracing.
     pub collect_affecting_sources: bool,
     /// Whether to parse data URIs into modules (as opposed to keeping them as externals)
     pub parse_data_uris: bool,
+    /// When set, warn when the version of a package installed in `node_modules` differs from the
+    /// version locked by the lockfile.
+    pub locked_versions: Option<ResolvedVc<LockedVersions>>,
+    /// Warn about invalid targets, unreachable conditions and missing files in the `exports` and
+    /// `imports` fields of the package.json files read while resolving.
+    pub validate_package_exports: bool,
+    /// Emit a [ResolveTrace] collectible for each decision of the resolver, see
+    /// [explain_resolve].
+    ///
+    /// [ResolveTrace]: crate::resolve::explain::ResolveTrace
+    /// [explain_resolve]: crate::resolve::explain::explain_resolve
+    pub explain: bool,
 
     pub placeholder_for_future_extensions: (),
 }
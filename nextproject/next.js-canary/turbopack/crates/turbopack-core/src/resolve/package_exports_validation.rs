@@ -0,0 +1,454 @@
+//! Lints for the `exports` and `imports` fields of package.json files. These catch mistakes which
+//! Node.js, TypeScript and bundlers handle differently or silently ignore, so that the package
+//! resolves differently than its author intended.
+
+use anyhow::Result;
+use jsonc_parser::{
+    CollectOptions, ParseOptions,
+    ast::{Object, Value},
+    common::{Range, Ranged},
+    parse_to_ast,
+};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{FileContent, FileSystemEntryType, FileSystemPath};
+
+use crate::{
+    file_source::FileSource,
+    issue::{
+        Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource,
+        OptionStyledString, StyledString,
+    },
+    source::Source,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Exports,
+    Imports,
+}
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::Exports => "exports",
+            Field::Imports => "imports",
+        }
+    }
+}
+
+struct Problem {
+    field: Field,
+    range: Range,
+    message: String,
+}
+
+/// A relative target without wildcards, which has to exist in the package directory.
+struct FileTarget {
+    field: Field,
+    range: Range,
+    target: String,
+}
+
+#[derive(Default)]
+struct Validation {
+    problems: Vec<Problem>,
+    file_targets: Vec<FileTarget>,
+}
+
+impl Validation {
+    fn problem(&mut self, field: Field, range: Range, message: String) {
+        self.problems.push(Problem {
+            field,
+            range,
+            message,
+        });
+    }
+
+    fn validate_package_json(&mut self, package_json: &Object) {
+        for prop in &package_json.properties {
+            match prop.name.as_str() {
+                "exports" => self.validate_exports(&prop.value),
+                "imports" => self.validate_imports(&prop.value),
+                _ => {}
+            }
+        }
+    }
+
+    fn validate_exports(&mut self, exports: &Value) {
+        let Value::Object(object) = exports else {
+            self.validate_target(Field::Exports, exports);
+            return;
+        };
+        let subpaths = object
+            .properties
+            .iter()
+            .filter(|prop| prop.name.as_str().starts_with('.'))
+            .count();
+        if subpaths == 0 {
+            // The object contains the conditions for the "." subpath
+            self.validate_target(Field::Exports, exports);
+            return;
+        }
+        if subpaths != object.properties.len() {
+            self.problem(
+                Field::Exports,
+                object.range,
+                "The \"exports\" field mixes subpaths (keys starting with \".\") and conditions. \
+                 Node.js rejects such an exports field."
+                    .to_string(),
+            );
+            return;
+        }
+        for prop in &object.properties {
+            let subpath = prop.name.as_str();
+            if subpath.matches('*').count() > 1 {
+                self.problem(
+                    Field::Exports,
+                    prop.name.range(),
+                    format!(
+                        "The subpath pattern \"{subpath}\" contains more than one \"*\". Only a \
+                         single \"*\" is supported."
+                    ),
+                );
+            }
+            self.validate_target(Field::Exports, &prop.value);
+        }
+    }
+
+    fn validate_imports(&mut self, imports: &Value) {
+        let Value::Object(object) = imports else {
+            self.problem(
+                Field::Imports,
+                imports.range(),
+                "The \"imports\" field must be an object.".to_string(),
+            );
+            return;
+        };
+        for prop in &object.properties {
+            let specifier = prop.name.as_str();
+            if !specifier.starts_with('#') || specifier == "#" || specifier.starts_with("#/") {
+                self.problem(
+                    Field::Imports,
+                    prop.name.range(),
+                    format!(
+                        "The key \"{specifier}\" can never be imported. Keys of the \"imports\" \
+                         field must start with \"#\" and must not be \"#\" or start with \"#/\"."
+                    ),
+                );
+                continue;
+            }
+            self.validate_target(Field::Imports, &prop.value);
+        }
+    }
+
+    fn validate_target(&mut self, field: Field, target: &Value) {
+        match target {
+            Value::StringLit(lit) => self.validate_target_string(field, lit.range, &lit.value),
+            Value::NullKeyword(_) => {}
+            Value::Array(array) => {
+                for element in &array.elements {
+                    self.validate_target(field, element);
+                }
+            }
+            Value::Object(conditions) => self.validate_conditions(field, conditions),
+            other => self.problem(
+                field,
+                other.range(),
+                "Invalid target. Targets must be strings, arrays, condition objects or null."
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn validate_target_string(&mut self, field: Field, range: Range, target: &str) {
+        let Some(subpath) = target.strip_prefix("./") else {
+            // Package imports may map to other packages
+            let is_bare_specifier = !target.starts_with('.')
+                && !target.starts_with('/')
+                && !target.starts_with('#')
+                && !target.contains(':');
+            if field == Field::Imports && is_bare_specifier {
+                return;
+            }
+            self.problem(
+                field,
+                range,
+                format!("The target \"{target}\" is invalid. Targets must start with \"./\"."),
+            );
+            return;
+        };
+        if subpath
+            .split(['/', '\\'])
+            .any(|segment| matches!(segment, "." | ".." | "node_modules"))
+        {
+            self.problem(
+                field,
+                range,
+                format!(
+                    "The target \"{target}\" is invalid. Targets must not contain \".\", \"..\" \
+                     or \"node_modules\" segments."
+                ),
+            );
+            return;
+        }
+        // Wildcard targets can't be checked without the requests, and trailing slashes are
+        // deprecated folder mappings.
+        if !subpath.is_empty() && !subpath.contains('*') && !subpath.ends_with('/') {
+            self.file_targets.push(FileTarget {
+                field,
+                range,
+                target: subpath.to_string(),
+            });
+        }
+    }
+
+    fn validate_conditions(&mut self, field: Field, conditions: &Object) {
+        let mut default_seen = false;
+        for (index, prop) in conditions.properties.iter().enumerate() {
+            let condition = prop.name.as_str();
+            if condition.starts_with('.') {
+                self.problem(
+                    field,
+                    prop.name.range(),
+                    format!(
+                        "\"{condition}\" is not a valid condition. Conditions must not start with \
+                         \".\"."
+                    ),
+                );
+            } else if default_seen {
+                self.problem(
+                    field,
+                    prop.name.range(),
+                    format!(
+                        "The condition \"{condition}\" is unreachable, because it's listed after \
+                         the \"default\" condition, which always matches."
+                    ),
+                );
+            } else if condition == "types" && index > 0 {
+                let first = conditions.properties[0].name.as_str();
+                self.problem(
+                    field,
+                    prop.name.range(),
+                    format!(
+                        "The \"types\" condition should be listed first. TypeScript uses the \
+                         first matching condition, so \"{first}\" is used instead."
+                    ),
+                );
+            }
+            default_seen |= condition == "default";
+            self.validate_target(field, &prop.value);
+        }
+    }
+}
+
+/// Validates the `exports` and `imports` fields of a package.json and emits a warning with the
+/// position in the package.json for each problem.
+///
+/// This checks for invalid targets, conditions which can never match because they are listed
+/// after `default`, `types` conditions which TypeScript doesn't pick up and targets which don't
+/// exist.
+#[turbo_tasks::function]
+pub async fn validate_package_exports(package_json_path: FileSystemPath) -> Result<Vc<()>> {
+    let FileContent::Content(file) = &*package_json_path.read().await? else {
+        return Ok(Vc::cell(()));
+    };
+    let Ok(text) = file.content().to_str() else {
+        return Ok(Vc::cell(()));
+    };
+    // Syntax errors are reported when the package.json is read for resolving
+    let Ok(parsed) = parse_to_ast(
+        &text,
+        &CollectOptions::default(),
+        &ParseOptions {
+            allow_comments: true,
+            allow_trailing_commas: true,
+            allow_loose_object_property_names: false,
+        },
+    ) else {
+        return Ok(Vc::cell(()));
+    };
+    let Some(Value::Object(package_json)) = &parsed.value else {
+        return Ok(Vc::cell(()));
+    };
+
+    let mut validation = Validation::default();
+    validation.validate_package_json(package_json);
+    let Validation {
+        mut problems,
+        file_targets,
+    } = validation;
+
+    let package_dir = package_json_path.parent();
+    for FileTarget {
+        field,
+        range,
+        target,
+    } in file_targets
+    {
+        let path = package_dir.join(&target)?;
+        if matches!(&*path.get_type().await?, FileSystemEntryType::NotFound) {
+            problems.push(Problem {
+                field,
+                range,
+                message: format!("The target \"./{target}\" doesn't exist."),
+            });
+        }
+    }
+
+    let source = ResolvedVc::upcast::<Box<dyn Source>>(
+        FileSource::new(package_json_path).to_resolved().await?,
+    );
+    for Problem {
+        field,
+        range,
+        message,
+    } in problems
+    {
+        PackageExportsIssue {
+            field: field.name().into(),
+            message: message.into(),
+            source: IssueSource::from_byte_offset(source, range.start as u32, range.end as u32)
+                .await?,
+        }
+        .resolved_cell()
+        .emit();
+    }
+
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::value(shared)]
+struct PackageExportsIssue {
+    field: RcStr,
+    message: RcStr,
+    source: IssueSource,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for PackageExportsIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Warning
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Resolve.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.source.file_path()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text(rcstr!("Problem in the ")),
+            StyledString::Code(self.field.clone()),
+            StyledString::Text(rcstr!(" field of package.json")),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(self.message.clone()).resolved_cell(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(Some(self.source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(package_json: &str) -> Vec<(Field, String, String)> {
+        let parsed = parse_to_ast(
+            package_json,
+            &CollectOptions::default(),
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        let Some(Value::Object(object)) = &parsed.value else {
+            panic!("expected an object");
+        };
+        let mut validation = Validation::default();
+        validation.validate_package_json(object);
+        validation
+            .problems
+            .into_iter()
+            .map(|problem| {
+                (
+                    problem.field,
+                    package_json[problem.range.start..problem.range.end].to_string(),
+                    problem.message,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_exports() {
+        let problems = validate(
+            r##"{
+                "exports": {
+                    ".": { "types": "./index.d.ts", "import": "./index.mjs", "default": "./index.js" },
+                    "./features/*": ["./features/*.js", null]
+                },
+                "imports": { "#dep": { "node": "dep-node", "default": "./dep.js" } }
+            }"##,
+        );
+        assert_eq!(problems, vec![]);
+    }
+
+    #[test]
+    fn invalid_targets() {
+        let problems = validate(
+            r##"{
+                "exports": { ".": "index.js", "./a": "./../a.js", "./b": 1 },
+                "imports": { "dep": "./dep.js", "#x": "/x.js" }
+            }"##,
+        );
+        let ranges = problems
+            .iter()
+            .map(|(field, range, _)| (*field, range.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![
+                (Field::Exports, r#""index.js""#),
+                (Field::Exports, r#""./../a.js""#),
+                (Field::Exports, "1"),
+                (Field::Imports, r#""dep""#),
+                (Field::Imports, r#""/x.js""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_subpaths_and_conditions() {
+        let problems = validate(r##"{ "exports": { ".": "./a.js", "import": "./b.js" } }"##);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].2.contains("mixes subpaths"));
+    }
+
+    #[test]
+    fn condition_order() {
+        let problems = validate(
+            r##"{ "exports": { "import": "./a.mjs", "types": "./a.d.ts", "default": "./a.js", "require": "./a.cjs" } }"##,
+        );
+        let ranges = problems
+            .iter()
+            .map(|(_, range, _)| range.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![r#""types""#, r#""require""#]);
+        assert!(problems[0].2.contains("\"import\" is used instead"));
+        assert!(problems[1].2.contains("unreachable"));
+    }
+}
@@ -55,6 +55,8 @@ impl AfterResolvePluginCondition {
 pub enum BeforeResolvePluginCondition {
     Request(ResolvedVc<Glob>),
     Modules(FxHashSet<RcStr>),
+    /// Matches URI requests with one of the protocols, e.g. `https:`.
+    Protocols(FxHashSet<RcStr>),
     Always,
     Never,
 }
@@ -88,6 +90,13 @@ impl BeforeResolvePluginCondition {
                     false
                 }
             }
+            BeforeResolvePluginCondition::Protocols(protocols) => {
+                if let Request::Uri { protocol, .. } = &*request.await? {
+                    protocols.contains(protocol)
+                } else {
+                    false
+                }
+            }
             BeforeResolvePluginCondition::Always => true,
             BeforeResolvePluginCondition::Never => false,
         }))
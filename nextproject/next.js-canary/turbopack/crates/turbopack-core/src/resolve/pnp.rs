@@ -0,0 +1,634 @@
+//! Resolving packages in projects installed with [Yarn Plug'n'Play].
+//!
+//! Instead of a `node_modules` folder, Plug'n'Play installs write a manifest which lists the
+//! location of every package and the dependencies each package is allowed to access. Packages are
+//! usually stored as zip archives in the Yarn cache, which are read with a [ZipFileSystem].
+//!
+//! [Yarn Plug'n'Play]: https://yarnpkg.com/advanced/pnp-spec
+
+use anyhow::{Context, Result, bail};
+use bincode::{Decode, Encode};
+use regex::Regex;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{NonLocalValue, ResolvedVc, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{
+    FileContent, FileSystem, FileSystemEntryType, FileSystemPath, FileSystemPathOption,
+    ZipFileSystem, json::parse_json_with_source_context,
+};
+use turbo_unix_path::{get_parent_path, join_path, normalize_path};
+
+use crate::resolve::pattern::Pattern;
+
+/// The files Yarn writes the Plug'n'Play data to, in the order they are looked up. The data is
+/// only written to a separate `.pnp.data.json` when `pnpEnableInlining` is disabled, otherwise
+/// it's inlined into the `.pnp.cjs` runtime.
+const PNP_MANIFEST_FILES: [&str; 2] = [".pnp.data.json", ".pnp.cjs"];
+
+/// Finds the Plug'n'Play manifest of the project in `project_dir`, if the project has been
+/// installed with Yarn Plug'n'Play.
+#[turbo_tasks::function]
+pub async fn find_pnp_manifest(project_dir: FileSystemPath) -> Result<Vc<FileSystemPathOption>> {
+    for name in PNP_MANIFEST_FILES {
+        let manifest = project_dir.join(name)?;
+        if *manifest.get_type().await? == FileSystemEntryType::File {
+            return Ok(Vc::cell(Some(manifest)));
+        }
+    }
+    Ok(Vc::cell(None))
+}
+
+/// A package, identified by its name and reference (e.g. `npm:1.0.0` or `workspace:.`). The name
+/// and reference of the top-level package are `None`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+struct PnpLocator {
+    name: Option<RcStr>,
+    reference: Option<RcStr>,
+}
+
+impl PnpLocator {
+    fn new(name: RcStr, target: RawDependencyTarget) -> Self {
+        match target {
+            RawDependencyTarget::Reference(reference) => PnpLocator {
+                name: Some(name),
+                reference: Some(reference),
+            },
+            RawDependencyTarget::Aliased(name, reference) => PnpLocator {
+                name: Some(name),
+                reference: Some(reference),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+struct PnpPackage {
+    locator: PnpLocator,
+    /// The location of the package, relative to the root of the file system of the manifest.
+    location: RcStr,
+    /// The dependencies the package is allowed to access. A `None` value is a peer dependency
+    /// which isn't provided by the ancestors of the package.
+    dependencies: FxHashMap<RcStr, Option<PnpLocator>>,
+}
+
+/// The parsed data of a Plug'n'Play manifest.
+#[turbo_tasks::value]
+#[derive(Debug)]
+pub struct PnpManifest {
+    /// The directory of the manifest, relative to the root of its file system.
+    project_path: RcStr,
+    packages: Vec<PnpPackage>,
+    packages_by_locator: FxHashMap<PnpLocator, usize>,
+    packages_by_location: FxHashMap<RcStr, usize>,
+    dependency_tree_roots: FxHashSet<PnpLocator>,
+    enable_top_level_fallback: bool,
+    fallback_pool: FxHashMap<RcStr, PnpLocator>,
+    /// Packages which must not use the fallback, by name and reference.
+    fallback_exclusion_list: FxHashMap<RcStr, FxHashSet<RcStr>>,
+    /// Paths relative to the project which are resolved without Plug'n'Play.
+    ignore_pattern: Option<RcStr>,
+}
+
+/// The result of [PnpManifest::resolve].
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum PnpResolution {
+    /// The issuer isn't part of the dependency tree, the request should be resolved by other
+    /// means.
+    Unhandled,
+    /// The matching dependencies of the issuer, as pairs of the requested name and the location
+    /// of the package.
+    Resolved(Vec<(RcStr, RcStr)>),
+    /// The request isn't allowed by the dependency rules of Plug'n'Play.
+    Error(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPnpData {
+    #[serde(default)]
+    dependency_tree_roots: Vec<RawLocator>,
+    #[serde(default)]
+    enable_top_level_fallback: bool,
+    #[serde(default)]
+    fallback_pool: Vec<(RcStr, RawDependencyTarget)>,
+    #[serde(default)]
+    fallback_exclusion_list: Vec<(RcStr, Vec<RcStr>)>,
+    #[serde(default)]
+    ignore_pattern_data: Option<RcStr>,
+    package_registry_data: Vec<(Option<RcStr>, Vec<(Option<RcStr>, RawPackageInformation)>)>,
+}
+
+#[derive(Deserialize)]
+struct RawLocator {
+    name: RcStr,
+    reference: RcStr,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPackageInformation {
+    package_location: RcStr,
+    #[serde(default)]
+    package_dependencies: Vec<(RcStr, Option<RawDependencyTarget>)>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDependencyTarget {
+    Reference(RcStr),
+    /// An aliased dependency, e.g. `"foo": "npm:bar@1.0.0"`, as the name and reference of the
+    /// actual package.
+    Aliased(RcStr, RcStr),
+}
+
+/// Reads and parses the Plug'n'Play manifest at `manifest`, which is either a `.pnp.data.json` or
+/// a `.pnp.cjs` file with the data inlined.
+#[turbo_tasks::function]
+pub async fn pnp_manifest(manifest: FileSystemPath) -> Result<Vc<PnpManifest>> {
+    let FileContent::Content(file) = &*manifest.read().await? else {
+        bail!("The Plug'n'Play manifest {} doesn't exist", manifest.path);
+    };
+    let content = file.content().to_str()?;
+    let data = if manifest.has_extension(".cjs") {
+        parse_json_with_source_context(&extract_runtime_state(&content).with_context(|| {
+            format!(
+                "Unable to find the Plug'n'Play data in {}. Set `pnpEnableInlining: false` in \
+                 .yarnrc.yml to write it to a separate .pnp.data.json file.",
+                manifest.path
+            )
+        })?)
+    } else {
+        parse_json_with_source_context(&content)
+    }
+    .with_context(|| format!("Unable to parse the Plug'n'Play manifest {}", manifest.path))?;
+    Ok(PnpManifest::new(data, &manifest.parent().path).cell())
+}
+
+/// Extracts the JSON of the `RAW_RUNTIME_STATE` string literal from a `.pnp.cjs` file.
+fn extract_runtime_state(source: &str) -> Option<String> {
+    let start = source.find("RAW_RUNTIME_STATE")?;
+    let source = &source[start..];
+    let quote_start = source.find('\'')?;
+    let mut json = String::new();
+    let mut chars = source[quote_start + 1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => return Some(json),
+            '\\' => match chars.next()? {
+                // Line continuation
+                '\n' => {}
+                '\r' => {
+                    chars.next();
+                }
+                c => json.push(c),
+            },
+            c => json.push(c),
+        }
+    }
+    None
+}
+
+/// Maps the path of a virtual package to the path of the actual package. Yarn creates virtual
+/// packages for each set of peer dependencies a package is used with, in the form of
+/// `<base>/__virtual__/<hash>/<depth>/<subpath>`, which is `<subpath>` relative to the parent of
+/// `<base>` after going up `<depth>` directories.
+fn devirtualize(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    let index = segments
+        .iter()
+        .position(|segment| *segment == "__virtual__" || *segment == "$$virtual")?;
+    let depth: usize = segments.get(index + 2)?.parse().ok()?;
+    let mut target = segments[..index].to_vec();
+    target.extend(std::iter::repeat_n("..", depth));
+    target.extend(segments.get(index + 3..).unwrap_or_default());
+    normalize_path(&target.join("/"))
+}
+
+impl PnpManifest {
+    fn new(data: RawPnpData, project_path: &str) -> Self {
+        let mut packages = Vec::new();
+        let mut packages_by_locator = FxHashMap::default();
+        let mut packages_by_location: FxHashMap<RcStr, usize> = FxHashMap::default();
+        for (name, references) in data.package_registry_data {
+            for (reference, info) in references {
+                // Locations outside of the file system can't be accessed anyway.
+                let Some(location) = join_path(project_path, &info.package_location) else {
+                    continue;
+                };
+                let location: RcStr = devirtualize(&location).unwrap_or(location).into();
+                let dependencies: FxHashMap<_, _> = info
+                    .package_dependencies
+                    .into_iter()
+                    .map(|(name, target)| {
+                        let locator = target.map(|target| PnpLocator::new(name.clone(), target));
+                        (name, locator)
+                    })
+                    .collect();
+                let index = packages.len();
+                let locator = PnpLocator {
+                    name: name.clone(),
+                    reference,
+                };
+                packages_by_locator.insert(locator.clone(), index);
+                if let Some(&existing) = packages_by_location.get(&location) {
+                    // The virtual instances of a package share its files, so they can't be told
+                    // apart by the location of the issuer. Merge their dependencies, so the peer
+                    // dependencies provided to any instance can be accessed.
+                    let existing: &mut PnpPackage = &mut packages[existing];
+                    for (name, locator) in dependencies.iter() {
+                        let entry = existing.dependencies.entry(name.clone()).or_insert(None);
+                        if entry.is_none() {
+                            *entry = locator.clone();
+                        }
+                    }
+                } else {
+                    packages_by_location.insert(location.clone(), index);
+                }
+                packages.push(PnpPackage {
+                    locator,
+                    location,
+                    dependencies,
+                });
+            }
+        }
+
+        PnpManifest {
+            project_path: project_path.into(),
+            packages,
+            packages_by_locator,
+            packages_by_location,
+            dependency_tree_roots: data
+                .dependency_tree_roots
+                .into_iter()
+                .map(|RawLocator { name, reference }| PnpLocator {
+                    name: Some(name),
+                    reference: Some(reference),
+                })
+                .collect(),
+            enable_top_level_fallback: data.enable_top_level_fallback,
+            fallback_pool: data
+                .fallback_pool
+                .into_iter()
+                .map(|(name, target)| (name.clone(), PnpLocator::new(name, target)))
+                .collect(),
+            fallback_exclusion_list: data
+                .fallback_exclusion_list
+                .into_iter()
+                .map(|(name, references)| (name, references.into_iter().collect()))
+                .collect(),
+            ignore_pattern: data.ignore_pattern_data,
+        }
+    }
+
+    /// Finds the package which contains `path`, i.e. the one with the longest location that is a
+    /// prefix of `path`.
+    fn find_package_by_path(&self, path: &str) -> Option<&PnpPackage> {
+        let mut path = path;
+        loop {
+            if let Some(&index) = self.packages_by_location.get(path) {
+                return Some(&self.packages[index]);
+            }
+            if path.is_empty() {
+                return None;
+            }
+            path = get_parent_path(path);
+        }
+    }
+
+    fn is_ignored(&self, path: &str) -> bool {
+        let Some(ignore_pattern) = &self.ignore_pattern else {
+            return false;
+        };
+        let relative_path = if self.project_path.is_empty() {
+            path
+        } else {
+            match path
+                .strip_prefix(&*self.project_path)
+                .and_then(|path| path.strip_prefix('/'))
+            {
+                Some(path) => path,
+                None => return false,
+            }
+        };
+        // The pattern is a JavaScript regular expression, patterns which aren't supported by the
+        // `regex` crate are ignored.
+        Regex::new(ignore_pattern).is_ok_and(|regex| regex.is_match(relative_path))
+    }
+
+    fn describe_issuer(&self, issuer: &PnpPackage) -> Option<String> {
+        match &issuer.locator.name {
+            Some(name) if !self.dependency_tree_roots.contains(&issuer.locator) => {
+                Some(name.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolves the dependency `name` of `issuer` to the location of the package.
+    fn resolve_dependency(&self, issuer: &PnpPackage, name: &str) -> Result<RcStr, String> {
+        let dependency = issuer.dependencies.get(name);
+        let mut locator = dependency.and_then(|locator| locator.as_ref());
+
+        // Packages which don't declare a dependency are allowed to access the dependencies of the
+        // top-level package instead, unless they are excluded.
+        if locator.is_none()
+            && self.enable_top_level_fallback
+            && let Some(issuer_name) = &issuer.locator.name
+        {
+            let excluded = issuer.locator.reference.as_ref().is_some_and(|reference| {
+                self.fallback_exclusion_list
+                    .get(issuer_name)
+                    .is_some_and(|references| references.contains(reference))
+            });
+            if !excluded {
+                locator = self
+                    .packages_by_locator
+                    .get(&PnpLocator {
+                        name: None,
+                        reference: None,
+                    })
+                    .and_then(|&index| self.packages[index].dependencies.get(name))
+                    .and_then(|locator| locator.as_ref())
+                    .or_else(|| self.fallback_pool.get(name));
+            }
+        }
+
+        let issuer_description = self.describe_issuer(issuer);
+        let Some(locator) = locator else {
+            return Err(match (dependency, issuer_description) {
+                (Some(None), None) => format!(
+                    "Your application tried to access {name} (a peer dependency); this isn't \
+                     allowed as there is no ancestor to satisfy the requirement. Add {name} to \
+                     the dependencies of your package.json."
+                ),
+                (Some(None), Some(issuer)) => format!(
+                    "{issuer} tried to access {name} (a peer dependency) but it isn't provided by \
+                     its ancestors; this makes the import ambiguous and unsound. A peer \
+                     dependency can only be accessed when the package depending on {issuer} also \
+                     depends on {name}."
+                ),
+                (_, None) => format!(
+                    "Your application tried to access {name}, but it isn't declared in your \
+                     dependencies; this makes the import ambiguous and unsound. Yarn Plug'n'Play \
+                     only allows packages to access the dependencies listed in their \
+                     package.json. Add {name} to the dependencies of your package.json."
+                ),
+                (_, Some(issuer)) => format!(
+                    "{issuer} tried to access {name}, but it isn't declared in its dependencies; \
+                     this makes the import ambiguous and unsound. Yarn Plug'n'Play only allows \
+                     packages to access the dependencies listed in their package.json. Ask the \
+                     maintainers of {issuer} to add {name} to its dependencies, or add it with \
+                     `packageExtensions` in your .yarnrc.yml."
+                ),
+            });
+        };
+
+        match self.packages_by_locator.get(locator) {
+            Some(&index) => Ok(self.packages[index].location.clone()),
+            None => Err(format!(
+                "{} depends on {}@{}, which is missing from the Plug'n'Play manifest. Try running \
+                 `yarn install`.",
+                issuer_description.as_deref().unwrap_or("Your application"),
+                locator.name.as_deref().unwrap_or(name),
+                locator.reference.as_deref().unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Resolves the packages matching `package_name` which the package containing `issuer_path`
+    /// is allowed to access. `issuer_path` is relative to the root of the file system of the
+    /// manifest.
+    pub(super) fn resolve(&self, issuer_path: &str, package_name: &Pattern) -> PnpResolution {
+        if self.is_ignored(issuer_path) {
+            return PnpResolution::Unhandled;
+        }
+        let Some(issuer) = self.find_package_by_path(issuer_path) else {
+            return PnpResolution::Unhandled;
+        };
+
+        if let Some(name) = package_name.as_constant_string() {
+            return match self.resolve_dependency(issuer, name) {
+                Ok(location) => PnpResolution::Resolved(vec![(name.clone(), location)]),
+                Err(message) => PnpResolution::Error(message),
+            };
+        }
+
+        // A dynamic request can only match the declared dependencies.
+        let mut packages = issuer
+            .dependencies
+            .keys()
+            .filter(|name| package_name.is_match(name))
+            .filter_map(|name| {
+                self.resolve_dependency(issuer, name)
+                    .ok()
+                    .map(|location| (name.clone(), location))
+            })
+            .collect::<Vec<_>>();
+        packages.sort();
+        PnpResolution::Resolved(packages)
+    }
+}
+
+/// Returns the path of `lookup_path` relative to the root of the file system `fs` of a
+/// Plug'n'Play manifest. Paths inside of zip archives are mapped to the path of the archive, e.g.
+/// `.yarn/cache/foo-npm-1.0.0-abc.zip/node_modules/foo`.
+pub(super) async fn pnp_issuer_path(
+    lookup_path: &FileSystemPath,
+    fs: ResolvedVc<Box<dyn FileSystem>>,
+) -> Result<Option<RcStr>> {
+    if lookup_path.fs == fs {
+        return Ok(Some(lookup_path.path.clone()));
+    }
+    if let Some(zip_fs) = ResolvedVc::try_downcast_type::<ZipFileSystem>(lookup_path.fs) {
+        let archive = &zip_fs.await?.archive;
+        if archive.fs == fs {
+            return Ok(join_path(&archive.path, &lookup_path.path).map(RcStr::from));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the path of a package location returned by [PnpManifest::resolve] on the file system
+/// `fs` of the manifest, or in the zip archive containing it.
+pub(super) async fn pnp_location_path(
+    location: &str,
+    fs: ResolvedVc<Box<dyn FileSystem>>,
+) -> Result<FileSystemPath> {
+    let root = fs.root().owned().await?;
+    let archive_end = location
+        .find(".zip/")
+        .map(|index| index + ".zip".len())
+        .or_else(|| location.ends_with(".zip").then_some(location.len()));
+    let Some(archive_end) = archive_end else {
+        return root.join(location);
+    };
+    let archive = root.join(&location[..archive_end])?;
+    ZipFileSystem::new(archive)
+        .root()
+        .owned()
+        .await?
+        .join(location[archive_end..].trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_rcstr::rcstr;
+
+    use super::*;
+
+    const DATA: &str = r#"{
+        "dependencyTreeRoots": [{ "name": "app", "reference": "workspace:." }],
+        "enableTopLevelFallback": true,
+        "fallbackExclusionList": [["strict", ["npm:1.0.0"]]],
+        "fallbackPool": [["hoisted", "npm:1.0.0"]],
+        "ignorePatternData": "^ignored(/|$)",
+        "packageRegistryData": [
+            [null, [[null, {
+                "packageLocation": "./",
+                "packageDependencies": [["app", "workspace:."], ["lodash", "npm:4.17.21"]]
+            }]]],
+            ["app", [["workspace:.", {
+                "packageLocation": "./",
+                "packageDependencies": [
+                    ["app", "workspace:."],
+                    ["lodash", "npm:4.17.21"],
+                    ["underscore", ["lodash", "npm:4.17.21"]],
+                    ["react", null]
+                ]
+            }]]],
+            ["hoisted", [["npm:1.0.0", {
+                "packageLocation": "./.yarn/cache/hoisted-npm-1.0.0-abc.zip/node_modules/hoisted/",
+                "packageDependencies": []
+            }]]],
+            ["lodash", [["npm:4.17.21", {
+                "packageLocation": "./.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash/",
+                "packageDependencies": [["lodash", "npm:4.17.21"], ["react", null]]
+            }]]],
+            ["strict", [["npm:1.0.0", {
+                "packageLocation": "./.yarn/cache/strict-npm-1.0.0-abc.zip/node_modules/strict/",
+                "packageDependencies": [["strict", "npm:1.0.0"]]
+            }]]],
+            ["plugin", [["virtual:abc#npm:1.0.0", {
+                "packageLocation": "./.yarn/__virtual__/plugin-virtual-abc/0/cache/plugin-npm-1.0.0-abc.zip/node_modules/plugin/",
+                "packageDependencies": [["plugin", "virtual:abc#npm:1.0.0"], ["lodash", "npm:4.17.21"]]
+            }]]]
+        ]
+    }"#;
+
+    fn manifest() -> PnpManifest {
+        PnpManifest::new(parse_json_with_source_context(DATA).unwrap(), "project")
+    }
+
+    fn resolve(issuer: &str, name: &str) -> PnpResolution {
+        manifest().resolve(issuer, &Pattern::Constant(name.into()))
+    }
+
+    #[test]
+    fn resolves_declared_dependencies() {
+        assert_eq!(
+            resolve("project/src", "lodash"),
+            PnpResolution::Resolved(vec![(
+                rcstr!("lodash"),
+                rcstr!("project/.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash")
+            )])
+        );
+        assert_eq!(
+            resolve("project/src", "underscore"),
+            PnpResolution::Resolved(vec![(
+                rcstr!("underscore"),
+                rcstr!("project/.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash")
+            )])
+        );
+        assert_eq!(
+            resolve(
+                "project/.yarn/cache/plugin-npm-1.0.0-abc.zip/node_modules/plugin/lib",
+                "lodash"
+            ),
+            PnpResolution::Resolved(vec![(
+                rcstr!("lodash"),
+                rcstr!("project/.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash")
+            )])
+        );
+    }
+
+    fn error(issuer: &str, name: &str) -> String {
+        match resolve(issuer, name) {
+            PnpResolution::Error(message) => message,
+            resolution => panic!("expected an error, got {resolution:?}"),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_top_level_dependencies() {
+        assert_eq!(
+            resolve(
+                "project/.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash",
+                "hoisted"
+            ),
+            PnpResolution::Resolved(vec![(
+                rcstr!("hoisted"),
+                rcstr!("project/.yarn/cache/hoisted-npm-1.0.0-abc.zip/node_modules/hoisted")
+            )])
+        );
+        assert!(
+            error(
+                "project/.yarn/cache/strict-npm-1.0.0-abc.zip/node_modules/strict",
+                "lodash"
+            )
+            .starts_with("strict tried to access lodash, but it isn't declared")
+        );
+    }
+
+    #[test]
+    fn explains_dependency_rules() {
+        assert!(
+            error("project/src", "express")
+                .starts_with("Your application tried to access express, but it isn't declared")
+        );
+        assert!(
+            error("project/src", "react")
+                .starts_with("Your application tried to access react (a peer dependency)")
+        );
+        assert!(
+            error(
+                "project/.yarn/cache/lodash-npm-4.17.21-abc.zip/node_modules/lodash",
+                "react"
+            )
+            .starts_with("lodash tried to access react (a peer dependency)")
+        );
+    }
+
+    #[test]
+    fn ignores_paths_outside_of_the_dependency_tree() {
+        assert_eq!(resolve("other", "lodash"), PnpResolution::Unhandled);
+        assert_eq!(
+            resolve("project/ignored/src", "lodash"),
+            PnpResolution::Unhandled
+        );
+    }
+
+    #[test]
+    fn extracts_inlined_runtime_state() {
+        let source = "#!/usr/bin/env node\n/* eslint-disable */\n\"use strict\";\n\nconst \
+                      RAW_RUNTIME_STATE =\n'{\\\n  \"__info\": [\"it\\'s generated\"]\\\n}';\n";
+        assert_eq!(
+            extract_runtime_state(source).as_deref(),
+            Some("{  \"__info\": [\"it's generated\"]}")
+        );
+    }
+
+    #[test]
+    fn devirtualizes_paths() {
+        assert_eq!(
+            devirtualize(".yarn/__virtual__/foo-virtual-abc/0/cache/foo.zip/node_modules/foo")
+                .as_deref(),
+            Some(".yarn/cache/foo.zip/node_modules/foo")
+        );
+        assert_eq!(
+            devirtualize(".yarn/__virtual__/foo-virtual-abc/1/packages/foo").as_deref(),
+            Some("packages/foo")
+        );
+        assert_eq!(devirtualize("packages/foo"), None);
+    }
+}
@@ -0,0 +1,873 @@
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use lightningcss::{
+    properties::{
+        Property,
+        animation::AnimationName,
+        custom::{Token, TokenList, TokenOrValue},
+        list::{CounterStyle, ListStyleType},
+    },
+    rules::{CssRule, CssRuleList, keyframes::KeyframesName, style::StyleRule},
+    selector::{Component, Selector},
+    stylesheet::{ParserOptions, PrinterOptions, StyleSheet},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{NonLocalValue, ResolvedVc, TaskInput, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::ChunkItem,
+    module_graph::style_groups::StyleGroups,
+    output::{OutputAsset, OutputAssets, OutputAssetsReference, OutputAssetsWithReferenced},
+    source::Source,
+};
+
+use crate::chunk::{CssChunk, CssChunkItem};
+
+#[derive(
+    TaskInput, Debug, Clone, PartialEq, Eq, Hash, NonLocalValue, TraceRawVcs, Encode, Decode,
+)]
+pub struct CriticalCssConfig {
+    /// Only markup before this marker is considered to be above the fold. When the marker is
+    /// missing, the whole document is considered.
+    pub fold_marker: RcStr,
+    /// The maximum number of bytes of CSS that are inlined into the HTML. Chunk items that would
+    /// exceed the budget are only loaded via their deferred chunk.
+    pub max_inline_size: usize,
+}
+
+impl Default for CriticalCssConfig {
+    fn default() -> Self {
+        Self {
+            fold_marker: rcstr!("<!-- turbopack:fold -->"),
+            // Roughly what fits into the initial TCP congestion window.
+            max_inline_size: 14 * 1024,
+        }
+    }
+}
+
+/// The result of matching the CSS chunks of a page against its static HTML.
+#[turbo_tasks::value(shared)]
+pub struct CriticalCss {
+    /// The minified CSS rules that are needed to render the markup above the fold.
+    pub code: RcStr,
+    pub chunks: Vec<CriticalCssChunk>,
+}
+
+#[derive(Clone, Debug)]
+#[turbo_tasks::value(shared)]
+pub struct CriticalCssChunk {
+    pub chunk: ResolvedVc<CssChunk>,
+    /// The number of rules that have been inlined, including rules nested in at-rules and style
+    /// rules.
+    pub critical_rules: usize,
+    /// The number of rules in the chunk, including nested rules.
+    pub total_rules: usize,
+    /// The index of the shared style group when the chunk content is a style group, see
+    /// [StyleGroups].
+    pub style_group: Option<usize>,
+}
+
+impl CriticalCssChunk {
+    /// A chunk only needs to be loaded when not all of its rules have been inlined.
+    pub fn is_deferred(&self) -> bool {
+        self.critical_rules < self.total_rules
+    }
+}
+
+/// Inlines the CSS rules that are needed for the markup above the fold into an entry HTML and
+/// defer-loads the CSS chunks that contain the remaining rules.
+#[turbo_tasks::value]
+pub struct CriticalCssHtmlAsset {
+    path: FileSystemPath,
+    html: ResolvedVc<Box<dyn Source>>,
+    chunks: ResolvedVc<OutputAssets>,
+    style_groups: ResolvedVc<StyleGroups>,
+    config: CriticalCssConfig,
+}
+
+#[turbo_tasks::value_impl]
+impl CriticalCssHtmlAsset {
+    /// Creates a new HTML asset at `path` from the static `html` source. `chunks` are the output
+    /// assets of the page's chunk group; only CSS chunks are considered.
+    #[turbo_tasks::function]
+    pub fn new(
+        path: FileSystemPath,
+        html: ResolvedVc<Box<dyn Source>>,
+        chunks: ResolvedVc<OutputAssets>,
+        style_groups: ResolvedVc<StyleGroups>,
+        config: CriticalCssConfig,
+    ) -> Vc<Self> {
+        CriticalCssHtmlAsset {
+            path,
+            html,
+            chunks,
+            style_groups,
+            config,
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn html_code(&self) -> Result<Vc<RcStr>> {
+        let content = self.html.content().file_content().await?;
+        Ok(Vc::cell(match &*content {
+            FileContent::Content(file) => file.content().to_str()?.into_owned().into(),
+            FileContent::NotFound => RcStr::default(),
+        }))
+    }
+
+    #[turbo_tasks::function]
+    pub async fn critical_css(self: Vc<Self>) -> Result<Vc<CriticalCss>> {
+        let this = self.await?;
+        let html = self.html_code().await?;
+        let markup = StaticMarkup::parse(&html, &this.config.fold_marker);
+
+        // Chunk items that were grouped into a shared chunk, mapped to the index of their style
+        // group.
+        let style_groups = this.style_groups.await?;
+        let mut style_group_indices = FxHashMap::default();
+        let shared_chunk_items = style_groups
+            .shared_chunk_items
+            .iter()
+            .map(|(chunk_item, batch)| {
+                let len = style_group_indices.len();
+                let index = *style_group_indices.entry(*batch).or_insert(len);
+                (chunk_item.chunk_item, index)
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        let mut code = String::new();
+        let mut chunks = Vec::new();
+        for &asset in this.chunks.await?.iter() {
+            let Some(chunk) = ResolvedVc::try_downcast_type::<CssChunk>(asset) else {
+                continue;
+            };
+            let mut critical_rules = 0;
+            let mut total_rules = 0;
+            let mut style_group = None;
+            for &chunk_item in chunk.await?.content.await?.chunk_items.iter() {
+                if let Some(&index) =
+                    shared_chunk_items.get(&ResolvedVc::upcast::<Box<dyn ChunkItem>>(chunk_item))
+                {
+                    style_group = Some(index);
+                }
+                let (critical, total) = extract_critical_rules(
+                    chunk_item,
+                    &markup,
+                    this.config.max_inline_size,
+                    &mut code,
+                )
+                .await?;
+                critical_rules += critical;
+                total_rules += total;
+            }
+            chunks.push(CriticalCssChunk {
+                chunk,
+                critical_rules,
+                total_rules,
+                style_group,
+            });
+        }
+
+        Ok(CriticalCss {
+            code: code.into(),
+            chunks,
+        }
+        .cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn deferred_chunk_paths(self: Vc<Self>) -> Result<Vc<Vec<RcStr>>> {
+        let this = self.await?;
+        let context_path = this.path.parent();
+        let mut paths = Vec::new();
+        for chunk in self.critical_css().await?.chunks.iter() {
+            if !chunk.is_deferred() {
+                continue;
+            }
+            let chunk_path = chunk.chunk.path().await?;
+            if let Some(relative_path) = context_path.get_relative_path_to(&chunk_path) {
+                paths.push(relative_path);
+            }
+        }
+        Ok(Vc::cell(paths))
+    }
+
+    /// The manifest that describes which rules have been inlined and which chunks are deferred.
+    #[turbo_tasks::function]
+    pub fn manifest(self: ResolvedVc<Self>) -> Vc<CriticalCssManifestAsset> {
+        CriticalCssManifestAsset { html: self }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for CriticalCssHtmlAsset {
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<OutputAssetsWithReferenced>> {
+        let mut assets = Vec::new();
+        for chunk in self.critical_css().await?.chunks.iter() {
+            if chunk.is_deferred() {
+                assets.push(ResolvedVc::upcast(chunk.chunk));
+            }
+        }
+        Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(assets)))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for CriticalCssHtmlAsset {
+    #[turbo_tasks::function]
+    fn path(&self) -> Vc<FileSystemPath> {
+        self.path.clone().cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for CriticalCssHtmlAsset {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let html = self.html_code().await?;
+        let critical_css = self.critical_css().await?;
+        let deferred_chunk_paths = self.deferred_chunk_paths().await?;
+
+        let mut head = String::new();
+        if !critical_css.code.is_empty() {
+            head.push_str("<style data-turbopack-critical>");
+            // The printed CSS never contains a closing style tag unless it's part of a string.
+            head.push_str(&critical_css.code.replace("</style", "<\\/style"));
+            head.push_str("</style>\n");
+        }
+        for path in deferred_chunk_paths.iter() {
+            head.push_str(&format!(
+                "<link data-turbopack rel=\"preload\" as=\"style\" href=\"{path}\" \
+                 onload=\"this.onload=null;this.rel='stylesheet'\">\n<noscript><link \
+                 data-turbopack rel=\"stylesheet\" href=\"{path}\"></noscript>\n"
+            ));
+        }
+
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(inject_into_head(&html, &head))).cell(),
+        ))
+    }
+}
+
+/// A JSON manifest for a [CriticalCssHtmlAsset] that lists the inlined and deferred CSS.
+#[turbo_tasks::value]
+pub struct CriticalCssManifestAsset {
+    html: ResolvedVc<CriticalCssHtmlAsset>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CriticalCssManifest<'a> {
+    html: &'a str,
+    inlined_bytes: usize,
+    chunks: Vec<CriticalCssManifestChunk>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CriticalCssManifestChunk {
+    path: RcStr,
+    critical_rules: usize,
+    total_rules: usize,
+    deferred: bool,
+    style_group: Option<usize>,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for CriticalCssManifestAsset {}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for CriticalCssManifestAsset {
+    #[turbo_tasks::function]
+    async fn path(&self) -> Result<Vc<FileSystemPath>> {
+        let html_path = self.html.path().await?;
+        Ok(html_path
+            .parent()
+            .join(&format!("{}.critical.json", html_path.file_name()))?
+            .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for CriticalCssManifestAsset {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let html_path = self.html.path().await?;
+        let context_path = html_path.parent();
+        let critical_css = self.html.critical_css().await?;
+
+        let mut chunks = Vec::with_capacity(critical_css.chunks.len());
+        for chunk in critical_css.chunks.iter() {
+            let chunk_path = chunk.chunk.path().await?;
+            chunks.push(CriticalCssManifestChunk {
+                path: context_path
+                    .get_relative_path_to(&chunk_path)
+                    .unwrap_or_else(|| chunk_path.path.clone()),
+                critical_rules: chunk.critical_rules,
+                total_rules: chunk.total_rules,
+                deferred: chunk.is_deferred(),
+                style_group: chunk.style_group,
+            });
+        }
+
+        let manifest = CriticalCssManifest {
+            html: html_path.file_name(),
+            inlined_bytes: critical_css.code.len(),
+            chunks,
+        };
+
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(serde_json::to_string_pretty(&manifest)?)).cell(),
+        ))
+    }
+}
+
+/// Appends the critical rules of `chunk_item` to `code` and returns the number of critical and
+/// total rules, see [count_rules].
+async fn extract_critical_rules(
+    chunk_item: ResolvedVc<Box<dyn CssChunkItem>>,
+    markup: &StaticMarkup,
+    max_inline_size: usize,
+    code: &mut String,
+) -> Result<(usize, usize)> {
+    let content = chunk_item.content().await?;
+    let css = content.inner_code.to_str()?;
+    let Ok(mut stylesheet) = StyleSheet::parse(
+        &css,
+        ParserOptions {
+            error_recovery: true,
+            ..Default::default()
+        },
+    ) else {
+        // Unparsable CSS is never inlined, the chunk is loaded as usual.
+        return Ok((0, 0));
+    };
+
+    let total = count_rules(&stylesheet.rules);
+    // Items with an import context (layers, media, supports) are wrapped by the chunk and can't
+    // be inlined without the wrapper.
+    if content.import_context.is_some() {
+        return Ok((0, total));
+    }
+    let critical = retain_critical_rules(&mut stylesheet.rules, markup);
+    if critical == 0 {
+        return Ok((0, total));
+    }
+
+    let printed = stylesheet.to_css(PrinterOptions {
+        minify: true,
+        ..Default::default()
+    })?;
+    if code.len() + printed.code.len() > max_inline_size {
+        return Ok((0, total));
+    }
+    code.push_str(&printed.code);
+    Ok((critical, total))
+}
+
+/// Removes all rules that don't apply to the static markup and returns the number of remaining
+/// rules, see [count_rules]. `@keyframes` and `@counter-style` rules are kept when a remaining rule
+/// references them by name.
+fn retain_critical_rules(rules: &mut CssRuleList<'_>, markup: &StaticMarkup) -> usize {
+    retain_matching_rules(rules, markup);
+    let mut names = FxHashSet::default();
+    collect_referenced_names(rules, &mut names);
+    retain_referenced_rules(rules, &names);
+    count_rules(rules)
+}
+
+/// Counts the rules at every nesting depth, so that a chunk of which only nested rules (e.g. in
+/// `@media`) have been removed is not considered to be fully inlined.
+fn count_rules(rules: &CssRuleList<'_>) -> usize {
+    rules
+        .0
+        .iter()
+        .map(|rule| {
+            1 + match rule {
+                CssRule::Style(style) => count_rules(&style.rules),
+                CssRule::Nesting(nesting) => count_rules(&nesting.style.rules),
+                CssRule::Media(media) => count_rules(&media.rules),
+                CssRule::Supports(supports) => count_rules(&supports.rules),
+                CssRule::LayerBlock(layer) => count_rules(&layer.rules),
+                CssRule::Container(container) => count_rules(&container.rules),
+                CssRule::Scope(scope) => count_rules(&scope.rules),
+                CssRule::StartingStyle(starting_style) => count_rules(&starting_style.rules),
+                CssRule::MozDocument(document) => count_rules(&document.rules),
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+/// Returns false when no rule is left.
+fn retain_matching_rules(rules: &mut CssRuleList<'_>, markup: &StaticMarkup) -> bool {
+    rules.0.retain_mut(|rule| match rule {
+        CssRule::Style(style) => retain_matching_style_rule(style, markup),
+        CssRule::Nesting(nesting) => retain_matching_style_rule(&mut nesting.style, markup),
+        CssRule::Media(media) => retain_matching_rules(&mut media.rules, markup),
+        CssRule::Supports(supports) => retain_matching_rules(&mut supports.rules, markup),
+        CssRule::LayerBlock(layer) => retain_matching_rules(&mut layer.rules, markup),
+        CssRule::Container(container) => retain_matching_rules(&mut container.rules, markup),
+        CssRule::Scope(scope) => retain_matching_rules(&mut scope.rules, markup),
+        CssRule::StartingStyle(starting_style) => {
+            retain_matching_rules(&mut starting_style.rules, markup)
+        }
+        CssRule::MozDocument(document) => retain_matching_rules(&mut document.rules, markup),
+        // Filtered by `retain_referenced_rules` once it's known which names are used.
+        CssRule::Keyframes(_) | CssRule::CounterStyle(_) => true,
+        // Imports, fonts and layer ordering affect the rendering of everything above the fold.
+        CssRule::Import(_)
+        | CssRule::FontFace(_)
+        | CssRule::FontPaletteValues(_)
+        | CssRule::FontFeatureValues(_)
+        | CssRule::LayerStatement(_)
+        | CssRule::Namespace(_)
+        | CssRule::Property(_)
+        | CssRule::CustomMedia(_)
+        | CssRule::ViewTransition(_)
+        | CssRule::NestedDeclarations(_) => true,
+        _ => false,
+    });
+    !rules.0.is_empty()
+}
+
+fn retain_matching_style_rule(style: &mut StyleRule<'_>, markup: &StaticMarkup) -> bool {
+    if !style.selectors.0.iter().any(|s| markup.matches(s)) {
+        // Nested rules are relative to this rule's selector, so they can't match either.
+        return false;
+    }
+    retain_matching_rules(&mut style.rules, markup);
+    true
+}
+
+/// Collects the names of keyframes and counter styles which are used by the declarations in
+/// `rules`.
+fn collect_referenced_names(rules: &CssRuleList<'_>, names: &mut FxHashSet<String>) {
+    for rule in &rules.0 {
+        match rule {
+            CssRule::Style(style) => collect_style_rule_names(style, names),
+            CssRule::Nesting(nesting) => collect_style_rule_names(&nesting.style, names),
+            CssRule::Media(media) => collect_referenced_names(&media.rules, names),
+            CssRule::Supports(supports) => collect_referenced_names(&supports.rules, names),
+            CssRule::LayerBlock(layer) => collect_referenced_names(&layer.rules, names),
+            CssRule::Container(container) => collect_referenced_names(&container.rules, names),
+            CssRule::Scope(scope) => collect_referenced_names(&scope.rules, names),
+            CssRule::StartingStyle(starting_style) => {
+                collect_referenced_names(&starting_style.rules, names)
+            }
+            CssRule::MozDocument(document) => collect_referenced_names(&document.rules, names),
+            CssRule::NestedDeclarations(nested) => {
+                collect_property_names(
+                    nested
+                        .declarations
+                        .declarations
+                        .iter()
+                        .chain(&nested.declarations.important_declarations),
+                    names,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_style_rule_names(style: &StyleRule<'_>, names: &mut FxHashSet<String>) {
+    collect_property_names(
+        style
+            .declarations
+            .declarations
+            .iter()
+            .chain(&style.declarations.important_declarations),
+        names,
+    );
+    collect_referenced_names(&style.rules, names);
+}
+
+fn collect_property_names<'a, 'i: 'a>(
+    properties: impl IntoIterator<Item = &'a Property<'i>>,
+    names: &mut FxHashSet<String>,
+) {
+    fn add_animation_name(name: &AnimationName<'_>, names: &mut FxHashSet<String>) {
+        match name {
+            AnimationName::Ident(ident) => {
+                names.insert(ident.0.to_string());
+            }
+            AnimationName::String(string) => {
+                names.insert(string.0.to_string());
+            }
+            AnimationName::None => {}
+        }
+    }
+
+    fn add_list_style_type(list_style_type: &ListStyleType<'_>, names: &mut FxHashSet<String>) {
+        if let ListStyleType::CounterStyle(CounterStyle::Name(name)) = list_style_type {
+            names.insert(name.0.to_string());
+        }
+    }
+
+    // Properties which aren't parsed (e.g. because they contain `var()`) and custom properties
+    // might reference any name, so all identifiers in them are considered.
+    fn add_tokens(tokens: &TokenList<'_>, names: &mut FxHashSet<String>) {
+        for token in &tokens.0 {
+            match token {
+                TokenOrValue::Token(Token::Ident(name) | Token::String(name)) => {
+                    names.insert(name.to_string());
+                }
+                TokenOrValue::AnimationName(name) => add_animation_name(name, names),
+                TokenOrValue::Function(function) => add_tokens(&function.arguments, names),
+                TokenOrValue::Var(variable) => {
+                    if let Some(fallback) = &variable.fallback {
+                        add_tokens(fallback, names);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for property in properties {
+        match property {
+            Property::Animation(animations, _) => {
+                for animation in animations {
+                    add_animation_name(&animation.name, names);
+                }
+            }
+            Property::AnimationName(animation_names, _) => {
+                for name in animation_names {
+                    add_animation_name(name, names);
+                }
+            }
+            Property::ListStyleType(list_style_type) => add_list_style_type(list_style_type, names),
+            Property::ListStyle(list_style) => {
+                add_list_style_type(&list_style.list_style_type, names)
+            }
+            Property::Unparsed(unparsed) => add_tokens(&unparsed.value, names),
+            Property::Custom(custom) => add_tokens(&custom.value, names),
+            _ => {}
+        }
+    }
+}
+
+/// Removes `@keyframes` and `@counter-style` rules whose names aren't in `names`. Returns false
+/// when no rule is left.
+fn retain_referenced_rules(rules: &mut CssRuleList<'_>, names: &FxHashSet<String>) -> bool {
+    rules.0.retain_mut(|rule| match rule {
+        CssRule::Keyframes(keyframes) => names.contains(match &keyframes.name {
+            KeyframesName::Ident(ident) => &*ident.0,
+            KeyframesName::Custom(name) => &**name,
+        }),
+        CssRule::CounterStyle(counter_style) => names.contains(&*counter_style.name.0),
+        CssRule::Style(style) => {
+            retain_referenced_rules(&mut style.rules, names);
+            true
+        }
+        CssRule::Nesting(nesting) => {
+            retain_referenced_rules(&mut nesting.style.rules, names);
+            true
+        }
+        CssRule::Media(media) => retain_referenced_rules(&mut media.rules, names),
+        CssRule::Supports(supports) => retain_referenced_rules(&mut supports.rules, names),
+        CssRule::LayerBlock(layer) => retain_referenced_rules(&mut layer.rules, names),
+        CssRule::Container(container) => retain_referenced_rules(&mut container.rules, names),
+        CssRule::Scope(scope) => retain_referenced_rules(&mut scope.rules, names),
+        CssRule::StartingStyle(starting_style) => {
+            retain_referenced_rules(&mut starting_style.rules, names)
+        }
+        CssRule::MozDocument(document) => retain_referenced_rules(&mut document.rules, names),
+        _ => true,
+    });
+    !rules.0.is_empty()
+}
+
+#[derive(Debug, Default)]
+struct StaticElement {
+    tag: String,
+    id: Option<String>,
+    classes: FxHashSet<String>,
+    attributes: FxHashSet<String>,
+}
+
+/// The elements of a static HTML document above the fold. This is not a full HTML parser, it
+/// only extracts what's needed to match selectors conservatively.
+#[derive(Debug, Default)]
+struct StaticMarkup {
+    elements: Vec<StaticElement>,
+}
+
+impl StaticMarkup {
+    fn parse(html: &str, fold_marker: &str) -> Self {
+        let html = match html.find(fold_marker) {
+            Some(end) if !fold_marker.is_empty() => &html[..end],
+            _ => html,
+        };
+
+        let mut elements = vec![];
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+                continue;
+            }
+            let end = rest.find('>').unwrap_or(rest.len());
+            let tag = &rest[..end];
+            rest = &rest[end.min(rest.len())..];
+            if tag.starts_with(['/', '!', '?']) {
+                continue;
+            }
+            let Some(element) = parse_element(tag) else {
+                continue;
+            };
+            let is_raw_text = matches!(&*element.tag, "script" | "style");
+            elements.push(element);
+            if is_raw_text {
+                // Skip over the content of raw text elements as it's not markup.
+                let close = format!("</{}", elements.last().unwrap().tag);
+                rest = rest.find(&close).map_or("", |end| &rest[end..]);
+            }
+        }
+
+        Self { elements }
+    }
+
+    /// Returns true when every compound selector of `selector` matches some element. The
+    /// relationship between the compounds is ignored, which may include more rules than necessary
+    /// but never too few.
+    fn matches(&self, selector: &Selector<'_>) -> bool {
+        let mut compound = Vec::new();
+        for component in selector.iter_raw_match_order() {
+            if let Component::Combinator(_) = component {
+                if !self.matches_compound(&compound) {
+                    return false;
+                }
+                compound.clear();
+            } else {
+                compound.push(component);
+            }
+        }
+        self.matches_compound(&compound)
+    }
+
+    fn matches_compound(&self, compound: &[&Component<'_>]) -> bool {
+        self.elements.iter().any(|element| {
+            compound
+                .iter()
+                .all(|component| self.matches_component(element, component))
+        })
+    }
+
+    fn matches_component(&self, element: &StaticElement, component: &Component<'_>) -> bool {
+        match component {
+            Component::LocalName(local) => local.lower_name.0.eq_ignore_ascii_case(&element.tag),
+            Component::ID(id) => element.id.as_deref() == Some(&*id.0),
+            Component::Class(class) => element.classes.contains(&*class.0),
+            Component::AttributeInNoNamespaceExists {
+                local_name_lower, ..
+            } => element.attributes.contains(&*local_name_lower.0),
+            Component::AttributeInNoNamespace { local_name, .. } => {
+                element.attributes.contains(&*local_name.0)
+            }
+            Component::AttributeOther(attr) => {
+                element.attributes.contains(&*attr.local_name_lower.0)
+            }
+            Component::Root => element.tag == "html",
+            Component::Is(selectors)
+            | Component::Where(selectors)
+            | Component::Any(_, selectors) => selectors.iter().any(|s| self.matches(s)),
+            // Pseudo classes, pseudo elements, negations and namespaces depend on state or
+            // structure that isn't known statically. Assume they match.
+            _ => true,
+        }
+    }
+}
+
+fn parse_element(tag: &str) -> Option<StaticElement> {
+    let tag = tag.strip_suffix('/').unwrap_or(tag);
+    let name_end = tag
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(tag.len());
+    let name = &tag[..name_end];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut element = StaticElement {
+        tag: name.to_ascii_lowercase(),
+        ..Default::default()
+    };
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let attr_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let attr = rest[..attr_end].to_ascii_lowercase();
+        rest = rest[attr_end..].trim_start();
+        let mut value = "";
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (v, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], &inner[(end + 1).min(inner.len())..])
+                }
+                _ => {
+                    let end = after_eq
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            value = v;
+            rest = remaining.trim_start();
+        }
+        match &*attr {
+            "id" => element.id = Some(value.to_string()),
+            "class" => element
+                .classes
+                .extend(value.split_ascii_whitespace().map(|c| c.to_string())),
+            _ => {}
+        }
+        if !attr.is_empty() {
+            element.attributes.insert(attr);
+        }
+    }
+    Some(element)
+}
+
+/// Inserts `head` before the closing `</head>` tag, or at the start of the document when there is
+/// none.
+fn inject_into_head(html: &str, head: &str) -> String {
+    let index = html
+        .find("</head>")
+        .or_else(|| html.find("</HEAD>"))
+        .unwrap_or(0);
+    let mut result = String::with_capacity(html.len() + head.len());
+    result.push_str(&html[..index]);
+    result.push_str(head);
+    result.push_str(&html[index..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use lightningcss::stylesheet::{ParserOptions, StyleSheet};
+
+    use super::{StaticMarkup, count_rules, inject_into_head, retain_critical_rules};
+
+    const HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head><style>.from-style { color: red }</style></head>
+<body>
+  <header id="top" class="header header--dark"><a href="/" data-home>Home</a></header>
+  <!-- <div class="commented"></div> -->
+  <!-- turbopack:fold -->
+  <footer class="footer"></footer>
+</body>
+</html>"#;
+
+    fn critical(css: &str) -> String {
+        let markup = StaticMarkup::parse(HTML, "<!-- turbopack:fold -->");
+        let mut ss = StyleSheet::parse(css, ParserOptions::default()).unwrap();
+        retain_critical_rules(&mut ss.rules, &markup);
+        ss.to_css(Default::default()).unwrap().code
+    }
+
+    #[test]
+    fn keeps_rules_above_the_fold() {
+        let css = critical(
+            ".header { color: red } .footer { color: blue } #top a:hover { color: green } \
+             [data-home] { margin: 0 } .commented, .from-style { display: none }",
+        );
+        assert!(css.contains(".header"));
+        assert!(css.contains("#top a:hover"));
+        assert!(css.contains("[data-home]"));
+        assert!(!css.contains(".footer"));
+        assert!(!css.contains(".commented"));
+        assert!(!css.contains(".from-style"));
+    }
+
+    #[test]
+    fn filters_nested_rules() {
+        let css = critical(
+            "@media (min-width: 600px) { .header--dark { color: white } .footer { color: black } \
+             } @media print { .footer { display: none } } @keyframes fade { from { opacity: 0 } }",
+        );
+        assert!(css.contains(".header--dark"));
+        assert!(!css.contains(".footer"));
+        assert!(!css.contains("print"));
+        assert!(!css.contains("@keyframes"));
+    }
+
+    #[test]
+    fn counts_removed_nested_rules() {
+        let markup = StaticMarkup::parse(HTML, "<!-- turbopack:fold -->");
+        let mut ss = StyleSheet::parse(
+            "@media (min-width: 600px) { .header { color: white } @supports (display: grid) { \
+             .footer { display: grid } } } @layer base { .header { margin: 0 } .footer { margin: \
+             0 } }",
+            ParserOptions::default(),
+        )
+        .unwrap();
+        let total = count_rules(&ss.rules);
+        assert_eq!(total, 7);
+        // Both top-level rules are kept, but the chunk is still needed for the nested rules
+        // which have been removed.
+        let critical = retain_critical_rules(&mut ss.rules, &markup);
+        assert_eq!(ss.rules.0.len(), 2);
+        assert_eq!(critical, 4);
+        assert!(critical < total);
+    }
+
+    #[test]
+    fn keeps_nested_rules() {
+        let css = critical(
+            ".header { color: red; & a { color: blue } & .footer { color: black } } .footer { & a \
+             { color: white } }",
+        );
+        assert!(css.contains(".header"));
+        assert!(css.contains("& a"));
+        assert!(!css.contains(".footer"));
+    }
+
+    #[test]
+    fn keeps_referenced_at_rules() {
+        let css = critical(
+            "@import \"fonts.css\"; .header { animation: 1s fade-in } [data-home] { list-style: \
+             inside stars } .footer { animation-name: slide } @keyframes fade-in { from { \
+             opacity: 0 } } @keyframes slide { from { left: 0 } } @counter-style stars { system: \
+             cyclic; symbols: \"*\" } @counter-style dots { system: cyclic; symbols: \".\" }",
+        );
+        assert!(css.contains("@import"));
+        assert!(css.contains("@keyframes fade-in"));
+        assert!(css.contains("@counter-style stars"));
+        assert!(!css.contains("@keyframes slide"));
+        assert!(!css.contains("@counter-style dots"));
+    }
+
+    #[test]
+    fn keeps_at_rules_referenced_through_variables() {
+        let css = critical(
+            ".header { --name: pulse; animation: 1s var(--name) } @keyframes pulse { from { \
+             opacity: 0 } }",
+        );
+        assert!(css.contains("@keyframes pulse"));
+    }
+
+    #[test]
+    fn injects_into_head() {
+        assert_eq!(
+            inject_into_head("<html><head></head></html>", "<style></style>"),
+            "<html><head><style></style></head></html>"
+        );
+        assert_eq!(
+            inject_into_head("<p>", "<style></style>"),
+            "<style></style><p>"
+        );
+    }
+}
@@ -8,6 +8,7 @@
 mod asset;
 pub mod chunk;
 mod code_gen;
+pub mod critical;
 pub mod embed;
 mod lifetime_util;
 mod module_asset;
@@ -108,7 +108,7 @@ async fn stylesheet_to_css(
         *get_lightningcss_browser_targets(environment.as_deref().copied(), handle_nesting).await?;
 
     let result = ss.to_css(PrinterOptions {
-        minify: matches!(minify_type, MinifyType::Minify { .. }),
+        minify: matches!(minify_type, MinifyType::Minify(_)),
         source_map: srcmap.as_mut(),
         targets,
         analyze_dependencies: None,
@@ -0,0 +1,827 @@
+use anyhow::{Result, bail};
+use apollo_compiler::{
+    Schema,
+    ast::{self, Definition, OperationType, Selection, Type, Value},
+    validation::{DiagnosticList, Valid},
+};
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use serde_json::{Value as JsonValue, json};
+use sha2::{Digest, Sha256};
+use swc_core::{
+    atoms::Atom,
+    common::{BytePos, DUMMY_SP, Span, util::take::Take},
+    ecma::{
+        ast::{
+            ArrayLit, Bool, Expr, ExprOrSpread, Id, Ident, IdentName, KeyValueProp, Lit,
+            MemberExpr, MemberProp, Null, Number, ObjectLit, Pat, Program, Prop, PropName,
+            PropOrSpread, TaggedTpl, Tpl, VarDecl, VarDeclKind,
+        },
+        visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{NonLocalValue, OperationValue, ResolvedVc, TaskInput, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_core::issue::{
+    Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource, OptionStyledString,
+    StyledString,
+};
+use turbopack_ecmascript::{CustomTransformer, TransformContext};
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    NonLocalValue,
+    OperationValue,
+    TaskInput,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphqlConfig {
+    /// Path to the schema (SDL) relative to the project. Documents are validated against it.
+    pub schema: Option<String>,
+    /// The template tags which are precompiled. Defaults to `gql` and `graphql`.
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub output: GraphqlOutput,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    NonLocalValue,
+    OperationValue,
+    TaskInput,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphqlOutput {
+    /// Replaces templates with the document AST, like `graphql-tag` would create at runtime.
+    #[default]
+    Document,
+    /// Like `Document`, but without the query text and with a `documentId` containing the
+    /// SHA-256 hash of the source text of the document, including the interpolated fragments, to
+    /// be used for persisted queries. The fragments need to be defined in a `const` of the same
+    /// module, as the hash is computed at build time.
+    PersistedHash,
+}
+
+/// Precompiles `gql` tagged templates into GraphQL document ASTs.
+#[derive(Debug)]
+pub struct GraphqlTransformer {
+    schema: Option<FileSystemPath>,
+    tags: Vec<Atom>,
+    output: GraphqlOutput,
+}
+
+impl GraphqlTransformer {
+    pub fn new(config: &GraphqlConfig, project_path: &FileSystemPath) -> Result<Self> {
+        Ok(Self {
+            schema: config
+                .schema
+                .as_ref()
+                .map(|schema| project_path.join(schema))
+                .transpose()?,
+            tags: match &config.tags {
+                Some(tags) => tags.iter().map(|tag| Atom::from(tag.as_str())).collect(),
+                None => vec![Atom::from("gql"), Atom::from("graphql")],
+            },
+            output: config.output,
+        })
+    }
+}
+
+#[async_trait]
+impl CustomTransformer for GraphqlTransformer {
+    #[tracing::instrument(level = tracing::Level::TRACE, name = "graphql", skip_all)]
+    async fn transform(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
+        // Reading the schema in this task makes the schema file a dependency of the module.
+        let schema = match &self.schema {
+            Some(schema) => Some(graphql_schema(schema.clone()).await?),
+            None => None,
+        };
+
+        let mut visitor = GraphqlVisitor::new(
+            &self.tags,
+            schema.as_ref().and_then(|schema| schema.schema.as_ref()),
+            self.output,
+            program,
+        );
+        program.visit_mut_with(&mut visitor);
+
+        for (message, span) in visitor.errors {
+            GraphqlIssue {
+                file_path: ctx.file_path.clone(),
+                title: rcstr!("Invalid GraphQL document"),
+                message: message.into(),
+                source: Some(IssueSource::from_swc_offsets(
+                    ctx.source,
+                    span.lo.to_u32(),
+                    span.hi.to_u32(),
+                )),
+            }
+            .resolved_cell()
+            .emit();
+        }
+
+        Ok(())
+    }
+}
+
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new", shared)]
+struct GraphqlSchema {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    schema: Option<Valid<Schema>>,
+}
+
+#[turbo_tasks::function]
+async fn graphql_schema(path: FileSystemPath) -> Result<Vc<GraphqlSchema>> {
+    let content = path.read().await?;
+    let FileContent::Content(file) = &*content else {
+        GraphqlIssue {
+            file_path: path.clone(),
+            title: rcstr!("GraphQL schema not found"),
+            message: format!("The GraphQL schema {path} does not exist.").into(),
+            source: None,
+        }
+        .resolved_cell()
+        .emit();
+        return Ok(GraphqlSchema { schema: None }.cell());
+    };
+
+    let schema =
+        match Schema::parse_and_validate(file.content().to_str()?.into_owned(), &*path.path) {
+            Ok(schema) => Some(schema),
+            Err(with_errors) => {
+                GraphqlIssue {
+                    file_path: path.clone(),
+                    title: rcstr!("Invalid GraphQL schema"),
+                    message: diagnostics_message(&with_errors.errors).into(),
+                    source: None,
+                }
+                .resolved_cell()
+                .emit();
+                None
+            }
+        };
+    Ok(GraphqlSchema { schema }.cell())
+}
+
+fn diagnostics_message(errors: &DiagnosticList) -> String {
+    errors
+        .iter()
+        .map(|diagnostic| match diagnostic.line_column_range() {
+            Some(range) => format!(
+                "{}:{}: {}",
+                range.start.line, range.start.column, diagnostic.error
+            ),
+            None => diagnostic.error.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collects the tagged templates assigned to `const`s, so that the source text of documents
+/// interpolating them is known at build time.
+struct TemplateCollector<'a> {
+    tags: &'a [Atom],
+    templates: FxHashMap<Id, Tpl>,
+}
+
+impl Visit for TemplateCollector<'_> {
+    fn visit_var_decl(&mut self, decl: &VarDecl) {
+        if decl.kind == VarDeclKind::Const {
+            for declarator in &decl.decls {
+                if let Pat::Ident(name) = &declarator.name
+                    && let Some(init) = &declarator.init
+                    && let Expr::TaggedTpl(TaggedTpl { tag, tpl, .. }) = &**init
+                    && let Expr::Ident(Ident { sym, .. }) = &**tag
+                    && self.tags.contains(sym)
+                {
+                    self.templates.insert(name.id.to_id(), (**tpl).clone());
+                }
+            }
+        }
+        decl.visit_children_with(self);
+    }
+}
+
+/// Fragments interpolated deeper than this are assumed to be cyclic.
+const MAX_INTERPOLATION_DEPTH: usize = 32;
+
+struct GraphqlVisitor<'a> {
+    tags: &'a [Atom],
+    schema: Option<&'a Valid<Schema>>,
+    output: GraphqlOutput,
+    templates: FxHashMap<Id, Tpl>,
+    errors: Vec<(String, Span)>,
+}
+
+impl<'a> GraphqlVisitor<'a> {
+    fn new(
+        tags: &'a [Atom],
+        schema: Option<&'a Valid<Schema>>,
+        output: GraphqlOutput,
+        program: &Program,
+    ) -> Self {
+        let mut collector = TemplateCollector {
+            tags,
+            templates: FxHashMap::default(),
+        };
+        program.visit_with(&mut collector);
+        Self {
+            tags,
+            schema,
+            output,
+            templates: collector.templates,
+            errors: Vec::new(),
+        }
+    }
+
+    /// The source text of the document, with interpolated documents replaced by their source
+    /// text like `graphql-tag` does at runtime. `None` if an interpolation isn't a template
+    /// assigned to a `const` of this module.
+    fn source_text(&self, tpl: &Tpl, depth: usize) -> Option<String> {
+        if depth > MAX_INTERPOLATION_DEPTH {
+            return None;
+        }
+        let mut text = String::new();
+        for (i, quasi) in tpl.quasis.iter().enumerate() {
+            text.push_str(&quasi.raw);
+            if let Some(expr) = tpl.exprs.get(i) {
+                let Expr::Ident(ident) = &**expr else {
+                    return None;
+                };
+                let fragment = self.templates.get(&ident.to_id())?;
+                text.push_str(&self.source_text(fragment, depth + 1)?);
+            }
+        }
+        Some(text)
+    }
+
+    fn compile(&mut self, tpl: &mut Tpl) -> Option<Expr> {
+        let text = tpl
+            .quasis
+            .iter()
+            .map(|quasi| &*quasi.raw)
+            .collect::<String>();
+
+        let document = match ast::Document::parse(text.as_str(), "graphql") {
+            Ok(document) => document,
+            Err(with_errors) => {
+                self.report(&with_errors.errors, tpl, &text);
+                return None;
+            }
+        };
+        let source = self.source_text(tpl, 0);
+
+        // Documents containing only fragments are validated as part of the operations using
+        // them. Fragments interpolated from other modules are only known at runtime, so such
+        // documents can't be validated.
+        if let Some(schema) = self.schema
+            && let Some(source) = &source
+            && document
+                .definitions
+                .iter()
+                .any(|definition| matches!(definition, Definition::OperationDefinition(_)))
+            && let Ok(full_document) = ast::Document::parse(source.as_str(), "graphql")
+            && let Err(with_errors) = full_document.to_executable_validate(schema)
+        {
+            self.report(&with_errors.errors, tpl, source);
+            return None;
+        }
+
+        let definitions = match definitions_to_json(&document) {
+            Ok(definitions) => definitions,
+            Err(err) => {
+                self.errors.push((err.to_string(), tpl.span));
+                return None;
+            }
+        };
+
+        let (output_key, output_value) = match (self.output, &source) {
+            (GraphqlOutput::Document, Some(source)) => (
+                "loc",
+                json_to_expr(&json!({
+                    "start": 0,
+                    "end": source.encode_utf16().count(),
+                    "source": {
+                        "body": source,
+                        "name": "GraphQL request",
+                        "locationOffset": { "line": 1, "column": 1 },
+                    },
+                })),
+            ),
+            (GraphqlOutput::Document, None) => ("loc", runtime_loc(tpl)),
+            (GraphqlOutput::PersistedHash, Some(source)) => (
+                "documentId",
+                json_to_expr(&json!(format!("{:x}", Sha256::digest(source.as_bytes())))),
+            ),
+            (GraphqlOutput::PersistedHash, None) => {
+                self.errors.push((
+                    "The hash of a persisted GraphQL document is computed at build time, so it \
+                     can only interpolate documents assigned to a `const` in the same module"
+                        .to_string(),
+                    tpl.span,
+                ));
+                return None;
+            }
+        };
+
+        let props = vec![
+            key_value("kind", json_to_expr(&json!("Document"))),
+            key_value(
+                "definitions",
+                Expr::Array(ArrayLit {
+                    span: DUMMY_SP,
+                    elems: definitions
+                        .iter()
+                        .map(|definition| Some(json_to_expr(definition).into()))
+                        .chain(tpl.exprs.iter_mut().map(|expr| {
+                            // Definitions of interpolated documents, usually fragments.
+                            Some(ExprOrSpread {
+                                spread: Some(DUMMY_SP),
+                                expr: Box::new(member(expr.take(), "definitions")),
+                            })
+                        }))
+                        .collect(),
+                }),
+            ),
+            key_value(output_key, output_value),
+        ];
+
+        Some(Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        }))
+    }
+
+    fn report(&mut self, errors: &DiagnosticList, tpl: &Tpl, text: &str) {
+        for diagnostic in errors.iter() {
+            let span = match diagnostic.line_column_range() {
+                // Offsets are only meaningful without interpolations.
+                Some(range) if tpl.exprs.is_empty() => {
+                    let lo = tpl.quasis[0].span.lo;
+                    let start = offset_of(text, range.start.line, range.start.column);
+                    let end = offset_of(text, range.end.line, range.end.column);
+                    Span::new(lo + BytePos(start as u32), lo + BytePos(end as u32))
+                }
+                _ => tpl.span,
+            };
+            self.errors.push((diagnostic.error.to_string(), span));
+        }
+    }
+}
+
+impl VisitMut for GraphqlVisitor<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        let Expr::TaggedTpl(TaggedTpl { tag, tpl, .. }) = expr else {
+            return;
+        };
+        let Expr::Ident(Ident { sym, .. }) = &**tag else {
+            return;
+        };
+        if !self.tags.contains(sym) {
+            return;
+        }
+        if let Some(compiled) = self.compile(tpl) {
+            *expr = compiled;
+        }
+    }
+}
+
+/// Converts a 1-based line and column (in chars) into a byte offset.
+fn offset_of(text: &str, line: usize, column: usize) -> usize {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    line_start
+        + text[line_start..]
+            .chars()
+            .take(column.saturating_sub(1))
+            .map(char::len_utf8)
+            .sum::<usize>()
+}
+
+/// Converts the definitions into the AST format of `graphql-js`.
+fn definitions_to_json(document: &ast::Document) -> Result<Vec<JsonValue>> {
+    document
+        .definitions
+        .iter()
+        .map(|definition| {
+            Ok(match definition {
+                Definition::OperationDefinition(operation) => json!({
+                    "kind": "OperationDefinition",
+                    "operation": match operation.operation_type {
+                        OperationType::Query => "query",
+                        OperationType::Mutation => "mutation",
+                        OperationType::Subscription => "subscription",
+                    },
+                    "name": operation.name.as_ref().map(name_to_json),
+                    "variableDefinitions": operation
+                        .variables
+                        .iter()
+                        .map(|variable| json!({
+                            "kind": "VariableDefinition",
+                            "variable": { "kind": "Variable", "name": name_to_json(&variable.name) },
+                            "type": type_to_json(&variable.ty),
+                            "defaultValue": variable.default_value.as_ref().map(|value| value_to_json(value)),
+                            "directives": directives_to_json(&variable.directives),
+                        }))
+                        .collect::<Vec<_>>(),
+                    "directives": directives_to_json(&operation.directives),
+                    "selectionSet": selection_set_to_json(&operation.selection_set),
+                }),
+                Definition::FragmentDefinition(fragment) => json!({
+                    "kind": "FragmentDefinition",
+                    "name": name_to_json(&fragment.name),
+                    "typeCondition": named_type_to_json(&fragment.type_condition),
+                    "directives": directives_to_json(&fragment.directives),
+                    "selectionSet": selection_set_to_json(&fragment.selection_set),
+                }),
+                _ => bail!("GraphQL documents may only contain operations and fragments"),
+            })
+        })
+        .collect()
+}
+
+fn name_to_json(name: &ast::Name) -> JsonValue {
+    json!({ "kind": "Name", "value": name.as_str() })
+}
+
+fn named_type_to_json(name: &ast::NamedType) -> JsonValue {
+    json!({ "kind": "NamedType", "name": name_to_json(name) })
+}
+
+fn type_to_json(ty: &Type) -> JsonValue {
+    match ty {
+        Type::Named(name) => named_type_to_json(name),
+        Type::NonNullNamed(name) => {
+            json!({ "kind": "NonNullType", "type": named_type_to_json(name) })
+        }
+        Type::List(inner) => json!({ "kind": "ListType", "type": type_to_json(inner) }),
+        Type::NonNullList(inner) => json!({
+            "kind": "NonNullType",
+            "type": { "kind": "ListType", "type": type_to_json(inner) },
+        }),
+    }
+}
+
+fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Null => json!({ "kind": "NullValue" }),
+        Value::Enum(name) => json!({ "kind": "EnumValue", "value": name.as_str() }),
+        Value::Variable(name) => json!({ "kind": "Variable", "name": name_to_json(name) }),
+        Value::String(value) => json!({ "kind": "StringValue", "value": value, "block": false }),
+        Value::Float(value) => json!({ "kind": "FloatValue", "value": value.as_str() }),
+        Value::Int(value) => json!({ "kind": "IntValue", "value": value.as_str() }),
+        Value::Boolean(value) => json!({ "kind": "BooleanValue", "value": value }),
+        Value::List(values) => json!({
+            "kind": "ListValue",
+            "values": values.iter().map(|value| value_to_json(value)).collect::<Vec<_>>(),
+        }),
+        Value::Object(fields) => json!({
+            "kind": "ObjectValue",
+            "fields": fields
+                .iter()
+                .map(|(name, value)| json!({
+                    "kind": "ObjectField",
+                    "name": name_to_json(name),
+                    "value": value_to_json(value),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn arguments_to_json(arguments: &[apollo_compiler::Node<ast::Argument>]) -> JsonValue {
+    arguments
+        .iter()
+        .map(|argument| {
+            json!({
+                "kind": "Argument",
+                "name": name_to_json(&argument.name),
+                "value": value_to_json(&argument.value),
+            })
+        })
+        .collect()
+}
+
+fn directives_to_json(directives: &ast::DirectiveList) -> JsonValue {
+    directives
+        .iter()
+        .map(|directive| {
+            json!({
+                "kind": "Directive",
+                "name": name_to_json(&directive.name),
+                "arguments": arguments_to_json(&directive.arguments),
+            })
+        })
+        .collect()
+}
+
+fn selection_set_to_json(selections: &[Selection]) -> JsonValue {
+    json!({
+        "kind": "SelectionSet",
+        "selections": selections
+            .iter()
+            .map(|selection| match selection {
+                Selection::Field(field) => json!({
+                    "kind": "Field",
+                    "alias": field.alias.as_ref().map(name_to_json),
+                    "name": name_to_json(&field.name),
+                    "arguments": arguments_to_json(&field.arguments),
+                    "directives": directives_to_json(&field.directives),
+                    "selectionSet": (!field.selection_set.is_empty())
+                        .then(|| selection_set_to_json(&field.selection_set)),
+                }),
+                Selection::FragmentSpread(spread) => json!({
+                    "kind": "FragmentSpread",
+                    "name": name_to_json(&spread.fragment_name),
+                    "directives": directives_to_json(&spread.directives),
+                }),
+                Selection::InlineFragment(fragment) => json!({
+                    "kind": "InlineFragment",
+                    "typeCondition": fragment.type_condition.as_ref().map(named_type_to_json),
+                    "directives": directives_to_json(&fragment.directives),
+                    "selectionSet": selection_set_to_json(&fragment.selection_set),
+                }),
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// The `loc` of a document with interpolations which aren't known at build time. Like
+/// `graphql-tag`, its body concatenates the source text of the interpolated documents at runtime.
+fn runtime_loc(tpl: &Tpl) -> Expr {
+    let body = Expr::Tpl(Tpl {
+        span: DUMMY_SP,
+        exprs: tpl
+            .exprs
+            .iter()
+            .map(|expr| {
+                Box::new(member(
+                    member(member(expr.clone(), "loc"), "source"),
+                    "body",
+                ))
+            })
+            .collect(),
+        quasis: tpl.quasis.clone(),
+    });
+    Expr::Object(ObjectLit {
+        span: DUMMY_SP,
+        props: vec![
+            key_value("start", json_to_expr(&json!(0))),
+            key_value(
+                "source",
+                Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props: vec![
+                        key_value("body", body),
+                        key_value("name", json_to_expr(&json!("GraphQL request"))),
+                        key_value(
+                            "locationOffset",
+                            json_to_expr(&json!({ "line": 1, "column": 1 })),
+                        ),
+                    ],
+                }),
+            ),
+        ],
+    })
+}
+
+fn member(obj: Box<Expr>, prop: &str) -> Expr {
+    Expr::Member(MemberExpr {
+        span: DUMMY_SP,
+        obj,
+        prop: MemberProp::Ident(IdentName::new(prop.into(), DUMMY_SP)),
+    })
+}
+
+fn key_value(key: &str, value: Expr) -> PropOrSpread {
+    PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Ident(IdentName::new(key.into(), DUMMY_SP)),
+        value: Box::new(value),
+    })))
+}
+
+fn json_to_expr(value: &JsonValue) -> Expr {
+    match value {
+        JsonValue::Null => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+        JsonValue::Bool(value) => Expr::Lit(Lit::Bool(Bool {
+            span: DUMMY_SP,
+            value: *value,
+        })),
+        JsonValue::Number(value) => Expr::Lit(Lit::Num(Number {
+            span: DUMMY_SP,
+            value: value.as_f64().unwrap_or_default(),
+            raw: None,
+        })),
+        JsonValue::String(value) => Expr::Lit(Lit::Str(value.as_str().into())),
+        JsonValue::Array(values) => Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: values
+                .iter()
+                .map(|value| Some(json_to_expr(value).into()))
+                .collect(),
+        }),
+        JsonValue::Object(map) => Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props: map
+                .iter()
+                .map(|(key, value)| key_value(key, json_to_expr(value)))
+                .collect(),
+        }),
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct GraphqlIssue {
+    file_path: FileSystemPath,
+    title: RcStr,
+    message: RcStr,
+    source: Option<IssueSource>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for GraphqlIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Error
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Transform.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(self.message.clone()).resolved_cell(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use apollo_compiler::Schema;
+    use sha2::{Digest, Sha256};
+    use swc_core::{
+        atoms::Atom,
+        common::{FileName, GLOBALS, Mark, SourceMap, sync::Lrc},
+        ecma::{
+            ast::EsVersion,
+            codegen::to_code,
+            parser::{Syntax, parse_file_as_program},
+            transforms::base::resolver,
+            visit::VisitMutWith,
+        },
+    };
+
+    use super::{GraphqlOutput, GraphqlVisitor};
+
+    const SCHEMA: &str = "type Query { user: User } type User { id: ID! name: String }";
+
+    /// Returns the transformed code and the errors.
+    fn transform(code: &str, output: GraphqlOutput, schema: Option<&str>) -> (String, Vec<String>) {
+        GLOBALS.set(&Default::default(), || {
+            let cm = Lrc::new(SourceMap::default());
+            let fm = cm.new_source_file(Lrc::new(FileName::Anon), code.to_string());
+            let mut program = parse_file_as_program(
+                &fm,
+                Syntax::Es(Default::default()),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            program.visit_mut_with(&mut resolver(Mark::new(), Mark::new(), false));
+
+            let schema = schema.map(|schema| Schema::parse_and_validate(schema, "schema").unwrap());
+            let tags = [Atom::from("gql")];
+            let mut visitor = GraphqlVisitor::new(&tags, schema.as_ref(), output, &program);
+            program.visit_mut_with(&mut visitor);
+            let errors = visitor
+                .errors
+                .into_iter()
+                .map(|(message, _)| message)
+                .collect();
+            (to_code(&program), errors)
+        })
+    }
+
+    fn sha256(text: &str) -> String {
+        format!("{:x}", Sha256::digest(text.as_bytes()))
+    }
+
+    #[test]
+    fn persisted_hash_covers_the_source_text_of_the_fragments() {
+        let fragment = "\n  fragment UserFields on User { id name }\n";
+        let query = "\n  query GetUser { user { ...UserFields } }\n  ";
+        let (code, errors) = transform(
+            &format!(
+                "const UserFields = gql`{fragment}`;\nconst GetUser = \
+                 gql`{query}${{UserFields}}\n`;"
+            ),
+            GraphqlOutput::PersistedHash,
+            Some(SCHEMA),
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(code.contains(&sha256(fragment)), "{code}");
+        // The exact text `graphql-tag` would send, not a reprint of the parsed document.
+        assert!(
+            code.contains(&sha256(&format!("{query}{fragment}\n"))),
+            "{code}"
+        );
+        assert!(!code.contains("GetUser {"), "{code}");
+    }
+
+    #[test]
+    fn persisted_hash_requires_fragments_of_the_same_module() {
+        let (_, errors) = transform(
+            "import { UserFields } from './fragments';\nconst GetUser = gql`query GetUser { user \
+             { ...UserFields } } ${UserFields}`;",
+            GraphqlOutput::PersistedHash,
+            None,
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].contains("can only interpolate documents assigned to a `const`"),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn document_keeps_the_source_text() {
+        let (code, errors) = transform(
+            "const GetUser = gql`query GetUser { user { id } }`;",
+            GraphqlOutput::Document,
+            Some(SCHEMA),
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(
+            code.contains(r#"body: "query GetUser { user { id } }""#),
+            "{code}"
+        );
+        assert!(code.contains("kind: \"OperationDefinition\""), "{code}");
+
+        // The source text of fragments from other modules is concatenated at runtime.
+        let (code, errors) = transform(
+            "import { UserFields } from './fragments';\nconst GetUser = gql`query GetUser { user \
+             { ...UserFields } } ${UserFields}`;",
+            GraphqlOutput::Document,
+            None,
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(code.contains("...UserFields.definitions"), "{code}");
+        assert!(code.contains("${UserFields.loc.source.body}"), "{code}");
+    }
+
+    #[test]
+    fn documents_are_validated_with_their_fragments() {
+        let (_, errors) = transform(
+            "const UserFields = gql`fragment UserFields on User { id }`;\nconst GetUser = \
+             gql`query GetUser { user { ...UserFields email } } ${UserFields}`;",
+            GraphqlOutput::Document,
+            Some(SCHEMA),
+        );
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].contains("email"), "{errors:?}");
+    }
+}
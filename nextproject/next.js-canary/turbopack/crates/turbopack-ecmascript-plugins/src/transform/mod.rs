@@ -1,5 +1,7 @@
 pub mod directives;
 pub mod emotion;
+pub mod graphql;
+pub mod registry;
 pub mod relay;
 pub mod styled_components;
 pub mod styled_jsx;
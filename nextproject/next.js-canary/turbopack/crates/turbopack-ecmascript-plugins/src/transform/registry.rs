@@ -0,0 +1,336 @@
+//! Native [CustomTransformer](turbopack_ecmascript::CustomTransformer)s selected by name.
+//!
+//! A host crate implements [CustomTransformerFactory] for each of its transforms and registers
+//! the factories by name in [CustomTransformerFactories]. A [CustomTransformerConfig] selects a
+//! registered factory together with its JSON options and the stage the transform runs in.
+//! [custom_transforms] turns a list of configs into [CustomTransforms], whose fields are the
+//! transforms of a `ModuleRuleEffect::ExtendEcmascriptTransforms`, so they can be added to the
+//! `module_rules` of a `ModuleOptionsContext` for the modules matching a `RuleCondition`.
+//!
+//! The factories and configs are turbo-tasks values, so changing them invalidates the modules
+//! they are applied to.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use bincode::{Decode, Encode};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{NonLocalValue, ResolvedVc, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_ecmascript::{EcmascriptInputTransform, EcmascriptInputTransforms, TransformPlugin};
+
+/// Creates a [CustomTransformer] from the JSON options of a [CustomTransformerConfig].
+///
+/// [CustomTransformer]: turbopack_ecmascript::CustomTransformer
+#[turbo_tasks::value_trait]
+pub trait CustomTransformerFactory {
+    #[turbo_tasks::function]
+    fn create(
+        self: Vc<Self>,
+        options: Vc<JsonValue>,
+        project_path: FileSystemPath,
+    ) -> Vc<TransformPlugin>;
+}
+
+/// The registered [CustomTransformerFactory]s by name.
+#[turbo_tasks::value(transparent)]
+pub struct CustomTransformerFactories(
+    BTreeMap<RcStr, ResolvedVc<Box<dyn CustomTransformerFactory>>>,
+);
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    TraceRawVcs,
+    NonLocalValue,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomTransformerStage {
+    /// Runs before TypeScript and decorators are transpiled.
+    Preprocess,
+    /// Runs on standard EcmaScript (plus JSX), like SWC plugins.
+    #[default]
+    Main,
+    /// Runs after JSX is transpiled.
+    Postprocess,
+}
+
+/// Selects a registered custom transformer.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, TraceRawVcs, NonLocalValue, Encode, Decode)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomTransformerConfig {
+    /// The name the factory was registered with.
+    pub name: RcStr,
+    /// Options passed to the factory.
+    #[serde(default)]
+    #[bincode(with = "turbo_bincode::serde_self_describing")]
+    pub options: JsonValue,
+    #[serde(default)]
+    pub stage: CustomTransformerStage,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct CustomTransformerConfigs(Vec<CustomTransformerConfig>);
+
+/// The transforms created from [CustomTransformerConfig]s, split by the stage they run in.
+#[turbo_tasks::value(shared)]
+pub struct CustomTransforms {
+    pub preprocess: ResolvedVc<EcmascriptInputTransforms>,
+    pub main: ResolvedVc<EcmascriptInputTransforms>,
+    pub postprocess: ResolvedVc<EcmascriptInputTransforms>,
+}
+
+/// Creates the transforms selected by `configs` using the registered `factories`, in the order of
+/// the configs.
+#[turbo_tasks::function]
+pub async fn custom_transforms(
+    factories: Vc<CustomTransformerFactories>,
+    configs: Vc<CustomTransformerConfigs>,
+    project_path: FileSystemPath,
+) -> Result<Vc<CustomTransforms>> {
+    let factories = factories.await?;
+    let mut preprocess = Vec::new();
+    let mut main = Vec::new();
+    let mut postprocess = Vec::new();
+    for config in configs.await?.iter() {
+        let Some(factory) = factories.get(&config.name) else {
+            bail!(
+                "no custom transformer named {} is registered, registered transformers are: {}",
+                config.name,
+                factories
+                    .keys()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        let transform = EcmascriptInputTransform::Plugin(
+            factory
+                .create(Vc::cell(config.options.clone()), project_path.clone())
+                .to_resolved()
+                .await?,
+        );
+        match config.stage {
+            CustomTransformerStage::Preprocess => preprocess.push(transform),
+            CustomTransformerStage::Main => main.push(transform),
+            CustomTransformerStage::Postprocess => postprocess.push(transform),
+        }
+    }
+    Ok(CustomTransforms {
+        preprocess: ResolvedVc::cell(preprocess),
+        main: ResolvedVc::cell(main),
+        postprocess: ResolvedVc::cell(postprocess),
+    }
+    .cell())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use serde_json::{Value as JsonValue, json};
+    use swc_core::ecma::ast::Program;
+    use turbo_rcstr::rcstr;
+    use turbo_tasks::{ResolvedVc, Vc};
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{File, FileContent, FileSystem, FileSystemPath, VirtualFileSystem};
+    use turbopack::{
+        ModuleAssetContext,
+        module_options::{ModuleOptionsContext, ModuleRule, ModuleRuleEffect, RuleCondition},
+    };
+    use turbopack_core::{
+        asset::AssetContent,
+        compile_time_info::CompileTimeInfo,
+        context::AssetContext,
+        environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+        ident::Layer,
+        reference_type::ReferenceType,
+        virtual_source::VirtualSource,
+    };
+    use turbopack_ecmascript::{
+        CustomTransformer, EcmascriptInputTransform, EcmascriptModuleAsset, TransformContext,
+        TransformPlugin,
+    };
+
+    use super::{
+        CustomTransformerConfig, CustomTransformerConfigs, CustomTransformerFactories,
+        CustomTransformerFactory, custom_transforms,
+    };
+
+    /// The `label` option and the file name of the modules transformed by [RecordingTransformer].
+    static TRANSFORMED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    #[derive(Debug)]
+    struct RecordingTransformer {
+        label: String,
+    }
+
+    #[async_trait]
+    impl CustomTransformer for RecordingTransformer {
+        async fn transform(
+            &self,
+            _program: &mut Program,
+            ctx: &TransformContext<'_>,
+        ) -> Result<()> {
+            TRANSFORMED
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", self.label, ctx.file_name_str));
+            Ok(())
+        }
+    }
+
+    #[turbo_tasks::value]
+    struct RecordingTransformerFactory;
+
+    #[turbo_tasks::value_impl]
+    impl CustomTransformerFactory for RecordingTransformerFactory {
+        #[turbo_tasks::function]
+        async fn create(
+            &self,
+            options: Vc<JsonValue>,
+            _project_path: FileSystemPath,
+        ) -> Result<Vc<TransformPlugin>> {
+            let label = options.await?["label"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            Ok(Vc::cell(Box::new(RecordingTransformer { label }) as _))
+        }
+    }
+
+    fn factories() -> Vc<CustomTransformerFactories> {
+        Vc::cell(
+            [(
+                rcstr!("recording"),
+                ResolvedVc::upcast(RecordingTransformerFactory.resolved_cell()),
+            )]
+            .into(),
+        )
+    }
+
+    fn configs(configs: JsonValue) -> Vc<CustomTransformerConfigs> {
+        Vc::cell(serde_json::from_value::<Vec<CustomTransformerConfig>>(configs).unwrap())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn transforms_are_split_by_stage() {
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(async {
+            let project_path = VirtualFileSystem::new().root().owned().await?;
+            let transforms = custom_transforms(
+                factories(),
+                configs(json!([
+                    { "name": "recording", "stage": "preprocess" },
+                    { "name": "recording", "options": { "label": "a" } },
+                    { "name": "recording", "options": { "label": "b" } },
+                ])),
+                project_path.clone(),
+            )
+            .await?;
+            assert_eq!(transforms.preprocess.await?.len(), 1);
+            assert_eq!(transforms.main.await?.len(), 2);
+            assert!(transforms.postprocess.await?.is_empty());
+            assert!(matches!(
+                transforms.main.await?[0],
+                EcmascriptInputTransform::Plugin(_)
+            ));
+
+            let error = custom_transforms(
+                factories(),
+                configs(json!([{ "name": "missing" }])),
+                project_path,
+            )
+            .await
+            .err()
+            .unwrap();
+            assert!(
+                format!("{error:?}").contains(
+                    "no custom transformer named missing is registered, registered transformers \
+                     are: recording"
+                ),
+                "{error:?}"
+            );
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn transforms_apply_through_module_rules() {
+        let tt = turbo_tasks::TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(async {
+            let root = VirtualFileSystem::new().root().owned().await?;
+            let transforms = custom_transforms(
+                factories(),
+                configs(json!([{ "name": "recording", "options": { "label": "rule" } }])),
+                root.clone(),
+            )
+            .await?;
+            let module_options_context = ModuleOptionsContext {
+                module_rules: vec![ModuleRule::new(
+                    RuleCondition::ResourcePathEndsWith(".transformed.js".into()),
+                    vec![ModuleRuleEffect::ExtendEcmascriptTransforms {
+                        preprocess: transforms.preprocess,
+                        main: transforms.main,
+                        postprocess: transforms.postprocess,
+                    }],
+                )],
+                ..Default::default()
+            };
+            let environment = Environment::new(ExecutionEnvironment::NodeJsLambda(
+                NodeJsEnvironment::default().resolved_cell(),
+            ));
+            let asset_context = ModuleAssetContext::new(
+                Default::default(),
+                CompileTimeInfo::new(environment).to_resolved().await?,
+                module_options_context.resolved_cell(),
+                Default::default(),
+                Layer::new(rcstr!("test")),
+            );
+
+            for name in ["a.transformed.js", "b.js"] {
+                let source = VirtualSource::new(
+                    root.join(name)?,
+                    AssetContent::file(
+                        FileContent::Content(File::from("export const a = 1;")).cell(),
+                    ),
+                );
+                let module = asset_context
+                    .process(Vc::upcast(source), ReferenceType::Undefined)
+                    .module()
+                    .to_resolved()
+                    .await?;
+                let module =
+                    ResolvedVc::try_downcast_type::<EcmascriptModuleAsset>(module).unwrap();
+                module.await?.parse().await?.await?;
+            }
+
+            assert_eq!(
+                *TRANSFORMED.lock().unwrap(),
+                ["rule a.transformed.js".to_string()]
+            );
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+/**
+ * This file contains the module federation runtime code specific to the
+ * Turbopack ECMAScript DOM runtime.
+ *
+ * It will be appended to the runtime code when module federation is enabled.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime-backend-dom.ts" />
+/// <reference path="../../../shared/module-federation-utils.ts" />
+
+/**
+ * Loads the entry script of a remote container. The container is defined once
+ * the chunks of the entry are loaded, which can be after the script has loaded.
+ */
+function loadRemoteContainerEntry(entryUrl: string): Promise<void> {
+  if (typeof importScripts === 'function') {
+    // We're in a web worker
+    try {
+      importScripts(entryUrl)
+    } catch (error) {
+      return Promise.reject(error)
+    }
+    return Promise.resolve()
+  }
+  return new Promise((resolve, reject) => {
+    const script = document.createElement('script')
+    script.src = entryUrl
+    script.onload = () => resolve()
+    script.onerror = () =>
+      reject(new Error(`Failed to load remote container entry ${entryUrl}`))
+    document.head.appendChild(script)
+  })
+}
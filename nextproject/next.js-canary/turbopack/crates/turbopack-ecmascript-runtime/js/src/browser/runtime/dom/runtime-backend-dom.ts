@@ -10,9 +10,9 @@
 /// <reference path="../../../shared/runtime-types.d.ts" />
 
 function getAssetSuffixFromScriptSrc() {
-  // TURBOPACK_ASSET_SUFFIX is set in web workers
+  // TURBOPACK_ASSET_SUFFIX is set in workers and worklets (which have no `self`)
   return (
-    (self.TURBOPACK_ASSET_SUFFIX ??
+    (globalThis.TURBOPACK_ASSET_SUFFIX ??
       document?.currentScript
         ?.getAttribute?.('src')
         ?.replace(/^(.*(?=\?)|^.*$)/, '')) ||
@@ -164,7 +164,7 @@ const chunkResolvers: Map<ChunkUrl, ChunkResolver> = new Map()
       if (isCss(chunkUrl)) {
         // ignore
       } else if (isJs(chunkUrl)) {
-        self.TURBOPACK_NEXT_CHUNK_URLS!.push(chunkUrl)
+        globalThis.TURBOPACK_NEXT_CHUNK_URLS!.push(chunkUrl)
         importScripts(chunkUrl)
       } else {
         throw new Error(
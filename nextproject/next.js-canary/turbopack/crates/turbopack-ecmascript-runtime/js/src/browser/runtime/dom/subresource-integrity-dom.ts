@@ -0,0 +1,115 @@
+/**
+ * This file contains the Subresource Integrity runtime code specific to the
+ * Turbopack ECMAScript DOM runtime.
+ *
+ * It will be appended to the runtime code when Subresource Integrity is
+ * enabled.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime-backend-dom.ts" />
+
+/**
+ * Subresource Integrity metadata of chunks, from the chunk data of the modules
+ * loading them.
+ */
+const chunkIntegrity: Map<ChunkUrl, string> = new Map()
+
+/**
+ * Maps chunk URLs to the promise of loading them with their integrity metadata.
+ */
+const chunkIntegrityLoads: Map<ChunkUrl, Promise<void>> = new Map()
+
+;(() => {
+  const loadChunkWithoutIntegrity = browserContextPrototype.l
+  browserContextPrototype.l = function loadChunkWithIntegrity(
+    this: TurbopackBrowserBaseContext<Module>,
+    chunkData: ChunkData
+  ): Promise<void> {
+    if (typeof chunkData !== 'string' && chunkData.integrity) {
+      chunkIntegrity.set(
+        getChunkRelativeUrl(chunkData.path),
+        chunkData.integrity
+      )
+    }
+    return loadChunkWithoutIntegrity.call(this, chunkData)
+  }
+
+  const loadChunkCached = BACKEND.loadChunkCached
+  BACKEND.loadChunkCached = (sourceType: SourceType, chunkUrl: ChunkUrl) => {
+    const integrity = chunkIntegrity.get(chunkUrl)
+    if (
+      !integrity ||
+      // Chunks of the runtime are already present in the DOM.
+      sourceType === SourceType.Runtime ||
+      // Workers load chunks with `importScripts`, which doesn't support
+      // integrity metadata.
+      typeof importScripts === 'function'
+    ) {
+      return loadChunkCached(sourceType, chunkUrl)
+    }
+
+    let promise = chunkIntegrityLoads.get(chunkUrl)
+    if (!promise) {
+      // The backend picks up the element instead of creating its own.
+      const stylesheetLoaded = appendChunkElement(chunkUrl, integrity)
+      const chunkLoaded = loadChunkCached(sourceType, chunkUrl)
+      // The backend considers existing stylesheets loaded right away.
+      promise = stylesheetLoaded
+        ? Promise.all([stylesheetLoaded, chunkLoaded]).then(() => {})
+        : chunkLoaded
+      chunkIntegrityLoads.set(chunkUrl, promise)
+    }
+    return promise
+  }
+
+  /**
+   * Appends the `<script>` or `<link>` element loading a chunk with its
+   * integrity metadata, unless the chunk is already in the DOM. Returns a
+   * promise for when a stylesheet is loaded.
+   */
+  function appendChunkElement(
+    chunkUrl: ChunkUrl,
+    integrity: string
+  ): Promise<void> | undefined {
+    if (isCss(chunkUrl)) {
+      if (
+        document.querySelector(`link[rel=stylesheet][href="${chunkUrl}"]`)
+      ) {
+        return undefined
+      }
+      const link = document.createElement('link')
+      link.rel = 'stylesheet'
+      link.href = chunkUrl
+      setIntegrity(link, integrity)
+      const loaded = new Promise<void>((resolve, reject) => {
+        link.onload = () => resolve()
+        link.onerror = () => reject()
+      })
+      // Append to the `head` for webpack compatibility.
+      document.head.appendChild(link)
+      return loaded
+    }
+    if (isJs(chunkUrl)) {
+      if (document.querySelector(`script[src="${chunkUrl}"]`)) {
+        return undefined
+      }
+      const script = document.createElement('script')
+      script.src = chunkUrl
+      setIntegrity(script, integrity)
+      // Append to the `head` for webpack compatibility.
+      document.head.appendChild(script)
+    }
+    return undefined
+  }
+
+  function setIntegrity(
+    element: HTMLScriptElement | HTMLLinkElement,
+    integrity: string
+  ) {
+    element.integrity = integrity
+    // Integrity checks require CORS for cross-origin chunks.
+    element.crossOrigin = 'anonymous'
+  }
+})()
@@ -0,0 +1,23 @@
+/**
+ * This file contains the module federation runtime code specific to the
+ * Turbopack ECMAScript "None" runtime (e.g. for Edge).
+ *
+ * It will be appended to the runtime code when module federation is enabled.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime-backend-edge.ts" />
+/// <reference path="../../../shared/module-federation-utils.ts" />
+
+/**
+ * Scripts can't be loaded at runtime, so remote containers need to be loaded
+ * together with the bundle.
+ */
+function loadRemoteContainerEntry(entryUrl: string): Promise<void> {
+  return Promise.reject(
+    new Error(
+      `Can't load remote container entry ${entryUrl}: remote containers need to be loaded before they are used in this runtime`
+    )
+  )
+}
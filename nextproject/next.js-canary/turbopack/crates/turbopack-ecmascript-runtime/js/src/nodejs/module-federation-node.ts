@@ -0,0 +1,36 @@
+/**
+ * This file contains the module federation runtime code specific to the
+ * Turbopack Node.js runtime.
+ *
+ * It will be appended to the runtime code when module federation is enabled.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime.ts" />
+/// <reference path="../shared/module-federation-utils.ts" />
+
+/**
+ * Requires the entry of a remote container. Paths are resolved relative to the
+ * output root.
+ */
+function loadRemoteContainerEntry(entryUrl: string): Promise<void> {
+  try {
+    let entryPath: string
+    if (entryUrl.startsWith('file:')) {
+      entryPath = url.fileURLToPath(entryUrl)
+    } else if (path.isAbsolute(entryUrl)) {
+      entryPath = entryUrl
+    } else if (/^[a-z][a-z\d+.-]*:/i.test(entryUrl)) {
+      throw new Error(
+        `Can't load remote container entry ${entryUrl}: only paths and file URLs are supported in Node.js`
+      )
+    } else {
+      entryPath = path.resolve(RUNTIME_ROOT, entryUrl)
+    }
+    require(entryPath)
+  } catch (error) {
+    return Promise.reject(error)
+  }
+  return Promise.resolve()
+}
@@ -0,0 +1,331 @@
+/**
+ * This file contains the module federation runtime code, which is shared
+ * between the browser and Node.js runtimes. It's only appended to the runtime
+ * code when module federation is enabled on the chunking context.
+ *
+ * Modules are shared between independently built bundles through a share
+ * scope, by package name and version. A host passes its share scope to remote
+ * containers via `container.init(shareScope)`, so all bundles negotiate shared
+ * modules through the same object.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime-utils.ts" />
+
+// @ts-ignore Defined by the module federation code of the specific runtime
+declare function loadRemoteContainerEntry(entryUrl: string): Promise<void>
+
+interface TurbopackModuleFederationContext<M> extends TurbopackBaseContext<M> {
+  H: RegisterShared
+  I: InitShareScope
+  K: LoadShared
+  O: LoadRemoteContainer
+}
+
+const moduleFederationContextPrototype =
+  Context.prototype as TurbopackModuleFederationContext<Module>
+
+type ShareScope = Record<string, Record<string, SharedModule>>
+
+type SharedModule = {
+  get: () => Promise<Exports>
+  loaded?: Promise<Exports>
+}
+
+type RemoteContainer = {
+  get: (exposed: string) => Promise<() => Exports>
+  init: (shareScope: ShareScope) => Promise<void> | void
+}
+
+type ParsedVersion = {
+  release: [number, number, number]
+  prerelease: string[]
+}
+
+/**
+ * How long to wait for a remote container after its entry script was loaded.
+ * The container is only defined once all chunks of its chunk group are loaded
+ * and its entry is evaluated.
+ */
+const REMOTE_CONTAINER_TIMEOUT = 60000
+
+let shareScope: ShareScope = Object.create(null)
+
+function addToShareScope(
+  scope: ShareScope,
+  name: string,
+  version: string,
+  module: SharedModule
+) {
+  const versions = (scope[name] ??= Object.create(null))
+  // The first registration of a version wins, so modules which might already
+  // be in use are never replaced.
+  versions[version] ??= module
+}
+
+/**
+ * Provides a module of this bundle to the share scope.
+ */
+function registerShared(
+  name: string,
+  version: string,
+  get: () => Promise<Exports>
+) {
+  addToShareScope(shareScope, name, version, { get })
+}
+moduleFederationContextPrototype.H = registerShared
+
+/**
+ * Switches to the share scope of the host, adding the modules of this bundle
+ * to it. Returns the current share scope when called without a scope.
+ */
+function initShareScope(scope?: ShareScope): ShareScope {
+  if (scope && scope !== shareScope) {
+    for (const name in shareScope) {
+      for (const version in shareScope[name]) {
+        addToShareScope(scope, name, version, shareScope[name][version])
+      }
+    }
+    shareScope = scope
+  }
+  return shareScope
+}
+moduleFederationContextPrototype.I = initShareScope
+
+/**
+ * Loads a shared module from the share scope, falling back to the module of
+ * this bundle when no suitable version was provided. The fallback is added to
+ * the share scope when its `version` is known, so other bundles can use it.
+ *
+ * Singletons use the version which is already loaded, or the highest version,
+ * even when it doesn't satisfy `requiredVersion`. Other modules use the highest
+ * version satisfying `requiredVersion`.
+ */
+function loadShared(
+  name: string,
+  version: string | null,
+  requiredVersion: VersionRange | null,
+  singleton: boolean,
+  fallback: () => Promise<Exports>
+): Promise<Exports> {
+  const versions = shareScope[name]
+  let module: SharedModule | undefined
+  if (versions) {
+    const candidates = Object.keys(versions).sort(
+      (a, b) => -compareVersions(a, b)
+    )
+    if (singleton) {
+      const version =
+        candidates.find((version) => versions[version].loaded) ?? candidates[0]
+      if (requiredVersion && !satisfiesVersion(version, requiredVersion)) {
+        console.warn(
+          `Shared singleton module ${name} is loaded in version ${version}, which doesn't satisfy the required version ${formatVersionRange(requiredVersion)}.`
+        )
+      }
+      module = versions[version]
+    } else {
+      const version = candidates.find(
+        (version) =>
+          !requiredVersion || satisfiesVersion(version, requiredVersion)
+      )
+      module = version ? versions[version] : undefined
+    }
+  }
+  if (!module) {
+    const loaded = fallback()
+    if (version) {
+      addToShareScope(shareScope, name, version, { get: fallback, loaded })
+    }
+    return loaded
+  }
+  return (module.loaded ??= module.get())
+}
+moduleFederationContextPrototype.K = loadShared
+
+function parseVersion(version: string): ParsedVersion | undefined {
+  const match =
+    /^v?(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+[0-9A-Za-z.-]+)?$/.exec(
+      version
+    )
+  if (!match) {
+    return undefined
+  }
+  return {
+    release: [Number(match[1]), Number(match[2]), Number(match[3])],
+    prerelease: match[4] ? match[4].split('.') : [],
+  }
+}
+
+function compareRelease(a: number[], b: number[]): number {
+  for (let i = 0; i < 3; i++) {
+    const diff = a[i] - b[i]
+    if (diff !== 0) return diff
+  }
+  return 0
+}
+
+/**
+ * Compares prerelease identifiers like semver: numeric identifiers are
+ * compared numerically and are lower than alphanumeric ones, and a release is
+ * higher than its prereleases.
+ */
+function comparePrerelease(a: string[], b: string[]): number {
+  if (a.length === 0 || b.length === 0) {
+    return b.length - a.length
+  }
+  for (let i = 0; i < Math.min(a.length, b.length); i++) {
+    if (a[i] === b[i]) continue
+    const aIsNumeric = /^\d+$/.test(a[i])
+    const bIsNumeric = /^\d+$/.test(b[i])
+    if (aIsNumeric && bIsNumeric) return Number(a[i]) - Number(b[i])
+    if (aIsNumeric !== bIsNumeric) return aIsNumeric ? -1 : 1
+    return a[i] < b[i] ? -1 : 1
+  }
+  return a.length - b.length
+}
+
+/**
+ * Orders versions by precedence. Invalid versions are lower than all valid
+ * ones.
+ */
+function compareVersions(a: string, b: string): number {
+  const left = parseVersion(a)
+  const right = parseVersion(b)
+  if (!left || !right) {
+    return (left ? 1 : 0) - (right ? 1 : 0)
+  }
+  return (
+    compareRelease(left.release, right.release) ||
+    comparePrerelease(left.prerelease, right.prerelease)
+  )
+}
+
+/**
+ * Like npm, prerelease versions only satisfy ranges which explicitly include
+ * prereleases, which desugared ranges never do.
+ */
+function satisfiesVersion(version: string, range: VersionRange): boolean {
+  const parsed = parseVersion(version)
+  if (!parsed || parsed.prerelease.length > 0) {
+    return false
+  }
+  return range.some((comparators) =>
+    comparators.every(([operator, ...required]) => {
+      const diff = compareRelease(parsed.release, required)
+      switch (operator) {
+        case '<':
+          return diff < 0
+        case '<=':
+          return diff <= 0
+        case '>':
+          return diff > 0
+        case '>=':
+          return diff >= 0
+        case '=':
+          return diff === 0
+      }
+    })
+  )
+}
+
+function formatVersionRange(range: VersionRange): string {
+  return range
+    .map(
+      (comparators) =>
+        comparators
+          .map(
+            ([operator, major, minor, patch]) =>
+              `${operator}${major}.${minor}.${patch}`
+          )
+          .join(' ') || '*'
+    )
+    .join(' || ')
+}
+
+const remoteContainers: Map<string, Promise<RemoteContainer>> = new Map()
+
+/**
+ * Loads the container entry script of a remote bundle, unless it was loaded
+ * before, and initializes the container with the share scope of this bundle.
+ * Loading is retried on the next call when it fails.
+ *
+ * Remote bundles need to use a different chunk loading global than the host.
+ */
+function loadRemoteContainer(
+  name: string,
+  entryUrl: string
+): Promise<RemoteContainer> {
+  let promise = remoteContainers.get(name)
+  if (!promise) {
+    const global = globalThis as Record<string, unknown>
+    const loaded =
+      global[name] !== undefined
+        ? Promise.resolve()
+        : loadRemoteContainerEntry(entryUrl)
+    const containerPromise = loaded
+      .then(() =>
+        waitForGlobal<RemoteContainer>(
+          name,
+          REMOTE_CONTAINER_TIMEOUT,
+          `Remote container ${name} wasn't initialized within ${REMOTE_CONTAINER_TIMEOUT}ms after loading ${entryUrl}`
+        )
+      )
+      .then(async (container) => {
+        if (
+          typeof container?.get !== 'function' ||
+          typeof container.init !== 'function'
+        ) {
+          throw new Error(
+            `The global ${name} defined by ${entryUrl} isn't a remote container`
+          )
+        }
+        await container.init(initShareScope())
+        return container
+      })
+    containerPromise.catch(() => {
+      if (remoteContainers.get(name) === containerPromise) {
+        remoteContainers.delete(name)
+      }
+    })
+    remoteContainers.set(name, containerPromise)
+    promise = containerPromise
+  }
+  return promise
+}
+moduleFederationContextPrototype.O = loadRemoteContainer
+
+/**
+ * Resolves with the value of the global variable `name` once it's defined, or
+ * rejects with `timeoutMessage` when it isn't defined within `timeout`
+ * milliseconds.
+ */
+function waitForGlobal<T>(
+  name: string,
+  timeout: number,
+  timeoutMessage: string
+): Promise<T> {
+  const global = globalThis as Record<string, unknown>
+  if (global[name] !== undefined) {
+    return Promise.resolve(global[name] as T)
+  }
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      delete global[name]
+      reject(new Error(timeoutMessage))
+    }, timeout)
+    Object.defineProperty(global, name, {
+      configurable: true,
+      set(value: T) {
+        clearTimeout(timer)
+        Object.defineProperty(global, name, {
+          value,
+          writable: true,
+          configurable: true,
+          enumerable: true,
+        })
+        resolve(value)
+      },
+    })
+  })
+}
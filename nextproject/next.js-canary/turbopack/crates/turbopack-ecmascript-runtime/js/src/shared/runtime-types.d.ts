@@ -39,6 +39,7 @@ type ChunkData =
       included: ModuleId[]
       excluded: ModuleId[]
       moduleChunks: ChunkPath[]
+      integrity?: string
     }
 
 type CommonJsRequire = (moduleId: ModuleId) => Exports
@@ -85,6 +86,35 @@ type CompressedModuleFactories = Array<ModuleId | Function>
 
 type RelativeURL = (inputUrl: string) => void
 type ResolvePathFromModule = (moduleId: string) => string
+type RegisterShared = (
+  name: string,
+  version: string,
+  get: () => Promise<Exports>
+) => void
+/**
+ * A comparator of a version range like `['>=', 1, 2, 3]`. Ranges are desugared
+ * into comparators at build time.
+ */
+type VersionComparator = [
+  '<' | '<=' | '>' | '>=' | '=',
+  number,
+  number,
+  number,
+]
+/**
+ * Satisfied by a version when all comparators of any of the comparator sets
+ * are satisfied.
+ */
+type VersionRange = VersionComparator[][]
+type LoadShared = (
+  name: string,
+  version: string | null,
+  requiredVersion: VersionRange | null,
+  singleton: boolean,
+  fallback: () => Promise<Exports>
+) => Promise<Exports>
+type InitShareScope = (scope?: Record<string, any>) => Record<string, any>
+type LoadRemoteContainer = (name: string, entryUrl: string) => Promise<any>
 
 type AsyncModule = (
   body: (
@@ -25,6 +25,8 @@ pub async fn get_browser_runtime_code(
     output_root_to_root_path: RcStr,
     generate_source_map: bool,
     chunk_loading_global: Vc<RcStr>,
+    module_federation: bool,
+    subresource_integrity: bool,
 ) -> Result<Vc<Code>> {
     let asset_context = get_runtime_asset_context(*environment).resolve().await?;
 
@@ -79,6 +81,19 @@ pub async fn get_browser_runtime_code(
         }
     };
 
+    // Only the DOM backend creates elements to load chunks.
+    if subresource_integrity && chunk_loading == &ChunkLoading::Dom {
+        runtime_backend_code.push("browser/runtime/dom/subresource-integrity-dom.ts");
+    }
+
+    if module_federation {
+        runtime_backend_code.push("shared/module-federation-utils.ts");
+        runtime_backend_code.push(match chunk_loading {
+            ChunkLoading::Edge => "browser/runtime/edge/module-federation-edge.ts",
+            _ => "browser/runtime/dom/module-federation-dom.ts",
+        });
+    }
+
     let mut code: CodeBuilder = CodeBuilder::default();
     let relative_root_path = output_root_to_root_path;
     let chunk_base_path = chunk_base_path.await?;
@@ -13,6 +13,7 @@ use crate::{asset_context::get_runtime_asset_context, embed_js::embed_static_cod
 pub async fn get_nodejs_runtime_code(
     environment: ResolvedVc<Environment>,
     generate_source_map: bool,
+    module_federation: bool,
 ) -> Result<Vc<Code>> {
     let asset_context = get_runtime_asset_context(*environment).resolve().await?;
 
@@ -48,6 +49,17 @@ pub async fn get_nodejs_runtime_code(
     code.push_code(&*shared_node_external_utils_code.await?);
     code.push_code(&*shared_node_wasm_utils_code.await?);
     code.push_code(&*runtime_code.await?);
+    if module_federation {
+        for module_federation_code in [
+            rcstr!("shared/module-federation-utils.ts"),
+            rcstr!("nodejs/module-federation-node.ts"),
+        ] {
+            code.push_code(
+                &*embed_static_code(asset_context, module_federation_code, generate_source_map)
+                    .await?,
+            );
+        }
+    }
 
     Ok(Code::cell(code.build()))
 }
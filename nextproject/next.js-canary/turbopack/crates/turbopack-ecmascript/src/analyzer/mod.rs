@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use num_bigint::BigInt;
 use num_traits::identities::Zero;
 use once_cell::sync::Lazy;
@@ -1788,6 +1788,7 @@ impl JsValue {
                     WellKnownFunctionKind::RequireContextRequire(..) => ("require.context(...)".to_string(), "The require.context(...) method from webpack: https://webpack.js.org/api/module-methods/#requirecontext"),
                     WellKnownFunctionKind::RequireContextRequireKeys(..) => ("require.context(...).keys".to_string(), "The require.context(...).keys method from webpack: https://webpack.js.org/guides/dependency-management/#requirecontext"),
                     WellKnownFunctionKind::RequireContextRequireResolve(..) => ("require.context(...).resolve".to_string(), "The require.context(...).resolve method from webpack: https://webpack.js.org/guides/dependency-management/#requirecontext"),
+                    WellKnownFunctionKind::ImportMetaGlob => ("import.meta.glob".to_string(), "The import.meta.glob method from Vite: https://vite.dev/guide/features.html#glob-import"),
                     WellKnownFunctionKind::Define => ("define".to_string(), "The define method from AMD"),
                     WellKnownFunctionKind::FsReadMethod(name) => (
                         format!("fs.{name}"),
@@ -1873,6 +1874,14 @@ impl JsValue {
                       "SharedWorker".to_string(),
                       "The standard SharedWorker constructor: https://developer.mozilla.org/en-US/docs/Web/API/SharedWorker/SharedWorker"
                     ),
+                    WellKnownFunctionKind::ServiceWorkerRegister => (
+                      "navigator.serviceWorker.register".to_string(),
+                      "The standard service worker registration: https://developer.mozilla.org/en-US/docs/Web/API/ServiceWorkerContainer/register"
+                    ),
+                    WellKnownFunctionKind::WorkletAddModule => (
+                      "Worklet.addModule".to_string(),
+                      "The standard worklet module loading: https://developer.mozilla.org/en-US/docs/Web/API/Worklet/addModule"
+                    ),
                     WellKnownFunctionKind::URLConstructor => (
                       "URL".to_string(),
                       "The standard URL constructor: https://developer.mozilla.org/en-US/docs/Web/API/URL/URL"
@@ -3403,6 +3412,113 @@ pub fn parse_require_context(args: &[JsValue]) -> Result<RequireContextOptions>
     })
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportMetaGlobOptions {
+    /// The glob patterns, relative to the importing module. Patterns starting with `!` exclude
+    /// matches.
+    pub patterns: Vec<RcStr>,
+    pub eager: bool,
+    /// The export to pick from the matched modules instead of the namespace object.
+    pub import: Option<RcStr>,
+    /// A query string (including the leading `?`) appended to the import requests.
+    pub query: Option<RcStr>,
+}
+
+/// Parse the arguments passed to an `import.meta.glob` invocation, validate them and convert
+/// them to the appropriate rust values.
+pub fn parse_import_meta_glob(args: &[JsValue]) -> Result<ImportMetaGlobOptions> {
+    if !(1..=2).contains(&args.len()) {
+        bail!("import.meta.glob() only supports 1-2 arguments");
+    }
+
+    let patterns: Vec<RcStr> = match &args[0] {
+        JsValue::Array { items, .. } => items
+            .iter()
+            .map(|item| item.as_str().map(RcStr::from))
+            .collect::<Option<_>>(),
+        pattern => pattern.as_str().map(|pattern| vec![pattern.into()]),
+    }
+    .context(
+        "import.meta.glob(patterns, ...) requires patterns to be a constant string or an array of \
+         constant strings",
+    )?;
+    for pattern in &patterns {
+        let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+        if !(pattern.starts_with("./") || pattern.starts_with("../")) {
+            bail!(
+                "import.meta.glob() only supports relative patterns starting with ./ or ../, got \
+                 {pattern}"
+            );
+        }
+    }
+    if patterns.iter().all(|pattern| pattern.starts_with('!')) {
+        bail!("import.meta.glob() requires at least one pattern which is not negated");
+    }
+
+    let mut options = ImportMetaGlobOptions {
+        patterns,
+        eager: false,
+        import: None,
+        query: None,
+    };
+    let Some(arg) = args.get(1) else {
+        return Ok(options);
+    };
+    let JsValue::Object { parts, .. } = arg else {
+        bail!("import.meta.glob(..., options) requires options to be an object literal");
+    };
+    for part in parts {
+        let ObjectPart::KeyValue(key, value) = part else {
+            bail!("import.meta.glob(..., options) doesn't support spread properties");
+        };
+        match key.as_str() {
+            Some("eager") => {
+                options.eager = value
+                    .as_bool()
+                    .context("import.meta.glob() requires eager to be a constant boolean")?;
+            }
+            Some("import") => {
+                options.import = Some(
+                    value
+                        .as_str()
+                        .context("import.meta.glob() requires import to be a constant string")?
+                        .into(),
+                );
+            }
+            Some("query") => {
+                let query = match value {
+                    JsValue::Object { parts, .. } => parts
+                        .iter()
+                        .map(|part| {
+                            let ObjectPart::KeyValue(key, value) = part else {
+                                return None;
+                            };
+                            let value = match value.as_bool() {
+                                Some(value) => value.to_string(),
+                                None => value.as_str()?.to_string(),
+                            };
+                            Some(format!("{}={value}", key.as_str()?))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .map(|pairs| pairs.join("&")),
+                    value => value.as_str().map(|query| query.to_string()),
+                }
+                .context(
+                    "import.meta.glob() requires query to be a constant string or an object of \
+                     constant values",
+                )?;
+                let query = query.strip_prefix('?').unwrap_or(&query);
+                if !query.is_empty() {
+                    options.query = Some(format!("?{query}").into());
+                }
+            }
+            Some(key) => bail!("import.meta.glob() doesn't support the {key} option"),
+            None => bail!("import.meta.glob(..., options) requires constant option keys"),
+        }
+    }
+    Ok(options)
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RequireContextValue(FxIndexMap<RcStr, RcStr>);
 
@@ -3447,6 +3563,7 @@ pub enum WellKnownFunctionKind {
     RequireContextRequire(RequireContextValue),
     RequireContextRequireKeys(RequireContextValue),
     RequireContextRequireResolve(RequireContextValue),
+    ImportMetaGlob,
     Define,
     FsReadMethod(Atom),
     PathToFileUrl,
@@ -3470,6 +3587,10 @@ pub enum WellKnownFunctionKind {
     SharedWorkerConstructor,
     // The worker_threads Worker class
     NodeWorkerConstructor,
+    /// `navigator.serviceWorker.register`
+    ServiceWorkerRegister,
+    /// `addModule` of a worklet, e.g. `CSS.paintWorklet.addModule`
+    WorkletAddModule,
     URLConstructor,
 }
 
@@ -3480,6 +3601,7 @@ impl WellKnownFunctionKind {
             Self::Require { .. } => Some(&["require"]),
             Self::RequireResolve => Some(&["require", "resolve"]),
             Self::RequireContext => Some(&["require", "context"]),
+            Self::ImportMetaGlob => Some(&["import", "meta", "glob"]),
             Self::Define => Some(&["define"]),
             _ => None,
         }
@@ -75,6 +75,7 @@ impl AsyncLoaderChunkItem {
             Ok(ChunkData::from_assets(
                 this.chunking_context.output_root().owned().await?,
                 *self.chunk_group().await?.assets,
+                *this.chunking_context.subresource_integrity().await?,
             ))
         }
         .instrument(span)
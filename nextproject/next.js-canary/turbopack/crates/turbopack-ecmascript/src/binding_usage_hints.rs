@@ -0,0 +1,250 @@
+//! Hints about dead code, based on the export usage computed by
+//! [turbopack_core::module_graph::binding_usage_info::compute_binding_usage_info].
+//!
+//! The hints are reported with the path of the affected module, so when they are collected
+//! together with the module graph they include its import traces.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexSet, ReadRef, ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::{
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::Module,
+    module_graph::{
+        ModuleGraph,
+        binding_usage_info::{BindingUsageInfo, ModuleExportUsageInfo},
+    },
+};
+
+use crate::{
+    chunk::{EcmascriptChunkPlaceable, EcmascriptExports},
+    references::esm::{EsmExport, EsmExports},
+};
+
+/// A barrel file is only reported when it forces at least this many modules to be evaluated.
+const BARREL_FILE_MIN_EVALUATED_MODULES: usize = 5;
+
+/// Emits [IssueSeverity::Hint] issues for
+/// - unused exports of first-party modules,
+/// - first-party modules which are imported, but none of their exports are used,
+/// - barrel files (modules which only re-export) which force the evaluation of re-exported modules
+///   whose exports aren't used, because those modules aren't side effect free.
+///
+/// `graph` must be the graph `binding_usage` was computed on, i.e. the graph still including the
+/// unused references.
+#[turbo_tasks::function]
+pub async fn emit_binding_usage_hints(
+    graph: Vc<ModuleGraph>,
+    binding_usage: Vc<BindingUsageInfo>,
+) -> Result<Vc<()>> {
+    let graph = graph.await?;
+    let binding_usage = binding_usage.await?;
+
+    let modules = graph
+        .iter_nodes()
+        .map(async |module| {
+            let path = module.ident().path().owned().await?;
+            let exports = if let Some(placeable) =
+                ResolvedVc::try_downcast::<Box<dyn EcmascriptChunkPlaceable>>(module)
+                && let EcmascriptExports::EsmExports(exports) = &*placeable.get_exports().await?
+            {
+                Some(exports.await?)
+            } else {
+                None
+            };
+            Ok((module, (path, exports)))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .collect::<FxHashMap<_, _>>();
+
+    // Modules with exports which are only evaluated for their side effects, by importing module.
+    let mut evaluated_only = FxHashMap::<_, FxIndexSet<_>>::default();
+    graph.traverse_edges_unordered(|parent, target| {
+        if let Some((parent, _)) = parent
+            && matches!(
+                binding_usage.export_usage(target),
+                Some(ModuleExportUsageInfo::Evaluation)
+            )
+            && modules
+                .get(&target)
+                .is_some_and(|(_, exports)| exports.as_ref().is_some_and(|e| has_exports(e)))
+        {
+            evaluated_only.entry(parent).or_default().insert(target);
+        }
+        Ok(())
+    })?;
+
+    for (module, (path, exports)) in &modules {
+        let Some(exports) = exports else {
+            continue;
+        };
+        let is_first_party = is_first_party(&path.path);
+
+        match binding_usage.export_usage(*module) {
+            Some(ModuleExportUsageInfo::Exports(used)) if is_first_party => {
+                let unused = exports
+                    .exports
+                    .keys()
+                    .filter(|name| !used.contains(*name))
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>();
+                if !unused.is_empty() {
+                    emit_hint(
+                        path,
+                        format!(
+                            "Unused export{} {}",
+                            if unused.len() == 1 { "" } else { "s" },
+                            unused.join(", ")
+                        ),
+                        "These exports are not imported by any module in the module graph and \
+                         could be removed."
+                            .to_string(),
+                    );
+                }
+            }
+            Some(ModuleExportUsageInfo::Evaluation) if is_first_party && has_exports(exports) => {
+                emit_hint(
+                    path,
+                    "Module is imported, but none of its exports are used".to_string(),
+                    "The module is only evaluated for its side effects. If it has none, the \
+                     imports of it could be removed."
+                        .to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        if is_barrel_file(exports)
+            && let Some(evaluated) = evaluated_only.get(module)
+            && evaluated.len() >= BARREL_FILE_MIN_EVALUATED_MODULES
+        {
+            let evaluated_paths = evaluated
+                .iter()
+                .filter_map(|evaluated| modules.get(evaluated))
+                .map(|(path, _)| path.path.as_str())
+                .collect::<Vec<_>>();
+            let (title, description) = barrel_file_hint(&evaluated_paths)?;
+            emit_hint(path, title, description);
+        }
+    }
+
+    Ok(Default::default())
+}
+
+fn is_first_party(path: &str) -> bool {
+    !path.split('/').any(|segment| segment == "node_modules")
+}
+
+/// The title and description of the hint for a barrel file which forces the evaluation of the
+/// re-exported modules at `evaluated_paths`.
+fn barrel_file_hint(evaluated_paths: &[&str]) -> Result<(String, String)> {
+    let count = evaluated_paths.len();
+    let mut description = format!(
+        "These {count} re-exported modules aren't side effect free, so they are evaluated even \
+         though none of their exports are used:\n"
+    );
+    for path in evaluated_paths {
+        writeln!(description, "- {path}")?;
+    }
+    description.push_str(
+        "Import from the modules directly, or mark them as side effect free with \
+         `\"sideEffects\": false` in the package.json of their package.",
+    );
+    Ok((
+        format!("Barrel file forces {count} modules to be evaluated"),
+        description,
+    ))
+}
+
+fn has_exports(exports: &EsmExports) -> bool {
+    !exports.exports.is_empty() || !exports.star_exports.is_empty()
+}
+
+/// A module which only re-exports other modules.
+fn is_barrel_file(exports: &ReadRef<EsmExports>) -> bool {
+    has_exports(exports)
+        && exports.exports.values().all(|export| {
+            matches!(
+                export,
+                EsmExport::ImportedBinding(..) | EsmExport::ImportedNamespace(_)
+            )
+        })
+}
+
+fn emit_hint(file_path: &FileSystemPath, title: String, description: String) {
+    BindingUsageHint {
+        file_path: file_path.clone(),
+        title: title.into(),
+        description: description.into(),
+    }
+    .resolved_cell()
+    .emit();
+}
+
+#[turbo_tasks::value(shared)]
+struct BindingUsageHint {
+    file_path: FileSystemPath,
+    title: RcStr,
+    description: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for BindingUsageHint {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Hint
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Analysis.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file_path.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(self.description.clone()).resolved_cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{barrel_file_hint, is_first_party};
+
+    #[test]
+    fn barrel_file_hint_blames_the_re_exported_modules() {
+        let (title, description) = barrel_file_hint(&["src/a.js", "src/b.js"]).unwrap();
+        assert_eq!(title, "Barrel file forces 2 modules to be evaluated");
+        assert_eq!(
+            description,
+            "These 2 re-exported modules aren't side effect free, so they are evaluated even \
+             though none of their exports are used:\n- src/a.js\n- src/b.js\nImport from the \
+             modules directly, or mark them as side effect free with `\"sideEffects\": false` in \
+             the package.json of their package."
+        );
+    }
+
+    #[test]
+    fn first_party_modules() {
+        assert!(is_first_party("src/index.js"));
+        assert!(is_first_party("src/node_modules_like/index.js"));
+        assert!(!is_first_party("node_modules/lib/index.js"));
+        assert!(!is_first_party("packages/app/node_modules/lib/index.js"));
+    }
+}
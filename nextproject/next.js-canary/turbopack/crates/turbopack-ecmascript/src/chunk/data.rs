@@ -14,6 +14,8 @@ pub enum EcmascriptChunkData<'a> {
         excluded: &'a [ModuleId],
         #[serde(skip_serializing_if = "<[_]>::is_empty", default)]
         module_chunks: &'a [String],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        integrity: Option<&'a str>,
     },
 }
 
@@ -24,8 +26,13 @@ impl EcmascriptChunkData<'_> {
             included,
             excluded,
             module_chunks,
+            integrity,
         } = chunk_data;
-        if included.is_empty() && excluded.is_empty() && module_chunks.is_empty() {
+        if included.is_empty()
+            && excluded.is_empty()
+            && module_chunks.is_empty()
+            && integrity.is_none()
+        {
             return EcmascriptChunkData::Simple(path);
         }
         EcmascriptChunkData::WithRuntimeInfo {
@@ -33,6 +40,7 @@ impl EcmascriptChunkData<'_> {
             included,
             excluded,
             module_chunks,
+            integrity: integrity.as_deref(),
         }
     }
 }
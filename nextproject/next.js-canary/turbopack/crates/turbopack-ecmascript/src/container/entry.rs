@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result, bail};
+use indoc::writedoc;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent, context::AssetContext, module::Module, reference_type::ReferenceType,
+    virtual_source::VirtualSource,
+};
+
+use super::{shared::SharedModuleConfig, version_range::validate_version};
+use crate::{
+    runtime_functions::{TURBOPACK_INIT_SHARE_SCOPE, TURBOPACK_REGISTER_SHARED},
+    utils::StringifyJs,
+};
+
+/// The modules exposed by a container, by their public name (e.g. `./Button`).
+#[turbo_tasks::value(transparent)]
+pub struct ExposedModules(
+    #[bincode(with = "turbo_bincode::indexmap")] FxIndexMap<RcStr, ResolvedVc<Box<dyn Module>>>,
+);
+
+/// The modules a container provides to the share scope.
+#[turbo_tasks::value(transparent)]
+pub struct ProvidedSharedModules(Vec<(SharedModuleConfig, ResolvedVc<Box<dyn Module>>)>);
+
+/// Creates the entry module of a container, which exposes `exposes` to other bundles.
+///
+/// Evaluating the module defines a global variable `name` with the container protocol:
+/// - `init(shareScope)` merges the modules provided by this bundle into the share scope of the host
+///   and makes this bundle consume shared modules from it. It must be called before `get`.
+/// - `get(exposed)` loads an exposed module in its own async chunk and resolves to a factory
+///   returning its exports.
+///
+/// The module is meant to be placed in an evaluated chunk group (e.g. with
+/// [turbopack_core::chunk::ChunkingContext::evaluated_chunk_group]) whose first chunk is served at
+/// a stable URL. A bundle exposing a container must use a different chunk loading global than the
+/// bundles consuming it, so their runtimes don't pick up each others' chunks. Its chunking context
+/// must have module federation enabled.
+#[turbo_tasks::function]
+pub async fn container_entry_module(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    path: FileSystemPath,
+    name: RcStr,
+    exposes: Vc<ExposedModules>,
+    shared: Vc<ProvidedSharedModules>,
+) -> Result<Vc<Box<dyn Module>>> {
+    let exposes = exposes.await?;
+    let shared = shared.await?;
+
+    let mut inner_assets = FxIndexMap::default();
+    let mut code = String::new();
+
+    writeln!(code, "const exposes = {{")?;
+    for (i, (exposed, module)) in exposes.iter().enumerate() {
+        let key: RcStr = format!("EXPOSED_{i}").into();
+        writeln!(
+            code,
+            "  {}: () => import({}),",
+            StringifyJs(exposed),
+            StringifyJs(&key)
+        )?;
+        inner_assets.insert(key, *module);
+    }
+    writeln!(code, "}};")?;
+
+    for (i, (config, module)) in shared.iter().enumerate() {
+        let Some(version) = &config.version else {
+            bail!(
+                "The version of the shared module {} is required to provide it",
+                config.share_key
+            );
+        };
+        validate_version(version).with_context(|| {
+            format!(
+                "The version of the shared module {} is invalid",
+                config.share_key
+            )
+        })?;
+        let key: RcStr = format!("SHARED_{i}").into();
+        writeln!(
+            code,
+            "{TURBOPACK_REGISTER_SHARED}({}, {}, () => import({}));",
+            StringifyJs(&config.share_key),
+            StringifyJs(version),
+            StringifyJs(&key)
+        )?;
+        inner_assets.insert(key, *module);
+    }
+
+    writedoc!(
+        code,
+        r#"
+
+            export function get(name) {{
+              const load = exposes[name];
+              if (!load) {{
+                return Promise.reject(
+                  new Error("Module " + name + " is not exposed by container " + {name})
+                );
+              }}
+              return load().then((m) => () => m);
+            }}
+
+            export function init(shareScope) {{
+              {TURBOPACK_INIT_SHARE_SCOPE}(shareScope);
+            }}
+
+            globalThis[{name}] = {{ get, init }};
+        "#,
+        name = StringifyJs(&name),
+    )?;
+
+    let source = VirtualSource::new(
+        path,
+        AssetContent::file(FileContent::Content(File::from(code)).cell()),
+    );
+    Ok(asset_context
+        .process(
+            Vc::upcast(source),
+            ReferenceType::Internal(ResolvedVc::cell(inner_assets)),
+        )
+        .module())
+}
@@ -0,0 +1,32 @@
+//! Module federation style containers, which share modules between independently built bundles
+//! at runtime.
+//!
+//! - A container entry ([entry::container_entry_module]) exposes selected modules of a bundle under
+//!   public names.
+//! - A [remote::RemoteModule] is a module exposed by the container of another bundle.
+//! - A [shared::ConsumeSharedModule] is a module which is negotiated with the other bundles via the
+//!   share scope of the runtime, so only a single copy of e.g. a framework is loaded.
+//!
+//! The runtime code for this is only included when module federation is enabled on the chunking
+//! context. Embedders decide which requests map to remote and shared modules, e.g. via import maps.
+
+pub mod entry;
+pub mod remote;
+pub mod shared;
+pub mod version_range;
+
+use anyhow::{Result, bail};
+use turbo_tasks::Vc;
+use turbopack_core::chunk::ChunkingContext;
+
+/// Fails when the runtime of `chunking_context` doesn't support module federation, instead of
+/// failing at runtime.
+async fn ensure_module_federation_enabled(
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+    module_kind: &str,
+) -> Result<()> {
+    if !*chunking_context.is_module_federation_enabled().await? {
+        bail!("{module_kind} require module federation to be enabled on the chunking context");
+    }
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use std::io::Write;
+
+use anyhow::Result;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem, rope::RopeBuilder};
+use turbopack_core::{
+    chunk::{AsyncModuleInfo, ChunkItem, ChunkType, ChunkableModule, ChunkingContext},
+    ident::{AssetIdent, Layer},
+    module::{Module, ModuleSideEffects},
+    module_graph::ModuleGraph,
+    output::OutputAssetsReference,
+    reference::ModuleReferences,
+};
+
+use super::ensure_module_federation_enabled;
+use crate::{
+    EcmascriptModuleContent,
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
+        EcmascriptChunkType, EcmascriptExports,
+    },
+    references::async_module::{AsyncModule, OptionAsyncModule},
+    runtime_functions::{TURBOPACK_EXPORT_NAMESPACE, TURBOPACK_LOAD_REMOTE_CONTAINER},
+    utils::StringifyJs,
+};
+
+/// A module exposed by the container of another, independently built bundle. The container entry
+/// script is loaded from `url` on first use, unless it was loaded before, and initialized with the
+/// share scope of this bundle, see [super::entry::container_entry_module].
+#[turbo_tasks::value]
+pub struct RemoteModule {
+    /// The name of the remote container, which is the global variable its entry defines.
+    container_name: RcStr,
+    /// The URL of the container entry script. In Node.js this can also be a path relative to the
+    /// output root.
+    url: RcStr,
+    /// The public name of the module in the container, e.g. `./Button`.
+    exposed: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteModule {
+    #[turbo_tasks::function]
+    pub fn new(container_name: RcStr, url: RcStr, exposed: RcStr) -> Vc<Self> {
+        Self::cell(RemoteModule {
+            container_name,
+            url,
+            exposed,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn content(
+        &self,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<EcmascriptModuleContent>> {
+        ensure_module_federation_enabled(chunking_context, "Remote modules").await?;
+
+        let mut code = RopeBuilder::default();
+        writeln!(
+            code,
+            "const container = await {TURBOPACK_LOAD_REMOTE_CONTAINER}({}, {});",
+            StringifyJs(&self.container_name),
+            StringifyJs(&self.url)
+        )?;
+        writeln!(
+            code,
+            "const factory = await container.get({});",
+            StringifyJs(&self.exposed)
+        )?;
+        writeln!(code)?;
+        writeln!(code, "{TURBOPACK_EXPORT_NAMESPACE}(factory());")?;
+
+        Ok(EcmascriptModuleContent {
+            inner_code: code.build(),
+            source_map: None,
+            is_esm: true,
+            strict: false,
+            additional_ids: Default::default(),
+        }
+        .cell())
+    }
+}
+
+/// The root of the paths of remote modules, which are `<container name>/<exposed module>`.
+#[turbo_tasks::function]
+pub fn remotes_fs_root() -> Vc<FileSystemPath> {
+    VirtualFileSystem::new_with_name(rcstr!("remotes")).root()
+}
+
+#[turbo_tasks::value_impl]
+impl Module for RemoteModule {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
+        Ok(AssetIdent::from_path(
+            remotes_fs_root()
+                .await?
+                .join(&self.container_name)?
+                .join(&self.exposed)?,
+        )
+        .with_layer(Layer::new(rcstr!("remote")))
+        .with_modifier(self.url.clone()))
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<turbopack_core::source::OptionSource> {
+        Vc::cell(None)
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<ModuleReferences> {
+        ModuleReferences::empty()
+    }
+
+    #[turbo_tasks::function]
+    fn is_self_async(&self) -> Vc<bool> {
+        Vc::cell(true)
+    }
+
+    #[turbo_tasks::function]
+    fn side_effects(self: Vc<Self>) -> Vc<ModuleSideEffects> {
+        ModuleSideEffects::SideEffectful.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for RemoteModule {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self: ResolvedVc<Self>,
+        _module_graph: Vc<ModuleGraph>,
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Box<dyn ChunkItem>> {
+        Vc::upcast(
+            RemoteModuleChunkItem {
+                module: self,
+                chunking_context,
+            }
+            .cell(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for RemoteModule {
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> Vc<EcmascriptExports> {
+        EcmascriptExports::DynamicNamespace.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn get_async_module(&self) -> Vc<OptionAsyncModule> {
+        Vc::cell(Some(
+            AsyncModule {
+                has_top_level_await: true,
+                import_externals: false,
+            }
+            .resolved_cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value]
+struct RemoteModuleChunkItem {
+    module: ResolvedVc<RemoteModule>,
+    chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for RemoteModuleChunkItem {}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for RemoteModuleChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> Vc<AssetIdent> {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn ty(self: Vc<Self>) -> Vc<Box<dyn ChunkType>> {
+        Vc::upcast(Vc::<EcmascriptChunkType>::default())
+    }
+
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        Vc::upcast(*self.module)
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        *self.chunking_context
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for RemoteModuleChunkItem {
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<EcmascriptChunkItemContent> {
+        panic!("content() should not be called");
+    }
+
+    #[turbo_tasks::function]
+    fn content_with_async_module_info(
+        &self,
+        async_module_info: Option<Vc<AsyncModuleInfo>>,
+        _estimated: bool,
+    ) -> Vc<EcmascriptChunkItemContent> {
+        let async_module_options = self
+            .module
+            .get_async_module()
+            .module_options(async_module_info);
+
+        EcmascriptChunkItemContent::new(
+            self.module.content(*self.chunking_context),
+            *self.chunking_context,
+            async_module_options,
+        )
+    }
+}
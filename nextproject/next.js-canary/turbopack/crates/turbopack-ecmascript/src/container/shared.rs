@@ -0,0 +1,301 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use bincode::{Decode, Encode};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{NonLocalValue, ResolvedVc, TaskInput, ValueToString, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem, rope::RopeBuilder};
+use turbopack_core::{
+    chunk::{
+        AsyncModuleInfo, ChunkItem, ChunkType, ChunkableModule, ChunkableModuleReference,
+        ChunkingContext, ChunkingType, ChunkingTypeOption,
+    },
+    ident::AssetIdent,
+    module::{Module, ModuleSideEffects},
+    module_graph::ModuleGraph,
+    output::OutputAssetsReference,
+    reference::{ModuleReference, ModuleReferences},
+    resolve::ModuleResolveResult,
+};
+
+use super::{
+    ensure_module_federation_enabled,
+    version_range::{parse_version_range, validate_version},
+};
+use crate::{
+    EcmascriptModuleContent,
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
+        EcmascriptChunkType, EcmascriptExports,
+    },
+    references::async_module::{AsyncModule, OptionAsyncModule},
+    runtime_functions::{
+        TURBOPACK_ASYNC_LOADER, TURBOPACK_EXPORT_NAMESPACE, TURBOPACK_LOAD_SHARED,
+    },
+    utils::{StringifyJs, StringifyModuleId},
+};
+
+/// How a module is shared between the bundles on a page.
+#[derive(
+    Clone, Debug, Eq, PartialEq, TraceRawVcs, TaskInput, Hash, NonLocalValue, Encode, Decode,
+)]
+pub struct SharedModuleConfig {
+    /// The key the module is shared under, usually the package name.
+    pub share_key: RcStr,
+    /// The version of the module provided by this bundle, usually from its package.json.
+    pub version: Option<RcStr>,
+    /// The semver range of versions provided by other bundles which can be used instead, e.g.
+    /// `^18.0.0`. All versions are accepted when this is `None`. Ranges are desugared at build
+    /// time, see [super::version_range].
+    pub required_version: Option<RcStr>,
+    /// Whether only a single version of the module may be loaded on the page, e.g. for React.
+    pub singleton: bool,
+}
+
+/// The root of the paths of sources which embedders map to [ConsumeSharedModule]s, which are
+/// `<share key>`.
+#[turbo_tasks::function]
+pub fn shared_fs_root() -> Vc<FileSystemPath> {
+    VirtualFileSystem::new_with_name(rcstr!("shared")).root()
+}
+
+/// Consumes a module from the share scope of the runtime. When no bundle provides a suitable
+/// version, the `fallback` module of this bundle is loaded instead, in a separate async chunk so
+/// it's only loaded when needed.
+#[turbo_tasks::value]
+pub struct ConsumeSharedModule {
+    fallback: ResolvedVc<Box<dyn ChunkableModule>>,
+    config: SharedModuleConfig,
+}
+
+#[turbo_tasks::value_impl]
+impl ConsumeSharedModule {
+    #[turbo_tasks::function]
+    pub fn new(
+        fallback: ResolvedVc<Box<dyn ChunkableModule>>,
+        config: SharedModuleConfig,
+    ) -> Vc<Self> {
+        Self::cell(ConsumeSharedModule { fallback, config })
+    }
+
+    #[turbo_tasks::function]
+    async fn content(
+        &self,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<Vc<EcmascriptModuleContent>> {
+        ensure_module_federation_enabled(chunking_context, "Shared modules").await?;
+
+        let share_key = &self.config.share_key;
+        if let Some(version) = &self.config.version {
+            validate_version(version).with_context(|| {
+                format!("The version of the shared module {share_key} is invalid")
+            })?;
+        }
+        let required_version = self
+            .config
+            .required_version
+            .as_deref()
+            .map(parse_version_range)
+            .transpose()
+            .with_context(|| {
+                format!("The required version of the shared module {share_key} is invalid")
+            })?;
+
+        let fallback_ident =
+            chunking_context.async_loader_chunk_item_ident(*ResolvedVc::upcast(self.fallback));
+        let fallback_id = chunking_context
+            .chunk_item_id_strategy()
+            .await?
+            .get_id_from_ident(fallback_ident)
+            .await?;
+
+        let mut code = RopeBuilder::default();
+        writeln!(
+            code,
+            "const mod = await {TURBOPACK_LOAD_SHARED}({}, {}, {}, {}, () => \
+             {TURBOPACK_ASYNC_LOADER}({}));",
+            StringifyJs(share_key),
+            StringifyJs(&self.config.version),
+            StringifyJs(&required_version),
+            self.config.singleton,
+            StringifyModuleId(&fallback_id),
+        )?;
+        writeln!(code)?;
+        writeln!(code, "{TURBOPACK_EXPORT_NAMESPACE}(mod);")?;
+
+        Ok(EcmascriptModuleContent {
+            inner_code: code.build(),
+            source_map: None,
+            is_esm: true,
+            strict: false,
+            additional_ids: Default::default(),
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for ConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.fallback
+            .ident()
+            .with_modifier(format!("consume shared {}", self.config.share_key).into())
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<turbopack_core::source::OptionSource> {
+        Vc::cell(None)
+    }
+
+    #[turbo_tasks::function]
+    async fn references(&self) -> Result<Vc<ModuleReferences>> {
+        Ok(Vc::cell(vec![ResolvedVc::upcast(
+            SharedFallbackReference {
+                module: ResolvedVc::upcast(self.fallback),
+            }
+            .resolved_cell(),
+        )]))
+    }
+
+    #[turbo_tasks::function]
+    fn is_self_async(&self) -> Vc<bool> {
+        Vc::cell(true)
+    }
+
+    #[turbo_tasks::function]
+    fn side_effects(self: Vc<Self>) -> Vc<ModuleSideEffects> {
+        ModuleSideEffects::SideEffectful.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for ConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self: ResolvedVc<Self>,
+        _module_graph: Vc<ModuleGraph>,
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Box<dyn ChunkItem>> {
+        Vc::upcast(
+            ConsumeSharedChunkItem {
+                module: self,
+                chunking_context,
+            }
+            .cell(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for ConsumeSharedModule {
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> Vc<EcmascriptExports> {
+        EcmascriptExports::DynamicNamespace.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn get_async_module(&self) -> Vc<OptionAsyncModule> {
+        Vc::cell(Some(
+            AsyncModule {
+                has_top_level_await: true,
+                import_externals: false,
+            }
+            .resolved_cell(),
+        ))
+    }
+}
+
+/// The reference to the fallback of a [ConsumeSharedModule], which is placed in an async chunk.
+#[turbo_tasks::value]
+struct SharedFallbackReference {
+    module: ResolvedVc<Box<dyn Module>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for SharedFallbackReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(&self) -> Vc<ModuleResolveResult> {
+        *ModuleResolveResult::module(self.module)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for SharedFallbackReference {
+    #[turbo_tasks::function]
+    fn chunking_type(&self) -> Vc<ChunkingTypeOption> {
+        Vc::cell(Some(ChunkingType::Async))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for SharedFallbackReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!(
+                "shared module fallback {}",
+                self.module.ident().to_string().await?
+            )
+            .into(),
+        ))
+    }
+}
+
+#[turbo_tasks::value]
+struct ConsumeSharedChunkItem {
+    module: ResolvedVc<ConsumeSharedModule>,
+    chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for ConsumeSharedChunkItem {}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for ConsumeSharedChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> Vc<AssetIdent> {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn ty(self: Vc<Self>) -> Vc<Box<dyn ChunkType>> {
+        Vc::upcast(Vc::<EcmascriptChunkType>::default())
+    }
+
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        Vc::upcast(*self.module)
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        *self.chunking_context
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for ConsumeSharedChunkItem {
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>) -> Vc<EcmascriptChunkItemContent> {
+        panic!("content() should not be called");
+    }
+
+    #[turbo_tasks::function]
+    fn content_with_async_module_info(
+        &self,
+        async_module_info: Option<Vc<AsyncModuleInfo>>,
+        _estimated: bool,
+    ) -> Vc<EcmascriptChunkItemContent> {
+        let async_module_options = self
+            .module
+            .get_async_module()
+            .module_options(async_module_info);
+
+        EcmascriptChunkItemContent::new(
+            self.module.content(*self.chunking_context),
+            *self.chunking_context,
+            async_module_options,
+        )
+    }
+}
@@ -0,0 +1,396 @@
+//! Versions and semver ranges of shared modules.
+//!
+//! Ranges are desugared into plain comparators at build time, following the rules of npm's
+//! `semver` package, so the runtime only needs to compare versions. Ranges which can't be
+//! desugared faithfully, e.g. ranges with prerelease versions, are rejected.
+
+use std::fmt::{self, Display};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ComparatorOperator {
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "=")]
+    Equal,
+}
+
+impl Display for ComparatorOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ComparatorOperator::Less => "<",
+            ComparatorOperator::LessOrEqual => "<=",
+            ComparatorOperator::Greater => ">",
+            ComparatorOperator::GreaterOrEqual => ">=",
+            ComparatorOperator::Equal => "=",
+        })
+    }
+}
+
+/// Compares a version with `major.minor.patch`, e.g. `>=1.2.3`. It's serialized as
+/// `[">=", 1, 2, 3]` for the runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Comparator(pub ComparatorOperator, pub u64, pub u64, pub u64);
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Comparator(operator, major, minor, patch) = self;
+        write!(f, "{operator}{major}.{minor}.{patch}")
+    }
+}
+
+/// A version range is satisfied by a version when all comparators of any of its comparator sets
+/// are satisfied. An empty comparator set is satisfied by all versions. Prerelease versions never
+/// satisfy a range.
+pub type VersionRange = Vec<Vec<Comparator>>;
+
+/// Checks that `version` is a valid semver version, e.g. `1.2.3` or `1.0.0-rc.1`.
+pub fn validate_version(version: &str) -> Result<()> {
+    let rest = version.strip_prefix('v').unwrap_or(version);
+    let (rest, build) = match rest.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (rest, None),
+    };
+    let (release, prerelease) = match rest.split_once('-') {
+        Some((release, prerelease)) => (release, Some(prerelease)),
+        None => (rest, None),
+    };
+    let is_identifier_list = |identifiers: &str| {
+        identifiers.split('.').all(|identifier| {
+            !identifier.is_empty()
+                && identifier
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    };
+    let components = release.split('.').collect::<Vec<_>>();
+    if components.len() != 3
+        || components
+            .iter()
+            .any(|component| component.is_empty() || !component.chars().all(|c| c.is_ascii_digit()))
+        || !prerelease.is_none_or(is_identifier_list)
+        || !build.is_none_or(is_identifier_list)
+    {
+        bail!("{version} is not a valid semver version");
+    }
+    Ok(())
+}
+
+/// Desugars an npm version range like `^1.2.0 || >=2.1 <3` into comparators.
+pub fn parse_version_range(range: &str) -> Result<VersionRange> {
+    range
+        .split("||")
+        .map(|comparator_set| {
+            parse_comparator_set(comparator_set.trim())
+                .with_context(|| format!("Unsupported version range {range}"))
+        })
+        .collect()
+}
+
+fn parse_comparator_set(comparator_set: &str) -> Result<Vec<Comparator>> {
+    let tokens = comparator_set.split_whitespace().collect::<Vec<_>>();
+    let mut comparators = Vec::new();
+
+    if let [from, "-", to] = tokens[..] {
+        // Hyphen ranges are inclusive on both sides, missing components of the upper bound are
+        // wildcards.
+        let from = PartialVersion::parse(from)?;
+        let to = PartialVersion::parse(to)?;
+        desugar(Operator::GreaterOrEqual, from, &mut comparators)?;
+        desugar(Operator::LessOrEqual, to, &mut comparators)?;
+        return Ok(comparators);
+    }
+
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        let (operator, version) = Operator::split(token);
+        // Allow whitespace between the operator and the version, e.g. `>= 1.2.3`.
+        let version = match version {
+            "" if operator != Operator::None => tokens
+                .next()
+                .with_context(|| format!("{token} is missing a version"))?,
+            version => version,
+        };
+        desugar(operator, PartialVersion::parse(version)?, &mut comparators)?;
+    }
+    Ok(comparators)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    None,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    Caret,
+    Tilde,
+}
+
+impl Operator {
+    fn split(token: &str) -> (Operator, &str) {
+        const OPERATORS: [(&str, Operator); 8] = [
+            ("~>", Operator::Tilde),
+            (">=", Operator::GreaterOrEqual),
+            ("<=", Operator::LessOrEqual),
+            (">", Operator::Greater),
+            ("<", Operator::Less),
+            ("=", Operator::Equal),
+            ("^", Operator::Caret),
+            ("~", Operator::Tilde),
+        ];
+        OPERATORS
+            .into_iter()
+            .find_map(|(prefix, operator)| token.strip_prefix(prefix).map(|rest| (operator, rest)))
+            .unwrap_or((Operator::None, token))
+    }
+}
+
+/// A version in a range, whose components can be missing or wildcards, e.g. `1.x` or `1.2`. A
+/// component following a wildcard is a wildcard too, like in `1.x.3`.
+#[derive(Clone, Copy, Debug)]
+struct PartialVersion {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+}
+
+impl PartialVersion {
+    fn parse(version: &str) -> Result<Self> {
+        let rest = version.strip_prefix(['v', '=']).unwrap_or(version);
+        let rest = rest.split_once('+').map_or(rest, |(rest, _build)| rest);
+        if rest.contains('-') {
+            bail!("{version} is a prerelease version, which are not supported in version ranges");
+        }
+        let components = rest.split('.').collect::<Vec<_>>();
+        if components.len() > 3 {
+            bail!("{version} is not a valid version");
+        }
+        let mut parsed = [None; 3];
+        for (parsed, component) in parsed.iter_mut().zip(components) {
+            *parsed = match component {
+                "x" | "X" | "*" => break,
+                component
+                    if !component.is_empty() && component.chars().all(|c| c.is_ascii_digit()) =>
+                {
+                    Some(
+                        component
+                            .parse()
+                            .with_context(|| format!("{version} is too large"))?,
+                    )
+                }
+                _ => bail!("{version} is not a valid version"),
+            };
+        }
+        let [major, minor, patch] = parsed;
+        Ok(PartialVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+fn increment(component: u64) -> Result<u64> {
+    component
+        .checked_add(1)
+        .context("version component is too large")
+}
+
+/// Desugars a single comparator like `^1.2` into `>=1.2.0 <2.0.0`.
+fn desugar(
+    operator: Operator,
+    version: PartialVersion,
+    comparators: &mut Vec<Comparator>,
+) -> Result<()> {
+    use ComparatorOperator::*;
+
+    let mut push = |operator, major, minor, patch| {
+        comparators.push(Comparator(operator, major, minor, patch));
+    };
+
+    match (operator, version.major, version.minor, version.patch) {
+        // `*`, `>=*` and `<=*` match all versions.
+        (
+            Operator::None
+            | Operator::Equal
+            | Operator::Caret
+            | Operator::Tilde
+            | Operator::GreaterOrEqual
+            | Operator::LessOrEqual,
+            None,
+            _,
+            _,
+        ) => {}
+        (Operator::Less | Operator::Greater, None, _, _) => {
+            bail!("{operator:?} with a wildcard version doesn't match any version")
+        }
+
+        (Operator::None | Operator::Equal, Some(major), minor, patch) => match (minor, patch) {
+            (Some(minor), Some(patch)) => push(Equal, major, minor, patch),
+            (Some(minor), None) => {
+                push(GreaterOrEqual, major, minor, 0);
+                push(Less, major, increment(minor)?, 0);
+            }
+            (None, _) => {
+                push(GreaterOrEqual, major, 0, 0);
+                push(Less, increment(major)?, 0, 0);
+            }
+        },
+
+        // `^` allows changes which don't modify the left-most non-zero component.
+        (Operator::Caret, Some(major), minor, patch) => match (major, minor, patch) {
+            (0, Some(0), Some(patch)) => {
+                push(GreaterOrEqual, 0, 0, patch);
+                push(Less, 0, 0, increment(patch)?);
+            }
+            (0, Some(minor), patch) => {
+                push(GreaterOrEqual, 0, minor, patch.unwrap_or(0));
+                push(Less, 0, increment(minor)?, 0);
+            }
+            (major, minor, patch) => {
+                push(
+                    GreaterOrEqual,
+                    major,
+                    minor.unwrap_or(0),
+                    patch.unwrap_or(0),
+                );
+                push(Less, increment(major)?, 0, 0);
+            }
+        },
+
+        // `~` allows patch changes when the minor version is given, minor changes otherwise.
+        (Operator::Tilde, Some(major), minor, patch) => match minor {
+            Some(minor) => {
+                push(GreaterOrEqual, major, minor, patch.unwrap_or(0));
+                push(Less, major, increment(minor)?, 0);
+            }
+            None => {
+                push(GreaterOrEqual, major, 0, 0);
+                push(Less, increment(major)?, 0, 0);
+            }
+        },
+
+        (Operator::Greater, Some(major), minor, patch) => match (minor, patch) {
+            (Some(minor), Some(patch)) => push(Greater, major, minor, patch),
+            (Some(minor), None) => push(GreaterOrEqual, major, increment(minor)?, 0),
+            (None, _) => push(GreaterOrEqual, increment(major)?, 0, 0),
+        },
+        (Operator::GreaterOrEqual, Some(major), minor, patch) => push(
+            GreaterOrEqual,
+            major,
+            minor.unwrap_or(0),
+            patch.unwrap_or(0),
+        ),
+        (Operator::Less, Some(major), minor, patch) => {
+            push(Less, major, minor.unwrap_or(0), patch.unwrap_or(0))
+        }
+        (Operator::LessOrEqual, Some(major), minor, patch) => match (minor, patch) {
+            (Some(minor), Some(patch)) => push(LessOrEqual, major, minor, patch),
+            (Some(minor), None) => push(Less, major, increment(minor)?, 0),
+            (None, _) => push(Less, increment(major)?, 0, 0),
+        },
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desugared(range: &str) -> String {
+        parse_version_range(range)
+            .unwrap()
+            .iter()
+            .map(|comparators| {
+                comparators
+                    .iter()
+                    .map(|comparator| comparator.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(" || ")
+    }
+
+    #[test]
+    fn caret_ranges() {
+        assert_eq!(desugared("^1.2.3"), ">=1.2.3 <2.0.0");
+        assert_eq!(desugared("^1.2"), ">=1.2.0 <2.0.0");
+        assert_eq!(desugared("^1"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared("^0.2.3"), ">=0.2.3 <0.3.0");
+        assert_eq!(desugared("^0.0.3"), ">=0.0.3 <0.0.4");
+        assert_eq!(desugared("^0.0"), ">=0.0.0 <0.1.0");
+        assert_eq!(desugared("^0.x"), ">=0.0.0 <1.0.0");
+    }
+
+    #[test]
+    fn tilde_ranges() {
+        assert_eq!(desugared("~1.2.3"), ">=1.2.3 <1.3.0");
+        assert_eq!(desugared("~1.2"), ">=1.2.0 <1.3.0");
+        assert_eq!(desugared("~1"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared("~0.0.3"), ">=0.0.3 <0.1.0");
+        assert_eq!(desugared("~>1.2"), ">=1.2.0 <1.3.0");
+    }
+
+    #[test]
+    fn x_ranges() {
+        assert_eq!(desugared("*"), "");
+        assert_eq!(desugared(""), "");
+        assert_eq!(desugared("1.x"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared("1.2.*"), ">=1.2.0 <1.3.0");
+        assert_eq!(desugared("1.x.3"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared("1"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared("v1.2.3"), "=1.2.3");
+        assert_eq!(desugared("=1.2.3"), "=1.2.3");
+    }
+
+    #[test]
+    fn primitive_ranges() {
+        assert_eq!(desugared(">=1 <2"), ">=1.0.0 <2.0.0");
+        assert_eq!(desugared(">= 1.2.3 < 2"), ">=1.2.3 <2.0.0");
+        assert_eq!(desugared(">1.2"), ">=1.3.0");
+        assert_eq!(desugared(">1.2.3"), ">1.2.3");
+        assert_eq!(desugared("<=1.2"), "<1.3.0");
+        assert_eq!(desugared("<=1.2.3"), "<=1.2.3");
+        assert_eq!(desugared("<1.2"), "<1.2.0");
+    }
+
+    #[test]
+    fn hyphen_and_union_ranges() {
+        assert_eq!(desugared("1.2.3 - 2.3.4"), ">=1.2.3 <=2.3.4");
+        assert_eq!(desugared("1.2 - 2.3"), ">=1.2.0 <2.4.0");
+        assert_eq!(desugared("1 - 2"), ">=1.0.0 <3.0.0");
+        assert_eq!(
+            desugared("^1.2.0 || >=3.1 <4"),
+            ">=1.2.0 <2.0.0 || >=3.1.0 <4.0.0"
+        );
+    }
+
+    #[test]
+    fn unsupported_ranges() {
+        assert!(parse_version_range("^1.2.3-rc.1").is_err());
+        assert!(parse_version_range(">*").is_err());
+        assert!(parse_version_range("1.2.3.4").is_err());
+        assert!(parse_version_range("latest").is_err());
+        assert!(parse_version_range(">=").is_err());
+    }
+
+    #[test]
+    fn versions() {
+        assert!(validate_version("1.2.3").is_ok());
+        assert!(validate_version("1.2.3-rc.1+build.5").is_ok());
+        assert!(validate_version("1.2").is_err());
+        assert!(validate_version("1.2.x").is_err());
+        assert!(validate_version("^1.2.3").is_err());
+    }
+}
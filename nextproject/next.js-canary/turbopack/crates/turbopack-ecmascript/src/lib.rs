@@ -11,8 +11,10 @@
 pub mod analyzer;
 pub mod annotations;
 pub mod async_chunk;
+pub mod binding_usage_hints;
 pub mod chunk;
 pub mod code_gen;
+pub mod container;
 mod errors;
 pub mod inlined_bytes_module;
 pub mod magic_identifier;
@@ -1077,7 +1079,7 @@ impl EcmascriptModuleContent {
             ..
         } = &*input;
 
-        let minify = chunking_context.minify_type().await?;
+        let minify = chunking_context.minify_type().owned().await?;
 
         let content = process_parse_result(
             *parsed,
@@ -1085,7 +1087,7 @@ impl EcmascriptModuleContent {
             *specified_module_type,
             *generate_source_map,
             *original_source_map,
-            *minify,
+            minify,
             Some(&*input),
             None,
         )
@@ -1171,7 +1173,7 @@ impl EcmascriptModuleContent {
                         *specified_module_type,
                         *generate_source_map,
                         *original_source_map,
-                        *chunking_context.minify_type().await?,
+                        chunking_context.minify_type().owned().await?,
                         Some(&*options),
                         Some(ScopeHoistingOptions {
                             module: *module,
@@ -1210,7 +1212,7 @@ impl EcmascriptModuleContent {
                 original_source_map: CodeGenResultOriginalSourceMap::ScopeHoisting(
                     original_source_maps,
                 ),
-                minify: *options.chunking_context.minify_type().await?,
+                minify: options.chunking_context.minify_type().owned().await?,
                 scope_hoisting_syntax_contexts: None,
             };
 
@@ -2125,7 +2127,7 @@ async fn emit_content(
             &mut bytes,
             generate_source_map.then_some(&mut mappings),
         );
-        if matches!(minify, MinifyType::Minify { .. }) {
+        if matches!(minify, MinifyType::Minify(_)) {
             wr.set_indent_str("");
         }
 
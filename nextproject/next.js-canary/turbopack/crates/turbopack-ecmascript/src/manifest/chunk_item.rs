@@ -34,6 +34,7 @@ impl ManifestChunkItem {
         Ok(ChunkData::from_assets(
             self.chunking_context.output_root().owned().await?,
             *self.manifest.chunk_group().await?.assets,
+            *self.chunking_context.subresource_integrity().await?,
         ))
     }
 }
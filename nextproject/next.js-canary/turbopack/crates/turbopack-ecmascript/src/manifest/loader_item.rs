@@ -71,6 +71,7 @@ impl ManifestLoaderChunkItem {
         Ok(ChunkData::from_assets(
             self.chunking_context.output_root().owned().await?,
             *chunks,
+            *self.chunking_context.subresource_integrity().await?,
         ))
     }
 
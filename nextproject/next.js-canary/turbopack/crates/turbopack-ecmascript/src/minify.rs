@@ -3,15 +3,16 @@ use std::sync::Arc;
 use anyhow::{Context, Result, bail};
 use bytes_str::BytesStr;
 use swc_core::{
-    atoms::atom,
+    atoms::{Atom, atom},
     base::try_with_handler,
     common::{
-        BytePos, FileName, FilePathMapping, GLOBALS, LineCol, Mark, SourceMap as SwcSourceMap,
-        comments::{Comments, SingleThreadedComments},
+        BytePos, DUMMY_SP, FileName, FilePathMapping, GLOBALS, LineCol, Mark,
+        SourceMap as SwcSourceMap,
+        comments::{Comment, CommentKind, Comments, SingleThreadedComments},
     },
     ecma::{
         self,
-        ast::{EsVersion, Program},
+        ast::{EsVersion, Expr, Ident, IdentName, MemberExpr, MemberProp, Program},
         codegen::{
             Emitter,
             text_writer::{self, JsWriter, WriteJs},
@@ -27,19 +28,23 @@ use swc_core::{
 };
 use tracing::instrument;
 use turbopack_core::{
-    chunk::MangleType,
+    chunk::{EcmaVersion, EcmascriptMinifyOptions, MangleType, MinifyComments},
     code_builder::{Code, CodeBuilder},
 };
 
 use crate::parse::{IdentCollector, generate_js_source_map};
 
 #[instrument(level = "info", name = "minify ecmascript code", skip_all)]
-pub fn minify(code: Code, source_maps: bool, mangle: Option<MangleType>) -> Result<Code> {
+pub fn minify(code: Code, source_maps: bool, options: &EcmascriptMinifyOptions) -> Result<Code> {
+    let mangle = options.mangle;
+    let ecma = options.ecma.map(es_version);
+
     // Pass None for the debug ID so we don't needlessly compute it for the pre-minified content, it
     // will be added by the Code object returned from this function
     let source_maps = source_maps.then(|| code.generate_source_map_ref(None));
 
     let generate_debug_id = code.should_generate_debug_id();
+    let mut license_comments = code.license_comments().to_vec();
     let source_code = BytesStr::from_utf8(code.into_source_code().into_bytes())?;
 
     let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
@@ -95,24 +100,39 @@ pub fn minify(code: Code, source_maps: bool, mangle: Option<MangleType>) -> Resu
                         None,
                         &MinifyOptions {
                             compress: Some(CompressOptions {
-                                // Only run 2 passes, this is a tradeoff between performance and
-                                // compression size. Default is 3 passes.
-                                passes: 2,
-                                keep_classnames: mangle.is_none(),
-                                keep_fnames: mangle.is_none(),
+                                // Only run 2 passes by default, this is a tradeoff between
+                                // performance and compression size. Default of swc is 3 passes.
+                                passes: options.passes.unwrap_or(2),
+                                keep_classnames: options.keep_classnames || mangle.is_none(),
+                                keep_fnames: options.keep_fnames || mangle.is_none(),
+                                drop_console: options.drop_console,
+                                pure_funcs: options
+                                    .pure_funcs
+                                    .iter()
+                                    .map(|name| pure_func_expr(name))
+                                    .collect(),
                                 ..Default::default()
+                            })
+                            .map(|compress| match ecma {
+                                Some(ecma) => CompressOptions { ecma, ..compress },
+                                None => compress,
                             }),
                             mangle: mangle.map(|mangle| {
-                                let reserved = vec![atom!("AbortSignal")];
+                                let reserved = [atom!("AbortSignal")]
+                                    .into_iter()
+                                    .chain(options.reserved.iter().map(|name| Atom::from(&**name)))
+                                    .collect();
+                                let mangle_options = MangleOptions {
+                                    reserved,
+                                    keep_class_names: options.keep_classnames,
+                                    keep_fn_names: options.keep_fnames,
+                                    ..Default::default()
+                                };
                                 match mangle {
-                                    MangleType::OptimalSize => MangleOptions {
-                                        reserved,
-                                        ..Default::default()
-                                    },
+                                    MangleType::OptimalSize => mangle_options,
                                     MangleType::Deterministic => MangleOptions {
-                                        reserved,
                                         disable_char_freq: true,
-                                        ..Default::default()
+                                        ..mangle_options
                                     },
                                 }
                             }),
@@ -141,11 +161,46 @@ pub fn minify(code: Code, source_maps: bool, mangle: Option<MangleType>) -> Resu
             })
             .map_err(|e| e.to_pretty_error())?;
 
-        let (src, src_map_buf) = print_program(cm.clone(), program, source_maps.is_some())?;
+        let printed_comments = match options.comments {
+            MinifyComments::None => None,
+            MinifyComments::Licenses => {
+                let (mut leading, mut trailing) = comments.borrow_all_mut();
+                for map in [&mut *leading, &mut *trailing] {
+                    map.retain(|_, list| {
+                        list.retain(is_license_comment);
+                        !list.is_empty()
+                    });
+                }
+                Some(&comments as &dyn Comments)
+            }
+            MinifyComments::ExtractLicenses => {
+                let (leading, trailing) = comments.borrow_all();
+                let mut extracted = leading
+                    .iter()
+                    .chain(trailing.iter())
+                    .flat_map(|(pos, list)| list.iter().map(move |comment| (*pos, comment)))
+                    .filter(|(_, comment)| is_license_comment(comment))
+                    .collect::<Vec<_>>();
+                extracted.sort_by_key(|(pos, _)| *pos);
+                license_comments.extend(extracted.into_iter().map(
+                    |(_, comment)| match comment.kind {
+                        CommentKind::Block => format!("/*{}*/", comment.text).into(),
+                        CommentKind::Line => format!("//{}", comment.text).into(),
+                    },
+                ));
+                None
+            }
+        };
+
+        let (src, src_map_buf) =
+            print_program(cm.clone(), program, printed_comments, source_maps.is_some())?;
         (src, src_map_buf, source_map_names)
     };
 
     let mut builder = CodeBuilder::new(source_maps.is_some(), generate_debug_id);
+    for comment in license_comments {
+        builder.push_license_comment(comment);
+    }
     if let Some(original_map) = source_maps.as_ref() {
         src_map_buf.shrink_to_fit();
         builder.push_source(
@@ -168,10 +223,51 @@ pub fn minify(code: Code, source_maps: bool, mangle: Option<MangleType>) -> Resu
     Ok(builder.build())
 }
 
+fn es_version(ecma: EcmaVersion) -> EsVersion {
+    match ecma {
+        EcmaVersion::Es5 => EsVersion::Es5,
+        EcmaVersion::Es2015 => EsVersion::Es2015,
+        EcmaVersion::Es2016 => EsVersion::Es2016,
+        EcmaVersion::Es2017 => EsVersion::Es2017,
+        EcmaVersion::Es2018 => EsVersion::Es2018,
+        EcmaVersion::Es2019 => EsVersion::Es2019,
+        EcmaVersion::Es2020 => EsVersion::Es2020,
+        EcmaVersion::Es2021 => EsVersion::Es2021,
+        EcmaVersion::Es2022 => EsVersion::Es2022,
+    }
+}
+
+/// Converts a function name like `console.info` into the expression that is matched against the
+/// callees.
+fn pure_func_expr(name: &str) -> Box<Expr> {
+    let mut segments = name.split('.');
+    let mut expr = Box::new(Expr::Ident(Ident::new_no_ctxt(
+        segments.next().unwrap_or_default().into(),
+        DUMMY_SP,
+    )));
+    for segment in segments {
+        expr = Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: expr,
+            prop: MemberProp::Ident(IdentName::new(segment.into(), DUMMY_SP)),
+        }));
+    }
+    expr
+}
+
+/// License comments are kept like terser does, e.g. `/*! ... */` or `/** @license MIT */`.
+fn is_license_comment(comment: &Comment) -> bool {
+    (comment.kind == CommentKind::Block && comment.text.starts_with('!'))
+        || ["@license", "@preserve", "@cc_on"]
+            .iter()
+            .any(|marker| comment.text.contains(marker))
+}
+
 // From https://github.com/swc-project/swc/blob/11efd4e7c5e8081f8af141099d3459c3534c1e1d/crates/swc/src/lib.rs#L523-L560
 fn print_program(
     cm: Arc<SwcSourceMap>,
     program: Program,
+    comments: Option<&dyn Comments>,
     source_maps: bool,
 ) -> Result<(String, Vec<(BytePos, LineCol)>)> {
     let mut src_map_buf = vec![];
@@ -188,7 +284,7 @@ fn print_program(
 
             let mut emitter = Emitter {
                 cfg: swc_core::ecma::codegen::Config::default().with_minify(true),
-                comments: None,
+                comments,
                 cm: cm.clone(),
                 wr,
             };
@@ -204,3 +300,142 @@ fn print_program(
 
     Ok((src, src_map_buf))
 }
+
+#[cfg(test)]
+mod tests {
+    use turbo_rcstr::rcstr;
+    use turbopack_core::{
+        chunk::{EcmascriptMinifyOptions, MinifyComments},
+        code_builder::{Code, CodeBuilder},
+    };
+
+    use super::minify;
+
+    fn minify_code(source: &str, options: &EcmascriptMinifyOptions) -> Code {
+        let mut builder = CodeBuilder::new(false, false);
+        builder.push_source(&source.to_string().into(), None);
+        minify(builder.build(), false, options).unwrap()
+    }
+
+    fn minify_source(source: &str, options: &EcmascriptMinifyOptions) -> String {
+        let code = minify_code(source, options);
+        code.source_code().to_str().unwrap().into_owned()
+    }
+
+    const CLASS_SOURCE: &str = r#"
+        (function () {
+            class ReportedError extends Error {}
+            globalThis.createError = function () {
+                return new ReportedError("failed");
+            };
+        })();
+    "#;
+
+    #[test]
+    fn keep_classnames() {
+        let default = minify_source(CLASS_SOURCE, &Default::default());
+        assert!(!default.contains("ReportedError"), "{default}");
+
+        let kept = minify_source(
+            CLASS_SOURCE,
+            &EcmascriptMinifyOptions {
+                keep_classnames: true,
+                ..Default::default()
+            },
+        );
+        assert!(kept.contains("class ReportedError"), "{kept}");
+    }
+
+    #[test]
+    fn drop_console_and_pure_funcs() {
+        let source = r#"
+            globalThis.f = function (value) {
+                console.log("value", value);
+                debugLog("value");
+                return value;
+            };
+        "#;
+
+        let default = minify_source(source, &Default::default());
+        assert!(default.contains("console.log"), "{default}");
+        assert!(default.contains("debugLog"), "{default}");
+
+        let dropped = minify_source(
+            source,
+            &EcmascriptMinifyOptions {
+                drop_console: true,
+                pure_funcs: vec![rcstr!("debugLog")],
+                ..Default::default()
+            },
+        );
+        assert!(!dropped.contains("console"), "{dropped}");
+        assert!(!dropped.contains("debugLog"), "{dropped}");
+    }
+
+    #[test]
+    fn reserved_names() {
+        let source = r#"
+            globalThis.f = function (importantName) {
+                return importantName + importantName;
+            };
+        "#;
+
+        let default = minify_source(source, &Default::default());
+        assert!(!default.contains("importantName"), "{default}");
+
+        let reserved = minify_source(
+            source,
+            &EcmascriptMinifyOptions {
+                reserved: vec![rcstr!("importantName")],
+                ..Default::default()
+            },
+        );
+        assert!(reserved.contains("importantName"), "{reserved}");
+    }
+
+    #[test]
+    fn license_comments() {
+        let source = r#"
+            /*! lib v1.0.0 | MIT */
+            // @license Apache-2.0
+            /* a regular comment */
+            globalThis.f = function () {
+                return 1;
+            };
+        "#;
+
+        let removed = minify_code(source, &Default::default());
+        let code = removed.source_code().to_str().unwrap();
+        assert!(!code.contains("lib v1.0.0"), "{code}");
+        assert!(!removed.has_license_comments());
+
+        let kept = minify_source(
+            source,
+            &EcmascriptMinifyOptions {
+                comments: MinifyComments::Licenses,
+                ..Default::default()
+            },
+        );
+        assert!(kept.contains("/*! lib v1.0.0 | MIT */"), "{kept}");
+        assert!(kept.contains("// @license Apache-2.0"), "{kept}");
+        assert!(!kept.contains("a regular comment"), "{kept}");
+
+        let extracted = minify_code(
+            source,
+            &EcmascriptMinifyOptions {
+                comments: MinifyComments::ExtractLicenses,
+                ..Default::default()
+            },
+        );
+        let code = extracted.source_code().to_str().unwrap();
+        assert!(!code.contains("lib v1.0.0"), "{code}");
+        assert!(!code.contains("@license"), "{code}");
+        assert_eq!(
+            extracted.license_comments(),
+            [
+                rcstr!("/*! lib v1.0.0 | MIT */"),
+                rcstr!("// @license Apache-2.0")
+            ]
+        );
+    }
+}
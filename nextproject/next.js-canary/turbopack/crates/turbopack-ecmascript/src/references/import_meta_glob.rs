@@ -0,0 +1,366 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use swc_core::{ecma::ast::Expr, quote};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{
+    NonLocalValue, ResolvedVc, ValueToString, Vc, debug::ValueDebugFormat, trace::TraceRawVcs,
+};
+use turbo_tasks_fs::{
+    DirectoryEntry, File, FileContent, FileSystemPath,
+    glob::{Glob, GlobOptions},
+};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{ChunkableModuleReference, ChunkingContext, ModuleChunkItemIdExt},
+    context::AssetContext,
+    ident::AssetIdent,
+    module::Module,
+    reference::ModuleReference,
+    reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+    resolve::{ModuleResolveResult, origin::ResolveOrigin},
+    source::Source,
+};
+
+use crate::{
+    analyzer::ImportMetaGlobOptions,
+    code_gen::{CodeGen, CodeGeneration, IntoCodeGenReference},
+    create_visitor,
+    references::AstPath,
+    runtime_functions::TURBOPACK_IMPORT,
+    utils::{StringifyJs, module_id_to_lit},
+};
+
+/// A source for the module generated for an `import.meta.glob()` call. It exports the map of
+/// matched modules as default export.
+#[turbo_tasks::value]
+pub struct ImportMetaGlobSource {
+    source: ResolvedVc<Box<dyn Source>>,
+    patterns: Vec<RcStr>,
+    eager: bool,
+    import: Option<RcStr>,
+    query: Option<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMetaGlobSource {
+    #[turbo_tasks::function]
+    pub fn new(
+        source: ResolvedVc<Box<dyn Source>>,
+        patterns: Vec<RcStr>,
+        eager: bool,
+        import: Option<RcStr>,
+        query: Option<RcStr>,
+    ) -> Vc<Self> {
+        ImportMetaGlobSource {
+            source,
+            patterns,
+            eager,
+            import,
+            query,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for ImportMetaGlobSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        let mut modifier = format!("import.meta.glob {}", self.patterns.join(" "));
+        if self.eager {
+            modifier.push_str(" eager");
+        }
+        if let Some(import) = &self.import {
+            modifier.push_str(&format!(" import {import}"));
+        }
+        if let Some(query) = &self.query {
+            modifier.push_str(&format!(" query {query}"));
+        }
+        // The module stays next to the importing module, so that the generated relative imports
+        // resolve from the same directory.
+        self.source
+            .ident()
+            .with_modifier(modifier.into())
+            .rename_as(rcstr!("*.mjs"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ImportMetaGlobSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let path = self.source.ident().path().owned().await?;
+        let keys = glob_keys(&path, &self.patterns).await?;
+        let code = glob_module_code(
+            &keys,
+            self.eager,
+            self.import.as_deref(),
+            self.query.as_deref(),
+        )?;
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(code)).cell(),
+        ))
+    }
+}
+
+/// Splits a relative glob pattern into the directory without any glob syntax and the remaining
+/// glob, e.g. `./pages/**/*.tsx` into `./pages` and `**/*.tsx`.
+fn split_glob_pattern(pattern: &str) -> (&str, &str) {
+    let mut base_end = 0;
+    for (index, _) in pattern.match_indices('/') {
+        if pattern[base_end..index].contains(['*', '?', '[', '{']) {
+            break;
+        }
+        base_end = index;
+    }
+    (&pattern[..base_end], &pattern[base_end + 1..])
+}
+
+/// Finds the files matching the patterns and returns their paths relative to the directory of
+/// `path`, which are used as keys of the generated map. The importing module is never included.
+async fn glob_keys(path: &FileSystemPath, patterns: &[RcStr]) -> Result<BTreeSet<RcStr>> {
+    let dir = path.parent();
+    let (negated, patterns): (Vec<_>, Vec<_>) = patterns
+        .iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let negated = negated
+        .into_iter()
+        .map(|pattern| {
+            Glob::parse(
+                pattern.trim_start_matches('!').into(),
+                GlobOptions::default(),
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut keys = BTreeSet::new();
+    for pattern in patterns {
+        let (base, glob) = split_glob_pattern(pattern);
+        let mut stack = vec![
+            dir.join(base)?
+                .read_glob(Glob::new(glob.into(), GlobOptions::default()))
+                .await?,
+        ];
+        while let Some(result) = stack.pop() {
+            for entry in result.results.values() {
+                let (DirectoryEntry::File(file_path) | DirectoryEntry::Symlink(file_path)) = entry
+                else {
+                    continue;
+                };
+                if file_path == path {
+                    continue;
+                }
+                let Some(key) = dir.get_relative_path_to(file_path) else {
+                    continue;
+                };
+                if !negated.iter().any(|glob| glob.matches(&key)) {
+                    keys.insert(key);
+                }
+            }
+            for inner in result.inner.values() {
+                stack.push(inner.await?);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Generates an ES module which exports a map from the keys to the modules, or to functions
+/// importing the modules when not `eager`.
+fn glob_module_code(
+    keys: &BTreeSet<RcStr>,
+    eager: bool,
+    import: Option<&str>,
+    query: Option<&str>,
+) -> Result<String> {
+    let mut code = String::new();
+    let mut entries = String::new();
+    for (index, key) in keys.iter().enumerate() {
+        let request = format!("{key}{}", query.unwrap_or_default());
+        let value = if eager {
+            let name = format!("__glob_{index}");
+            match import {
+                Some(import) => writeln!(
+                    code,
+                    "import {{ {} as {name} }} from {};",
+                    StringifyJs(import),
+                    StringifyJs(&request)
+                )?,
+                None => writeln!(code, "import * as {name} from {};", StringifyJs(&request))?,
+            }
+            name
+        } else {
+            match import {
+                Some(import) => format!(
+                    "() => import({}).then((m) => m[{}])",
+                    StringifyJs(&request),
+                    StringifyJs(import)
+                ),
+                None => format!("() => import({})", StringifyJs(&request)),
+            }
+        };
+        writeln!(entries, "  {}: {value},", StringifyJs(key))?;
+    }
+    write!(code, "export default {{\n{entries}}};\n")?;
+    Ok(code)
+}
+
+/// A reference for `import.meta.glob()`, will replace it with the default export of the
+/// generated module.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct ImportMetaGlobAssetReference {
+    pub origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+    pub source: ResolvedVc<ImportMetaGlobSource>,
+    pub patterns: Vec<RcStr>,
+}
+
+impl ImportMetaGlobAssetReference {
+    pub async fn new(
+        source: ResolvedVc<Box<dyn Source>>,
+        origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+        options: ImportMetaGlobOptions,
+    ) -> Result<Self> {
+        let ImportMetaGlobOptions {
+            patterns,
+            eager,
+            import,
+            query,
+        } = options;
+        Ok(ImportMetaGlobAssetReference {
+            origin,
+            source: ImportMetaGlobSource::new(*source, patterns.clone(), eager, import, query)
+                .to_resolved()
+                .await?,
+            patterns,
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        self.origin
+            .asset_context()
+            .process(
+                Vc::upcast(*self.source),
+                ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::Undefined),
+            )
+            .module()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(self: Vc<Self>) -> Result<Vc<ModuleResolveResult>> {
+        Ok(*ModuleResolveResult::module(
+            self.module().to_resolved().await?,
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ImportMetaGlobAssetReference {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(format!("import.meta.glob {}", self.patterns.join(" ")).into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for ImportMetaGlobAssetReference {}
+
+impl IntoCodeGenReference for ImportMetaGlobAssetReference {
+    fn into_code_gen_reference(
+        self,
+        path: AstPath,
+    ) -> (ResolvedVc<Box<dyn ModuleReference>>, CodeGen) {
+        let reference = self.resolved_cell();
+        (
+            ResolvedVc::upcast(reference),
+            CodeGen::ImportMetaGlobAssetReferenceCodeGen(ImportMetaGlobAssetReferenceCodeGen {
+                reference,
+                path,
+            }),
+        )
+    }
+}
+
+#[derive(
+    PartialEq, Eq, TraceRawVcs, ValueDebugFormat, NonLocalValue, Hash, Debug, Encode, Decode,
+)]
+pub struct ImportMetaGlobAssetReferenceCodeGen {
+    path: AstPath,
+    reference: ResolvedVc<ImportMetaGlobAssetReference>,
+}
+
+impl ImportMetaGlobAssetReferenceCodeGen {
+    pub async fn code_generation(
+        &self,
+        chunking_context: Vc<Box<dyn ChunkingContext>>,
+    ) -> Result<CodeGeneration> {
+        let module_id = self
+            .reference
+            .module()
+            .chunk_item_id(chunking_context)
+            .await?;
+
+        let visitor = create_visitor!(self.path, visit_mut_expr, |expr: &mut Expr| {
+            if let Expr::Call(_) = expr {
+                *expr = quote!(
+                    "$turbopack_import($id).default" as Expr,
+                    turbopack_import: Expr = TURBOPACK_IMPORT.into(),
+                    id: Expr = module_id_to_lit(&module_id)
+                );
+            }
+        });
+
+        Ok(CodeGeneration::visitors(vec![visitor]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use turbo_rcstr::RcStr;
+
+    use super::{glob_module_code, split_glob_pattern};
+
+    #[test]
+    fn split_pattern() {
+        assert_eq!(split_glob_pattern("./pages/*.tsx"), ("./pages", "*.tsx"));
+        assert_eq!(
+            split_glob_pattern("../src/**/index.ts"),
+            ("../src", "**/index.ts")
+        );
+        assert_eq!(split_glob_pattern("./a/{b,c}/d.ts"), ("./a", "{b,c}/d.ts"));
+        assert_eq!(split_glob_pattern("./a/b.ts"), ("./a", "b.ts"));
+    }
+
+    #[test]
+    fn module_code() {
+        let keys: BTreeSet<RcStr> = ["./pages/a.tsx".into(), "./pages/b.tsx".into()].into();
+        assert_eq!(
+            glob_module_code(&keys, false, None, None).unwrap(),
+            "export default {\n  \"./pages/a.tsx\": () => import(\"./pages/a.tsx\"),\n  \
+             \"./pages/b.tsx\": () => import(\"./pages/b.tsx\"),\n};\n"
+        );
+        assert_eq!(
+            glob_module_code(&keys, false, Some("setup"), Some("?raw")).unwrap(),
+            "export default {\n  \"./pages/a.tsx\": () => import(\"./pages/a.tsx?raw\").then((m) \
+             => m[\"setup\"]),\n  \"./pages/b.tsx\": () => import(\"./pages/b.tsx?raw\").then((m) \
+             => m[\"setup\"]),\n};\n"
+        );
+        assert_eq!(
+            glob_module_code(&keys, true, Some("default"), None).unwrap(),
+            "import { \"default\" as __glob_0 } from \"./pages/a.tsx\";\nimport { \"default\" as \
+             __glob_1 } from \"./pages/b.tsx\";\nexport default {\n  \"./pages/a.tsx\": \
+             __glob_0,\n  \"./pages/b.tsx\": __glob_1,\n};\n"
+        );
+    }
+}
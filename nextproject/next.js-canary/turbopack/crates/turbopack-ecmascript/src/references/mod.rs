@@ -8,6 +8,7 @@ pub mod esm;
 pub mod exports_info;
 pub mod external_module;
 pub mod ident;
+pub mod import_meta_glob;
 pub mod member;
 pub mod node;
 pub mod pattern_mapping;
@@ -108,7 +109,7 @@ use crate::{
         },
         imports::{ImportAnnotations, ImportAttributes, ImportedSymbol, Reexport},
         linker::link,
-        parse_require_context, side_effects,
+        parse_import_meta_glob, parse_require_context, side_effects,
         top_level_await::has_top_level_await,
         well_known::replace_well_known,
     },
@@ -136,6 +137,7 @@ use crate::{
         },
         exports_info::{ExportsInfoBinding, ExportsInfoRef},
         ident::IdentReplacement,
+        import_meta_glob::ImportMetaGlobAssetReference,
         member::MemberReplacement,
         node::PackageJsonReference,
         raw::{DirAssetReference, FileSourceReference},
@@ -156,6 +158,7 @@ use crate::{
         WebpackChunkAssetReference, WebpackEntryAssetReference, WebpackRuntimeAssetReference,
         parse::{WebpackRuntime, webpack_runtime},
     },
+    worker_chunk::WorkerType,
 };
 
 #[turbo_tasks::value(shared)]
@@ -1339,12 +1342,16 @@ async fn analyze_ecmascript_module_internal(
                         continue;
                     }
 
-                    let func = analysis_state
-                        .link_value(
-                            JsValue::member(obj.clone(), prop),
-                            eval_context.imports.get_attributes(span),
-                        )
-                        .await?;
+                    let func = if !new && let Some(kind) = standalone_worker_function(&obj, &prop) {
+                        JsValue::WellKnownFunction(kind)
+                    } else {
+                        analysis_state
+                            .link_value(
+                                JsValue::member(obj.clone(), prop),
+                                eval_context.imports.get_attributes(span),
+                            )
+                            .await?
+                    };
 
                     if !new
                         && matches!(
@@ -1696,6 +1703,22 @@ async fn compile_time_info_for_module_options(
     .cell())
 }
 
+/// Service workers and worklets are registered through objects that can't be tracked by the
+/// analyzer (`navigator.serviceWorker`, `audioContext.audioWorklet`, ...), so the calls are
+/// matched syntactically by the property names, as webpack does.
+fn standalone_worker_function(obj: &JsValue, prop: &JsValue) -> Option<WellKnownFunctionKind> {
+    let JsValue::Member(_, _, box property) = obj else {
+        return None;
+    };
+    match (property.as_str()?, prop.as_str()?) {
+        ("serviceWorker", "register") => Some(WellKnownFunctionKind::ServiceWorkerRegister),
+        ("paintWorklet" | "audioWorklet" | "layoutWorklet" | "animationWorklet", "addModule") => {
+            Some(WellKnownFunctionKind::WorkletAddModule)
+        }
+        _ => None,
+    }
+}
+
 async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
     ast_path: &[AstParentKind],
     span: Span,
@@ -2211,6 +2234,76 @@ where
             );
         }
 
+        WellKnownFunctionKind::ServiceWorkerRegister | WellKnownFunctionKind::WorkletAddModule => {
+            let args = linked_args().await?;
+            if let Some(url @ JsValue::Url(_, JsValueUrlKind::Relative)) = args.first() {
+                let (name, worker_type) = match func {
+                    WellKnownFunctionKind::ServiceWorkerRegister => (
+                        "navigator.serviceWorker.register",
+                        WorkerType::ServiceWorker,
+                    ),
+                    WellKnownFunctionKind::WorkletAddModule => {
+                        ("Worklet.addModule", WorkerType::Worklet)
+                    }
+                    _ => unreachable!(),
+                };
+                let pat = js_value_to_pattern(url);
+                if !pat.has_constant_parts() {
+                    let (args, hints) = explain_args(args);
+                    handler.span_warn_with_code(
+                        span,
+                        &format!("{name}({args}) is very dynamic{hints}",),
+                        DiagnosticId::Lint(
+                            errors::failed_to_analyze::ecmascript::NEW_WORKER.to_string(),
+                        ),
+                    );
+                    if ignore_dynamic_requests {
+                        return Ok(());
+                    }
+                }
+
+                if *compile_time_info.environment().rendering().await? == Rendering::Client {
+                    analysis.add_reference_code_gen(
+                        WorkerAssetReference::new_standalone_worker(
+                            worker_type,
+                            origin,
+                            Request::parse(pat).to_resolved().await?,
+                            issue_source(source, span),
+                            error_mode,
+                            tracing_only,
+                        ),
+                        ast_path.to_vec().into(),
+                    );
+                }
+            }
+            // Ignore (e.g. dynamic parameter or string literal), just as Webpack does
+        }
+        WellKnownFunctionKind::ImportMetaGlob => {
+            let args = linked_args().await?;
+            let options = match parse_import_meta_glob(args) {
+                Ok(options) => options,
+                Err(err) => {
+                    let (args, hints) = explain_args(args);
+                    handler.span_err_with_code(
+                        span,
+                        &format!(
+                            "import.meta.glob({args}) is not statically analyze-able: {}{hints}",
+                            PrettyPrintError(&err)
+                        ),
+                        DiagnosticId::Error(
+                            errors::failed_to_analyze::ecmascript::IMPORT_META_GLOB.to_string(),
+                        ),
+                    );
+                    return Ok(());
+                }
+            };
+
+            analysis.add_reference_code_gen(
+                ImportMetaGlobAssetReference::new(source, origin, options).await?,
+                ast_path.to_vec().into(),
+            );
+        }
+
         WellKnownFunctionKind::FsReadMethod(name) if analysis.analyze_mode.is_tracing_assets() => {
             let args = linked_args().await?;
             if !args.is_empty() {
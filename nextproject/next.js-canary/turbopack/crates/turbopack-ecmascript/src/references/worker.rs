@@ -86,6 +86,26 @@ impl WorkerAssetReference {
         }
     }
 
+    /// A service worker or worklet, which is loaded from a standalone script.
+    pub fn new_standalone_worker(
+        worker_type: WorkerType,
+        origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+        request: ResolvedVc<Request>,
+        issue_source: IssueSource,
+        error_mode: ResolveErrorMode,
+        tracing_only: bool,
+    ) -> Self {
+        debug_assert!(worker_type.is_standalone());
+        WorkerAssetReference {
+            worker_type,
+            origin,
+            request: WorkerRequest::Url(request),
+            issue_source,
+            error_mode,
+            tracing_only,
+        }
+    }
+
     pub fn new_node_worker_thread(
         origin: ResolvedVc<Box<dyn ResolveOrigin>>,
         context_dir: FileSystemPath,
@@ -117,7 +137,13 @@ impl ModuleReference for WorkerAssetReference {
         let asset_context = self.origin.asset_context().to_resolved().await?;
 
         let result = match (&self.worker_type, &self.request) {
-            (WorkerType::WebWorker | WorkerType::SharedWebWorker, WorkerRequest::Url(request)) => {
+            (
+                WorkerType::WebWorker
+                | WorkerType::SharedWebWorker
+                | WorkerType::ServiceWorker
+                | WorkerType::Worklet,
+                WorkerRequest::Url(request),
+            ) => {
                 // Web worker resolution uses url_resolve
                 url_resolve(
                     *self.origin,
@@ -276,19 +302,20 @@ impl WorkerAssetReference {
 impl ValueToString for WorkerAssetReference {
     #[turbo_tasks::function]
     async fn to_string(&self) -> Result<Vc<RcStr>> {
+        let request = match &self.request {
+            WorkerRequest::Url(request) => request.to_string().await?,
+            WorkerRequest::Pattern { path, .. } => path.to_string().await?,
+        };
         Ok(Vc::cell(
-            format!(
-                "new {}({})",
-                match self.worker_type {
-                    WorkerType::WebWorker => "WebWorker",
-                    WorkerType::SharedWebWorker => "SharedWorker",
-                    WorkerType::NodeWorkerThread => "NodeWorkerThread",
-                },
-                match &self.request {
-                    WorkerRequest::Url(request) => request.to_string().await?,
-                    WorkerRequest::Pattern { path, .. } => path.to_string().await?,
+            match self.worker_type {
+                WorkerType::WebWorker => format!("new WebWorker({request})"),
+                WorkerType::SharedWebWorker => format!("new SharedWorker({request})"),
+                WorkerType::ServiceWorker => {
+                    format!("navigator.serviceWorker.register({request})")
                 }
-            )
+                WorkerType::Worklet => format!("worklet.addModule({request})"),
+                WorkerType::NodeWorkerThread => format!("new NodeWorkerThread({request})"),
+            }
             .into(),
         ))
     }
@@ -344,6 +371,38 @@ impl WorkerAssetReferenceCodeGen {
         // Transform `new Worker(url, opts)` into `require(id)(Worker, opts)`
         // The loader module exports a function that creates the worker with all necessary
         // configuration (entrypoint, chunks, forwarded globals, etc.)
+        if reference.worker_type.is_standalone() {
+            // Transform `navigator.serviceWorker.register(url, opts)` into
+            // `navigator.serviceWorker.register(require(id), opts)`
+            // The loader module exports the URL of the standalone worker script.
+            let visitor = create_visitor!(self.path, visit_mut_expr, |expr: &mut Expr| {
+                let message = if let Expr::Call(call_expr) = expr {
+                    match call_expr.args.first_mut() {
+                        Some(ExprOrSpread {
+                            spread: None,
+                            expr: url_expr,
+                        }) => {
+                            *url_expr = Box::new(pm.create_require(*url_expr.take()));
+                            return;
+                        }
+                        Some(ExprOrSpread {
+                            spread: Some(_),
+                            expr: _,
+                        }) => "spread operator is illegal in worker registrations.",
+                        _ => "worker registrations require at least 1 argument",
+                    }
+                } else {
+                    "visitor must be executed on a CallExpr"
+                };
+                *expr = *quote_expr!(
+                    "(() => { throw new Error($message); })()",
+                    message: Expr = Expr::Lit(Lit::Str(message.into()))
+                );
+            });
+
+            return Ok(CodeGeneration::visitors(vec![visitor]));
+        }
+
         let visitor = create_visitor!(self.path, visit_mut_expr, |expr: &mut Expr| {
             let message = if let Expr::New(new_expr) = expr {
                 if let Some(args) = &mut new_expr.args {
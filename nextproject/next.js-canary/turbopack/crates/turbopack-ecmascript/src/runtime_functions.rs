@@ -93,6 +93,12 @@ pub const TURBOPACK_REQUIRE_REAL: &TurbopackRuntimeFunctionShortcut = make_short
 pub const TURBOPACK_WASM: &TurbopackRuntimeFunctionShortcut = make_shortcut!("w");
 pub const TURBOPACK_WASM_MODULE: &TurbopackRuntimeFunctionShortcut = make_shortcut!("u");
 pub const TURBOPACK_GLOBAL: &TurbopackRuntimeFunctionShortcut = make_shortcut!("g");
+// Only implemented by runtimes with module federation enabled, so these are not available to user
+// code.
+pub const TURBOPACK_REGISTER_SHARED: &TurbopackRuntimeFunctionShortcut = make_shortcut!("H");
+pub const TURBOPACK_LOAD_SHARED: &TurbopackRuntimeFunctionShortcut = make_shortcut!("K");
+pub const TURBOPACK_INIT_SHARE_SCOPE: &TurbopackRuntimeFunctionShortcut = make_shortcut!("I");
+pub const TURBOPACK_LOAD_REMOTE_CONTAINER: &TurbopackRuntimeFunctionShortcut = make_shortcut!("O");
 
 /// Adding an entry to this list will automatically ensure that `__turbopack_XXX__` can be called
 /// from user code (by inserting a replacement into free_var_references)
@@ -5,7 +5,7 @@ use serde::Serialize;
 use swc_core::{
     common::{DUMMY_SP, SyntaxContext},
     ecma::{
-        ast::{Expr, Lit, Str},
+        ast::{Expr, Ident, Lit, Str},
         visit::AstParentKind,
     },
 };
@@ -151,6 +151,12 @@ where
     }
 }
 
+/// Whether `name` can be used as the name of a binding in an ES module. Other names need to be
+/// written as string literals in import and export specifiers.
+pub fn is_valid_binding_name(name: &str) -> bool {
+    Ident::verify_symbol(name).is_ok()
+}
+
 pub struct FormatIter<T: Iterator, F: Fn() -> T>(pub F);
 
 macro_rules! format_iter {
@@ -270,9 +276,23 @@ mod tests {
 
     use crate::{
         analyzer::{ConstantString, ConstantValue, JsValue},
-        utils::js_value_to_pattern,
+        utils::{is_valid_binding_name, js_value_to_pattern},
     };
 
+    #[test]
+    fn test_is_valid_binding_name() {
+        assert!(is_valid_binding_name("foo"));
+        assert!(is_valid_binding_name("$_foo1"));
+        assert!(is_valid_binding_name("caf\u{e9}"));
+        assert!(!is_valid_binding_name(""));
+        assert!(!is_valid_binding_name("1foo"));
+        assert!(!is_valid_binding_name("foo-bar"));
+        assert!(!is_valid_binding_name("default"));
+        assert!(!is_valid_binding_name("await"));
+        assert!(!is_valid_binding_name("eval"));
+        assert!(!is_valid_binding_name("implements"));
+    }
+
     #[test]
     fn test_path_normalization_in_pattern() {
         assert_eq!(
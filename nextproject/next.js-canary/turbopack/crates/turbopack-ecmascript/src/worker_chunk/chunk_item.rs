@@ -1,7 +1,8 @@
 use anyhow::{Result, bail};
 use indoc::formatdoc;
 use turbo_rcstr::rcstr;
-use turbo_tasks::{ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks::{ResolvedVc, TryJoinIterExt, ValueToString, Vc};
+use turbo_tasks_hash::hash_xxh3_hash64;
 use turbopack_core::{
     chunk::{
         AsyncModuleInfo, ChunkData, ChunkItem, ChunkType, ChunkingContext, ChunkingContextExt,
@@ -40,17 +41,18 @@ impl WorkerLoaderChunkItem {
         let module = self.module.await?;
 
         Ok(match self.worker_type {
-            WorkerType::WebWorker | WorkerType::SharedWebWorker => {
-                self.chunking_context.evaluated_chunk_group_assets(
-                    module
-                        .inner
-                        .ident()
-                        .with_modifier(self.worker_type.chunk_modifier_str()),
-                    ChunkGroup::Isolated(ResolvedVc::upcast(module.inner)),
-                    *self.module_graph,
-                    AvailabilityInfo::root(),
-                )
-            }
+            WorkerType::WebWorker
+            | WorkerType::SharedWebWorker
+            | WorkerType::ServiceWorker
+            | WorkerType::Worklet => self.chunking_context.evaluated_chunk_group_assets(
+                module
+                    .inner
+                    .ident()
+                    .with_modifier(self.worker_type.chunk_modifier_str()),
+                ChunkGroup::Isolated(ResolvedVc::upcast(module.inner)),
+                *self.module_graph,
+                AvailabilityInfo::root(),
+            ),
             // WorkerThreads are treated as an entry point, webworkers probably should too but
             // currently it would lead to a cascade that we need to address.
             WorkerType::NodeWorkerThread => {
@@ -92,12 +94,44 @@ impl WorkerLoaderChunkItem {
         })
     }
 
+    /// The script of a service worker or worklet, which contains all chunks of the worker. It's
+    /// placed at a stable path, as the browser compares it on updates (service workers) or keeps
+    /// it in a module map (worklets). The name contains a hash of the worker module, so that
+    /// workers with the same file name in different directories don't collide.
+    ///
+    /// The scope of a service worker defaults to the directory of its script, so service workers
+    /// are emitted at the output root instead of next to the chunks. When the output root is
+    /// served from a sub path (e.g. `/_next/`), the server has to send a `Service-Worker-Allowed`
+    /// header with the script to allow registering it with a broader `scope`.
+    #[turbo_tasks::function]
+    async fn standalone_entrypoint(self: Vc<Self>) -> Result<Vc<Box<dyn OutputAsset>>> {
+        let this = self.await?;
+        let module = this.module.await?;
+        let ident = module.inner.ident();
+        let file_name = standalone_worker_file_name(
+            ident.path().await?.file_stem(),
+            &*ident.to_string().await?,
+        );
+        let directory = if this.worker_type == WorkerType::ServiceWorker {
+            this.chunking_context.output_root().owned().await?
+        } else {
+            this.chunking_context.chunk_root_path().owned().await?
+        };
+        let path = directory.join(&file_name)?;
+        Ok(this
+            .chunking_context
+            .standalone_worker_entrypoint(path, *self.chunk_group().await?.assets))
+    }
+
     #[turbo_tasks::function]
     async fn chunks_data(self: Vc<Self>) -> Result<Vc<ChunksData>> {
         let this = self.await?;
         Ok(ChunkData::from_assets(
             this.chunking_context.output_root().owned().await?,
             *self.chunk_group().await?.assets,
+            // Workers load their chunks with `importScripts`, which doesn't support integrity
+            // metadata.
+            None,
         ))
     }
 }
@@ -120,8 +154,8 @@ impl EcmascriptChunkItem for WorkerLoaderChunkItem {
         if estimated {
             // In estimation mode we cannot call into chunking context APIs
             // otherwise we will induce a turbo tasks cycle. But we only need an
-            // approximate solution. We'll use the same estimate for both web
-            // and Node.js workers.
+            // approximate solution. We'll use the same estimate for all kinds of
+            // workers.
             return Ok(EcmascriptChunkItemContent {
                 inner_code: formatdoc! {
                     r#"
@@ -137,6 +171,22 @@ impl EcmascriptChunkItem for WorkerLoaderChunkItem {
         }
 
         let code = match this.worker_type {
+            WorkerType::ServiceWorker | WorkerType::Worklet => {
+                // Export the URL of the standalone script, which is passed to
+                // `navigator.serviceWorker.register()` or `addModule()`.
+                let entrypoint_path = self.standalone_entrypoint().path().owned().await?;
+                let url = this
+                    .chunking_context
+                    .asset_url(entrypoint_path, None)
+                    .await?;
+
+                formatdoc! {
+                    r#"
+                        {TURBOPACK_EXPORT_VALUE}({url});
+                    "#,
+                    url = StringifyJs(&*url),
+                }
+            }
             WorkerType::WebWorker | WorkerType::SharedWebWorker => {
                 // For web workers, generate code that exports a function to create the worker.
                 // The function takes (WorkerConstructor, workerOptions) and calls createWorker
@@ -219,6 +269,17 @@ impl OutputAssetsReference for WorkerLoaderChunkItem {
             WorkerType::WebWorker | WorkerType::SharedWebWorker => Ok(self
                 .chunk_group()
                 .concatenate_asset(this.chunking_context.worker_entrypoint())),
+            WorkerType::ServiceWorker | WorkerType::Worklet => {
+                let chunk_group = self.chunk_group().await?;
+                Ok(OutputAssetsWithReferenced {
+                    assets: ResolvedVc::cell(vec![
+                        self.standalone_entrypoint().to_resolved().await?,
+                    ]),
+                    referenced_assets: chunk_group.referenced_assets,
+                    references: chunk_group.references,
+                }
+                .cell())
+            }
             WorkerType::NodeWorkerThread => {
                 // Node.js workers don't need a separate entrypoint asset
                 Ok(self.chunk_group())
@@ -256,3 +317,34 @@ impl ChunkItem for WorkerLoaderChunkItem {
         *ResolvedVc::upcast(self.module)
     }
 }
+
+/// The file name of a standalone worker script, which contains a hash of the identifier of the
+/// worker module to tell apart workers with the same file name.
+fn standalone_worker_file_name(file_stem: Option<&str>, ident: &str) -> String {
+    format!(
+        "{}-{:08x}.js",
+        file_stem.unwrap_or("worker"),
+        hash_xxh3_hash64(ident)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::standalone_worker_file_name;
+
+    #[test]
+    fn standalone_worker_file_names_are_unique() {
+        let a =
+            standalone_worker_file_name(Some("sw"), "[project]/src/a/sw.js [client] (ecmascript)");
+        let b =
+            standalone_worker_file_name(Some("sw"), "[project]/src/b/sw.js [client] (ecmascript)");
+        assert!(a.starts_with("sw-") && a.ends_with(".js"));
+        assert_ne!(a, b);
+        // The name is stable, as the browser compares the script on updates
+        assert_eq!(
+            a,
+            standalone_worker_file_name(Some("sw"), "[project]/src/a/sw.js [client] (ecmascript)")
+        );
+        assert!(standalone_worker_file_name(None, "[project]/src/worklet").starts_with("worker-"));
+    }
+}
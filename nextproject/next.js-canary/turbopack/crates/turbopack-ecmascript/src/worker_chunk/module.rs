@@ -18,7 +18,7 @@ use super::{chunk_item::WorkerLoaderChunkItem, worker_type::WorkerType};
 
 /// The WorkerLoaderModule is a module that creates a separate root chunk group for the given module
 /// and exports a URL (for web workers) or file path (for Node.js workers) to pass to the worker
-/// constructor.
+/// constructor. Service workers and worklets get the URL of a standalone script instead.
 #[turbo_tasks::value]
 pub struct WorkerLoaderModule {
     pub inner: ResolvedVc<Box<dyn ChunkableModule>>,
@@ -117,7 +117,10 @@ impl ChunkableModuleReference for WorkerModuleReference {
     fn chunking_type(&self) -> Vc<ChunkingTypeOption> {
         Vc::cell(Some(ChunkingType::Isolated {
             _ty: match self.worker_type {
-                WorkerType::SharedWebWorker | WorkerType::WebWorker => ChunkGroupType::Evaluated,
+                WorkerType::SharedWebWorker
+                | WorkerType::WebWorker
+                | WorkerType::ServiceWorker
+                | WorkerType::Worklet => ChunkGroupType::Evaluated,
                 WorkerType::NodeWorkerThread => ChunkGroupType::Entry,
             },
             merge_tag: None,
@@ -141,6 +144,8 @@ impl ValueToString for WorkerModuleReference {
             WorkerType::WebWorker => rcstr!("web worker module"),
             WorkerType::NodeWorkerThread => rcstr!("node worker thread module"),
             WorkerType::SharedWebWorker => rcstr!("shared web worker module"),
+            WorkerType::ServiceWorker => rcstr!("service worker module"),
+            WorkerType::Worklet => rcstr!("worklet module"),
         })
     }
 }
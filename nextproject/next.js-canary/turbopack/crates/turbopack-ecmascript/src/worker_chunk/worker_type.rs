@@ -9,6 +9,10 @@ use turbopack_core::reference_type::{ReferenceType, WorkerReferenceSubType};
 pub enum WorkerType {
     WebWorker,
     SharedWebWorker,
+    /// A service worker registered with `navigator.serviceWorker.register()`.
+    ServiceWorker,
+    /// A worklet module added with `addModule()`, e.g. `CSS.paintWorklet.addModule()`.
+    Worklet,
     NodeWorkerThread,
 }
 
@@ -16,6 +20,8 @@ impl WorkerType {
     pub fn modifier_str(&self) -> RcStr {
         match self {
             WorkerType::SharedWebWorker | WorkerType::WebWorker => rcstr!("worker loader"),
+            WorkerType::ServiceWorker => rcstr!("service worker loader"),
+            WorkerType::Worklet => rcstr!("worklet loader"),
             WorkerType::NodeWorkerThread => rcstr!("node worker thread loader"),
         }
     }
@@ -23,6 +29,8 @@ impl WorkerType {
     pub fn chunk_modifier_str(&self) -> RcStr {
         match self {
             WorkerType::SharedWebWorker | WorkerType::WebWorker => rcstr!("worker"),
+            WorkerType::ServiceWorker => rcstr!("service worker"),
+            WorkerType::Worklet => rcstr!("worklet"),
             WorkerType::NodeWorkerThread => rcstr!("node worker thread"),
         }
     }
@@ -31,7 +39,15 @@ impl WorkerType {
         ReferenceType::Worker(match self {
             WorkerType::WebWorker => WorkerReferenceSubType::WebWorker,
             WorkerType::SharedWebWorker => WorkerReferenceSubType::SharedWorker,
+            WorkerType::ServiceWorker => WorkerReferenceSubType::ServiceWorker,
+            WorkerType::Worklet => WorkerReferenceSubType::Worklet,
             WorkerType::NodeWorkerThread => WorkerReferenceSubType::NodeWorker,
         })
     }
+
+    /// Whether the worker is loaded from a single script with a stable URL, which can't load
+    /// chunks at runtime.
+    pub fn is_standalone(&self) -> bool {
+        matches!(self, WorkerType::ServiceWorker | WorkerType::Worklet)
+    }
 }
@@ -1,13 +1,33 @@
 use anyhow::Result;
-use turbo_rcstr::rcstr;
+use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{Vc, fxindexmap};
 use turbo_tasks_env::{CommandLineProcessEnv, CustomProcessEnv, ProcessEnv};
 use turbo_tasks_fs::FileSystemPath;
 
-use crate::TryDotenvProcessEnv;
+use crate::{TryDotenvProcessEnv, ValidatedProcessEnv};
+
+/// The dotenv files for `node_env`, in order of precedence.
+pub(crate) fn dotenv_files(node_env: &str) -> Vec<RcStr> {
+    [
+        Some(format!(".env.{node_env}.local").into()),
+        if node_env == "test" {
+            None
+        } else {
+            Some(rcstr!(".env.local"))
+        },
+        Some(format!(".env.{node_env}").into()),
+        Some(rcstr!(".env")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
 
 /// Loads a series of dotenv files according to the precedence rules set by
 /// https://nextjs.org/docs/app/building-your-application/configuring/environment-variables#environment-variable-load-order
+///
+/// The variables are validated against the `env.schema.json` file in `project_path`, if it
+/// exists.
 #[turbo_tasks::function]
 pub async fn load_env(project_path: FileSystemPath) -> Result<Vc<Box<dyn ProcessEnv>>> {
     let env: Vc<Box<dyn ProcessEnv>> = Vc::upcast(CommandLineProcessEnv::new());
@@ -22,23 +42,12 @@ pub async fn load_env(project_path: FileSystemPath) -> Result<Vc<Box<dyn Process
         }),
     ));
 
-    let mut files = [
-        Some(format!(".env.{node_env}.local").into()),
-        if node_env == "test" {
-            None
-        } else {
-            Some(rcstr!(".env.local"))
-        },
-        Some(format!(".env.{node_env}").into()),
-        Some(rcstr!(".env")),
-    ]
-    .into_iter()
-    .flatten();
-
-    let env = files.try_fold(env, |prior, f| {
-        let path = project_path.join(&f)?;
-        anyhow::Ok(Vc::upcast(TryDotenvProcessEnv::new(prior, path)))
-    })?;
+    let env = dotenv_files(&node_env)
+        .into_iter()
+        .try_fold(env, |prior, f| {
+            let path = project_path.join(&f)?;
+            anyhow::Ok(Vc::upcast(TryDotenvProcessEnv::new(prior, path)))
+        })?;
 
-    Ok(env)
+    Ok(Vc::upcast(ValidatedProcessEnv::new(env, project_path)))
 }
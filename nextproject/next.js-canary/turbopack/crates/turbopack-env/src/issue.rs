@@ -1,20 +1,28 @@
 use turbo_rcstr::rcstr;
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueSource, IssueStage, OptionIssueSource, OptionStyledString, StyledString,
+};
 
 /// An issue that occurred while resolving the parsing or evaluating the .env.
 #[turbo_tasks::value(shared)]
 pub struct ProcessEnvIssue {
     pub path: FileSystemPath,
     pub description: ResolvedVc<StyledString>,
+    /// Defaults to "Error loading dotenv file".
+    pub title: Option<ResolvedVc<StyledString>>,
+    pub source: Option<IssueSource>,
 }
 
 #[turbo_tasks::value_impl]
 impl Issue for ProcessEnvIssue {
     #[turbo_tasks::function]
     fn title(&self) -> Vc<StyledString> {
-        StyledString::Text(rcstr!("Error loading dotenv file")).cell()
+        match self.title {
+            Some(title) => *title,
+            None => StyledString::Text(rcstr!("Error loading dotenv file")).cell(),
+        }
     }
 
     #[turbo_tasks::function]
@@ -31,4 +39,9 @@ impl Issue for ProcessEnvIssue {
     fn description(&self) -> Vc<OptionStyledString> {
         Vc::cell(Some(self.description))
     }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(self.source)
+    }
 }
@@ -8,6 +8,9 @@
 //! have higher priority to define a environment variable (later dotenv files
 //! cannot override it). Later dotenv files can reference variables prior
 //! defined variables.
+//!
+//! The loaded variables can be validated against an env schema, which also
+//! declares the variables that may be inlined into client bundles.
 
 #![feature(min_specialization)]
 #![feature(arbitrary_self_types)]
@@ -17,9 +20,11 @@ mod asset;
 pub mod dotenv;
 mod embeddable;
 mod issue;
+pub mod schema;
 mod try_env;
 
 pub use asset::ProcessEnvAsset;
 pub use embeddable::EmbeddableProcessEnv;
 pub use issue::ProcessEnvIssue;
+pub use schema::{ValidatedProcessEnv, client_env_free_var_references};
 pub use try_env::TryDotenvProcessEnv;
@@ -0,0 +1,514 @@
+//! Validation of environment variables against an `env.schema.json` file.
+//!
+//! The schema maps variable names to their definitions:
+//!
+//! ```json
+//! {
+//!   "DATABASE_URL": { "type": "url", "required": true },
+//!   "PORT": { "type": "number", "default": "3000" },
+//!   "PUBLIC_API_URL": { "type": "url", "client": true }
+//! }
+//! ```
+//!
+//! Only variables marked with `"client": true` are inlined into client bundles. Reading any other
+//! variable of the schema in client code is an error, and reading a variable of the environment
+//! which isn't declared in the schema is a warning. Variable names are case-sensitive.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use serde::Deserialize;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{FxIndexMap, NonLocalValue, ResolvedVc, Vc, trace::TraceRawVcs};
+use turbo_tasks_env::{CommandLineProcessEnv, EnvMap, ProcessEnv};
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_core::{
+    compile_time_info::{
+        CompileTimeDefineValue, DefinableNameSegment, FreeVarReference, FreeVarReferences,
+    },
+    file_source::FileSource,
+    issue::{IssueExt, IssueSeverity, IssueSource, StyledString},
+    source_pos::SourcePos,
+};
+
+use crate::{ProcessEnvIssue, dotenv::dotenv_files};
+
+pub const ENV_SCHEMA_FILE: &str = "env.schema.json";
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    TraceRawVcs,
+    NonLocalValue,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvVarType {
+    #[default]
+    String,
+    /// A finite number.
+    Number,
+    /// One of `true`, `false`, `1` or `0`, ignoring casing.
+    Bool,
+    /// An absolute URL.
+    Url,
+}
+
+impl EnvVarType {
+    fn is_valid(self, value: &str) -> bool {
+        match self {
+            EnvVarType::String => true,
+            EnvVarType::Number => value
+                .trim()
+                .parse::<f64>()
+                .is_ok_and(|number| number.is_finite()),
+            EnvVarType::Bool => ["true", "false", "1", "0"]
+                .iter()
+                .any(|bool| value.trim().eq_ignore_ascii_case(bool)),
+            EnvVarType::Url => url::Url::parse(value.trim()).is_ok(),
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            EnvVarType::String => "a string",
+            EnvVarType::Number => "a number",
+            EnvVarType::Bool => "a boolean (true, false, 1 or 0)",
+            EnvVarType::Url => "an absolute URL",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct EnvVarDefinition {
+    #[serde(rename = "type", default)]
+    ty: EnvVarType,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    client: bool,
+    default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub struct EnvVarSchema {
+    pub name: RcStr,
+    pub ty: EnvVarType,
+    pub required: bool,
+    /// Whether the variable may be inlined into client bundles.
+    pub client: bool,
+    pub default: Option<RcStr>,
+    /// The position of the definition in the schema file.
+    pub position: SourcePos,
+}
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct EnvSchema {
+    pub path: FileSystemPath,
+    pub variables: Vec<EnvVarSchema>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionEnvSchema(Option<ResolvedVc<EnvSchema>>);
+
+/// Converts a byte offset into a line and column.
+fn position_at(text: &str, offset: usize) -> SourcePos {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    SourcePos {
+        line: before.matches('\n').count() as u32,
+        column: (before.len() - line_start) as u32,
+    }
+}
+
+fn schema_issue(
+    path: FileSystemPath,
+    source: Option<IssueSource>,
+    description: String,
+) -> ProcessEnvIssue {
+    ProcessEnvIssue {
+        path,
+        description: StyledString::Text(description.into()).resolved_cell(),
+        title: Some(StyledString::Text(rcstr!("Invalid environment variables")).resolved_cell()),
+        source,
+    }
+}
+
+/// Reads the env schema at `path`. Returns `None` when the file doesn't exist or is invalid, in
+/// which case an issue is emitted.
+#[turbo_tasks::function]
+pub async fn read_env_schema(path: FileSystemPath) -> Result<Vc<OptionEnvSchema>> {
+    let content = path.read().await?;
+    let FileContent::Content(file) = &*content else {
+        return Ok(Vc::cell(None));
+    };
+    let text = file.content().to_str()?;
+
+    let definitions = match serde_json::from_str::<BTreeMap<String, EnvVarDefinition>>(&text) {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            let position = SourcePos {
+                line: err.line().saturating_sub(1) as u32,
+                column: err.column().saturating_sub(1) as u32,
+            };
+            let source = ResolvedVc::upcast(FileSource::new(path.clone()).to_resolved().await?);
+            schema_issue(
+                path,
+                Some(IssueSource::from_line_col(source, position, position)),
+                format!("Unable to parse {ENV_SCHEMA_FILE}: {err}"),
+            )
+            .resolved_cell()
+            .emit();
+            return Ok(Vc::cell(None));
+        }
+    };
+
+    let variables = definitions
+        .into_iter()
+        .map(|(name, definition)| EnvVarSchema {
+            position: text
+                .find(&format!("\"{name}\""))
+                .map(|offset| position_at(&text, offset))
+                .unwrap_or_default(),
+            name: name.into(),
+            ty: definition.ty,
+            required: definition.required,
+            client: definition.client,
+            default: definition.default.map(RcStr::from),
+        })
+        .collect();
+
+    Ok(Vc::cell(Some(
+        EnvSchema { path, variables }.resolved_cell(),
+    )))
+}
+
+/// Finds the line which defines `name` in the dotenv files of `project_path`.
+async fn find_dotenv_definition(
+    project_path: &FileSystemPath,
+    node_env: &str,
+    name: &str,
+) -> Result<Option<IssueSource>> {
+    for file in dotenv_files(node_env) {
+        let path = project_path.join(&file)?;
+        let content = path.read().await?;
+        let FileContent::Content(file) = &*content else {
+            continue;
+        };
+        let text = file.content().to_str()?;
+        for (line, line_text) in text.lines().enumerate() {
+            let trimmed = line_text.trim_start();
+            let definition = trimmed
+                .strip_prefix("export ")
+                .unwrap_or(trimmed)
+                .trim_start();
+            if definition
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+            {
+                let position = SourcePos {
+                    line: line as u32,
+                    column: (line_text.len() - definition.len()) as u32,
+                };
+                let source = ResolvedVc::upcast(FileSource::new(path).to_resolved().await?);
+                return Ok(Some(IssueSource::from_line_col(source, position, position)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Validates the variables of the prior env against the env schema of the project. Emits issues
+/// for missing or invalid variables and applies the defaults of the schema.
+#[turbo_tasks::value]
+pub struct ValidatedProcessEnv {
+    prior: ResolvedVc<Box<dyn ProcessEnv>>,
+    project_path: FileSystemPath,
+}
+
+#[turbo_tasks::value_impl]
+impl ValidatedProcessEnv {
+    #[turbo_tasks::function]
+    pub fn new(prior: ResolvedVc<Box<dyn ProcessEnv>>, project_path: FileSystemPath) -> Vc<Self> {
+        ValidatedProcessEnv {
+            prior,
+            project_path,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProcessEnv for ValidatedProcessEnv {
+    #[turbo_tasks::function]
+    async fn read_all(&self) -> Result<Vc<EnvMap>> {
+        let prior = self.prior.read_all();
+        let Some(schema) = *read_env_schema(self.project_path.join(ENV_SCHEMA_FILE)?).await? else {
+            return Ok(prior);
+        };
+        let schema = schema.await?;
+        let schema_source =
+            ResolvedVc::upcast(FileSource::new(schema.path.clone()).to_resolved().await?);
+
+        let mut env = prior.owned().await?;
+        let node_env = env
+            .get("NODE_ENV")
+            .cloned()
+            .unwrap_or(rcstr!("development"));
+        let command_line_env = CommandLineProcessEnv::new().read_all().await?;
+
+        for variable in &schema.variables {
+            let schema_location = Some(IssueSource::from_line_col(
+                schema_source,
+                variable.position,
+                variable.position,
+            ));
+            let name = &variable.name;
+            let Some(value) = env.get(name) else {
+                if let Some(default) = &variable.default {
+                    env.insert(name.clone(), default.clone());
+                } else if variable.required {
+                    schema_issue(
+                        schema.path.clone(),
+                        schema_location,
+                        format!(
+                            "The environment variable `{name}` is required by {ENV_SCHEMA_FILE}, \
+                             but it isn't set."
+                        ),
+                    )
+                    .resolved_cell()
+                    .emit();
+                }
+                continue;
+            };
+            if variable.ty.is_valid(value) {
+                continue;
+            }
+
+            // Values from the command line aren't defined in any file, so point to the schema
+            // instead.
+            let source = if command_line_env.contains_key(name) {
+                schema_location
+            } else {
+                find_dotenv_definition(&self.project_path, &node_env, name)
+                    .await?
+                    .or(schema_location)
+            };
+            // The value isn't included in the message as it might be a secret.
+            schema_issue(
+                schema.path.clone(),
+                source,
+                format!(
+                    "The environment variable `{name}` must be {}.",
+                    variable.ty.description()
+                ),
+            )
+            .resolved_cell()
+            .emit();
+        }
+
+        Ok(Vc::cell(env))
+    }
+}
+
+/// Free var references for `process.env.*` in client code. Client variables of the env schema
+/// are inlined. Reading a server-only variable of the schema is reported as an error, and reading
+/// a variable of `env` which isn't declared in the schema as a warning. Both evaluate to
+/// `undefined`.
+#[turbo_tasks::function]
+pub async fn client_env_free_var_references(
+    project_path: FileSystemPath,
+    env: Vc<Box<dyn ProcessEnv>>,
+) -> Result<Vc<FreeVarReferences>> {
+    let Some(schema) = *read_env_schema(project_path.join(ENV_SCHEMA_FILE)?).await? else {
+        return Ok(FreeVarReferences::empty());
+    };
+    let schema = schema.await?;
+    let env = env.read_all().await?;
+
+    let key = |name: &RcStr| {
+        vec![
+            DefinableNameSegment::from("process"),
+            DefinableNameSegment::from("env"),
+            DefinableNameSegment::from(name.clone()),
+        ]
+    };
+    let mut references = FxIndexMap::default();
+    for variable in &schema.variables {
+        let name = &variable.name;
+        let value = match env.get(name) {
+            Some(value) if variable.client => CompileTimeDefineValue::String(value.clone()),
+            _ => CompileTimeDefineValue::Undefined,
+        };
+        let reference = if variable.client {
+            FreeVarReference::Value(value)
+        } else {
+            FreeVarReference::ReportUsage {
+                message: format!(
+                    "`process.env.{name}` is a server-only environment variable and can't be used \
+                     in client code. Set `\"client\": true` for it in {ENV_SCHEMA_FILE} to inline \
+                     it into client bundles."
+                )
+                .into(),
+                severity: IssueSeverity::Error,
+                inner: Some(Box::new(FreeVarReference::Value(value))),
+            }
+        };
+        references.insert(key(name), reference);
+    }
+    for name in env.keys() {
+        references
+            .entry(key(name))
+            .or_insert_with(|| FreeVarReference::ReportUsage {
+                message: format!(
+                    "`process.env.{name}` isn't declared in {ENV_SCHEMA_FILE} and can't be used \
+                     in client code. Declare it with `\"client\": true` to inline it into client \
+                     bundles."
+                )
+                .into(),
+                severity: IssueSeverity::Warning,
+                inner: Some(Box::new(FreeVarReference::Value(
+                    CompileTimeDefineValue::Undefined,
+                ))),
+            });
+    }
+    Ok(Vc::cell(references))
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::TurboTasks;
+    use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "PUBLIC_URL": { "type": "url", "client": true },
+        "SECRET": { "required": true },
+        "PORT": { "type": "number", "default": "3000" }
+    }"#;
+
+    fn env(variables: &[(&str, &str)]) -> Vc<Box<dyn ProcessEnv>> {
+        Vc::upcast(Vc::<EnvMap>::cell(
+            variables
+                .iter()
+                .map(|&(name, value)| (RcStr::from(name), RcStr::from(value)))
+                .collect(),
+        ))
+    }
+
+    fn key(name: &str) -> Vec<DefinableNameSegment> {
+        vec![
+            DefinableNameSegment::from("process"),
+            DefinableNameSegment::from("env"),
+            DefinableNameSegment::from(name),
+        ]
+    }
+
+    async fn run(test: impl Future<Output = Result<()>> + Send + 'static) {
+        let tt = TurboTasks::new(TurboTasksBackend::new(
+            BackendOptions::default(),
+            noop_backing_storage(),
+        ));
+        tt.run_once(test).await.unwrap();
+    }
+
+    async fn project_path(directory: &tempfile::TempDir) -> Result<FileSystemPath> {
+        std::fs::write(directory.path().join(ENV_SCHEMA_FILE), SCHEMA)?;
+        DiskFileSystem::new(rcstr!("project"), directory.path().to_str().unwrap().into())
+            .root()
+            .owned()
+            .await
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn client_references() {
+        run(async {
+            let directory = tempfile::tempdir()?;
+            let references = client_env_free_var_references(
+                project_path(&directory).await?,
+                env(&[
+                    ("PUBLIC_URL", "https://example.com"),
+                    ("SECRET", "hunter2"),
+                    ("HOME", "/home/user"),
+                ]),
+            )
+            .await?;
+
+            assert_eq!(
+                references.get(&key("PUBLIC_URL")),
+                Some(&FreeVarReference::Value(CompileTimeDefineValue::String(
+                    rcstr!("https://example.com")
+                )))
+            );
+            // Values of variables which aren't exposed are never inlined.
+            for (name, expected_severity) in [
+                ("SECRET", IssueSeverity::Error),
+                ("HOME", IssueSeverity::Warning),
+            ] {
+                let Some(FreeVarReference::ReportUsage {
+                    severity, inner, ..
+                }) = references.get(&key(name))
+                else {
+                    panic!("reading `{name}` should be reported");
+                };
+                assert_eq!(*severity, expected_severity);
+                assert_eq!(
+                    inner.as_deref(),
+                    Some(&FreeVarReference::Value(CompileTimeDefineValue::Undefined))
+                );
+            }
+            assert!(matches!(
+                references.get(&key("PORT")),
+                Some(FreeVarReference::ReportUsage {
+                    severity: IssueSeverity::Error,
+                    ..
+                })
+            ));
+            assert_eq!(references.get(&key("UNSET")), None);
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn names_are_case_sensitive() {
+        run(async {
+            let directory = tempfile::tempdir()?;
+            let project_path = project_path(&directory).await?;
+            let prior = env(&[("public_url", "https://example.com"), ("port", "1")]);
+
+            let references = client_env_free_var_references(project_path.clone(), prior).await?;
+            assert_eq!(
+                references.get(&key("PUBLIC_URL")),
+                Some(&FreeVarReference::Value(CompileTimeDefineValue::Undefined))
+            );
+            assert!(matches!(
+                references.get(&key("public_url")),
+                Some(FreeVarReference::ReportUsage { .. })
+            ));
+
+            let validated = Vc::upcast::<Box<dyn ProcessEnv>>(ValidatedProcessEnv::new(
+                prior.to_resolved().await?,
+                project_path,
+            ))
+            .read_all()
+            .await?;
+            assert_eq!(validated.get("PORT"), Some(&rcstr!("3000")));
+            assert_eq!(validated.get("port"), Some(&rcstr!("1")));
+            Ok(())
+        })
+        .await
+    }
+}
@@ -57,6 +57,8 @@ impl ProcessEnv for TryDotenvProcessEnv {
                     // Issue context, not the description). So extract the real error.
                     description: StyledString::Text(e.root_cause().to_string().into())
                         .resolved_cell(),
+                    title: None,
+                    source: None,
                 }
                 .resolved_cell()
                 .emit();
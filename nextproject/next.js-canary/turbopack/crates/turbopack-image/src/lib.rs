@@ -2,3 +2,5 @@
 #![feature(arbitrary_self_types_pointers)]
 
 pub mod process;
+pub mod srcset;
+pub mod svg;
@@ -1,4 +1,6 @@
 pub mod svg;
+pub mod svg_markup;
+mod svg_path;
 
 use std::{io::Cursor, str::FromStr};
 
@@ -18,7 +20,8 @@ use image::{
 use mime::Mime;
 use turbo_rcstr::rcstr;
 use turbo_tasks::{
-    NonLocalValue, PrettyPrintError, ResolvedVc, Vc, debug::ValueDebugFormat, trace::TraceRawVcs,
+    NonLocalValue, PrettyPrintError, ResolvedVc, TaskInput, Vc, debug::ValueDebugFormat,
+    trace::TraceRawVcs,
 };
 use turbo_tasks_fs::{File, FileContent, FileSystemPath};
 use turbopack_core::{
@@ -32,7 +35,7 @@ use turbopack_core::{
 use self::svg::calculate;
 
 /// Small placeholder version of the image.
-#[derive(PartialEq, Eq, TraceRawVcs, ValueDebugFormat, NonLocalValue, Encode, Decode)]
+#[derive(Clone, PartialEq, Eq, TraceRawVcs, ValueDebugFormat, NonLocalValue, Encode, Decode)]
 pub struct BlurPlaceholder {
     pub data_url: String,
     pub width: u32,
@@ -54,7 +57,7 @@ impl BlurPlaceholder {
 /// Gathered meta information about an image.
 #[allow(clippy::manual_non_exhaustive)]
 #[turbo_tasks::value]
-#[derive(Default)]
+#[derive(Clone, Default)]
 #[non_exhaustive]
 pub struct ImageMetaData {
     pub width: u32,
@@ -104,7 +107,10 @@ fn extension_to_image_format(extension: &str) -> Option<ImageFormat> {
     })
 }
 
-fn result_to_issue<T>(source: ResolvedVc<Box<dyn Source>>, result: Result<T>) -> Option<T> {
+pub(crate) fn result_to_issue<T>(
+    source: ResolvedVc<Box<dyn Source>>,
+    result: Result<T>,
+) -> Option<T> {
     match result {
         Ok(r) => Some(r),
         Err(err) => {
@@ -335,14 +341,36 @@ fn image_format_to_mime_type(format: ImageFormat) -> Result<Option<Mime>> {
     })
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionImageMetaData(Option<ImageMetaData>);
+
 /// Analyze an image and return meta information about it.
 /// Optionally computes a blur placeholder.
+///
+/// Images which can't be decoded get placeholder dimensions, see [get_decoded_meta_data] to
+/// handle them separately.
 #[turbo_tasks::function]
 pub async fn get_meta_data(
     image: ResolvedVc<Box<dyn Source>>,
     content: Vc<FileContent>,
     blur_placeholder: Option<Vc<BlurPlaceholderOptions>>,
 ) -> Result<Vc<ImageMetaData>> {
+    if let Some(meta) = &*get_decoded_meta_data(*image, content, blur_placeholder).await? {
+        return Ok(meta.clone().cell());
+    }
+    let path = image.ident().path().await?;
+    let mime_type = (path.extension() == "svg").then_some(mime::IMAGE_SVG);
+    Ok(ImageMetaData::fallback_value(mime_type).cell())
+}
+
+/// Like [get_meta_data], but returns `None` when the image can't be decoded and its dimensions are
+/// unknown.
+#[turbo_tasks::function]
+pub async fn get_decoded_meta_data(
+    image: ResolvedVc<Box<dyn Source>>,
+    content: Vc<FileContent>,
+    blur_placeholder: Option<Vc<BlurPlaceholderOptions>>,
+) -> Result<Vc<OptionImageMetaData>> {
     let FileContent::Content(content) = &*content.await? else {
         bail!("Input image not found");
     };
@@ -356,29 +384,28 @@ pub async fn get_meta_data(
             std::str::from_utf8(&bytes).context("Input image is not valid utf-8"),
         );
         let Some(content) = content else {
-            return Ok(ImageMetaData::fallback_value(Some(mime::IMAGE_SVG)).cell());
+            return Ok(Vc::cell(None));
         };
         let info = result_to_issue(
             image,
             calculate(content).context("Failed to parse svg source code for image dimensions"),
         );
         let Some((width, height)) = info else {
-            return Ok(ImageMetaData::fallback_value(Some(mime::IMAGE_SVG)).cell());
+            return Ok(Vc::cell(None));
         };
-        return Ok(ImageMetaData {
+        return Ok(Vc::cell(Some(ImageMetaData {
             width,
             height,
             mime_type: Some(mime::IMAGE_SVG),
             blur_placeholder: None,
-        }
-        .cell());
+        })));
     }
     let Some((image_buffer, format)) = load_image(image, &bytes, extension) else {
-        return Ok(ImageMetaData::fallback_value(None).cell());
+        return Ok(Vc::cell(None));
     };
 
     match image_buffer {
-        ImageBuffer::Raw(..) => Ok(ImageMetaData::fallback_value(None).cell()),
+        ImageBuffer::Raw(..) => Ok(Vc::cell(None)),
         ImageBuffer::Decoded(image_data) => {
             let (width, height) = image_data.dimensions();
             let blur_placeholder = if let Some(blur_placeholder) = blur_placeholder {
@@ -403,7 +430,7 @@ pub async fn get_meta_data(
                 None
             };
 
-            Ok(ImageMetaData {
+            Ok(Vc::cell(Some(ImageMetaData {
                 width,
                 height,
                 mime_type: if let Some(format) = format {
@@ -412,19 +439,94 @@ pub async fn get_meta_data(
                     None
                 },
                 blur_placeholder,
-            }
-            .cell())
+            })))
+        }
+    }
+}
+
+/// The format an image is encoded to when optimizing it.
+#[derive(
+    Eq, PartialEq, Clone, Copy, Debug, Hash, TaskInput, TraceRawVcs, NonLocalValue, Encode, Decode,
+)]
+pub enum ImageVariantFormat {
+    /// Keep the format of the source image.
+    Original,
+    Png,
+    Jpeg,
+    WebP,
+    Avif,
+}
+
+impl ImageVariantFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "original" => ImageVariantFormat::Original,
+            "png" => ImageVariantFormat::Png,
+            "jpg" | "jpeg" => ImageVariantFormat::Jpeg,
+            "webp" => ImageVariantFormat::WebP,
+            "avif" => ImageVariantFormat::Avif,
+            _ => return None,
+        })
+    }
+
+    /// The file extension of the encoded image, or `None` to keep the source extension.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            ImageVariantFormat::Original => None,
+            ImageVariantFormat::Png => Some("png"),
+            ImageVariantFormat::Jpeg => Some("jpg"),
+            ImageVariantFormat::WebP => Some("webp"),
+            ImageVariantFormat::Avif => Some("avif"),
+        }
+    }
+
+    /// Whether encoding into this format has been compiled into the current build.
+    pub fn is_supported(self) -> bool {
+        match self {
+            ImageVariantFormat::WebP => cfg!(feature = "webp"),
+            ImageVariantFormat::Avif => cfg!(feature = "avif"),
+            _ => true,
+        }
+    }
+
+    fn image_format(self) -> Option<ImageFormat> {
+        match self {
+            ImageVariantFormat::Original => None,
+            ImageVariantFormat::Png => Some(ImageFormat::Png),
+            ImageVariantFormat::Jpeg => Some(ImageFormat::Jpeg),
+            ImageVariantFormat::WebP => Some(ImageFormat::WebP),
+            ImageVariantFormat::Avif => Some(ImageFormat::Avif),
         }
     }
 }
 
 #[turbo_tasks::function]
-pub async fn optimize(
+pub fn optimize(
+    source: ResolvedVc<Box<dyn Source>>,
+    content: Vc<FileContent>,
+    max_width: u32,
+    max_height: u32,
+    quality: u8,
+) -> Vc<FileContent> {
+    optimize_as(
+        *source,
+        content,
+        max_width,
+        max_height,
+        quality,
+        ImageVariantFormat::Original,
+    )
+}
+
+/// Like [optimize], but re-encodes the image into `target_format`.
+#[turbo_tasks::function]
+pub async fn optimize_as(
     source: ResolvedVc<Box<dyn Source>>,
     content: Vc<FileContent>,
     max_width: u32,
     max_height: u32,
     quality: u8,
+    target_format: ImageVariantFormat,
 ) -> Result<Vc<FileContent>> {
     let FileContent::Content(content) = &*content.await? else {
         return Ok(FileContent::NotFound.cell());
@@ -438,6 +540,28 @@ pub async fn optimize(
     };
     match image {
         ImageBuffer::Raw(buffer) => {
+            if let Some(target_format) = target_format.image_format()
+                && Some(target_format) != format
+            {
+                ImageProcessingIssue {
+                    source: IssueSource::from_source_only(source),
+                    message: StyledString::Text(
+                        format!(
+                            "This image can't be decoded, so it can't be converted to \
+                             {target_format:?}. The original image is used instead."
+                        )
+                        .into(),
+                    )
+                    .resolved_cell(),
+                    title: Some(
+                        StyledString::Text(rcstr!("Image can't be converted")).resolved_cell(),
+                    ),
+                    issue_severity: Some(IssueSeverity::Warning),
+                }
+                .resolved_cell()
+                .emit();
+            }
+
             #[cfg(not(feature = "avif"))]
             if matches!(format, Some(ImageFormat::Avif)) {
                 return Ok(FileContent::Content(
@@ -475,7 +599,10 @@ pub async fn optimize(
                 image
             };
 
-            let format = format.unwrap_or(ImageFormat::Jpeg);
+            let format = target_format
+                .image_format()
+                .or(format)
+                .unwrap_or(ImageFormat::Jpeg);
             let (data, mime_type) = encode_image(image, format, quality)?;
 
             Ok(FileContent::Content(File::from(data).with_content_type(mime_type)).cell())
@@ -484,11 +611,11 @@ pub async fn optimize(
 }
 
 #[turbo_tasks::value]
-struct ImageProcessingIssue {
-    message: ResolvedVc<StyledString>,
-    title: Option<ResolvedVc<StyledString>>,
-    issue_severity: Option<IssueSeverity>,
-    source: IssueSource,
+pub(crate) struct ImageProcessingIssue {
+    pub(crate) message: ResolvedVc<StyledString>,
+    pub(crate) title: Option<ResolvedVc<StyledString>>,
+    pub(crate) issue_severity: Option<IssueSeverity>,
+    pub(crate) source: IssueSource,
 }
 
 #[turbo_tasks::value_impl]
@@ -0,0 +1,416 @@
+//! Minification of SVG markup and its conversion into a React component.
+
+use std::{fmt::Write, sync::Arc};
+
+use anyhow::{Result, bail};
+use roxmltree::{Attribute, Document, Node, ParsingOptions};
+use swc_core::{
+    atoms::{Atom, atom},
+    common::{DUMMY_SP, SourceMap},
+    ecma::{
+        ast::{
+            Expr, Ident, IdentName, JSXAttr, JSXAttrName, JSXAttrOrSpread, JSXAttrValue,
+            JSXClosingElement, JSXElement, JSXElementChild, JSXElementName, JSXExpr,
+            JSXExprContainer, JSXOpeningElement, KeyValueProp, Lit, Module, ObjectLit, Prop,
+            PropName, PropOrSpread, SpreadElement, Str,
+        },
+        codegen::{Emitter, text_writer::JsWriter},
+    },
+    quote,
+};
+
+use super::svg_path::minify_path_data;
+
+/// Elements which are only read by editors. They are removed together with their children.
+const EDITOR_ONLY_ELEMENTS: &[&str] = &["metadata"];
+
+/// Namespaces that editors use to store their own data in SVG files.
+const EDITOR_NAMESPACES: &[&str] = &[
+    "http://www.inkscape.org/namespaces/inkscape",
+    "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd",
+    "http://www.bohemiancoding.com/sketch/ns",
+    "http://www.serif.com/",
+];
+
+/// Elements in which whitespace in text content is significant.
+const WHITESPACE_SENSITIVE_ELEMENTS: &[&str] = &[
+    "desc", "script", "style", "text", "textPath", "title", "tspan",
+];
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+fn parse(content: &str) -> Result<Document<'_>> {
+    let document = Document::parse_with_options(
+        content,
+        ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )?;
+    let root = document.root_element();
+    if root.tag_name().name() != "svg" {
+        bail!(
+            "Expected a <svg> root element, but found <{}>",
+            root.tag_name().name()
+        );
+    }
+    Ok(document)
+}
+
+fn is_editor_namespace(namespace: Option<&str>) -> bool {
+    namespace.is_some_and(|namespace| EDITOR_NAMESPACES.contains(&namespace))
+}
+
+fn is_editor_only_element(node: Node) -> bool {
+    EDITOR_ONLY_ELEMENTS.contains(&node.tag_name().name())
+        || is_editor_namespace(node.tag_name().namespace())
+}
+
+/// The text of a node with insignificant whitespace removed, or `None` if nothing is left.
+fn significant_text<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    let text = node.text()?;
+    let text = if node
+        .parent_element()
+        .is_some_and(|parent| WHITESPACE_SENSITIVE_ELEMENTS.contains(&parent.tag_name().name()))
+    {
+        text
+    } else {
+        text.trim()
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// The qualified name of an element as it's written in the source, including its prefix.
+fn qualified_name<'a>(content: &'a str, node: Node) -> &'a str {
+    let tag = &content[node.range().start + 1..];
+    &tag[..tag
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(tag.len())]
+}
+
+fn write_escaped(result: &mut String, text: &str, escape_quotes: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' if escape_quotes => result.push_str("&quot;"),
+            c => result.push(c),
+        }
+    }
+}
+
+fn write_attribute(result: &mut String, name: &str, value: &str) {
+    let _ = write!(result, " {name}=\"");
+    write_escaped(result, value, true);
+    result.push('"');
+}
+
+fn write_optimized_element(result: &mut String, content: &str, node: Node) {
+    let name = qualified_name(content, node);
+    result.push('<');
+    result.push_str(name);
+    // Only the namespaces declared on this element, not the inherited ones.
+    let parent = node.parent_element();
+    for namespace in node.namespaces() {
+        let inherited = parent.is_some_and(|parent| {
+            parent
+                .namespaces()
+                .any(|other| other.name() == namespace.name() && other.uri() == namespace.uri())
+        });
+        if inherited
+            || namespace.uri() == XML_NAMESPACE
+            || is_editor_namespace(Some(namespace.uri()))
+        {
+            continue;
+        }
+        match namespace.name() {
+            Some(prefix) => write_attribute(result, &format!("xmlns:{prefix}"), namespace.uri()),
+            None => write_attribute(result, "xmlns", namespace.uri()),
+        }
+    }
+    for attribute in node.attributes() {
+        if is_editor_namespace(attribute.namespace()) {
+            continue;
+        }
+        let name = &content[attribute.range_qname()];
+        if node.tag_name().name() == "path" && name == "d" {
+            write_attribute(result, name, &minify_path_data(attribute.value()));
+        } else {
+            write_attribute(result, name, attribute.value());
+        }
+    }
+    let mut empty = true;
+    for child in node.children() {
+        if child.is_element() && is_editor_only_element(child) {
+            continue;
+        }
+        let text = if child.is_text() {
+            match significant_text(child) {
+                Some(text) => Some(text),
+                None => continue,
+            }
+        } else if child.is_element() {
+            None
+        } else {
+            // Comments and processing instructions.
+            continue;
+        };
+        if empty {
+            result.push('>');
+            empty = false;
+        }
+        match text {
+            Some(text)
+                if matches!(node.tag_name().name(), "script" | "style")
+                    && text.contains(['<', '&'])
+                    && !text.contains("]]>") =>
+            {
+                let _ = write!(result, "<![CDATA[{text}]]>");
+            }
+            Some(text) => write_escaped(result, text, false),
+            None => write_optimized_element(result, content, child),
+        }
+    }
+    if empty {
+        result.push_str("/>");
+    } else {
+        let _ = write!(result, "</{name}>");
+    }
+}
+
+/// Minifies SVG markup. Comments, doctypes, editor metadata and insignificant whitespace are
+/// removed, empty elements are self-closed and path data is minified.
+pub fn optimize(content: &str) -> Result<String> {
+    let document = parse(content)?;
+    let mut result = String::with_capacity(content.len());
+    write_optimized_element(&mut result, content, document.root_element());
+    Ok(result)
+}
+
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut uppercase = false;
+    for c in name.chars() {
+        if c == '-' {
+            uppercase = true;
+        } else if uppercase {
+            result.extend(c.to_uppercase());
+            uppercase = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn jsx_attribute_name(attribute: &Attribute) -> String {
+    let name = attribute.name();
+    match attribute.namespace() {
+        Some(XLINK_NAMESPACE) => camel_case(&format!("xlink-{name}")),
+        Some(XML_NAMESPACE) => camel_case(&format!("xml-{name}")),
+        Some(_) => camel_case(name),
+        None => match name {
+            "class" => "className".to_string(),
+            "for" => "htmlFor".to_string(),
+            _ if name.starts_with("data-") || name.starts_with("aria-") => name.to_string(),
+            _ => camel_case(name),
+        },
+    }
+}
+
+fn str_lit(value: &str) -> Str {
+    Atom::from(value).into()
+}
+
+/// A string attribute value. JSX strings can't contain escape sequences, so quotes are written
+/// as entities.
+fn jsx_attribute_str(value: &str) -> Str {
+    let mut raw = String::with_capacity(value.len() + 2);
+    raw.push('"');
+    write_escaped(&mut raw, value, true);
+    raw.push('"');
+    Str {
+        raw: Some(raw.into()),
+        ..str_lit(value)
+    }
+}
+
+fn jsx_expr_container(expr: Expr) -> JSXExprContainer {
+    JSXExprContainer {
+        span: DUMMY_SP,
+        expr: JSXExpr::Expr(Box::new(expr)),
+    }
+}
+
+/// Converts an inline `style` attribute into a style object for React.
+fn jsx_style_object(style: &str) -> Expr {
+    let props = style
+        .split(';')
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let property = property.trim();
+            let property = if property.starts_with("--") {
+                property.to_string()
+            } else {
+                camel_case(property.strip_prefix("-ms-").unwrap_or(property))
+            };
+            Some(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Str(str_lit(&property)),
+                value: Box::new(Lit::Str(str_lit(value.trim())).into()),
+            }))))
+        })
+        .collect();
+    ObjectLit {
+        span: DUMMY_SP,
+        props,
+    }
+    .into()
+}
+
+fn jsx_element(node: Node) -> JSXElement {
+    let name = JSXElementName::Ident(Ident::new_no_ctxt(node.tag_name().name().into(), DUMMY_SP));
+    let attrs = node
+        .attributes()
+        .map(|attribute| {
+            let value = if attribute.namespace().is_none() && attribute.name() == "style" {
+                JSXAttrValue::JSXExprContainer(jsx_expr_container(jsx_style_object(
+                    attribute.value(),
+                )))
+            } else {
+                JSXAttrValue::Str(jsx_attribute_str(attribute.value()))
+            };
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span: DUMMY_SP,
+                name: JSXAttrName::Ident(IdentName::new(
+                    jsx_attribute_name(&attribute).into(),
+                    DUMMY_SP,
+                )),
+                value: Some(value),
+            })
+        })
+        .collect();
+    let children: Vec<_> = node
+        .children()
+        .filter_map(|child| {
+            if child.is_element() {
+                Some(JSXElementChild::JSXElement(Box::new(jsx_element(child))))
+            } else {
+                // Text is always written as a string literal, so it doesn't need JSX escaping.
+                let text = significant_text(child)?;
+                Some(JSXElementChild::JSXExprContainer(jsx_expr_container(
+                    Lit::Str(str_lit(text)).into(),
+                )))
+            }
+        })
+        .collect();
+    let self_closing = children.is_empty();
+    JSXElement {
+        span: DUMMY_SP,
+        opening: JSXOpeningElement {
+            name: name.clone(),
+            span: DUMMY_SP,
+            attrs,
+            self_closing,
+            type_args: None,
+        },
+        closing: (!self_closing).then(|| JSXClosingElement {
+            span: DUMMY_SP,
+            name,
+        }),
+        children,
+    }
+}
+
+/// Converts SVG markup into a JSX module that default exports a React component rendering the
+/// SVG. The props of the component are spread onto the root `<svg>` element.
+pub fn to_jsx_component(content: &str) -> Result<String> {
+    let document = parse(content)?;
+    let mut svg = jsx_element(document.root_element());
+    svg.opening
+        .attrs
+        .push(JSXAttrOrSpread::SpreadElement(SpreadElement {
+            dot3_token: DUMMY_SP,
+            expr: Box::new(Ident::new_no_ctxt(atom!("props"), DUMMY_SP).into()),
+        }));
+    let module = Module {
+        span: DUMMY_SP,
+        body: vec![
+            quote!("import * as React from \"react\";" as ModuleItem),
+            quote!(
+                "const SvgComponent = (props) => $svg;" as ModuleItem,
+                svg: Expr = Expr::JSXElement(Box::new(svg)),
+            ),
+            quote!("export default SvgComponent;" as ModuleItem),
+        ],
+        shebang: None,
+    };
+
+    let source_map: Arc<SourceMap> = Default::default();
+    let mut bytes = Vec::new();
+    let mut emitter = Emitter {
+        cfg: Default::default(),
+        cm: source_map.clone(),
+        comments: None,
+        wr: JsWriter::new(source_map, "\n", &mut bytes, None),
+    };
+    emitter.emit_module(&module)?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{optimize, to_jsx_component};
+
+    #[test]
+    fn test_optimize() -> Result<()> {
+        let svg = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Generator: Editor -->
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd" width="24" height="24" inkscape:version="1.0">
+  <metadata><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" /></metadata>
+  <sodipodi:namedview pagecolor="#ffffff" />
+  <title> Icon &amp; more </title>
+  <g fill='"none"'>
+    <path d="M 0,0 L 10.0 10 L 20 0.5" ></path>
+    <style><![CDATA[a > b { fill: red }]]></style>
+  </g>
+</svg>
+"##;
+        assert_eq!(
+            optimize(svg)?,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"><title> Icon &amp; more </title><g fill="&quot;none&quot;"><path d="M0 0 10 10 20 .5"/><style><![CDATA[a > b { fill: red }]]></style></g></svg>"#
+        );
+        assert!(optimize("<svg><g></svg>").is_err());
+        assert!(optimize(r#"<svg width=24></svg>"#).is_err());
+        assert!(optimize("<g></g>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_jsx_component() -> Result<()> {
+        let svg = r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink" class="icon" aria-label='say "hi"' style="fill-opacity: 0.5; -webkit-mask: none"><use xlink:href="#a" stroke-width="2" data-id="x"/><style>.a { fill: red }</style><text>a &amp; b</text></svg>"##;
+        let component = to_jsx_component(svg)?;
+        for expected in [
+            r#"import * as React from "react";"#,
+            r#"<svg className="icon" aria-label="say &quot;hi&quot;" style={{"#,
+            r#""fillOpacity": "0.5""#,
+            r#""WebkitMask": "none""#,
+            "{...props}>",
+            r##"<use xlinkHref="#a" strokeWidth="2" data-id="x"/>"##,
+            r#"<style>{".a { fill: red }"}</style>"#,
+            r#"<text>{"a & b"}</text></svg>"#,
+            "export default SvgComponent;",
+        ] {
+            assert!(
+                component.contains(expected),
+                "expected {expected:?} in {component}"
+            );
+        }
+        assert!(to_jsx_component("<g></g>").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+//! Minification of SVG path data, the `d` attribute of `<path>` elements.
+//!
+//! The path is rewritten without changing its geometry: numbers are written in their shortest
+//! form, separators are only kept where they are needed and command letters are omitted when the
+//! command is repeated.
+
+/// The number of parameters of a path command, or `None` for an unknown command.
+fn parameter_count(command: u8) -> Option<usize> {
+    Some(match command.to_ascii_lowercase() {
+        b'z' => 0,
+        b'h' | b'v' => 1,
+        b'm' | b'l' | b't' => 2,
+        b's' | b'q' => 4,
+        b'c' => 6,
+        b'a' => 7,
+        _ => return None,
+    })
+}
+
+/// The command used for parameters that follow a command without a command letter.
+fn implicit_command(command: u8) -> u8 {
+    match command {
+        b'M' => b'L',
+        b'm' => b'l',
+        command => command,
+    }
+}
+
+/// Whether the parameter at `index` of `command` is an arc flag, which is a single `0` or `1`.
+fn is_flag(command: u8, index: usize) -> bool {
+    command.eq_ignore_ascii_case(&b'a') && (index == 3 || index == 4)
+}
+
+struct Segment<'a> {
+    command: u8,
+    parameters: Vec<&'a str>,
+}
+
+struct Parser<'a> {
+    data: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespace with at most one comma.
+    fn skip_separator(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn number(&mut self) -> Option<&'a str> {
+        let start = self.position;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.position += 1;
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.position = start;
+            return None;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa_end = self.position;
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.position = mantissa_end;
+            }
+        }
+        Some(&self.data[start..self.position])
+    }
+
+    fn flag(&mut self) -> Option<&'a str> {
+        let start = self.position;
+        matches!(self.peek(), Some(b'0' | b'1')).then(|| {
+            self.position += 1;
+            &self.data[start..self.position]
+        })
+    }
+
+    /// Parses the path data into segments with a single set of parameters each. Returns `None`
+    /// when the path data is invalid.
+    fn segments(mut self) -> Option<Vec<Segment<'a>>> {
+        let mut segments = Vec::new();
+        self.skip_whitespace();
+        while let Some(command) = self.peek() {
+            let count = parameter_count(command)?;
+            if segments.is_empty() && !command.eq_ignore_ascii_case(&b'm') {
+                return None;
+            }
+            self.position += 1;
+            self.skip_whitespace();
+            let mut command = command;
+            loop {
+                let mut parameters = Vec::with_capacity(count);
+                for index in 0..count {
+                    if index > 0 {
+                        self.skip_separator();
+                    }
+                    parameters.push(if is_flag(command, index) {
+                        self.flag()?
+                    } else {
+                        self.number()?
+                    });
+                }
+                segments.push(Segment {
+                    command,
+                    parameters,
+                });
+                command = implicit_command(command);
+                if count == 0 {
+                    self.skip_whitespace();
+                    break;
+                }
+                self.skip_separator();
+                if !self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.'))
+                {
+                    break;
+                }
+            }
+        }
+        Some(segments)
+    }
+}
+
+/// Writes a number in its shortest form, e.g. `-0.50` as `-.5` and `1.0e+02` as `1e2`.
+fn write_number(result: &mut String, number: &str) {
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(index) => (&number[..index], Some(&number[index + 1..])),
+        None => (number, None),
+    };
+    let (negative, mantissa) = split_sign(mantissa);
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    if integer.is_empty() && fraction.is_empty() {
+        result.push('0');
+        return;
+    }
+    if negative {
+        result.push('-');
+    }
+    result.push_str(integer);
+    if !fraction.is_empty() {
+        result.push('.');
+        result.push_str(fraction);
+    }
+    if let Some(exponent) = exponent {
+        let (negative, digits) = split_sign(exponent);
+        let digits = digits.trim_start_matches('0');
+        if !digits.is_empty() {
+            result.push('e');
+            if negative {
+                result.push('-');
+            }
+            result.push_str(digits);
+        }
+    }
+}
+
+fn split_sign(number: &str) -> (bool, &str) {
+    match number.as_bytes().first() {
+        Some(b'-') => (true, &number[1..]),
+        Some(b'+') => (false, &number[1..]),
+        _ => (false, number),
+    }
+}
+
+/// Minifies SVG path data. Invalid path data is returned unchanged, as renderers draw invalid
+/// paths up to the first error.
+pub fn minify_path_data(data: &str) -> String {
+    let Some(segments) = (Parser { data, position: 0 }).segments() else {
+        return data.to_string();
+    };
+
+    let mut result = String::with_capacity(data.len());
+    let mut previous_command = None;
+    // Whether the last written parameter needs a separator before a number which doesn't start
+    // with a sign or a dot, and before a number starting with a dot, respectively.
+    let mut needs_separator = false;
+    let mut needs_separator_before_dot = false;
+    for Segment {
+        command,
+        parameters,
+    } in segments
+    {
+        let repeated = previous_command.map(implicit_command) == Some(command);
+        if !repeated || parameters.is_empty() {
+            result.push(command as char);
+            needs_separator = false;
+            needs_separator_before_dot = false;
+        }
+        for (index, parameter) in parameters.into_iter().enumerate() {
+            let start = result.len();
+            write_number(&mut result, parameter);
+            let separator = match result.as_bytes()[start] {
+                b'-' => false,
+                b'.' => needs_separator_before_dot,
+                _ => needs_separator,
+            };
+            if separator {
+                result.insert(start, ' ');
+            }
+            let written = &result[start..];
+            needs_separator = true;
+            needs_separator_before_dot =
+                is_flag(command, index) || !written.contains('.') || written.contains('e');
+        }
+        previous_command = Some(command);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minify_path_data;
+
+    #[test]
+    fn test_collapse_commands() {
+        assert_eq!(
+            minify_path_data("M 10 10 L 20 20 L 30 10 Z"),
+            "M10 10 20 20 30 10Z"
+        );
+        assert_eq!(
+            minify_path_data("M 0,0 M 5,5 h 10 h 5 v -3 z m 1 1 z"),
+            "M0 0M5 5h10 5v-3zm1 1z"
+        );
+        assert_eq!(
+            minify_path_data("m 1 2 3 4 l 5 6 L 7 8"),
+            "m1 2 3 4 5 6L7 8"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            minify_path_data("m0.5,-0.50 l 1.0e+1 -.5 .5 0 +0.25 -000.0"),
+            "m.5-.5 1e1-.5.5 0 .25 0"
+        );
+        assert_eq!(
+            minify_path_data("M1e-2.5 1E05 0 L 1.5e3 .5"),
+            "M1e-2 .5 1e5 0 1.5e3 .5"
+        );
+        assert_eq!(minify_path_data("M-1-2-3-4"), "M-1-2-3-4");
+    }
+
+    #[test]
+    fn test_arcs() {
+        assert_eq!(
+            minify_path_data("M 0 0 A 5 5 0 0 1 10 10 a5,5 0 01.5.5"),
+            "M0 0A5 5 0 0 1 10 10a5 5 0 0 1 .5.5"
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for data in ["M 10", "M 10 10 X 5", "L 10 10", "M 10 10 A 5 5 0 2 0 1 1"] {
+            assert_eq!(minify_path_data(data), data);
+        }
+        assert_eq!(minify_path_data(""), "");
+    }
+}
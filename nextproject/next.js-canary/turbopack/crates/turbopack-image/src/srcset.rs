@@ -0,0 +1,397 @@
+use std::io::Write;
+
+use anyhow::{Context, Result, bail};
+use turbo_rcstr::rcstr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{FileContent, rope::RopeBuilder};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    issue::{IssueExt, IssueSeverity, IssueSource, StyledString},
+    source::Source,
+};
+
+use crate::process::{
+    BlurPlaceholderOptions, ImageProcessingIssue, ImageVariantFormat, get_decoded_meta_data,
+    optimize_as,
+};
+
+const DEFAULT_QUALITY: u8 = 75;
+
+/// Options for generating responsive image variants, parsed from an import query like
+/// `?srcset=320,640,1280&format=webp,avif&quality=80`.
+#[derive(Debug, Clone)]
+#[turbo_tasks::value(shared)]
+pub struct SrcSetOptions {
+    /// The requested widths, sorted ascending.
+    pub widths: Vec<u32>,
+    /// The requested formats in order of preference. The original format is always last, as
+    /// it's the fallback for browsers that don't support any of the other formats.
+    pub formats: Vec<ImageVariantFormat>,
+    pub quality: u8,
+}
+
+impl SrcSetOptions {
+    /// Parses the options from a resource query. Returns `None` when the query doesn't contain a
+    /// `srcset` parameter.
+    pub fn from_query(query: &str) -> Result<Option<Self>> {
+        let mut widths = None;
+        let mut formats = Vec::new();
+        let mut quality = DEFAULT_QUALITY;
+        for pair in query.trim_start_matches('?').split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = value.replace("%2C", ",").replace("%2c", ",");
+            match key {
+                "srcset" => {
+                    let mut parsed = value
+                        .split(',')
+                        .map(|width| {
+                            width
+                                .trim()
+                                .parse::<u32>()
+                                .ok()
+                                .filter(|&width| width > 0)
+                                .with_context(|| format!("invalid srcset width {width:?}"))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    parsed.sort_unstable();
+                    parsed.dedup();
+                    widths = Some(parsed);
+                }
+                "format" => {
+                    for name in value.split(',') {
+                        let format = ImageVariantFormat::from_name(name.trim())
+                            .with_context(|| format!("unsupported image format {name:?}"))?;
+                        if !formats.contains(&format) {
+                            formats.push(format);
+                        }
+                    }
+                }
+                "quality" => {
+                    quality = value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|quality| (1..=100).contains(quality))
+                        .with_context(|| format!("invalid image quality {value:?}"))?;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(widths) = widths else {
+            return Ok(None);
+        };
+        if widths.is_empty() {
+            bail!("srcset query must contain at least one width");
+        }
+        formats.retain(|format| *format != ImageVariantFormat::Original);
+        formats.push(ImageVariantFormat::Original);
+
+        Ok(Some(SrcSetOptions {
+            widths,
+            formats,
+            quality,
+        }))
+    }
+
+    /// The widths to generate for an image that is `intrinsic_width` pixels wide. Images are
+    /// never upscaled, so larger widths collapse into the intrinsic width.
+    fn widths_for(&self, intrinsic_width: u32) -> Vec<u32> {
+        let mut widths = self
+            .widths
+            .iter()
+            .map(|&width| width.min(intrinsic_width))
+            .collect::<Vec<_>>();
+        widths.dedup();
+        widths
+    }
+}
+
+/// A single resized and re-encoded version of an image.
+#[turbo_tasks::value]
+pub struct ImageVariantSource {
+    image: ResolvedVc<Box<dyn Source>>,
+    width: u32,
+    format: ImageVariantFormat,
+    quality: u8,
+}
+
+#[turbo_tasks::value_impl]
+impl ImageVariantSource {
+    #[turbo_tasks::function]
+    pub fn new(
+        image: ResolvedVc<Box<dyn Source>>,
+        width: u32,
+        format: ImageVariantFormat,
+        quality: u8,
+    ) -> Vc<Self> {
+        ImageVariantSource {
+            image,
+            width,
+            format,
+            quality,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for ImageVariantSource {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
+        let ident = self.image.ident();
+        let path = ident.path().await?;
+        let extension = self.format.extension().unwrap_or(path.extension());
+        Ok(ident
+            .with_modifier(format!("image variant {}w q{}", self.width, self.quality).into())
+            .rename_as(format!("*.{}w.{extension}", self.width).into()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ImageVariantSource {
+    #[turbo_tasks::function]
+    fn content(&self) -> Vc<AssetContent> {
+        AssetContent::file(optimize_as(
+            *self.image,
+            self.image.content().file_content(),
+            self.width,
+            u32::MAX,
+            self.quality,
+            self.format,
+        ))
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct ImageVariantSources(Vec<ResolvedVc<ImageVariantSource>>);
+
+#[turbo_tasks::function]
+fn blur_options() -> Vc<BlurPlaceholderOptions> {
+    BlurPlaceholderOptions {
+        quality: 70,
+        size: 8,
+    }
+    .cell()
+}
+
+/// A source that transforms an image imported with a `srcset` query into javascript code which
+/// exports the generated variants as `{ srcSet, sources, width, height, placeholder }`.
+///
+/// The variants are imported as inner assets named `VARIANT_0`, `VARIANT_1`, ... in the order
+/// returned by [ImageSrcSetSource::variants].
+#[turbo_tasks::value]
+pub struct ImageSrcSetSource {
+    image: ResolvedVc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ImageSrcSetSource {
+    #[turbo_tasks::function]
+    pub fn new(image: ResolvedVc<Box<dyn Source>>) -> Vc<Self> {
+        ImageSrcSetSource { image }.cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn options(&self) -> Result<Vc<SrcSetOptions>> {
+        let ident = self.image.ident().await?;
+        Ok(SrcSetOptions::from_query(&ident.query)?
+            .context("Image import has no srcset query")?
+            .cell())
+    }
+
+    /// The variants of the image, grouped by format and ordered by width. Empty when the image
+    /// can't be decoded, as the widths of the variants depend on its intrinsic width.
+    #[turbo_tasks::function]
+    pub async fn variants(self: Vc<Self>) -> Result<Vc<ImageVariantSources>> {
+        let image = self.await?.image;
+        let options = self.options().await?;
+        let Some(meta) =
+            &*get_decoded_meta_data(*image, image.content().file_content(), None).await?
+        else {
+            ImageProcessingIssue {
+                source: IssueSource::from_source_only(image),
+                message: StyledString::Text(rcstr!(
+                    "The dimensions of this image are unknown because it can't be decoded, so no \
+                     srcset variants are generated"
+                ))
+                .resolved_cell(),
+                title: Some(StyledString::Text(rcstr!("Image srcset skipped")).resolved_cell()),
+                issue_severity: Some(IssueSeverity::Warning),
+            }
+            .resolved_cell()
+            .emit();
+            return Ok(Vc::cell(Vec::new()));
+        };
+
+        let mut variants = Vec::new();
+        for &format in &options.formats {
+            if !format.is_supported() {
+                ImageProcessingIssue {
+                    source: IssueSource::from_source_only(image),
+                    message: StyledString::Text(
+                        format!(
+                            "This version of Turbopack can't encode {format:?} images, the \
+                             variants are skipped"
+                        )
+                        .into(),
+                    )
+                    .resolved_cell(),
+                    title: Some(
+                        StyledString::Text(rcstr!("Image format not supported")).resolved_cell(),
+                    ),
+                    issue_severity: Some(IssueSeverity::Warning),
+                }
+                .resolved_cell()
+                .emit();
+                continue;
+            }
+            for width in options.widths_for(meta.width) {
+                variants.push(
+                    ImageVariantSource::new(*image, width, format, options.quality)
+                        .to_resolved()
+                        .await?,
+                );
+            }
+        }
+        Ok(Vc::cell(variants))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for ImageSrcSetSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.image
+            .ident()
+            .with_modifier(rcstr!("srcset image object"))
+            .rename_as(rcstr!("*.mjs"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ImageSrcSetSource {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let image = self.await?.image;
+        let options = self.options().await?;
+        let variants = self.variants().await?;
+        let meta =
+            get_decoded_meta_data(*image, image.content().file_content(), Some(blur_options()))
+                .await?;
+
+        let mut result = RopeBuilder::from("");
+        for i in 0..variants.len() {
+            writeln!(result, "import variant{i} from \"VARIANT_{i}\";")?;
+        }
+
+        let mut sources: Vec<(ImageVariantFormat, Vec<String>)> = Vec::new();
+        for (i, variant) in variants.iter().enumerate() {
+            let variant = variant.await?;
+            let candidate = format!("${{variant{i}}} {}w", variant.width);
+            match sources.last_mut() {
+                Some((format, candidates)) if *format == variant.format => {
+                    candidates.push(candidate)
+                }
+                _ => sources.push((variant.format, vec![candidate])),
+            }
+        }
+
+        writeln!(result, "const sources = [")?;
+        for (format, candidates) in &sources {
+            let mime_type = match format {
+                ImageVariantFormat::Original => meta
+                    .as_ref()
+                    .and_then(|meta| meta.mime_type.as_ref())
+                    .map(|m| m.to_string()),
+                ImageVariantFormat::Png => Some(mime::IMAGE_PNG.to_string()),
+                ImageVariantFormat::Jpeg => Some(mime::IMAGE_JPEG.to_string()),
+                ImageVariantFormat::WebP => Some("image/webp".to_string()),
+                ImageVariantFormat::Avif => Some("image/avif".to_string()),
+            };
+            writeln!(
+                result,
+                "    {{ type: {}, srcSet: `{}` }},",
+                serde_json::to_string(&mime_type)?,
+                candidates.join(", ")
+            )?;
+        }
+        writeln!(result, "];")?;
+
+        // The intrinsic size of the largest variant, or zero when there are no variants.
+        let (width, height) = match &*meta {
+            Some(meta) => {
+                let width = options.widths_for(meta.width).last().copied().unwrap_or(0);
+                let height = if meta.width > 0 {
+                    (meta.height as f64 * width as f64 / meta.width as f64).round() as u32
+                } else {
+                    meta.height
+                };
+                (width, height)
+            }
+            None => (0, 0),
+        };
+        let placeholder = meta
+            .as_ref()
+            .and_then(|meta| meta.blur_placeholder.as_ref())
+            .map(|placeholder| placeholder.data_url.as_str());
+        writeln!(
+            result,
+            "/** @type {{{{ srcSet: string, sources: {{ type: string | null, srcSet: string }}[], \
+             width: number, height: number, placeholder: string | null }}}} */"
+        )?;
+        writeln!(
+            result,
+            "const image = {{ srcSet: sources.length > 0 ? sources[sources.length - 1].srcSet : \
+             \"\", sources, width: {width}, height: {height}, placeholder: {} }};",
+            serde_json::to_string(&placeholder)?
+        )?;
+        writeln!(result, "export default image;")?;
+
+        Ok(AssetContent::File(FileContent::Content(result.build().into()).resolved_cell()).cell())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SrcSetOptions;
+    use crate::process::ImageVariantFormat;
+
+    #[test]
+    fn parse_srcset_query() {
+        let options = SrcSetOptions::from_query("?srcset=1280,320,640,640&format=webp,avif")
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.widths, vec![320, 640, 1280]);
+        assert_eq!(
+            options.formats,
+            vec![
+                ImageVariantFormat::WebP,
+                ImageVariantFormat::Avif,
+                ImageVariantFormat::Original
+            ]
+        );
+        assert_eq!(options.quality, 75);
+        assert_eq!(options.widths_for(800), vec![320, 640, 800]);
+        assert_eq!(options.widths_for(200), vec![200]);
+
+        let options = SrcSetOptions::from_query("?format=original,png&srcset=100%2C200&quality=90")
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.widths, vec![100, 200]);
+        assert_eq!(
+            options.formats,
+            vec![ImageVariantFormat::Png, ImageVariantFormat::Original]
+        );
+        assert_eq!(options.quality, 90);
+    }
+
+    #[test]
+    fn parse_invalid_srcset_query() {
+        assert!(SrcSetOptions::from_query("?format=webp").unwrap().is_none());
+        assert!(SrcSetOptions::from_query("?srcset=").is_err());
+        assert!(SrcSetOptions::from_query("?srcset=0").is_err());
+        assert!(SrcSetOptions::from_query("?srcset=100&format=gif").is_err());
+        assert!(SrcSetOptions::from_query("?srcset=100&quality=0").is_err());
+    }
+}
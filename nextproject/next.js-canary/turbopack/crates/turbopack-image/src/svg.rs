@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use turbo_rcstr::rcstr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileContent};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    source::Source,
+};
+
+use crate::process::{result_to_issue, svg_markup};
+
+async fn read_markup(source: ResolvedVc<Box<dyn Source>>) -> Result<Option<String>> {
+    let content = source.content().file_content().await?;
+    let FileContent::Content(file) = &*content else {
+        return Ok(None);
+    };
+    Ok(Some(
+        file.content()
+            .to_str()
+            .context("SVG image is not valid utf-8")?
+            .into_owned(),
+    ))
+}
+
+/// An SVG image with minified markup. When the markup can't be parsed, an issue is emitted and
+/// the original content is used.
+#[turbo_tasks::value]
+pub struct OptimizedSvgSource {
+    source: ResolvedVc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl OptimizedSvgSource {
+    #[turbo_tasks::function]
+    pub fn new(source: ResolvedVc<Box<dyn Source>>) -> Vc<Self> {
+        OptimizedSvgSource { source }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for OptimizedSvgSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source.ident().with_modifier(rcstr!("optimized svg"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for OptimizedSvgSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let Some(markup) = read_markup(self.source).await? else {
+            return Ok(self.source.content());
+        };
+        let Some(optimized) = result_to_issue(
+            self.source,
+            svg_markup::optimize(&markup).context("Failed to optimize svg source code"),
+        ) else {
+            return Ok(self.source.content());
+        };
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(optimized)).cell(),
+        ))
+    }
+}
+
+/// A source that transforms an SVG image into a JSX module which default exports a React
+/// component rendering the (minified) SVG. When the SVG can't be converted, an issue is emitted
+/// and the module throws when it's evaluated.
+#[turbo_tasks::value]
+pub struct SvgComponentSource {
+    source: ResolvedVc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl SvgComponentSource {
+    #[turbo_tasks::function]
+    pub fn new(source: ResolvedVc<Box<dyn Source>>) -> Vc<Self> {
+        SvgComponentSource { source }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for SvgComponentSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source
+            .ident()
+            .with_modifier(rcstr!("svg component"))
+            .rename_as(rcstr!("*.jsx"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for SvgComponentSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let Some(markup) = read_markup(self.source).await? else {
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        };
+        let component = match result_to_issue(
+            self.source,
+            svg_markup::optimize(&markup)
+                .and_then(|markup| svg_markup::to_jsx_component(&markup))
+                .context("Failed to convert svg source code into a component"),
+        ) {
+            Some(component) => component,
+            None => format!(
+                "throw new Error({});\n",
+                serde_json::to_string("Failed to convert svg source code into a component")?
+            ),
+        };
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(component)).cell(),
+        ))
+    }
+}
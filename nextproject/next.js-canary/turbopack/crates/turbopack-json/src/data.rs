@@ -0,0 +1,332 @@
+//! Support for data files in formats other than JSON (JSON5/JSONC, YAML and TOML).
+//!
+//! Data files are parsed in Rust and turned into an ES module which exports the document as the
+//! default export and each top-level key as a named export, so that unused keys can be removed by
+//! tree shaking.
+
+use std::fmt::{Display, Write};
+
+use anyhow::Result;
+use bincode::{Decode, Encode};
+use serde_json::Value;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{NonLocalValue, ResolvedVc, TaskInput, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    issue::{
+        Issue, IssueExt, IssueSource, IssueStage, OptionIssueSource, OptionStyledString,
+        StyledString,
+    },
+    source::Source,
+    source_pos::SourcePos,
+};
+use turbopack_ecmascript::utils::is_valid_binding_name;
+
+/// Values with a larger serialized size are emitted as `JSON.parse` calls, which are faster to
+/// parse for JS engines. See https://v8.dev/blog/cost-of-javascript-2019#json
+const JSON_PARSE_THRESHOLD: usize = 10_000;
+
+#[derive(
+    PartialOrd,
+    Ord,
+    Eq,
+    PartialEq,
+    Hash,
+    Debug,
+    Copy,
+    Clone,
+    TaskInput,
+    TraceRawVcs,
+    NonLocalValue,
+    Encode,
+    Decode,
+)]
+pub enum DataFormat {
+    /// JSON5 files (.json5), which is also used for JSON with comments (.jsonc).
+    Json5,
+    /// YAML files (.yaml, .yml).
+    Yaml,
+    /// TOML files (.toml).
+    Toml,
+}
+
+impl Display for DataFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DataFormat::Json5 => "JSON5",
+            DataFormat::Yaml => "YAML",
+            DataFormat::Toml => "TOML",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DataParseError {
+    pub message: String,
+    pub position: Option<SourcePos>,
+}
+
+impl DataFormat {
+    /// Parses a document into a JSON value.
+    pub fn parse(self, text: &str) -> Result<Value, DataParseError> {
+        match self {
+            DataFormat::Json5 => json5::from_str(text).map_err(|err| match err {
+                json5::Error::Message { msg, location } => DataParseError {
+                    message: msg,
+                    position: location.map(|location| SourcePos {
+                        line: location.line.saturating_sub(1) as u32,
+                        column: location.column.saturating_sub(1) as u32,
+                    }),
+                },
+            }),
+            DataFormat::Yaml => {
+                let yaml_error = |err: serde_yaml_ng::Error| DataParseError {
+                    position: err
+                        .location()
+                        .map(|location| position_at(text, location.index())),
+                    message: err.to_string(),
+                };
+                let mut value =
+                    serde_yaml_ng::from_str::<serde_yaml_ng::Value>(text).map_err(yaml_error)?;
+                value.apply_merge().map_err(yaml_error)?;
+                serde_json::to_value(value).map_err(|err| DataParseError {
+                    message: err.to_string(),
+                    position: None,
+                })
+            }
+            DataFormat::Toml => match text.parse::<toml::Table>() {
+                Ok(table) => Ok(toml_to_json(toml::Value::Table(table))),
+                Err(err) => Err(DataParseError {
+                    message: err.message().to_string(),
+                    position: err.span().map(|span| position_at(text, span.start)),
+                }),
+            },
+        }
+    }
+}
+
+/// Converts a byte offset into a line and column.
+fn position_at(text: &str, offset: usize) -> SourcePos {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    SourcePos {
+        line: before.matches('\n').count() as u32,
+        column: (before.len() - line_start) as u32,
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        // Like `JSON.stringify`, non-finite numbers are turned into `null`.
+        toml::Value::Float(float) => Value::from(float),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Returns a JavaScript expression which evaluates to `value`.
+pub fn value_expression(value: &Value) -> Result<String> {
+    let json = serde_json::to_string(value)?;
+    // In object literals `__proto__` sets the prototype instead of creating a property, unlike in
+    // `JSON.parse`.
+    if json.len() > JSON_PARSE_THRESHOLD || json.contains("\"__proto__\":") {
+        Ok(format!("JSON.parse({})", serde_json::to_string(&json)?))
+    } else {
+        Ok(json)
+    }
+}
+
+/// Generates an ES module that default exports `value`. When `value` is an object, the top-level
+/// keys that are valid identifiers are also exported as named exports, and the default export is
+/// built from these bindings so that unused keys can be removed by tree shaking.
+pub fn esm_code(value: &Value) -> Result<String> {
+    let Value::Object(object) = value else {
+        return Ok(format!("export default {};\n", value_expression(value)?));
+    };
+    let mut code = String::new();
+    let mut properties = Vec::with_capacity(object.len());
+    for (key, value) in object {
+        let expression = value_expression(value)?;
+        if is_valid_binding_name(key) {
+            writeln!(code, "export const {key} = {expression};")?;
+            properties.push(key.clone());
+        } else {
+            properties.push(format!("{}: {expression}", serde_json::to_string(key)?));
+        }
+    }
+    if properties.is_empty() {
+        writeln!(code, "export default {{}};")?;
+    } else {
+        writeln!(
+            code,
+            "export default {{\n    {},\n}};",
+            properties.join(",\n    ")
+        )?;
+    }
+    Ok(code)
+}
+
+/// A source that transforms a data file into an ES module.
+#[turbo_tasks::value]
+pub struct DataSource {
+    source: ResolvedVc<Box<dyn Source>>,
+    format: DataFormat,
+}
+
+#[turbo_tasks::value_impl]
+impl DataSource {
+    #[turbo_tasks::function]
+    pub fn new(source: ResolvedVc<Box<dyn Source>>, format: DataFormat) -> Vc<Self> {
+        DataSource { source, format }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for DataSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source
+            .ident()
+            .with_modifier(format!("{} data", self.format).into())
+            .rename_as(rcstr!("*.mjs"))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for DataSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let content = self.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        };
+        let code = match self.format.parse(&file.content().to_str()?) {
+            Ok(value) => esm_code(&value)?,
+            Err(err) => {
+                let source = match err.position {
+                    Some(position) => IssueSource::from_line_col(self.source, position, position),
+                    None => IssueSource::from_source_only(self.source),
+                };
+                DataParsingIssue {
+                    format: self.format,
+                    message: err.message.into(),
+                    source,
+                }
+                .resolved_cell()
+                .emit();
+                format!(
+                    "throw new Error({});\n",
+                    serde_json::to_string(&format!("Unable to parse {} file", self.format))?
+                )
+            }
+        };
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(code)).cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value]
+struct DataParsingIssue {
+    format: DataFormat,
+    message: RcStr,
+    source: IssueSource,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for DataParsingIssue {
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.source.file_path()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Parse.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(format!("Parsing {} source code failed", self.format).into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(Some(self.source))
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(self.message.clone()).resolved_cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use turbopack_core::source_pos::SourcePos;
+
+    use super::{DataFormat, esm_code};
+
+    #[test]
+    fn parse_json5() {
+        let value = DataFormat::Json5
+            .parse("// comment\n{ a: 1, /* b */ 'c': [true, null,], }")
+            .unwrap();
+        assert_eq!(value, json!({ "a": 1, "c": [true, null] }));
+
+        let err = DataFormat::Json5.parse("{\n  a: 1,\n  b: }").unwrap_err();
+        assert_eq!(err.position, Some(SourcePos { line: 2, column: 5 }));
+    }
+
+    #[test]
+    fn parse_yaml() {
+        let value = DataFormat::Yaml
+            .parse("base: &base\n  a: 1\nderived:\n  <<: *base\n  b: [x, y]\n")
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({ "base": { "a": 1 }, "derived": { "a": 1, "b": ["x", "y"] } })
+        );
+
+        let err = DataFormat::Yaml.parse("a: 1\nb: [1, 2\n").unwrap_err();
+        assert_eq!(err.position.map(|position| position.line), Some(2));
+    }
+
+    #[test]
+    fn parse_toml() {
+        let value = DataFormat::Toml
+            .parse("title = \"x\"\n[server]\nport = 80\ndate = 1979-05-27\n")
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({ "title": "x", "server": { "port": 80, "date": "1979-05-27" } })
+        );
+
+        let err = DataFormat::Toml.parse("a = 1\nb = = 2\n").unwrap_err();
+        assert_eq!(err.position, Some(SourcePos { line: 1, column: 4 }));
+    }
+
+    #[test]
+    fn generate_esm() {
+        assert_eq!(esm_code(&json!([1, 2])).unwrap(), "export default [1,2];\n");
+        assert_eq!(
+            esm_code(&json!({ "a": 1, "b-c": { "__proto__": {} }, "default": 2 })).unwrap(),
+            "export const a = 1;\nexport default {\n    a,\n    \"b-c\": \
+             JSON.parse(\"{\\\"__proto__\\\":{}}\"),\n    \"default\": 2,\n};\n"
+        );
+    }
+}
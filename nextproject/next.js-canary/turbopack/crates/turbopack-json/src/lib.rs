@@ -3,16 +3,22 @@
 //! JSON assets are parsed to ensure they contain valid JSON.
 //!
 //! When imported from ES modules, they produce a module that exports the
-//! JSON value as an object.
+//! JSON value as an object. The top-level keys of a JSON object are also
+//! exported as named ESM bindings.
+//!
+//! JSON5, YAML and TOML files are supported by the [data] module.
 
 #![feature(min_specialization)]
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
-use std::fmt::Write;
+pub mod data;
+
+use std::{borrow::Cow, collections::BTreeMap, fmt::Write};
 
 use anyhow::{Error, Result, bail};
-use turbo_rcstr::rcstr;
+use serde_json::Value;
+use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{ResolvedVc, ValueToString, Vc};
 use turbo_tasks_fs::{FileContent, FileJsonContent};
 use turbopack_core::{
@@ -21,7 +27,7 @@ use turbopack_core::{
     code_builder::CodeBuilder,
     ident::AssetIdent,
     module::{Module, ModuleSideEffects},
-    module_graph::ModuleGraph,
+    module_graph::{ModuleGraph, binding_usage_info::ModuleExportUsageInfo},
     output::OutputAssetsReference,
     source::Source,
 };
@@ -30,7 +36,9 @@ use turbopack_ecmascript::{
         EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
         EcmascriptChunkType, EcmascriptExports,
     },
-    runtime_functions::TURBOPACK_EXPORT_VALUE,
+    references::esm::{EsmExport, EsmExports, Liveness},
+    runtime_functions::{TURBOPACK_ESM, TURBOPACK_EXPORT_VALUE},
+    utils::StringifyJs,
 };
 
 #[turbo_tasks::value]
@@ -82,8 +90,81 @@ impl ChunkableModule for JsonModuleAsset {
 #[turbo_tasks::value_impl]
 impl EcmascriptChunkPlaceable for JsonModuleAsset {
     #[turbo_tasks::function]
-    fn get_exports(&self) -> Vc<EcmascriptExports> {
-        EcmascriptExports::Value.cell()
+    async fn get_exports(&self) -> Result<Vc<EcmascriptExports>> {
+        let data = self.source.content().file_content().parse_json().await?;
+        let FileJsonContent::Content(data) = &*data else {
+            return Ok(EcmascriptExports::Value.cell());
+        };
+        let Some(keys) = named_exports(data) else {
+            return Ok(EcmascriptExports::Value.cell());
+        };
+        let exports = [rcstr!("default")]
+            .into_iter()
+            .chain(keys.map(|key| RcStr::from(key.as_str())))
+            .map(|name| {
+                (
+                    name.clone(),
+                    EsmExport::LocalBinding(name, Liveness::Constant),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        Ok(EcmascriptExports::EsmExports(
+            EsmExports {
+                exports,
+                star_exports: vec![],
+            }
+            .resolved_cell(),
+        )
+        .cell())
+    }
+}
+
+/// The top-level keys of a JSON object which are exported as named bindings, or `None` for other
+/// JSON values, which are only exported as a value. `default` is the whole document and
+/// `__esModule` can't be redefined on the exports object, so these keys are only available as
+/// properties of the default export.
+fn named_exports(data: &Value) -> Option<impl Iterator<Item = &String>> {
+    let Value::Object(object) = data else {
+        return None;
+    };
+    Some(
+        object
+            .keys()
+            .filter(|key| !matches!(key.as_str(), "default" | "__esModule")),
+    )
+}
+
+/// Generates the code exporting the JSON document `data`, which is already reduced to the used
+/// keys. `value` is the expression evaluating to `data`.
+fn export_code(data: &Value, value: &str, export_usage: &ModuleExportUsageInfo) -> String {
+    let Some(keys) = named_exports(data) else {
+        return format!("{TURBOPACK_EXPORT_VALUE}({value});");
+    };
+    let mut bindings = Vec::new();
+    if export_usage.is_export_used(&rcstr!("default")) {
+        bindings.push(r#""default", 0, json"#.to_string());
+    }
+    bindings.extend(keys.map(|key| format!("{key}, 0, json[{key}]", key = StringifyJs(key))));
+    format!(
+        "const json = {value};\n{TURBOPACK_ESM}([{}]);",
+        bindings.join(", ")
+    )
+}
+
+/// Top-level keys of a JSON object can be imported as named exports. When the default export and
+/// the namespace are unused, only the keys that are imported by name are included.
+fn used_value<'a>(data: &'a Value, export_usage: &ModuleExportUsageInfo) -> Cow<'a, Value> {
+    match data {
+        Value::Object(object) if !export_usage.is_export_used(&rcstr!("default")) => {
+            Cow::Owned(Value::Object(
+                object
+                    .iter()
+                    .filter(|(key, _)| export_usage.is_export_used(&RcStr::from(key.as_str())))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ))
+        }
+        _ => Cow::Borrowed(data),
     }
 }
 
@@ -131,18 +212,25 @@ impl EcmascriptChunkItem for JsonChunkItem {
         let data = content.parse_json().await?;
         match &*data {
             FileJsonContent::Content(data) => {
+                let export_usage = self
+                    .chunking_context
+                    .module_export_usage(Vc::upcast(*self.module))
+                    .await?;
+                let export_usage = export_usage.export_usage.await?;
+                let data = used_value(data, &export_usage);
                 let data_str = data.to_string();
 
                 let mut code = CodeBuilder::default();
 
-                let source_code = if data_str.len() > 10_000 {
+                let value = if data_str.len() > 10_000 {
                     // Only use JSON.parse if the content is larger than 10kb
                     // https://v8.dev/blog/cost-of-javascript-2019#json
                     let js_str_content = serde_json::to_string(&data_str)?;
-                    format!("{TURBOPACK_EXPORT_VALUE}(JSON.parse({js_str_content}));")
+                    Cow::Owned(format!("JSON.parse({js_str_content})"))
                 } else {
-                    format!("{TURBOPACK_EXPORT_VALUE}({data_str});")
+                    Cow::Borrowed(data_str.as_str())
                 };
+                let source_code = export_code(&data, &value, &export_usage);
 
                 let source_code = source_code.into();
                 let source_map = serde_json::json!({
@@ -191,3 +279,57 @@ impl EcmascriptChunkItem for JsonChunkItem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use turbopack_core::module_graph::binding_usage_info::ModuleExportUsageInfo;
+
+    use super::{export_code, used_value};
+
+    #[test]
+    fn only_used_keys_are_included() {
+        let data = json!({ "version": "1.0.0", "name": "app", "dependencies": {} });
+        let exports = |names: &[&str]| {
+            ModuleExportUsageInfo::Exports(names.iter().map(|name| (*name).into()).collect())
+        };
+
+        assert_eq!(
+            *used_value(&data, &exports(&["version", "missing"])),
+            json!({ "version": "1.0.0" })
+        );
+        assert_eq!(*used_value(&data, &exports(&["version", "default"])), data);
+        assert_eq!(*used_value(&data, &ModuleExportUsageInfo::All), data);
+        assert_eq!(
+            *used_value(&data, &ModuleExportUsageInfo::Evaluation),
+            json!({})
+        );
+        assert_eq!(*used_value(&json!([1]), &exports(&["version"])), json!([1]));
+    }
+
+    #[test]
+    fn top_level_keys_are_exported_as_bindings() {
+        let all = ModuleExportUsageInfo::All;
+        assert_eq!(
+            export_code(
+                &json!({ "version": "1.0.0", "default": 1, "__esModule": true }),
+                "data",
+                &all
+            ),
+            "const json = data;\n__turbopack_context__.s([\"default\", 0, json, \"version\", 0, \
+             json[\"version\"]]);"
+        );
+        assert_eq!(
+            export_code(
+                &json!({ "version": "1.0.0" }),
+                "data",
+                &ModuleExportUsageInfo::Exports(["version".into()].into_iter().collect())
+            ),
+            "const json = data;\n__turbopack_context__.s([\"version\", 0, json[\"version\"]]);"
+        );
+        assert_eq!(
+            export_code(&json!([1]), "data", &all),
+            "__turbopack_context__.v(data);"
+        );
+    }
+}
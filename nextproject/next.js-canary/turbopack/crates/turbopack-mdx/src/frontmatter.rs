@@ -0,0 +1,79 @@
+use turbopack_json::data::DataFormat;
+
+/// A YAML (`---`) or TOML (`+++`) frontmatter block at the start of a document.
+#[derive(Debug, PartialEq)]
+pub struct Frontmatter<'a> {
+    pub format: DataFormat,
+    /// The content between the fences, starting on the second line of the document.
+    pub content: &'a str,
+    /// The byte offset after the closing fence.
+    pub end: usize,
+}
+
+/// Finds the frontmatter block at the start of `text`. Like in markdown, the block must be
+/// closed by a line consisting of the same fence.
+pub fn find_frontmatter(text: &str) -> Option<Frontmatter<'_>> {
+    let mut lines = text.split_inclusive('\n');
+    let fence = lines.next()?.trim_end();
+    let format = match fence {
+        "---" => DataFormat::Yaml,
+        "+++" => DataFormat::Toml,
+        _ => return None,
+    };
+    let start = text.find('\n')? + 1;
+    let mut offset = start;
+    for line in lines {
+        if line.trim_end() == fence {
+            return Some(Frontmatter {
+                format,
+                content: &text[start..offset],
+                end: offset + line.len(),
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Removes the frontmatter block from `text` while keeping its line breaks, so that positions
+/// in the rest of the document stay the same.
+pub fn strip_frontmatter(text: &str, frontmatter: &Frontmatter) -> String {
+    let mut stripped = text[..frontmatter.end]
+        .chars()
+        .filter(|&c| c == '\n')
+        .collect::<String>();
+    stripped.push_str(&text[frontmatter.end..]);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use turbopack_json::data::DataFormat;
+
+    use super::{find_frontmatter, strip_frontmatter};
+
+    #[test]
+    fn yaml_frontmatter() {
+        let text = "---\ntitle: Hello\n---\n# Hello\n";
+        let frontmatter = find_frontmatter(text).unwrap();
+        assert_eq!(frontmatter.format, DataFormat::Yaml);
+        assert_eq!(frontmatter.content, "title: Hello\n");
+        assert_eq!(strip_frontmatter(text, &frontmatter), "\n\n\n# Hello\n");
+    }
+
+    #[test]
+    fn toml_frontmatter() {
+        let text = "+++\r\ntitle = \"Hello\"\r\n+++\r\n";
+        let frontmatter = find_frontmatter(text).unwrap();
+        assert_eq!(frontmatter.format, DataFormat::Toml);
+        assert_eq!(frontmatter.content, "title = \"Hello\"\r\n");
+        assert_eq!(frontmatter.end, text.len());
+    }
+
+    #[test]
+    fn no_frontmatter() {
+        assert_eq!(find_frontmatter("# Hello\n---\n"), None);
+        assert_eq!(find_frontmatter("---\ntitle: Hello\n"), None);
+        assert_eq!(find_frontmatter("----\n----\n"), None);
+    }
+}
@@ -0,0 +1,432 @@
+//! A small syntax highlighter for fenced code blocks.
+//!
+//! The highlighter is token based and doesn't try to fully parse the languages, which is enough
+//! for documentation snippets. Tokens get the class names used by Prism (`token keyword`, `token
+//! string`, ...), so that existing Prism themes can be used to style the output.
+
+use mdxjs::hast;
+
+struct Language {
+    keywords: &'static [&'static str],
+    /// Keywords that are highlighted as `boolean` instead of `keyword`.
+    booleans: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Whether strings followed by a colon are object keys, as in JSON.
+    string_keys: bool,
+}
+
+const JAVASCRIPT: Language = Language {
+    keywords: &[
+        "abstract",
+        "as",
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "declare",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "get",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "keyof",
+        "let",
+        "namespace",
+        "new",
+        "null",
+        "of",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "return",
+        "satisfies",
+        "set",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "with",
+        "yield",
+    ],
+    booleans: &["true", "false"],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    string_keys: false,
+};
+
+const JSON: Language = Language {
+    keywords: &["null"],
+    booleans: &["true", "false"],
+    line_comment: None,
+    block_comment: None,
+    quotes: &['"'],
+    string_keys: true,
+};
+
+const RUST: Language = Language {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+        "unsafe", "use", "where", "while",
+    ],
+    booleans: &["true", "false"],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    string_keys: false,
+};
+
+const CSS: Language = Language {
+    keywords: &[],
+    booleans: &[],
+    line_comment: None,
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    string_keys: false,
+};
+
+const SHELL: Language = Language {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+    booleans: &["true", "false"],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\''],
+    string_keys: false,
+};
+
+const PYTHON: Language = Language {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+        "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+        "with", "yield",
+    ],
+    booleans: &["True", "False"],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\''],
+    string_keys: false,
+};
+
+fn language(name: &str) -> Option<&'static Language> {
+    Some(match name {
+        "js" | "jsx" | "mjs" | "cjs" | "javascript" | "ts" | "tsx" | "mts" | "cts"
+        | "typescript" => &JAVASCRIPT,
+        "json" | "json5" | "jsonc" => &JSON,
+        "rs" | "rust" => &RUST,
+        "css" | "scss" | "less" => &CSS,
+        "sh" | "bash" | "shell" | "zsh" => &SHELL,
+        "py" | "python" => &PYTHON,
+        _ => return None,
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Splits `code` into tokens. Plain text is returned with a `None` class.
+fn tokenize<'a>(code: &'a str, language: &Language) -> Vec<(Option<&'static str>, &'a str)> {
+    let mut tokens: Vec<(Option<&'static str>, &'a str)> = Vec::new();
+    let mut start = 0;
+    while let Some(c) = code[start..].chars().next() {
+        let rest = &code[start..];
+        let (class, len) = if let Some(len) = language
+            .line_comment
+            .filter(|start| rest.starts_with(start))
+            .map(|_| rest.find('\n').unwrap_or(rest.len()))
+        {
+            (Some("comment"), len)
+        } else if let Some((start, end)) = language
+            .block_comment
+            .filter(|(start, _)| rest.starts_with(start))
+        {
+            let len = rest[start.len()..]
+                .find(end)
+                .map_or(rest.len(), |index| start.len() + index + end.len());
+            (Some("comment"), len)
+        } else if language.quotes.contains(&c) {
+            let mut len = rest.len();
+            let mut escaped = false;
+            for (index, ch) in rest.char_indices().skip(1) {
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == c {
+                    len = index + 1;
+                    break;
+                } else if ch == '\n' && c != '`' {
+                    len = index;
+                    break;
+                }
+            }
+            let class = if language.string_keys && rest[len..].trim_start().starts_with(':') {
+                "property"
+            } else {
+                "string"
+            };
+            (Some(class), len)
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '.' || ch == '_'))
+                .unwrap_or(rest.len());
+            (Some("number"), len)
+        } else if is_word_char(c) {
+            let len = rest
+                .char_indices()
+                .skip(1)
+                .find(|&(_, ch)| !is_word_char(ch))
+                .map_or(rest.len(), |(index, _)| index);
+            let word = &rest[..len];
+            let class = if language.keywords.contains(&word) {
+                Some("keyword")
+            } else if language.booleans.contains(&word) {
+                Some("boolean")
+            } else if rest[len..].starts_with('(') {
+                Some("function")
+            } else {
+                None
+            };
+            (class, len)
+        } else if "{}[]();,.:".contains(c) {
+            (Some("punctuation"), 1)
+        } else if "+-*/%=<>!&|^~?".contains(c) {
+            (Some("operator"), 1)
+        } else {
+            (None, c.len_utf8())
+        };
+        match tokens.last_mut() {
+            // Merge adjacent plain text to keep the output small.
+            Some((None, last)) if class.is_none() => {
+                *last = &code[start - last.len()..start + len];
+            }
+            _ => tokens.push((class, &rest[..len])),
+        }
+        start += len;
+    }
+    tokens
+}
+
+/// Returns the language of a `<code>` element from its `language-*` class name.
+fn code_language(code: &hast::Element) -> Option<&str> {
+    code.properties
+        .iter()
+        .find_map(|(name, value)| match value {
+            hast::PropertyValue::SpaceSeparated(classes) if name == "className" => classes
+                .iter()
+                .find_map(|class| class.strip_prefix("language-")),
+            _ => None,
+        })
+}
+
+/// Replaces the text of a `<code>` element with one `<span>` per token. Returns `false` when the
+/// language isn't supported.
+fn highlight_code(code: &mut hast::Element) -> bool {
+    let Some(language) = code_language(code).and_then(language) else {
+        return false;
+    };
+    let [hast::Node::Text(text)] = &code.children[..] else {
+        return false;
+    };
+    code.children = tokenize(&text.value, language)
+        .into_iter()
+        .map(|(class, text)| {
+            let text = hast::Node::Text(hast::Text {
+                value: text.into(),
+                position: None,
+            });
+            match class {
+                Some(class) => hast::Node::Element(hast::Element {
+                    tag_name: "span".into(),
+                    properties: vec![(
+                        "className".into(),
+                        hast::PropertyValue::SpaceSeparated(vec!["token".into(), class.into()]),
+                    )],
+                    children: vec![text],
+                    position: None,
+                }),
+                None => text,
+            }
+        })
+        .collect();
+    true
+}
+
+/// Highlights the fenced code blocks (`<pre><code class="language-*">`) in supported languages.
+/// The `language-*` class name is also added to the `<pre>` element, as Prism themes style it.
+pub fn highlight_code_blocks(node: &mut hast::Node) {
+    if let hast::Node::Element(element) = node
+        && element.tag_name == "pre"
+    {
+        let mut language_class = None;
+        for child in &mut element.children {
+            if let hast::Node::Element(code) = child
+                && code.tag_name == "code"
+                && highlight_code(code)
+            {
+                language_class = code_language(code).map(|lang| format!("language-{lang}"));
+            }
+        }
+        if let Some(class) = language_class {
+            element.properties.push((
+                "className".into(),
+                hast::PropertyValue::SpaceSeparated(vec![class]),
+            ));
+        }
+        return;
+    }
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            highlight_code_blocks(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mdxjs::hast;
+
+    use super::{JAVASCRIPT, JSON, highlight_code_blocks, tokenize};
+
+    #[test]
+    fn tokenize_javascript() {
+        assert_eq!(
+            tokenize("const a = f('}') // x\n", &JAVASCRIPT),
+            vec![
+                (Some("keyword"), "const"),
+                (None, " a "),
+                (Some("operator"), "="),
+                (None, " "),
+                (Some("function"), "f"),
+                (Some("punctuation"), "("),
+                (Some("string"), "'}'"),
+                (Some("punctuation"), ")"),
+                (None, " "),
+                (Some("comment"), "// x"),
+                (None, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_json() {
+        assert_eq!(
+            tokenize("{\"a\": true}", &JSON),
+            vec![
+                (Some("punctuation"), "{"),
+                (Some("property"), "\"a\""),
+                (Some("punctuation"), ":"),
+                (None, " "),
+                (Some("boolean"), "true"),
+                (Some("punctuation"), "}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_fenced_code() {
+        let code = |lang: &str, value: &str| {
+            hast::Node::Element(hast::Element {
+                tag_name: "pre".into(),
+                properties: vec![],
+                children: vec![hast::Node::Element(hast::Element {
+                    tag_name: "code".into(),
+                    properties: vec![(
+                        "className".into(),
+                        hast::PropertyValue::SpaceSeparated(vec![format!("language-{lang}")]),
+                    )],
+                    children: vec![hast::Node::Text(hast::Text {
+                        value: value.into(),
+                        position: None,
+                    })],
+                    position: None,
+                })],
+                position: None,
+            })
+        };
+        let text = |value: &str| {
+            hast::Node::Text(hast::Text {
+                value: value.into(),
+                position: None,
+            })
+        };
+
+        let span = |class: &str, value: &str| {
+            hast::Node::Element(hast::Element {
+                tag_name: "span".into(),
+                properties: vec![(
+                    "className".into(),
+                    hast::PropertyValue::SpaceSeparated(vec!["token".into(), class.into()]),
+                )],
+                children: vec![text(value)],
+                position: None,
+            })
+        };
+        let mut node = code("js", "let x = 1;\n");
+        highlight_code_blocks(&mut node);
+        let hast::Node::Element(pre) = &node else {
+            unreachable!()
+        };
+        assert_eq!(
+            pre.properties,
+            vec![(
+                "className".into(),
+                hast::PropertyValue::SpaceSeparated(vec!["language-js".into()])
+            )]
+        );
+        let hast::Node::Element(code_element) = &pre.children[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            code_element.children,
+            vec![
+                span("keyword", "let"),
+                text(" x "),
+                span("operator", "="),
+                text(" "),
+                span("number", "1"),
+                span("punctuation", ";"),
+                text("\n"),
+            ]
+        );
+
+        let mut node = code("brainfuck", "+[-]");
+        highlight_code_blocks(&mut node);
+        assert_eq!(node, code("brainfuck", "+[-]"));
+    }
+}
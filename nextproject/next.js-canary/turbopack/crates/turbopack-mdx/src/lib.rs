@@ -2,8 +2,18 @@
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
+mod frontmatter;
+mod highlight;
+mod toc;
+
+use std::fmt::{Display, Write};
+
 use anyhow::Result;
-use mdxjs::{MdxParseOptions, Options, compile};
+use markdown::{Location, message::Message};
+use mdxjs::{
+    MdxParseOptions, Options, hast_util_to_swc, mdast_util_from_mdx, mdast_util_to_hast,
+    mdx_plugin_recma_document, mdx_plugin_recma_jsx_rewrite,
+};
 use serde::Deserialize;
 use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{ResolvedVc, ValueDefault, Vc};
@@ -19,6 +29,13 @@ use turbopack_core::{
     source_pos::SourcePos,
     source_transform::SourceTransform,
 };
+use turbopack_json::data::{DataFormat, DataParseError, value_expression};
+
+use crate::{
+    frontmatter::{find_frontmatter, strip_frontmatter},
+    highlight::highlight_code_blocks,
+    toc::{add_heading_ids, table_of_contents},
+};
 
 #[turbo_tasks::value(shared, operation)]
 #[derive(Hash, Debug, Clone, Deserialize)]
@@ -45,6 +62,13 @@ pub struct MdxTransformOptions {
     pub provider_import_source: Option<RcStr>,
     /// Determines how to parse mdx contents.
     pub mdx_type: Option<MdxParseConstructs>,
+    /// Parses YAML (`---`) or TOML (`+++`) frontmatter and exports it as `frontmatter`.
+    pub frontmatter: Option<bool>,
+    /// Exports the headings of the document as `tableOfContents`, an array of
+    /// `{ depth, value, id }` objects, and renders the headings with these ids.
+    pub table_of_contents: Option<bool>,
+    /// Highlights fenced code blocks in common languages, using the class names of Prism.
+    pub highlight_code: Option<bool>,
 }
 
 impl Default for MdxTransformOptions {
@@ -56,6 +80,9 @@ impl Default for MdxTransformOptions {
             jsx_import_source: None,
             provider_import_source: None,
             mdx_type: Some(MdxParseConstructs::Commonmark),
+            frontmatter: None,
+            table_of_contents: None,
+            highlight_code: None,
         }
     }
 }
@@ -170,9 +197,36 @@ impl MdxTransformedAsset {
             ..Default::default()
         };
 
-        let result = compile(&file.content().to_str()?, &options);
+        let extensions = MdxExtensions {
+            frontmatter: transform_options.frontmatter.unwrap_or(false),
+            table_of_contents: transform_options.table_of_contents.unwrap_or(false),
+            highlight_code: transform_options.highlight_code.unwrap_or(false),
+        };
+        let compiled = compile(&file.content().to_str()?, &options, extensions)?;
+
+        if let Some(err) = compiled.frontmatter_error {
+            let source = match err.error.position {
+                Some(position) => {
+                    // The frontmatter content starts on the second line.
+                    let position = SourcePos {
+                        line: position.line + 1,
+                        column: position.column,
+                    };
+                    IssueSource::from_line_col(self.source, position, position)
+                }
+                None => IssueSource::from_source_only(self.source),
+            };
+            MdxIssue {
+                source,
+                reason: err.to_string().into(),
+                mdx_rule_id: rcstr!("frontmatter"),
+                mdx_source: rcstr!("turbopack-mdx"),
+            }
+            .resolved_cell()
+            .emit();
+        }
 
-        match result {
+        match compiled.code {
             Ok(mdx_jsx_component) => Ok(MdxTransformResult {
                 content: AssetContent::file(
                     FileContent::Content(File::from(Rope::from(mdx_jsx_component))).cell(),
@@ -230,6 +284,119 @@ impl MdxTransformedAsset {
     }
 }
 
+/// The features that [`compile`] adds on top of `mdxjs`.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MdxExtensions {
+    /// Parses YAML (`---`) or TOML (`+++`) frontmatter and exports it as `frontmatter`.
+    pub frontmatter: bool,
+    /// Exports the headings of the document as `tableOfContents`, an array of
+    /// `{ depth, value, id }` objects, and renders the headings with these ids.
+    pub table_of_contents: bool,
+    /// Highlights fenced code blocks in common languages, using the class names of Prism.
+    pub highlight_code: bool,
+}
+
+/// A frontmatter block that couldn't be parsed. It is exported as an empty object instead.
+#[derive(Debug)]
+pub struct FrontmatterError {
+    pub format: DataFormat,
+    pub error: DataParseError,
+}
+
+impl Display for FrontmatterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unable to parse {} frontmatter: {}",
+            self.format, self.error.message
+        )
+    }
+}
+
+pub struct CompiledMdx {
+    /// The JavaScript module, or the error that prevented compiling the document.
+    pub code: Result<String, Message>,
+    pub frontmatter_error: Option<FrontmatterError>,
+}
+
+/// Turns MDX into JavaScript like [`mdxjs::compile`], with the enabled `extensions`.
+pub fn compile(value: &str, options: &Options, extensions: MdxExtensions) -> Result<CompiledMdx> {
+    let mut value = value;
+    let stripped;
+    let mut code_exports = String::new();
+    let mut frontmatter_error = None;
+
+    if extensions.frontmatter
+        && let Some(frontmatter) = find_frontmatter(value)
+    {
+        let data = match frontmatter.format.parse(frontmatter.content) {
+            Ok(serde_json::Value::Null) => serde_json::Value::Object(Default::default()),
+            Ok(data) => data,
+            Err(error) => {
+                frontmatter_error = Some(FrontmatterError {
+                    format: frontmatter.format,
+                    error,
+                });
+                serde_json::Value::Object(Default::default())
+            }
+        };
+        writeln!(
+            code_exports,
+            "export const frontmatter = {};",
+            value_expression(&data)?
+        )?;
+        stripped = strip_frontmatter(value, &frontmatter);
+        value = &stripped;
+    }
+
+    let code = match compile_mdx(value, options, extensions) {
+        Ok((mut code, table_of_contents)) => {
+            if let Some(table_of_contents) = table_of_contents {
+                writeln!(
+                    code_exports,
+                    "export const tableOfContents = {};",
+                    serde_json::to_string(&table_of_contents)?
+                )?;
+            }
+            code.push_str(&code_exports);
+            Ok(code)
+        }
+        Err(err) => Err(err),
+    };
+
+    Ok(CompiledMdx {
+        code,
+        frontmatter_error,
+    })
+}
+
+/// The steps of [`mdxjs::compile`], adding the ids of the table of contents to the headings and
+/// highlighting the code blocks.
+fn compile_mdx(
+    value: &str,
+    options: &Options,
+    extensions: MdxExtensions,
+) -> Result<(String, Option<Vec<toc::TocEntry>>), Message> {
+    let mdast = mdast_util_from_mdx(value, options)?;
+    let mut hast = mdast_util_to_hast(&mdast);
+    if extensions.highlight_code {
+        highlight_code_blocks(&mut hast);
+    }
+    let table_of_contents = extensions.table_of_contents.then(|| {
+        let entries = table_of_contents(&mdast);
+        add_heading_ids(&mut hast, &mut entries.iter().map(|entry| entry.id.clone()));
+        entries
+    });
+
+    let location = Location::new(value.as_bytes());
+    let mut explicit_jsxs = Default::default();
+    let mut program = hast_util_to_swc(&hast, options, Some(&location), &mut explicit_jsxs)?;
+    mdx_plugin_recma_document(&mut program, options, Some(&location))?;
+    mdx_plugin_recma_jsx_rewrite(&mut program, options, Some(&location), &explicit_jsxs)?;
+    Ok((program.serialize(), table_of_contents))
+}
+
 #[turbo_tasks::value]
 struct MdxTransformResult {
     content: ResolvedVc<AssetContent>,
@@ -276,3 +443,80 @@ impl Issue for MdxIssue {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mdxjs::Options;
+
+    use super::{MdxExtensions, compile};
+
+    #[test]
+    fn heading_ids_match_table_of_contents() {
+        let extensions = MdxExtensions {
+            table_of_contents: true,
+            ..Default::default()
+        };
+        let compiled = compile("# Hello\n\n## Hello\n", &Options::default(), extensions).unwrap();
+        let code = compiled.code.unwrap();
+        assert!(code.contains(r#"id: "hello""#), "{code}");
+        assert!(code.contains(r#"id: "hello-1""#), "{code}");
+        assert!(
+            code.contains(
+                r#"export const tableOfContents = [{"depth":1,"value":"Hello","id":"hello"},{"depth":2,"value":"Hello","id":"hello-1"}];"#
+            ),
+            "{code}"
+        );
+    }
+
+    #[test]
+    fn frontmatter() {
+        let extensions = MdxExtensions {
+            frontmatter: true,
+            ..Default::default()
+        };
+        let compiled = compile(
+            "---\ntitle: Hi\n---\n# Hi\n",
+            &Options::default(),
+            extensions,
+        )
+        .unwrap();
+        assert!(compiled.frontmatter_error.is_none());
+        let code = compiled.code.unwrap();
+        assert!(
+            code.contains(r#"export const frontmatter = {"title":"Hi"};"#),
+            "{code}"
+        );
+
+        let compiled = compile(
+            "---\ntitle: [\n---\n# Hi\n",
+            &Options::default(),
+            extensions,
+        )
+        .unwrap();
+        assert!(compiled.frontmatter_error.is_some());
+        let code = compiled.code.unwrap();
+        assert!(code.contains("export const frontmatter = {};"), "{code}");
+    }
+
+    #[test]
+    fn highlight_code() {
+        let extensions = MdxExtensions {
+            highlight_code: true,
+            ..Default::default()
+        };
+        let compiled = compile(
+            "```js\nconst a = 1;\n```\n\n```brainfuck\n+[-]\n```\n",
+            &Options::default(),
+            extensions,
+        )
+        .unwrap();
+        let code = compiled.code.unwrap();
+        assert!(code.contains(r#"className: "token keyword""#), "{code}");
+        assert!(code.contains(r#"children: "const""#), "{code}");
+        assert!(
+            code.contains(r#"className: "language-brainfuck""#),
+            "{code}"
+        );
+        assert!(code.contains(r#"children: "+[-]\n""#), "{code}");
+    }
+}
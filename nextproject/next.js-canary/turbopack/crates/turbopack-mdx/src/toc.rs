@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use markdown::mdast::Node;
+use mdxjs::hast;
+use serde::Serialize;
+
+/// An entry of the `tableOfContents` export.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TocEntry {
+    pub depth: u8,
+    pub value: String,
+    /// The slug of the heading, generated like `github-slugger` (and `rehype-slug`) does.
+    pub id: String,
+}
+
+/// Collects the headings of a document in document order.
+pub fn table_of_contents(root: &Node) -> Vec<TocEntry> {
+    fn visit(node: &Node, slugger: &mut Slugger, entries: &mut Vec<TocEntry>) {
+        if let Node::Heading(heading) = node {
+            let value = node.to_string();
+            entries.push(TocEntry {
+                depth: heading.depth,
+                id: slugger.slug(&value),
+                value,
+            });
+        } else if let Some(children) = node.children() {
+            for child in children {
+                visit(child, slugger, entries);
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    visit(root, &mut Slugger::default(), &mut entries);
+    entries
+}
+
+/// Adds the `ids` of the table of contents to the rendered headings, in document order. Headings
+/// that already have an id, like the label of the footnotes section, are skipped.
+pub fn add_heading_ids(node: &mut hast::Node, ids: &mut impl Iterator<Item = String>) {
+    if let hast::Node::Element(element) = node
+        && matches!(
+            element.tag_name.as_str(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+        )
+    {
+        if !element.properties.iter().any(|(name, _)| name == "id")
+            && let Some(id) = ids.next()
+        {
+            element
+                .properties
+                .push(("id".into(), hast::PropertyValue::String(id)));
+        }
+        return;
+    }
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            add_heading_ids(child, ids);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    /// Generates a unique slug for `value`. Repeated slugs get a numeric suffix.
+    fn slug(&mut self, value: &str) -> String {
+        let original = value
+            .to_lowercase()
+            .chars()
+            .filter(|&c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+            .map(|c| if c == ' ' { '-' } else { c })
+            .collect::<String>();
+        let mut slug = original.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.entry(original.clone()).or_default();
+            *count += 1;
+            slug = format!("{original}-{count}");
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Slugger;
+
+    #[test]
+    fn slugs() {
+        let mut slugger = Slugger::default();
+        assert_eq!(slugger.slug("Hello, World!"), "hello-world");
+        assert_eq!(slugger.slug("Hello World"), "hello-world-1");
+        assert_eq!(slugger.slug("hello-world-1"), "hello-world-1-1");
+        assert_eq!(slugger.slug("Über `code`_x"), "über-code_x");
+    }
+}
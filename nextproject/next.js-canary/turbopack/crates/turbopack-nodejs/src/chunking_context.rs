@@ -104,6 +104,11 @@ impl NodeJsChunkingContextBuilder {
         self
     }
 
+    pub fn module_federation(mut self, enable_module_federation: bool) -> Self {
+        self.chunking_context.enable_module_federation = enable_module_federation;
+        self
+    }
+
     pub fn runtime_type(mut self, runtime_type: RuntimeType) -> Self {
         self.chunking_context.runtime_type = runtime_type;
         self
@@ -206,6 +211,8 @@ pub struct NodeJsChunkingContext {
     enable_module_merging: bool,
     /// Enable dynamic chunk content loading.
     enable_dynamic_chunk_content_loading: bool,
+    /// Include the module federation runtime code.
+    enable_module_federation: bool,
     /// Whether to minify resulting chunks
     minify_type: MinifyType,
     /// Whether to generate source maps
@@ -258,6 +265,7 @@ impl NodeJsChunkingContext {
                 enable_nested_async_availability: false,
                 enable_module_merging: false,
                 enable_dynamic_chunk_content_loading: false,
+                enable_module_federation: false,
                 environment,
                 runtime_type,
                 minify_type: MinifyType::NoMinify,
@@ -289,7 +297,7 @@ impl NodeJsChunkingContext {
     /// Returns the minify type.
     #[turbo_tasks::function]
     pub fn minify_type(&self) -> Vc<MinifyType> {
-        self.minify_type.cell()
+        self.minify_type.clone().cell()
     }
 
     #[turbo_tasks::function]
@@ -369,9 +377,14 @@ impl ChunkingContext for NodeJsChunkingContext {
         Vc::cell(self.enable_dynamic_chunk_content_loading)
     }
 
+    #[turbo_tasks::function]
+    fn is_module_federation_enabled(&self) -> Vc<bool> {
+        Vc::cell(self.enable_module_federation)
+    }
+
     #[turbo_tasks::function]
     pub fn minify_type(&self) -> Vc<MinifyType> {
-        self.minify_type.cell()
+        self.minify_type.clone().cell()
     }
 
     #[turbo_tasks::function]
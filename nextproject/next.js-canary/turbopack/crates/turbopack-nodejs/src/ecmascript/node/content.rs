@@ -44,7 +44,7 @@ impl EcmascriptBuildNodeChunkContent {
 #[turbo_tasks::value_impl]
 impl EcmascriptBuildNodeChunkContent {
     #[turbo_tasks::function]
-    async fn code(&self) -> Result<Vc<Code>> {
+    pub(super) async fn code(&self) -> Result<Vc<Code>> {
         use std::io::Write;
         let source_maps = *self
             .chunking_context
@@ -69,8 +69,8 @@ impl EcmascriptBuildNodeChunkContent {
 
         let mut code = code.build();
 
-        if let MinifyType::Minify { mangle } = *self.chunking_context.minify_type().await? {
-            code = minify(code, source_maps, mangle)?;
+        if let MinifyType::Minify(options) = &*self.chunking_context.minify_type().await? {
+            code = minify(code, source_maps, options)?;
         }
 
         Ok(code.cell())
@@ -82,7 +82,7 @@ impl EcmascriptBuildNodeChunkContent {
             self.chunking_context.output_root().owned().await?,
             self.chunk.path().owned().await?,
             *self.content,
-            *self.chunking_context.minify_type().await?,
+            self.chunking_context.minify_type().owned().await?,
         ))
     }
 }
@@ -103,7 +103,10 @@ impl VersionedContent for EcmascriptBuildNodeChunkContent {
         Ok(AssetContent::file(
             FileContent::Content(File::from(
                 self.code()
-                    .to_rope_with_magic_comments(|| *this.source_map)
+                    .to_rope_with_magic_comments(
+                        || *this.source_map,
+                        Some(this.chunk.license_comments()),
+                    )
                     .await?,
             ))
             .cell(),
@@ -10,6 +10,7 @@ use turbopack_core::{
     chunk::ChunkingContext,
     code_builder::{Code, CodeBuilder},
     ident::AssetIdent,
+    license_comments::LicenseCommentsAsset,
     output::{OutputAsset, OutputAssetsReference, OutputAssetsWithReferenced},
     source_map::{GenerateSourceMap, SourceMapAsset},
 };
@@ -95,11 +96,16 @@ impl EcmascriptBuildNodeRuntimeChunk {
             )?;
         }
 
+        let module_federation = *this
+            .chunking_context
+            .is_module_federation_enabled()
+            .await?;
         match *this.chunking_context.runtime_type().await? {
             RuntimeType::Development => {
                 let runtime_code = turbopack_ecmascript_runtime::get_nodejs_runtime_code(
                     this.chunking_context.environment(),
                     generate_source_map,
+                    module_federation,
                 );
                 code.push_code(&*runtime_code.await?);
             }
@@ -107,6 +113,7 @@ impl EcmascriptBuildNodeRuntimeChunk {
                 let runtime_code = turbopack_ecmascript_runtime::get_nodejs_runtime_code(
                     this.chunking_context.environment(),
                     generate_source_map,
+                    module_federation,
                 );
                 code.push_code(&*runtime_code.await?);
             }
@@ -139,6 +146,16 @@ impl EcmascriptBuildNodeRuntimeChunk {
             Vc::upcast(self),
         ))
     }
+
+    #[turbo_tasks::function]
+    async fn license_comments(self: Vc<Self>) -> Result<Vc<LicenseCommentsAsset>> {
+        let this = self.await?;
+        Ok(LicenseCommentsAsset::new(
+            Vc::upcast(*this.chunking_context),
+            self.ident_for_path(),
+            self.code(),
+        ))
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -164,6 +181,18 @@ impl OutputAssetsReference for EcmascriptBuildNodeRuntimeChunk {
             references.push(ResolvedVc::upcast(self.source_map().to_resolved().await?))
         }
 
+        if this
+            .chunking_context
+            .minify_type()
+            .await?
+            .extracts_license_comments()
+            && self.code().await?.has_license_comments()
+        {
+            references.push(ResolvedVc::upcast(
+                self.license_comments().to_resolved().await?,
+            ));
+        }
+
         Ok(OutputAssetsWithReferenced::from_assets(Vc::cell(
             references,
         )))
@@ -190,7 +219,10 @@ impl Asset for EcmascriptBuildNodeRuntimeChunk {
         Ok(AssetContent::file(
             FileContent::Content(File::from(
                 self.code()
-                    .to_rope_with_magic_comments(|| self.source_map())
+                    .to_rope_with_magic_comments(
+                        || self.source_map(),
+                        Some(self.license_comments()),
+                    )
                     .await?,
             ))
             .cell(),
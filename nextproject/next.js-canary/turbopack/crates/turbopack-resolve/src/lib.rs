@@ -6,3 +6,4 @@ pub mod node_native_binding;
 pub mod resolve;
 pub mod resolve_options_context;
 pub mod typescript;
+pub mod url_imports;
@@ -5,15 +5,20 @@ use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack_core::resolve::{
     AliasMap, AliasPattern, ExternalTraced, ExternalType, FindContextFileResult, find_context_file,
+    lockfile::find_locked_versions,
     options::{
         ConditionValue, ImportMap, ImportMapping, ResolutionConditions, ResolveInPackage,
         ResolveIntoPackage, ResolveModules, ResolveOptions,
     },
+    pnp::find_pnp_manifest,
 };
 
 use crate::{
     resolve_options_context::{ResolveOptionsContext, TsConfigHandling},
-    typescript::{apply_tsconfig_resolve_options, tsconfig, tsconfig_resolve_options},
+    typescript::{
+        apply_tsconfig_resolve_options, owning_tsconfig, tsconfig, tsconfig_resolve_options,
+    },
+    url_imports::apply_url_imports_resolve_options,
 };
 
 #[turbo_tasks::function]
@@ -147,6 +152,11 @@ async fn base_resolve_options(
         ext.push(rcstr!(".json"));
         ext
     };
+    let before_resolve_plugins = opt.before_resolve_plugins.clone();
+    let locked_versions = match &opt.enable_node_modules {
+        Some(dir) if opt.check_locked_versions => *find_locked_versions(dir.clone()).await?,
+        _ => None,
+    };
     Ok(ResolveOptions {
         extensions,
         modules: if let Some(environment) = emulating {
@@ -161,6 +171,12 @@ async fn base_resolve_options(
         } else {
             let mut mods = Vec::new();
             if let Some(dir) = &opt.enable_node_modules {
+                // Projects installed with Yarn Plug'n'Play don't have a node_modules folder
+                if let Some(manifest) = &*find_pnp_manifest(dir.clone()).await? {
+                    mods.push(ResolveModules::PnP {
+                        manifest: manifest.clone(),
+                    });
+                }
                 mods.push(ResolveModules::Nested(
                     dir.clone(),
                     vec![rcstr!("node_modules")],
@@ -202,9 +218,11 @@ async fn base_resolve_options(
         import_map: Some(import_map),
         resolved_map: opt.resolved_map,
         after_resolve_plugins: opt.after_resolve_plugins.clone(),
-        before_resolve_plugins: opt.before_resolve_plugins.clone(),
+        before_resolve_plugins,
         loose_errors: opt.loose_errors,
         collect_affecting_sources: opt.collect_affecting_sources,
+        locked_versions,
+        validate_package_exports: opt.validate_package_exports,
         ..Default::default()
     }
     .cell())
@@ -238,7 +256,7 @@ pub async fn resolve_options(
             anyhow::Ok::<Vc<ResolveOptions>>(match &*tsconfig {
                 FindContextFileResult::Found(path, _) => apply_tsconfig_resolve_options(
                     resolve_options,
-                    tsconfig_resolve_options(path.clone()),
+                    tsconfig_resolve_options(owning_tsconfig(path.clone(), &resolve_path).await?),
                 ),
                 FindContextFileResult::NotFound(_) => resolve_options,
             })
@@ -255,7 +273,9 @@ pub async fn resolve_options(
                     // If the file exists, use it.
                     apply_tsconfig_resolve_options(
                         resolve_options,
-                        tsconfig_resolve_options(tsconfig_path.clone()),
+                        tsconfig_resolve_options(
+                            owning_tsconfig(tsconfig_path.clone(), &resolve_path).await?,
+                        ),
                     )
                 } else {
                     // Otherwise, try and find one.
@@ -282,6 +302,13 @@ pub async fn resolve_options(
             resolve_options.with_extended_fallback_import_map(*fallback_import_map)
         })
         .unwrap_or(resolve_options);
+    // The URL imports plugin depends on whether `resolve_path` is in the vendor directory
+    let resolve_options = options_context_value
+        .url_imports
+        .map(|url_imports| {
+            apply_url_imports_resolve_options(resolve_options, *url_imports, resolve_path.clone())
+        })
+        .unwrap_or(resolve_options);
 
     Ok(resolve_options)
 }
@@ -12,6 +12,8 @@ use turbopack_core::{
     },
 };
 
+use crate::url_imports::UrlImportsOptions;
+
 #[derive(Default, Debug, Clone, TraceRawVcs, PartialEq, Eq, NonLocalValue, Encode, Decode)]
 pub enum TsConfigHandling {
     /// Ignore tsconfig and jsconfig files
@@ -35,7 +37,8 @@ pub struct ResolveOptionsContext {
     // Enable resolving of .mjs files without the .mjs extension
     pub enable_mjs_extension: bool,
     /// Enable resolving of the node_modules folder when within the provided
-    /// directory
+    /// directory. Packages of projects installed with Yarn Plug'n'Play are
+    /// resolved with the `.pnp.cjs` or `.pnp.data.json` in that directory.
     pub enable_node_modules: Option<FileSystemPath>,
     /// A specific path to a tsconfig.json file to use for resolving modules. If `None`, one will
     /// be looked up through the filesystem
@@ -76,6 +79,15 @@ pub struct ResolveOptionsContext {
     pub loose_errors: bool,
     /// Collect affecting sources for each resolve result.  Useful for tracing.
     pub collect_affecting_sources: bool,
+    /// Warn when a package installed in node_modules doesn't match the version locked by the
+    /// `pnpm-lock.yaml`, `package-lock.json` or `yarn.lock` next to `enable_node_modules`.
+    pub check_locked_versions: bool,
+    /// Warn about invalid targets, unreachable conditions and missing files in the `exports` and
+    /// `imports` fields of package.json files.
+    pub validate_package_exports: bool,
+    /// Resolve `https:` imports to modules stored in a vendor directory, fetching them when they
+    /// aren't vendored yet.
+    pub url_imports: Option<ResolvedVc<UrlImportsOptions>>,
 
     pub placeholder_for_future_extensions: (),
 }
@@ -129,6 +141,18 @@ impl ResolveOptionsContext {
         );
         Ok(resolve_options_context.cell())
     }
+
+    /// Returns a new [Vc<ResolveOptionsContext>] which resolves `https:` imports with the given
+    /// options.
+    #[turbo_tasks::function]
+    pub async fn with_url_imports(
+        self: Vc<Self>,
+        url_imports: ResolvedVc<UrlImportsOptions>,
+    ) -> Result<Vc<Self>> {
+        let mut resolve_options_context = self.owned().await?;
+        resolve_options_context.url_imports = Some(url_imports);
+        Ok(resolve_options_context.cell())
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -4,7 +4,10 @@ use anyhow::Result;
 use serde_json::Value as JsonValue;
 use turbo_rcstr::{RcStr, rcstr};
 use turbo_tasks::{FxIndexMap, ResolvedVc, ValueDefault, Vc, fxindexset};
-use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemPath, FileSystemPathOption};
+use turbo_tasks_fs::{
+    FileContent, FileJsonContent, FileSystemEntryType, FileSystemPath, FileSystemPathOption,
+    glob::Glob,
+};
 use turbopack_core::{
     asset::Asset,
     context::AssetContext,
@@ -232,6 +235,183 @@ pub async fn read_from_tsconfigs<T>(
     Ok(None)
 }
 
+#[turbo_tasks::value(transparent)]
+struct TsConfigReferences(Vec<FileSystemPath>);
+
+/// Returns the tsconfig files of the projects referenced by the `references` of a tsconfig.json.
+/// Unlike most options, `references` isn't inherited through `extends`.
+#[turbo_tasks::function]
+async fn tsconfig_references(tsconfig: FileSystemPath) -> Result<Vc<TsConfigReferences>> {
+    let FileJsonContent::Content(json) = &*tsconfig.read().parse_json_with_comments().await? else {
+        return Ok(Vc::cell(Vec::new()));
+    };
+    let Some(references) = json["references"].as_array() else {
+        return Ok(Vc::cell(Vec::new()));
+    };
+
+    let dir = tsconfig.parent();
+    let mut tsconfigs = Vec::new();
+    for reference in references {
+        let Some(path) = reference["path"].as_str() else {
+            continue;
+        };
+        // A reference either points at the tsconfig file or at the directory containing it.
+        let mut referenced = dir.join(path)?;
+        if !path.ends_with(".json") {
+            referenced = referenced.join("tsconfig.json")?;
+        }
+        if matches!(&*referenced.get_type().await?, FileSystemEntryType::File) {
+            tsconfigs.push(referenced);
+        } else {
+            TsConfigIssue {
+                severity: IssueSeverity::Warning,
+                // TODO: this should point at the reference
+                source: IssueSource::from_source_only(ResolvedVc::upcast(
+                    FileSource::new(tsconfig.clone()).to_resolved().await?,
+                )),
+                message: format!("references: \"{path}\" doesn't point to a tsconfig.json file")
+                    .into(),
+            }
+            .resolved_cell()
+            .emit();
+        }
+    }
+    Ok(Vc::cell(tsconfigs))
+}
+
+/// Returns the tsconfig of the project owning `lookup_path`, following the `references` of
+/// composite projects. A directory is owned by the first referenced project which includes files in
+/// it, or by `tsconfig` itself when there is no such project.
+pub async fn owning_tsconfig(
+    mut tsconfig: FileSystemPath,
+    lookup_path: &FileSystemPath,
+) -> Result<FileSystemPath> {
+    let mut visited = vec![tsconfig.clone()];
+    'references: loop {
+        let references = tsconfig_references(tsconfig.clone()).await?;
+        for referenced in references.iter() {
+            if visited.contains(referenced) {
+                continue;
+            }
+            if *tsconfig_includes_directory(referenced.clone(), lookup_path.clone()).await? {
+                tsconfig = referenced.clone();
+                visited.push(tsconfig.clone());
+                continue 'references;
+            }
+        }
+        return Ok(tsconfig);
+    }
+}
+
+/// Whether the `files` or `include` of a tsconfig.json list files directly in `dir`, and `dir` isn't
+/// excluded by its `exclude`. Resolve options apply to whole directories, so only the directory part
+/// of these patterns is matched.
+#[turbo_tasks::function]
+async fn tsconfig_includes_directory(
+    tsconfig: FileSystemPath,
+    dir: FileSystemPath,
+) -> Result<Vc<bool>> {
+    let configs = read_tsconfigs(
+        tsconfig.read(),
+        ResolvedVc::upcast(FileSource::new(tsconfig.clone()).to_resolved().await?),
+        node_cjs_resolve_options(tsconfig.root().owned().await?),
+    )
+    .await?;
+
+    // Like the other options, `files`, `include` and `exclude` are relative to the tsconfig
+    // declaring them.
+    let read_patterns = async |key: &'static str| {
+        let Some((patterns, source)) = read_from_tsconfigs(&configs, |json, source| {
+            json[key].as_array().map(|patterns| {
+                let patterns = patterns
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str().map(str::to_string))
+                    .collect::<Vec<_>>();
+                (patterns, source)
+            })
+        })
+        .await?
+        else {
+            return anyhow::Ok(None);
+        };
+        let base = source.ident().path().await?.parent();
+        Ok(Some(
+            patterns
+                .iter()
+                .filter_map(|pattern| base.try_join(pattern))
+                .collect::<Vec<_>>(),
+        ))
+    };
+    let files = read_patterns("files").await?;
+    let include = match read_patterns("include").await? {
+        Some(include) => include,
+        // Without `files` and `include`, all files in the directory of the tsconfig are included.
+        None if files.is_none() => vec![tsconfig.parent().join("**/*")?],
+        None => Vec::new(),
+    };
+    let exclude = read_patterns("exclude").await?.unwrap_or_default();
+
+    if files.iter().flatten().any(|file| file.parent() == dir) {
+        return Ok(Vc::cell(true));
+    }
+
+    let matches = |patterns: Vec<String>| -> Result<bool> {
+        if patterns.is_empty() {
+            return Ok(false);
+        }
+        let glob = Glob::parse(
+            format!("{{{}}}", patterns.join(",")).into(),
+            Default::default(),
+        )?;
+        Ok(glob.matches(&dir.path))
+    };
+    let included = matches(include.iter().flat_map(included_directories).collect())?;
+    let excluded = matches(exclude.iter().flat_map(excluded_directories).collect())?;
+    Ok(Vc::cell(included && !excluded))
+}
+
+/// Splits an `include` or `exclude` pattern into its directory and its last segment. Patterns
+/// without a wildcard or an extension in their last segment match directories, i.e. all files in
+/// them, in which case there is no last segment.
+fn split_pattern(pattern: &FileSystemPath) -> (&str, Option<&str>) {
+    let path = pattern.path.as_str();
+    let (parent, last) = path.rsplit_once('/').unwrap_or(("", path));
+    if last.contains(['*', '?', '.']) {
+        (parent, Some(last))
+    } else {
+        (path, None)
+    }
+}
+
+/// Glob patterns for the directories containing files matched by an `include` pattern.
+fn included_directories(pattern: &FileSystemPath) -> Vec<String> {
+    match split_pattern(pattern) {
+        (dir, Some(_)) => match dir.strip_suffix("/**") {
+            Some(dir) => directory_and_subdirectories(dir),
+            None if dir == "**" => directory_and_subdirectories(""),
+            None => vec![dir.to_string()],
+        },
+        (dir, None) => directory_and_subdirectories(dir),
+    }
+}
+
+/// Glob patterns for the directories excluded by an `exclude` pattern. Patterns matching files
+/// don't exclude whole directories.
+fn excluded_directories(pattern: &FileSystemPath) -> Vec<String> {
+    match split_pattern(pattern) {
+        (_, Some(_)) => Vec::new(),
+        (dir, None) => directory_and_subdirectories(dir),
+    }
+}
+
+fn directory_and_subdirectories(dir: &str) -> Vec<String> {
+    if dir.is_empty() {
+        vec!["**".to_string()]
+    } else {
+        vec![dir.to_string(), format!("{dir}/**")]
+    }
+}
+
 /// Resolve options specific to tsconfig.json.
 #[turbo_tasks::value]
 #[derive(Default)]
@@ -239,6 +419,8 @@ pub struct TsConfigResolveOptions {
     base_url: Option<FileSystemPath>,
     import_map: Option<ResolvedVc<ImportMap>>,
     is_module_resolution_nodenext: bool,
+    root_dirs: Vec<FileSystemPath>,
+    custom_conditions: Vec<RcStr>,
 }
 
 #[turbo_tasks::value_impl]
@@ -362,10 +544,43 @@ pub async fn tsconfig_resolve_options(
     .await?
     .unwrap_or_default();
 
+    // `rootDirs` are relative to the tsconfig declaring them.
+    let root_dirs = if let Some((root_dirs, source)) =
+        read_from_tsconfigs(&configs, |json, source| {
+            json["compilerOptions"]["rootDirs"]
+                .as_array()
+                .map(|root_dirs| (root_dirs.clone(), source))
+        })
+        .await?
+    {
+        let dir = source.ident().path().await?.parent();
+        root_dirs
+            .iter()
+            .filter_map(|root_dir| dir.try_join(root_dir.as_str()?))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let custom_conditions = read_from_tsconfigs(&configs, |json, _| {
+        json["compilerOptions"]["customConditions"]
+            .as_array()
+            .map(|conditions| {
+                conditions
+                    .iter()
+                    .filter_map(|condition| condition.as_str().map(RcStr::from))
+                    .collect::<Vec<_>>()
+            })
+    })
+    .await?
+    .unwrap_or_default();
+
     Ok(TsConfigResolveOptions {
         base_url,
         import_map,
         is_module_resolution_nodenext,
+        root_dirs,
+        custom_conditions,
     }
     .cell())
 }
@@ -406,6 +621,16 @@ pub async fn apply_tsconfig_resolve_options(
     }
     resolve_options.enable_typescript_with_output_extension =
         tsconfig_resolve_options.is_module_resolution_nodenext;
+    if !tsconfig_resolve_options.root_dirs.is_empty() {
+        resolve_options.root_dirs = tsconfig_resolve_options.root_dirs.clone();
+    }
+    if !tsconfig_resolve_options.custom_conditions.is_empty() {
+        for conditions in get_condition_maps(&mut resolve_options) {
+            for condition in &tsconfig_resolve_options.custom_conditions {
+                conditions.insert(condition.clone(), ConditionValue::Set);
+            }
+        }
+    }
 
     Ok(resolve_options.cell())
 }
@@ -0,0 +1,626 @@
+//! Resolving `https:` imports, like Deno does. Fetched modules are stored in a vendor directory
+//! and their integrity is recorded in a lockfile, so that builds are reproducible and work offline
+//! once all imports are vendored.
+//!
+//! The vendor directory mirrors the URLs: `https://esm.sh/v135/react@18/es2022/react.mjs` is stored
+//! as `https/esm.sh/v135/react@18/es2022/react-<hash>.mjs`. Relative and server-relative imports in
+//! vendored modules are resolved against the URL of the importing module.
+
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{
+    NonLocalValue, ResolvedVc, TryJoinIterExt, ValueToString, Vc, trace::TraceRawVcs,
+};
+use turbo_tasks_fetch::FetchClientConfig;
+use turbo_tasks_fs::{File, FileContent, FileSystemPath, rope::Rope};
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::SubresourceIntegrityAlgorithm,
+    ident::AssetIdent,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module_graph::ModuleGraph,
+    output::{OutputAsset, OutputAssets, OutputAssetsReference},
+    reference_type::ReferenceType,
+    resolve::{
+        RequestKey, ResolveResult, ResolveResultItem, ResolveResultOption,
+        options::ResolveOptions,
+        parse::Request,
+        pattern::Pattern,
+        plugin::{BeforeResolvePlugin, BeforeResolvePluginCondition},
+    },
+    source::Source,
+};
+use url::Url;
+
+/// Where URL imports are vendored and locked.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct UrlImportsOptions {
+    /// The directory fetched modules are stored in.
+    pub vendor_dir: FileSystemPath,
+    /// The lockfile recording the integrity of each imported URL.
+    pub lockfile: FileSystemPath,
+    /// Fail on URLs which aren't vendored or locked instead of fetching them.
+    pub frozen: bool,
+}
+
+/// Resolves `https:` imports to modules in the vendor directory, fetching them when they aren't
+/// vendored yet.
+#[turbo_tasks::value]
+pub struct UrlImportsResolvePlugin {
+    options: ResolvedVc<UrlImportsOptions>,
+    /// Whether the plugin resolves requests from the vendor directory, where relative imports
+    /// are resolved against the URL of the importing module.
+    in_vendor_dir: bool,
+}
+
+#[turbo_tasks::value_impl]
+impl UrlImportsResolvePlugin {
+    /// Creates the plugin for resolving requests from `resolve_path`.
+    #[turbo_tasks::function]
+    pub async fn new(
+        options: ResolvedVc<UrlImportsOptions>,
+        resolve_path: FileSystemPath,
+    ) -> Result<Vc<Self>> {
+        let in_vendor_dir = resolve_path.is_inside_or_equal_ref(&options.await?.vendor_dir);
+        Ok(UrlImportsResolvePlugin {
+            options,
+            in_vendor_dir,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl BeforeResolvePlugin for UrlImportsResolvePlugin {
+    #[turbo_tasks::function]
+    fn before_resolve_condition(&self) -> Vc<BeforeResolvePluginCondition> {
+        if self.in_vendor_dir {
+            BeforeResolvePluginCondition::Always.cell()
+        } else {
+            BeforeResolvePluginCondition::Protocols(
+                [rcstr!("https:"), rcstr!("http:")].into_iter().collect(),
+            )
+            .cell()
+        }
+    }
+
+    #[turbo_tasks::function]
+    async fn before_resolve(
+        &self,
+        lookup_path: FileSystemPath,
+        _reference_type: ReferenceType,
+        request: Vc<Request>,
+    ) -> Result<Vc<ResolveResultOption>> {
+        let options = self.options.await?;
+        let url: RcStr = match &*request.await? {
+            Request::Uri {
+                protocol,
+                remainder,
+                ..
+            } if protocol == "https:" || protocol == "http:" => {
+                format!("{protocol}{remainder}").into()
+            }
+            Request::Relative {
+                path: Pattern::Constant(path),
+                query,
+                ..
+            }
+            | Request::ServerRelative {
+                path: Pattern::Constant(path),
+                query,
+                ..
+            } if self.in_vendor_dir => {
+                let Some(base) = options
+                    .vendor_dir
+                    .get_path_to(&lookup_path)
+                    .and_then(vendored_directory_url)
+                else {
+                    return Ok(ResolveResultOption::none());
+                };
+                let Ok(url) = base.join(&format!("{path}{query}")) else {
+                    return Ok(ResolveResultOption::none());
+                };
+                url.as_str().into()
+            }
+            _ => return Ok(ResolveResultOption::none()),
+        };
+
+        Ok(ResolveResultOption::some(
+            resolve_url_import(*self.options, url).to_resolved().await?,
+        ))
+    }
+}
+
+/// Adds the [UrlImportsResolvePlugin] for resolving requests from `resolve_path`.
+#[turbo_tasks::function]
+pub async fn apply_url_imports_resolve_options(
+    resolve_options: Vc<ResolveOptions>,
+    url_imports: ResolvedVc<UrlImportsOptions>,
+    resolve_path: FileSystemPath,
+) -> Result<Vc<ResolveOptions>> {
+    let mut resolve_options = resolve_options.owned().await?;
+    resolve_options
+        .before_resolve_plugins
+        .push(ResolvedVc::upcast(
+            UrlImportsResolvePlugin::new(*url_imports, resolve_path)
+                .to_resolved()
+                .await?,
+        ));
+    Ok(resolve_options.cell())
+}
+
+/// Returns the URL of a directory in the vendor directory, e.g. `https://esm.sh/v135/` for
+/// `https/esm.sh/v135`.
+fn vendored_directory_url(path: &str) -> Option<Url> {
+    let mut segments = path.split('/');
+    let scheme = segments.next()?;
+    let mut url = format!("{scheme}://{}/", decode_segment(segments.next()?));
+    for segment in segments {
+        url.push_str(&decode_segment(segment));
+        url.push('/');
+    }
+    Url::parse(&url).ok()
+}
+
+/// Escapes the characters which are valid in URLs, but not in file names on Windows. The path
+/// segments of a URL are percent-encoded already, so `%` doesn't need to be escaped.
+fn encode_segment(segment: &str) -> String {
+    segment
+        .replace(':', "%3A")
+        .replace('*', "%2A")
+        .replace('|', "%7C")
+}
+
+fn decode_segment(segment: &str) -> String {
+    segment
+        .replace("%3A", ":")
+        .replace("%2A", "*")
+        .replace("%7C", "|")
+}
+
+/// Returns the path of the vendored module for `url`, relative to the vendor directory.
+fn vendored_path(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    let host = match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    let mut path = format!("{}/{}", url.scheme(), encode_segment(&host));
+    let mut segments = url.path_segments()?.collect::<Vec<_>>();
+    let file_name = segments.pop().unwrap_or_default();
+    for segment in segments {
+        path.push('/');
+        path.push_str(&encode_segment(segment));
+    }
+
+    // The URL of a module doesn't need to have an extension, and the query can make the same path
+    // return different modules. The hash keeps the file names unique, and also avoids conflicts
+    // with directories of the same name.
+    let file_name = Path::new(file_name);
+    let stem = file_name
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '@' | '-'))
+        .collect::<String>();
+    let extension = file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("js");
+    let hash = encode_hex(hash_xxh3_hash64(url.as_str()));
+    path.push('/');
+    if stem.is_empty() {
+        path.push_str(&format!("{hash}.{extension}"));
+    } else {
+        path.push_str(&format!("{stem}-{hash}.{extension}"));
+    }
+    Some(path)
+}
+
+/// Browsers only allow `http:` for loopback addresses, e.g. a local development server.
+fn is_secure(url: &Url) -> bool {
+    match url.scheme() {
+        "https" => true,
+        "http" => match url.host() {
+            Some(url::Host::Domain(domain)) => domain == "localhost",
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn integrity(content: &Rope) -> RcStr {
+    SubresourceIntegrityAlgorithm::Sha256
+        .integrity(content)
+        .into()
+}
+
+#[turbo_tasks::function]
+async fn resolve_url_import(
+    options: Vc<UrlImportsOptions>,
+    url: RcStr,
+) -> Result<Vc<ResolveResult>> {
+    let options = options.await?;
+    let emit = |problem: UrlImportProblem| -> Result<Vc<ResolveResult>> {
+        UrlImportIssue {
+            url: url.clone(),
+            lockfile: options.lockfile.clone(),
+            problem,
+        }
+        .resolved_cell()
+        .emit();
+        Ok(*ResolveResult::unresolvable())
+    };
+
+    let Some((parsed, path)) = Url::parse(&url)
+        .ok()
+        .and_then(|parsed| vendored_path(&parsed).map(|path| (parsed, path)))
+    else {
+        return emit(UrlImportProblem::InvalidUrl);
+    };
+    if !is_secure(&parsed) {
+        return emit(UrlImportProblem::Insecure);
+    }
+    let path = options.vendor_dir.join(&path)?;
+    let locked = read_url_imports_lockfile(options.lockfile.clone())
+        .await?
+        .get(&url)
+        .cloned();
+
+    let mut fetched = false;
+    let content = match &*path.read().await? {
+        FileContent::Content(file) => {
+            let integrity = integrity(file.content());
+            match locked {
+                Some(locked) if locked != integrity => {
+                    return emit(UrlImportProblem::IntegrityMismatch {
+                        expected: locked,
+                        actual: integrity,
+                    });
+                }
+                None if options.frozen => return emit(UrlImportProblem::NotLocked),
+                _ => {}
+            }
+            path.read().to_resolved().await?
+        }
+        FileContent::NotFound if options.frozen => return emit(UrlImportProblem::NotVendored),
+        FileContent::NotFound => {
+            let response = match &*FetchClientConfig::default()
+                .cell()
+                .fetch(url.clone(), None)
+                .await?
+            {
+                Ok(response) => response.await?,
+                Err(err) => {
+                    err.to_issue(IssueSeverity::Error, options.lockfile.clone())
+                        .to_resolved()
+                        .await?
+                        .emit();
+                    return Ok(*ResolveResult::unresolvable());
+                }
+            };
+            let body = Rope::from(response.body.await?.0.clone());
+            let integrity = integrity(&body);
+            if let Some(locked) = locked
+                && locked != integrity
+            {
+                return emit(UrlImportProblem::IntegrityMismatch {
+                    expected: locked,
+                    actual: integrity,
+                });
+            }
+            fetched = true;
+            FileContent::Content(File::from(body)).resolved_cell()
+        }
+    };
+
+    Ok(*ResolveResult::primary_with_key(
+        RequestKey::new(url.clone()),
+        ResolveResultItem::Source(ResolvedVc::upcast(
+            UrlImportSource {
+                url,
+                path,
+                content,
+                fetched,
+            }
+            .resolved_cell(),
+        )),
+    ))
+}
+
+/// A module imported by URL, stored in the vendor directory.
+#[turbo_tasks::value]
+pub struct UrlImportSource {
+    url: RcStr,
+    path: FileSystemPath,
+    /// The content is not read from `path`, as a fetched module is only written to the vendor
+    /// directory when the build emits its output, see [vendor_url_imports].
+    content: ResolvedVc<FileContent>,
+    /// Whether the module was fetched because it isn't vendored yet.
+    fetched: bool,
+}
+
+#[turbo_tasks::value_impl]
+impl Source for UrlImportSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(self.path.clone())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for UrlImportSource {
+    #[turbo_tasks::function]
+    fn content(&self) -> Result<Vc<AssetContent>> {
+        AssetContent::file(self.content)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct UrlImportsLockfileJson {
+    version: u32,
+    /// The integrity of each URL, e.g. `sha256-<base64 digest>`.
+    integrity: BTreeMap<RcStr, RcStr>,
+}
+
+const LOCKFILE_VERSION: u32 = 1;
+
+#[turbo_tasks::value(transparent)]
+pub struct UrlImportsLockfile(BTreeMap<RcStr, RcStr>);
+
+/// Reads the integrity of each URL recorded in the lockfile. A missing lockfile is empty.
+#[turbo_tasks::function]
+pub async fn read_url_imports_lockfile(lockfile: FileSystemPath) -> Result<Vc<UrlImportsLockfile>> {
+    let FileContent::Content(file) = &*lockfile.read().await? else {
+        return Ok(Vc::cell(BTreeMap::new()));
+    };
+    let json: UrlImportsLockfileJson = serde_json::from_str(&file.content().to_str()?)
+        .with_context(|| format!("Unable to parse {}", lockfile.path))?;
+    Ok(Vc::cell(json.integrity))
+}
+
+/// A fetched module, which is written to its path in the vendor directory like an output asset.
+#[turbo_tasks::value]
+struct VendoredUrlImport {
+    source: ResolvedVc<UrlImportSource>,
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for VendoredUrlImport {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!("vendored {}", self.source.await?.url).into(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAssetsReference for VendoredUrlImport {}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for VendoredUrlImport {
+    #[turbo_tasks::function]
+    async fn path(&self) -> Result<Vc<FileSystemPath>> {
+        Ok(self.source.await?.path.clone().cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for VendoredUrlImport {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        AssetContent::file(self.source.await?.content)
+    }
+}
+
+async fn url_import_sources(graph: Vc<ModuleGraph>) -> Result<Vec<ResolvedVc<UrlImportSource>>> {
+    let graph = graph.await?;
+    Ok(graph
+        .iter_nodes()
+        .map(async |module| {
+            Ok(
+                (*module.source().await?)
+                    .and_then(ResolvedVc::try_downcast_type::<UrlImportSource>),
+            )
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Returns the modules of the module graph which were fetched because they aren't vendored yet.
+/// Emitting these output assets stores the modules in the vendor directory, so that resolving
+/// itself has no side effects.
+#[turbo_tasks::function]
+pub async fn vendor_url_imports(graph: Vc<ModuleGraph>) -> Result<Vc<OutputAssets>> {
+    let mut assets = Vec::new();
+    for source in url_import_sources(graph).await? {
+        if source.await?.fetched {
+            assets.push(ResolvedVc::upcast(
+                VendoredUrlImport { source }.resolved_cell(),
+            ));
+        }
+    }
+    Ok(Vc::cell(assets))
+}
+
+/// Adds the integrity of every URL imported by a module of the module graph to the lockfile.
+/// Entries for URLs which are no longer imported are kept, so that building a subset of the
+/// entries doesn't drop them.
+#[turbo_tasks::function]
+pub async fn update_url_imports_lockfile(
+    options: Vc<UrlImportsOptions>,
+    graph: Vc<ModuleGraph>,
+) -> Result<Vc<()>> {
+    let options = options.await?;
+    let sources = url_import_sources(graph).await?;
+
+    let locked = read_url_imports_lockfile(options.lockfile.clone()).await?;
+    let mut integrity = (*locked).clone();
+    for source in sources {
+        let source = source.await?;
+        let FileContent::Content(file) = &*source.content.await? else {
+            continue;
+        };
+        integrity.insert(source.url.clone(), self::integrity(file.content()));
+    }
+    if integrity == *locked {
+        return Ok(Vc::cell(()));
+    }
+
+    let json = UrlImportsLockfileJson {
+        version: LOCKFILE_VERSION,
+        integrity,
+    };
+    let mut content = serde_json::to_string_pretty(&json)?;
+    content.push('\n');
+    options
+        .lockfile
+        .write(FileContent::Content(File::from(content)).cell())
+        .await?;
+    Ok(Vc::cell(()))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+enum UrlImportProblem {
+    InvalidUrl,
+    Insecure,
+    NotVendored,
+    NotLocked,
+    IntegrityMismatch { expected: RcStr, actual: RcStr },
+}
+
+#[turbo_tasks::value(shared)]
+struct UrlImportIssue {
+    url: RcStr,
+    lockfile: FileSystemPath,
+    problem: UrlImportProblem,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for UrlImportIssue {
+    fn severity(&self) -> IssueSeverity {
+        IssueSeverity::Error
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Resolve.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.lockfile.clone().cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        let title = match &self.problem {
+            UrlImportProblem::InvalidUrl => rcstr!("Invalid URL import "),
+            UrlImportProblem::Insecure => rcstr!("Insecure URL import "),
+            UrlImportProblem::NotVendored => rcstr!("URL import is not vendored: "),
+            UrlImportProblem::NotLocked => rcstr!("URL import is not locked: "),
+            UrlImportProblem::IntegrityMismatch { .. } => {
+                rcstr!("URL import doesn't match the lockfile: ")
+            }
+        };
+        StyledString::Line(vec![
+            StyledString::Text(title),
+            StyledString::Code(self.url.clone()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let description = match &self.problem {
+            UrlImportProblem::InvalidUrl => StyledString::Text(rcstr!(
+                "The URL can't be mapped to a file in the vendor directory."
+            )),
+            UrlImportProblem::Insecure => StyledString::Text(rcstr!(
+                "Modules can only be imported over https:, or over http: from localhost."
+            )),
+            UrlImportProblem::NotVendored => StyledString::Text(rcstr!(
+                "The module isn't stored in the vendor directory, and modules can't be fetched in \
+                 frozen mode. Build without --frozen to fetch it."
+            )),
+            UrlImportProblem::NotLocked => StyledString::Text(rcstr!(
+                "The lockfile doesn't contain the integrity of the module, and can't be updated \
+                 in frozen mode. Build without --frozen to update it."
+            )),
+            UrlImportProblem::IntegrityMismatch { expected, actual } => StyledString::Stack(vec![
+                StyledString::Line(vec![
+                    StyledString::Text(rcstr!("Expected: ")),
+                    StyledString::Code(expected.clone()),
+                ]),
+                StyledString::Line(vec![
+                    StyledString::Text(rcstr!("Actual: ")),
+                    StyledString::Code(actual.clone()),
+                ]),
+                StyledString::Text(rcstr!(
+                    "The module changed since it was locked. Remove the entry from the lockfile \
+                     and the module from the vendor directory if the change is expected."
+                )),
+            ]),
+        };
+        Vc::cell(Some(description.resolved_cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendored_path_round_trip() {
+        let url = Url::parse("https://esm.sh/v135/react@18.2.0/es2022/react.mjs").unwrap();
+        let path = vendored_path(&url).unwrap();
+        let (directory, file_name) = path.rsplit_once('/').unwrap();
+        assert_eq!(directory, "https/esm.sh/v135/react@18.2.0/es2022");
+        assert!(file_name.starts_with("react-") && file_name.ends_with(".mjs"));
+        assert_eq!(
+            vendored_directory_url(directory).unwrap().as_str(),
+            "https://esm.sh/v135/react@18.2.0/es2022/"
+        );
+    }
+
+    #[test]
+    fn vendored_path_with_port_and_query() {
+        let url = Url::parse("http://127.0.0.1:8080/pkg?target=es2022").unwrap();
+        let path = vendored_path(&url).unwrap();
+        let (directory, file_name) = path.rsplit_once('/').unwrap();
+        assert_eq!(directory, "http/127.0.0.1%3A8080");
+        assert!(file_name.starts_with("pkg-") && file_name.ends_with(".js"));
+        assert_ne!(
+            path,
+            vendored_path(&Url::parse("http://127.0.0.1:8080/pkg").unwrap()).unwrap()
+        );
+        assert_eq!(
+            vendored_directory_url(directory).unwrap().as_str(),
+            "http://127.0.0.1:8080/"
+        );
+    }
+
+    #[test]
+    fn insecure_urls() {
+        assert!(is_secure(&Url::parse("https://esm.sh/react").unwrap()));
+        assert!(is_secure(
+            &Url::parse("http://localhost:3000/a.js").unwrap()
+        ));
+        assert!(is_secure(&Url::parse("http://127.0.0.1/a.js").unwrap()));
+        assert!(!is_secure(&Url::parse("http://esm.sh/react").unwrap()));
+    }
+}
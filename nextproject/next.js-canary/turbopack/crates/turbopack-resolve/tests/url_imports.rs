@@ -0,0 +1,189 @@
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+#![cfg(test)]
+
+use anyhow::Result;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::Vc;
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+use turbo_tasks_testing::{Registration, register, run_once};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    reference_type::ReferenceType,
+    resolve::{ResolveResultOption, parse::Request, plugin::BeforeResolvePlugin},
+};
+use turbopack_resolve::url_imports::{UrlImportsOptions, UrlImportsResolvePlugin};
+
+static REGISTRATION: Registration = register!();
+
+fn plugin(
+    root: &FileSystemPath,
+    resolve_path: FileSystemPath,
+    frozen: bool,
+) -> Result<Vc<Box<dyn BeforeResolvePlugin>>> {
+    let options = UrlImportsOptions {
+        vendor_dir: root.join("vendor")?,
+        lockfile: root.join("url-imports.lock")?,
+        frozen,
+    }
+    .cell();
+    Ok(Vc::upcast(UrlImportsResolvePlugin::new(
+        options,
+        resolve_path,
+    )))
+}
+
+/// Returns the content of the module the request resolves to, or `None` if it's unresolvable.
+async fn resolve(
+    plugin: Vc<Box<dyn BeforeResolvePlugin>>,
+    lookup_path: FileSystemPath,
+    request: &str,
+) -> Result<Option<String>> {
+    let request = Request::parse_string(RcStr::from(request));
+    assert!(
+        *plugin.before_resolve_condition().matches(request).await?,
+        "the plugin should run for the request"
+    );
+    let result: Vc<ResolveResultOption> =
+        plugin.before_resolve(lookup_path, ReferenceType::Undefined, request);
+    let result = (*result.await?).expect("the plugin should handle the request");
+    let Some(source) = *result.first_source().await? else {
+        return Ok(None);
+    };
+    let AssetContent::File(content) = &*source.content().await? else {
+        panic!("expected a file");
+    };
+    let FileContent::Content(file) = &*content.await? else {
+        panic!("expected the file to exist");
+    };
+    Ok(Some(file.content().to_str()?.into_owned()))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fetches_url_imports() {
+    run_once(&REGISTRATION, || async {
+        let mut server = mockito::Server::new_async().await;
+        let module_mock = server
+            .mock("GET", "/pkg/mod.js")
+            .with_body("export { dep } from './dep.js';")
+            .create_async()
+            .await;
+        let dep_mock = server
+            .mock("GET", "/pkg/dep.js")
+            .with_body("export const dep = 1;")
+            .create_async()
+            .await;
+
+        let scratch = tempfile::tempdir()?;
+        let root = DiskFileSystem::new(rcstr!("temp"), scratch.path().to_str().unwrap().into())
+            .root()
+            .owned()
+            .await?;
+        let module = resolve(
+            plugin(&root, root.clone(), false)?,
+            root.clone(),
+            &format!("{}/pkg/mod.js", server.url()),
+        )
+        .await?;
+        assert_eq!(module.as_deref(), Some("export { dep } from './dep.js';"));
+        module_mock.assert_async().await;
+        // Fetched modules are written by the build when it emits its output, not when resolving
+        assert!(!scratch.path().join("vendor").exists());
+
+        // Relative imports in a vendored module are resolved against its URL
+        let host = server.host_with_port().replace(':', "%3A");
+        let vendored_dir = root.join(&format!("vendor/http/{host}/pkg"))?;
+        let dep = resolve(
+            plugin(&root, vendored_dir.clone(), false)?,
+            vendored_dir,
+            "./dep.js",
+        )
+        .await?;
+        assert_eq!(dep.as_deref(), Some("export const dep = 1;"));
+        dep_mock.assert_async().await;
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn frozen_fails_on_cache_miss() {
+    run_once(&REGISTRATION, || async {
+        let mut server = mockito::Server::new_async().await;
+        let module_mock = server
+            .mock("GET", "/mod.js")
+            .with_body("export default 1;")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let scratch = tempfile::tempdir()?;
+        let root = DiskFileSystem::new(rcstr!("temp"), scratch.path().to_str().unwrap().into())
+            .root()
+            .owned()
+            .await?;
+
+        let module = resolve(
+            plugin(&root, root.clone(), true)?,
+            root.clone(),
+            &format!("{}/mod.js", server.url()),
+        )
+        .await?;
+        assert_eq!(module, None);
+        module_mock.assert_async().await;
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rejects_insecure_url_imports() {
+    run_once(&REGISTRATION, || async {
+        let scratch = tempfile::tempdir()?;
+        let root = DiskFileSystem::new(rcstr!("temp"), scratch.path().to_str().unwrap().into())
+            .root()
+            .owned()
+            .await?;
+
+        let module = resolve(
+            plugin(&root, root.clone(), false)?,
+            root.clone(),
+            "http://example.com/mod.js",
+        )
+        .await?;
+        assert_eq!(module, None);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn only_runs_for_url_requests_outside_the_vendor_dir() {
+    run_once(&REGISTRATION, || async {
+        let scratch = tempfile::tempdir()?;
+        let root = DiskFileSystem::new(rcstr!("temp"), scratch.path().to_str().unwrap().into())
+            .root()
+            .owned()
+            .await?;
+        let matches = async |resolve_path: FileSystemPath, request: &str| -> Result<bool> {
+            let condition = plugin(&root, resolve_path, false)?.before_resolve_condition();
+            Ok(*condition
+                .matches(Request::parse_string(RcStr::from(request)))
+                .await?)
+        };
+
+        assert!(matches(root.clone(), "https://example.com/mod.js").await?);
+        assert!(!matches(root.clone(), "./mod.js").await?);
+        assert!(!matches(root.clone(), "react").await?);
+        assert!(matches(root.join("vendor/https/example.com")?, "./mod.js").await?);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
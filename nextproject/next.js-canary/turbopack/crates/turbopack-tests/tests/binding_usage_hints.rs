@@ -0,0 +1,171 @@
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+#![cfg(test)]
+
+mod util;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use dunce::canonicalize;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{ResolvedVc, TurboTasks, Vc};
+use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+use turbo_unix_path::sys_to_unix;
+use turbopack::{
+    ModuleAssetContext,
+    module_options::{EcmascriptOptionsContext, ModuleOptionsContext},
+};
+use turbopack_core::{
+    chunk::EvaluatableAsset,
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+    file_source::FileSource,
+    ident::Layer,
+    issue::{CollectibleIssuesExt, IssueFilter, IssueSeverity, StyledString},
+    module_graph::{
+        ModuleGraph, SingleModuleGraph, binding_usage_info::compute_binding_usage_info,
+        chunk_group_info::ChunkGroupEntry,
+    },
+    reference_type::{EntryReferenceSubType, ReferenceType},
+};
+use turbopack_ecmascript::binding_usage_hints::emit_binding_usage_hints;
+use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+
+use crate::util::REPO_ROOT;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn emits_binding_usage_hints() -> Result<()> {
+    let fixture =
+        canonicalize(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/binding_usage_hints"))?;
+    let relative_path = fixture.strip_prefix(&*REPO_ROOT)?;
+    let relative_path = RcStr::from(sys_to_unix(relative_path.to_str().unwrap()));
+
+    let tt = TurboTasks::new(TurboTasksBackend::new(
+        BackendOptions::default(),
+        noop_backing_storage(),
+    ));
+    let mut hints = tt
+        .run_once(async move {
+            let op = emit_hints_operation(relative_path);
+            op.read_strongly_consistent().await?;
+            let issues = op
+                .peek_issues()
+                .get_plain_issues(IssueFilter::everything())
+                .await?;
+            Ok(issues
+                .iter()
+                .filter(|issue| issue.severity == IssueSeverity::Hint)
+                .map(|issue| {
+                    let file_name = issue
+                        .file_path
+                        .split_once("/input/")
+                        .map_or(issue.file_path.as_str(), |(_, file_name)| file_name)
+                        .to_string();
+                    let StyledString::Text(title) = &issue.title else {
+                        panic!("unexpected title {:?}", issue.title);
+                    };
+                    let Some(StyledString::Text(description)) = &issue.description else {
+                        panic!("unexpected description {:?}", issue.description);
+                    };
+                    (file_name, title.to_string(), description.to_string())
+                })
+                .collect::<Vec<_>>())
+        })
+        .await?;
+    hints.sort();
+
+    const EVALUATED_ONLY: &str = "Module is imported, but none of its exports are used";
+    let titles = hints
+        .iter()
+        .map(|(file_name, title, _)| (file_name.as_str(), title.as_str()))
+        .collect::<Vec<_>>();
+    // Modules in `node_modules` are third party, their unused exports (`y` of `pkg`) and
+    // evaluation-only imports (`pkg/evaluated.js`) aren't reported.
+    assert_eq!(
+        titles,
+        vec![
+            ("b.js", EVALUATED_ONLY),
+            ("barrel.js", "Barrel file forces 5 modules to be evaluated"),
+            ("c.js", EVALUATED_ONLY),
+            ("d.js", EVALUATED_ONLY),
+            ("e.js", EVALUATED_ONLY),
+            ("evaluated.js", EVALUATED_ONLY),
+            ("f.js", EVALUATED_ONLY),
+            ("lib.js", "Unused exports alsoUnused, unused"),
+        ]
+    );
+
+    let (_, _, barrel_description) = hints
+        .iter()
+        .find(|(file_name, ..)| file_name == "barrel.js")
+        .context("expected a barrel file hint")?;
+    for file_name in ["b.js", "c.js", "d.js", "e.js", "f.js"] {
+        assert!(
+            barrel_description.contains(&format!("/input/{file_name}\n")),
+            "{file_name} should be blamed in {barrel_description:?}"
+        );
+    }
+    assert!(!barrel_description.contains("/input/a.js"));
+
+    Ok(())
+}
+
+#[turbo_tasks::function(operation)]
+async fn emit_hints_operation(relative_path: RcStr) -> Result<Vc<()>> {
+    let project_fs = DiskFileSystem::new(rcstr!("project"), REPO_ROOT.clone());
+    let project_root = project_fs.root().owned().await?;
+    let project_path = project_root.join(&relative_path)?;
+
+    let env = Environment::new(ExecutionEnvironment::NodeJsBuildTime(
+        NodeJsEnvironment::default().resolved_cell(),
+    ))
+    .to_resolved()
+    .await?;
+    let compile_time_info = CompileTimeInfo::builder(env).cell().await?;
+
+    let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        compile_time_info,
+        ModuleOptionsContext {
+            ecmascript: EcmascriptOptionsContext {
+                infer_module_side_effects: true,
+                ..Default::default()
+            },
+            environment: Some(env),
+            ..Default::default()
+        }
+        .cell(),
+        ResolveOptionsContext {
+            enable_node_modules: Some(project_root.clone()),
+            ..Default::default()
+        }
+        .cell(),
+        Layer::new(rcstr!("test")),
+    ));
+
+    let entry_module = asset_context
+        .process(
+            Vc::upcast(FileSource::new(project_path.join("input/index.js")?)),
+            ReferenceType::Entry(EntryReferenceSubType::Undefined),
+        )
+        .module();
+    let Some(entry) =
+        ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module.to_resolved().await?)
+    else {
+        bail!("Entry module is not evaluatable");
+    };
+
+    let module_graph = ModuleGraph::from_single_graph(SingleModuleGraph::new_with_entries(
+        ResolvedVc::cell(vec![ChunkGroupEntry::Entry(vec![ResolvedVc::upcast(
+            entry,
+        )])]),
+        false,
+        true,
+    ));
+    let binding_usage = compute_binding_usage_info(module_graph, true);
+    emit_binding_usage_hints(module_graph.connect(), binding_usage.connect()).await?;
+
+    Ok(Default::default())
+}
@@ -0,0 +1,3 @@
+export const a = 'a'
+
+console.log('a')
@@ -0,0 +1,6 @@
+export { a } from './a.js'
+export { b } from './b.js'
+export { c } from './c.js'
+export { d } from './d.js'
+export { e } from './e.js'
+export { f } from './f.js'
@@ -0,0 +1,3 @@
+export const c = 'c'
+
+console.log('c')
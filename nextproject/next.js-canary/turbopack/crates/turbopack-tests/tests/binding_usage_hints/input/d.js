@@ -0,0 +1,3 @@
+export const d = 'd'
+
+console.log('d')
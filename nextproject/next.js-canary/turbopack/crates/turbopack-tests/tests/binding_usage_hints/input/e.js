@@ -0,0 +1,3 @@
+export const e = 'e'
+
+console.log('e')
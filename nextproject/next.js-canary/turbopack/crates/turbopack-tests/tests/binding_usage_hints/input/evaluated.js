@@ -0,0 +1,3 @@
+export const value = 'value'
+
+console.log('evaluated')
@@ -0,0 +1,3 @@
+export const f = 'f'
+
+console.log('f')
@@ -0,0 +1,7 @@
+import { used } from './lib.js'
+import './evaluated.js'
+import { a } from './barrel.js'
+import { x } from 'pkg'
+import 'pkg/evaluated.js'
+
+console.log(used, a, x)
@@ -0,0 +1,3 @@
+export const used = 'used'
+export const unused = 'unused'
+export function alsoUnused() {}
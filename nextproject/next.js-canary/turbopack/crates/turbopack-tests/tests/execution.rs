@@ -5,7 +5,7 @@
 
 mod util;
 
-use std::{env, path::PathBuf};
+use std::{collections::HashMap, env, path::PathBuf};
 
 use anyhow::{Context, Result};
 use bincode::{Decode, Encode};
@@ -26,10 +26,14 @@ use turbo_tasks_fs::{
 use turbo_unix_path::sys_to_unix;
 use turbopack::{
     ModuleAssetContext,
+    module_federation::{
+        ModuleFederationOptions, RemoteContainer, SharedModule, insert_module_federation_aliases,
+        module_federation_container_entry, module_federation_module_rules,
+    },
     module_options::{EcmascriptOptionsContext, ModuleOptionsContext, TypescriptTransformOptions},
 };
 use turbopack_core::{
-    chunk::{ChunkingConfig, MangleType, MinifyType},
+    chunk::{ChunkingConfig, EcmascriptMinifyOptions, EvaluatableAsset, MangleType, MinifyType},
     compile_time_defines,
     compile_time_info::CompileTimeInfo,
     condition::ContextCondition,
@@ -48,7 +52,9 @@ use turbopack_core::{
     },
 };
 use turbopack_css::chunk::CssChunkType;
-use turbopack_ecmascript::{TreeShakingMode, chunk::EcmascriptChunkType};
+use turbopack_ecmascript::{
+    TreeShakingMode, chunk::EcmascriptChunkType, container::shared::SharedModuleConfig,
+};
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_node::{
     debug::should_debug,
@@ -253,6 +259,95 @@ struct TestOptions {
     minify: bool,
     #[serde(default)]
     production_chunking: bool,
+    #[serde(default)]
+    module_federation: Option<TestModuleFederationOptions>,
+}
+
+/// Module federation options with paths relative to the `input` directory, see
+/// [ModuleFederationOptions].
+#[derive(
+    PartialEq, Eq, Debug, Deserialize, TraceRawVcs, ValueDebugFormat, NonLocalValue, Encode, Decode,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TestModuleFederationOptions {
+    #[serde(default)]
+    name: Option<RcStr>,
+    #[serde(default)]
+    exposes: HashMap<RcStr, RcStr>,
+    #[serde(default)]
+    remotes: HashMap<RcStr, TestRemoteContainer>,
+    #[serde(default)]
+    shared: Vec<TestSharedModule>,
+}
+
+#[derive(
+    PartialEq, Eq, Debug, Deserialize, TraceRawVcs, ValueDebugFormat, NonLocalValue, Encode, Decode,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TestRemoteContainer {
+    name: RcStr,
+    url: RcStr,
+}
+
+#[derive(
+    PartialEq, Eq, Debug, Deserialize, TraceRawVcs, ValueDebugFormat, NonLocalValue, Encode, Decode,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TestSharedModule {
+    share_key: RcStr,
+    #[serde(default)]
+    version: Option<RcStr>,
+    #[serde(default)]
+    required_version: Option<RcStr>,
+    #[serde(default)]
+    singleton: bool,
+    fallback: RcStr,
+}
+
+impl TestModuleFederationOptions {
+    fn to_options(&self, input_path: &FileSystemPath) -> Result<ModuleFederationOptions> {
+        let mut exposes = self
+            .exposes
+            .iter()
+            .map(|(exposed, path)| Ok((exposed.clone(), input_path.join(path)?)))
+            .collect::<Result<Vec<_>>>()?;
+        exposes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut remotes = self
+            .remotes
+            .iter()
+            .map(|(prefix, remote)| {
+                (
+                    prefix.clone(),
+                    RemoteContainer {
+                        name: remote.name.clone(),
+                        url: remote.url.clone(),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        remotes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let shared = self
+            .shared
+            .iter()
+            .map(|shared| {
+                Ok(SharedModule {
+                    config: SharedModuleConfig {
+                        share_key: shared.share_key.clone(),
+                        version: shared.version.clone(),
+                        required_version: shared.required_version.clone(),
+                        singleton: shared.singleton,
+                    },
+                    fallback: input_path.join(&shared.fallback)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(ModuleFederationOptions {
+            name: self.name.clone(),
+            exposes: exposes.into_iter().collect(),
+            remotes: remotes.into_iter().collect(),
+            shared,
+        })
+    }
 }
 
 fn default_tree_shaking_mode() -> Option<TreeShakingMode> {
@@ -272,6 +367,7 @@ impl Default for TestOptions {
             scope_hoisting: default_true(),
             minify: false,
             production_chunking: false,
+            module_federation: None,
         }
     }
 }
@@ -397,6 +493,18 @@ async fn run_test_operation(prepared_test: ResolvedVc<PreparedTest>) -> Result<V
             .resolved_cell(),
     );
 
+    let module_federation = match &options.module_federation {
+        Some(module_federation) => Some(
+            module_federation
+                .to_options(&project_path.join("input")?)?
+                .resolved_cell(),
+        ),
+        None => None,
+    };
+    if let Some(module_federation) = module_federation {
+        insert_module_federation_aliases(&mut import_map, *module_federation).await?;
+    }
+
     let mut fallback_import_map = ImportMap::empty();
     fallback_import_map.insert_exact_alias(
         rcstr!("fallback"),
@@ -424,6 +532,12 @@ async fn run_test_operation(prepared_test: ResolvedVc<PreparedTest>) -> Result<V
             },
             environment: Some(env),
             tree_shaking_mode: options.tree_shaking_mode,
+            module_rules: match module_federation {
+                Some(module_federation) => {
+                    module_federation_module_rules(module_federation).await?
+                }
+                None => vec![],
+            },
             rules: vec![(
                 ContextCondition::InNodeModules,
                 ModuleOptionsContext {
@@ -482,7 +596,27 @@ async fn run_test_operation(prepared_test: ResolvedVc<PreparedTest>) -> Result<V
         )
         .module();
 
-    let entries = get_evaluate_entries(jest_entry_asset, asset_context, None);
+    // The container of the test bundle is evaluated before the tests, so they can consume it as
+    // a remote.
+    let runtime_entries = if let Some(module_federation) = module_federation
+        && module_federation.await?.name.is_some()
+    {
+        let container_entry = module_federation_container_entry(
+            asset_context,
+            project_path.join("input/__container__.js")?,
+            *module_federation,
+        )
+        .to_resolved()
+        .await?;
+        let container_entry =
+            ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(container_entry)
+                .context("The container entry must be evaluatable")?;
+        Some(ResolvedVc::cell(vec![container_entry]))
+    } else {
+        None
+    };
+
+    let entries = get_evaluate_entries(jest_entry_asset, asset_context, runtime_entries);
 
     let single_graph = SingleModuleGraph::new_with_entries(
         entries.graph_entries().to_resolved().await?,
@@ -519,10 +653,12 @@ async fn run_test_operation(prepared_test: ResolvedVc<PreparedTest>) -> Result<V
     )
     .source_map_source_type(turbopack_core::chunk::SourceMapSourceType::RelativeUri)
     .module_merging(options.scope_hoisting)
+    .module_federation(options.module_federation.is_some())
     .minify_type(if options.minify {
-        MinifyType::Minify {
+        MinifyType::Minify(EcmascriptMinifyOptions {
             mangle: Some(MangleType::OptimalSize),
-        }
+            ..Default::default()
+        })
     } else {
         MinifyType::NoMinify
     })
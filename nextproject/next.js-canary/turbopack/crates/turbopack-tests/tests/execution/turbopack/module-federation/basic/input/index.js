@@ -0,0 +1,18 @@
+it('should load modules exposed by a remote container', async () => {
+  const { answer } = await import('remote/answer')
+  expect(answer).toBe(42)
+})
+
+it('should load shared modules provided by the container', async () => {
+  const { version } = await import('shared-lib')
+  expect(version).toBe('1.2.3')
+})
+
+it('should fall back to shared modules without a version', async () => {
+  const { name } = await import('unversioned-lib')
+  expect(name).toBe('unversioned')
+})
+
+it('should reject when a remote container fails to load', async () => {
+  await expect(import('missing/thing')).rejects.toThrow()
+})
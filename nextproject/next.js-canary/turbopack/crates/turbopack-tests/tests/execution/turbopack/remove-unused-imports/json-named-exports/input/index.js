@@ -0,0 +1,12 @@
+import { version, messages } from './data.json'
+
+it('should only include the imported keys of a JSON module', () => {
+  expect(version).toBe('1.0.0')
+  expect(messages).toEqual({ hello: 'Hello' })
+
+  const [, factory] = Array.from(__turbopack_modules__).find(([id]) =>
+    id.includes('input/data.json')
+  )
+  expect(factory.toString()).toContain('"version"')
+  expect(factory.toString()).not.toContain('unusedKey')
+})
@@ -0,0 +1,35 @@
+it('should import the matched modules eagerly', () => {
+  const modules = import.meta.glob('./pages/**/*.js', { eager: true })
+  expect(Object.keys(modules)).toEqual([
+    './pages/a.js',
+    './pages/b.js',
+    './pages/nested/c.js',
+  ])
+  expect(Object.values(modules).map((m) => m.default)).toEqual(['a', 'b', 'c'])
+})
+
+it('should only import the named export with the import option', () => {
+  const names = import.meta.glob('./pages/*.js', { eager: true, import: 'name' })
+  expect(names).toEqual({
+    './pages/a.js': 'page a',
+    './pages/b.js': 'page b',
+  })
+})
+
+it('should import the matched modules lazily', async () => {
+  const modules = import.meta.glob('./pages/*.js')
+  expect(Object.keys(modules)).toEqual(['./pages/a.js', './pages/b.js'])
+  for (const load of Object.values(modules)) {
+    expect(typeof load).toBe('function')
+  }
+  const a = await modules['./pages/a.js']()
+  expect(a.default).toBe('a')
+  expect(a.name).toBe('page a')
+
+  const names = import.meta.glob('./pages/nested/*.js', { import: 'name' })
+  await expect(names['./pages/nested/c.js']()).resolves.toBe('page c')
+})
+
+it('should not match the importing module', () => {
+  expect(import.meta.glob('./*.js', { eager: true })).toEqual({})
+})
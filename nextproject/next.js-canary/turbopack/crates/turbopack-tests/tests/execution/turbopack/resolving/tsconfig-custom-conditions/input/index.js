@@ -0,0 +1,5 @@
+import pkg from 'pkg'
+
+it('should resolve exports with the customConditions of the tsconfig', () => {
+  expect(pkg).toBe('custom')
+})
@@ -0,0 +1,7 @@
+import app from './src/app'
+import helper from './test/helper'
+
+it('should resolve with the options of the referenced project including the file', () => {
+  expect(app).toBe('app')
+  expect(helper).toBe('test')
+})
@@ -0,0 +1,9 @@
+(module
+  (global $answer (export "answer") i32 (i32.const 42))
+  (global $counter (export "counter") (mut i32) (i32.const 0))
+  (func $increment (export "increment")
+    (global.set $counter
+      (i32.add
+        (global.get $counter)
+        (i32.const 1))))
+)
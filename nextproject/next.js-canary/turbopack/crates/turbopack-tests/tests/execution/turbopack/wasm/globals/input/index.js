@@ -0,0 +1,23 @@
+const globalsAsyncModule = require('./globals')
+
+describe('wasm globals', () => {
+  it('should export immutable globals as their value', async () => {
+    // globals.js is an async module, so we require it and await inside this function to make sure the entrypoint isn't async.
+    const { answer } = await globalsAsyncModule
+
+    expect(answer).toEqual(42)
+  })
+
+  it('should export mutable globals as WebAssembly.Global', async () => {
+    // globals.js is an async module, so we require it and await inside this function to make sure the entrypoint isn't async.
+    const { counter, increment } = await globalsAsyncModule
+
+    expect(counter).toBeInstanceOf(WebAssembly.Global)
+    const initial = counter.value
+    increment()
+    expect(counter.value).toEqual(initial + 1)
+    counter.value = 10
+    increment()
+    expect(counter.value).toEqual(11)
+  })
+})
@@ -0,0 +1,245 @@
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+#![cfg(test)]
+
+mod util;
+
+use std::{
+    collections::VecDeque,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+use dunce::canonicalize;
+use rustc_hash::FxHashSet;
+use turbo_rcstr::{RcStr, rcstr};
+use turbo_tasks::{ResolvedVc, TurboTasks, Vc};
+use turbo_tasks_backend::{BackendOptions, TurboTasksBackend, noop_backing_storage};
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem};
+use turbo_unix_path::sys_to_unix;
+use turbopack::{ModuleAssetContext, module_options::ModuleOptionsContext};
+use turbopack_browser::BrowserChunkingContext;
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ChunkingContextExt, EvaluatableAsset, availability_info::AvailabilityInfo},
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
+    file_source::FileSource,
+    ident::Layer,
+    module::Module,
+    module_graph::{
+        ModuleGraph, SingleModuleGraph,
+        chunk_group_info::{ChunkGroup, ChunkGroupEntry},
+    },
+    output::{OutputAsset, OutputAssetsReference},
+    reference_type::{EntryReferenceSubType, ReferenceType},
+};
+use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+
+use crate::util::REPO_ROOT;
+
+/// The JavaScript files emitted for the fixture, as paths relative to the
+/// output directory together with their content.
+#[turbo_tasks::value(transparent)]
+struct EmittedScripts(Vec<(RcStr, RcStr)>);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn emits_standalone_service_workers_and_worklets() -> Result<()> {
+    let fixture =
+        canonicalize(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/standalone_workers"))?;
+    let relative_path = fixture.strip_prefix(&*REPO_ROOT)?;
+    let relative_path = RcStr::from(sys_to_unix(relative_path.to_str().unwrap()));
+
+    let tt = TurboTasks::new(TurboTasksBackend::new(
+        BackendOptions::default(),
+        noop_backing_storage(),
+    ));
+    let scripts = tt
+        .run_once(async move {
+            Ok(emit_operation(relative_path)
+                .read_strongly_consistent()
+                .await?
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>())
+        })
+        .await?;
+
+    let file_names = scripts
+        .iter()
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+
+    // Service workers control the scope of the directory they are served from,
+    // so each registered script is emitted at the output root.
+    let service_workers = file_names
+        .iter()
+        .filter(|path| path.starts_with("sw-") && path.ends_with(".js"))
+        .collect::<FxHashSet<_>>();
+    assert_eq!(
+        service_workers.len(),
+        2,
+        "expected a service worker script per registration in {file_names:?}"
+    );
+
+    let (_, worklet) = scripts
+        .iter()
+        .find(|(path, _)| path.starts_with("chunks/checkerboard-") && path.ends_with(".js"))
+        .with_context(|| format!("expected a paint worklet script in {file_names:?}"))?;
+    assert!(
+        worklet.starts_with("globalThis.TURBOPACK_ASSET_SUFFIX = "),
+        "worklet script should start with the standalone prelude"
+    );
+    assert!(!worklet.contains("self.TURBOPACK"));
+
+    // Worklet global scopes have neither `self` nor `document`, the script
+    // must still load and register the paint class.
+    assert_eq!(run_worklet(worklet)?, r#"["checkerboard"]"#);
+
+    Ok(())
+}
+
+#[turbo_tasks::function(operation)]
+async fn emit_operation(relative_path: RcStr) -> Result<Vc<EmittedScripts>> {
+    let project_fs = DiskFileSystem::new(rcstr!("project"), REPO_ROOT.clone());
+    let project_root = project_fs.root().owned().await?;
+    let project_path = project_root.join(&relative_path)?;
+    let project_path_to_project_root = project_path
+        .get_relative_path_to(&project_root)
+        .context("Project path is in root path")?;
+
+    let env = Environment::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: true,
+            web_worker: false,
+            service_worker: false,
+            browserslist_query: rcstr!("Chrome 102"),
+        }
+        .resolved_cell(),
+    ))
+    .to_resolved()
+    .await?;
+    let compile_time_info = CompileTimeInfo::builder(env).cell().await?;
+
+    let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        compile_time_info,
+        ModuleOptionsContext {
+            environment: Some(env),
+            ..Default::default()
+        }
+        .cell(),
+        ResolveOptionsContext::default().cell(),
+        Layer::new(rcstr!("test")),
+    ));
+
+    let entry_module = asset_context
+        .process(
+            Vc::upcast(FileSource::new(project_path.join("input/index.js")?)),
+            ReferenceType::Entry(EntryReferenceSubType::Undefined),
+        )
+        .module();
+    let Some(entry) =
+        ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module.to_resolved().await?)
+    else {
+        bail!("Entry module is not evaluatable");
+    };
+    let entry_modules = vec![ResolvedVc::upcast(entry)];
+
+    let module_graph = ModuleGraph::from_single_graph(SingleModuleGraph::new_with_entries(
+        ResolvedVc::cell(vec![ChunkGroupEntry::Entry(entry_modules.clone())]),
+        false,
+        true,
+    ))
+    .connect();
+
+    let output_path = project_path.join("output")?;
+    let chunking_context = BrowserChunkingContext::builder(
+        project_root,
+        output_path.clone(),
+        project_path_to_project_root,
+        output_path.clone(),
+        output_path.join("chunks")?,
+        output_path.join("static")?,
+        env,
+        RuntimeType::Production,
+    )
+    .build();
+
+    let chunk_group = chunking_context.evaluated_chunk_group_assets(
+        entry_module.ident(),
+        ChunkGroup::Entry(entry_modules.into_iter().collect()),
+        module_graph,
+        AvailabilityInfo::root(),
+    );
+
+    let mut seen = FxHashSet::default();
+    let mut queue: VecDeque<ResolvedVc<Box<dyn OutputAsset>>> = chunk_group
+        .expand_all_assets()
+        .await?
+        .iter()
+        .copied()
+        .collect();
+    let mut scripts = Vec::new();
+    while let Some(asset) = queue.pop_front() {
+        let path = asset.path().owned().await?;
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        queue.extend(asset.references().all_assets().await?.iter().copied());
+
+        if path.extension_ref() != Some("js") {
+            continue;
+        }
+        let Some(relative) = output_path.get_path_to(&path) else {
+            continue;
+        };
+        let FileContent::Content(file) = &*asset.content().file_content().await? else {
+            bail!("{relative} has no content");
+        };
+        scripts.push((relative.into(), file.content().to_str()?.into()));
+    }
+    scripts.sort();
+
+    Ok(Vc::cell(scripts))
+}
+
+/// Evaluates a worklet script in a fresh V8 context that only provides
+/// `registerPaint`, and returns the names of the registered paint classes.
+fn run_worklet(code: &str) -> Result<String> {
+    let mut child = Command::new("node")
+        .arg("-e")
+        .arg(
+            r#"
+const vm = require('vm')
+const code = require('fs').readFileSync(0, 'utf8')
+const registered = []
+vm.runInNewContext(code, {
+  console,
+  registerPaint: (name) => registered.push(name),
+})
+setTimeout(() => process.stdout.write(JSON.stringify(registered)), 100)
+"#,
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to spawn node")?;
+    child
+        .stdin
+        .take()
+        .context("node stdin is piped")?
+        .write_all(code.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "worklet script failed to run: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,3 @@
+self.addEventListener('fetch', () => {
+  console.log('a')
+})
@@ -0,0 +1,6 @@
+registerPaint(
+  'checkerboard',
+  class {
+    paint() {}
+  }
+)
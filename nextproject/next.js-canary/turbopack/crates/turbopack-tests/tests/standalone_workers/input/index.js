@@ -0,0 +1,5 @@
+navigator.serviceWorker.register(new URL('./a/sw.js', import.meta.url))
+navigator.serviceWorker.register(new URL('./b/sw.js', import.meta.url), {
+  scope: '/',
+})
+CSS.paintWorklet.addModule(new URL('./checkerboard.js', import.meta.url))
@@ -1,19 +1,84 @@
-use std::collections::BTreeMap;
-
 use anyhow::Result;
-use turbo_tasks::Vc;
+use bincode::{Decode, Encode};
+use turbo_tasks::{NonLocalValue, Vc, trace::TraceRawVcs};
 use turbo_tasks_fs::FileContent;
 use turbopack_core::asset::Asset;
-use wasmparser::{Chunk, Parser, Payload};
+use wasmparser::{Chunk, DataKind, ElementKind, ExternalKind, Parser, Payload, TypeRef};
 
 use crate::source::WebAssemblySource;
 
+/// The kind of an imported or exported WebAssembly item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub(crate) enum WebAssemblyItemKind {
+    Function,
+    Table,
+    Memory,
+    Global { mutable: bool },
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub(crate) struct WebAssemblyImport {
+    pub module: String,
+    pub name: String,
+    pub kind: WebAssemblyItemKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub(crate) struct WebAssemblyExport {
+    pub name: String,
+    pub kind: WebAssemblyItemKind,
+}
+
 /// Imports and exports of a WebAssembly file.
 #[turbo_tasks::value]
 #[derive(Default)]
 pub(crate) struct WebAssemblyAnalysis {
-    pub imports: BTreeMap<String, Vec<String>>,
-    pub exports: Vec<String>,
+    pub imports: Vec<WebAssemblyImport>,
+    pub exports: Vec<WebAssemblyExport>,
+    /// Whether the module has a start function, which runs when the module is instantiated.
+    pub has_start: bool,
+    /// Whether the module has active data or element segments, which are copied into memories
+    /// and tables when the module is instantiated.
+    pub has_active_segments: bool,
+    /// The module specifier of the JavaScript glue generated by `wasm-bindgen --target bundler`,
+    /// if the module was built with it.
+    pub wasm_bindgen_glue: Option<String>,
+}
+
+impl WebAssemblyAnalysis {
+    /// The import module specifiers in the order of their first import.
+    pub fn import_modules(&self) -> Vec<&str> {
+        let mut modules = Vec::new();
+        for import in &self.imports {
+            if !modules.contains(&import.module.as_str()) {
+                modules.push(import.module.as_str());
+            }
+        }
+        modules
+    }
+}
+
+/// wasm-bindgen prefixes the names of all imports from its glue code with one of these.
+const WASM_BINDGEN_IMPORT_PREFIXES: &[&str] = &["__wbg_", "__wbindgen_"];
+
+/// Detects the glue module of a module built with wasm-bindgen. All imports from the glue module
+/// are generated by wasm-bindgen, and the glue module is named after the WebAssembly file with a
+/// `_bg.js` suffix.
+fn detect_wasm_bindgen_glue(imports: &[WebAssemblyImport]) -> Option<String> {
+    let glue = &imports
+        .iter()
+        .find(|import| import.module.ends_with("_bg.js"))?
+        .module;
+    imports
+        .iter()
+        .filter(|import| &import.module == glue)
+        .all(|import| {
+            WASM_BINDGEN_IMPORT_PREFIXES
+                .iter()
+                .any(|prefix| import.name.starts_with(prefix))
+        })
+        .then(|| glue.clone())
 }
 
 /// Analyse a WebAssembly file.
@@ -23,13 +88,18 @@ pub(crate) struct WebAssemblyAnalysis {
 pub(crate) async fn analyze(source: Vc<WebAssemblySource>) -> Result<Vc<WebAssemblyAnalysis>> {
     let content = source.content().file_content().await?;
 
-    let mut analysis = WebAssemblyAnalysis::default();
-
     let FileContent::Content(file) = &*content else {
-        return Ok(analysis.cell());
+        return Ok(WebAssemblyAnalysis::default().cell());
     };
 
-    let mut bytes = &*file.content().to_bytes();
+    Ok(analyze_bytes(&file.content().to_bytes())?.cell())
+}
+
+pub(crate) fn analyze_bytes(mut bytes: &[u8]) -> Result<WebAssemblyAnalysis> {
+    let mut analysis = WebAssemblyAnalysis::default();
+
+    // The mutability of all globals in the global index space, imported globals come first.
+    let mut mutable_globals = Vec::new();
 
     let mut parser = Parser::new(0);
     loop {
@@ -47,18 +117,67 @@ pub(crate) async fn analyze(source: Vc<WebAssemblySource>) -> Result<Vc<WebAssem
                 for import in s {
                     let import = import?;
 
-                    analysis
-                        .imports
-                        .entry(import.module.to_string())
-                        .or_default()
-                        .push(import.name.to_string());
+                    let kind = match import.ty {
+                        TypeRef::Func(_) => WebAssemblyItemKind::Function,
+                        TypeRef::Table(_) => WebAssemblyItemKind::Table,
+                        TypeRef::Memory(_) => WebAssemblyItemKind::Memory,
+                        TypeRef::Global(ty) => {
+                            mutable_globals.push(ty.mutable);
+                            WebAssemblyItemKind::Global {
+                                mutable: ty.mutable,
+                            }
+                        }
+                        TypeRef::Tag(_) => WebAssemblyItemKind::Tag,
+                    };
+                    analysis.imports.push(WebAssemblyImport {
+                        module: import.module.to_string(),
+                        name: import.name.to_string(),
+                        kind,
+                    });
+                }
+            }
+            Payload::GlobalSection(s) => {
+                for global in s {
+                    mutable_globals.push(global?.ty.mutable);
                 }
             }
             Payload::ExportSection(s) => {
                 for export in s {
                     let export = export?;
 
-                    analysis.exports.push(export.name.to_string());
+                    let kind = match export.kind {
+                        ExternalKind::Func => WebAssemblyItemKind::Function,
+                        ExternalKind::Table => WebAssemblyItemKind::Table,
+                        ExternalKind::Memory => WebAssemblyItemKind::Memory,
+                        ExternalKind::Global => WebAssemblyItemKind::Global {
+                            mutable: mutable_globals
+                                .get(export.index as usize)
+                                .copied()
+                                .unwrap_or(true),
+                        },
+                        ExternalKind::Tag => WebAssemblyItemKind::Tag,
+                    };
+                    analysis.exports.push(WebAssemblyExport {
+                        name: export.name.to_string(),
+                        kind,
+                    });
+                }
+            }
+            Payload::StartSection { .. } => {
+                analysis.has_start = true;
+            }
+            Payload::DataSection(s) => {
+                for data in s {
+                    if matches!(data?.kind, DataKind::Active { .. }) {
+                        analysis.has_active_segments = true;
+                    }
+                }
+            }
+            Payload::ElementSection(s) => {
+                for element in s {
+                    if matches!(element?.kind, ElementKind::Active { .. }) {
+                        analysis.has_active_segments = true;
+                    }
                 }
             }
 
@@ -73,5 +192,42 @@ pub(crate) async fn analyze(source: Vc<WebAssemblySource>) -> Result<Vc<WebAssem
         }
     }
 
-    Ok(analysis.cell())
+    analysis.wasm_bindgen_glue = detect_wasm_bindgen_glue(&analysis.imports);
+
+    Ok(analysis)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::analyze_bytes;
+
+    fn analyze_wat(wat: &str) -> Result<super::WebAssemblyAnalysis> {
+        analyze_bytes(&wat::parse_str(wat)?)
+    }
+
+    #[test]
+    fn test_side_effects() -> Result<()> {
+        let analysis = analyze_wat(r#"(module (func (export "f")))"#)?;
+        assert!(!analysis.has_start && !analysis.has_active_segments);
+
+        let analysis = analyze_wat(r#"(module (func $f) (start $f))"#)?;
+        assert!(analysis.has_start);
+
+        let analysis = analyze_wat(
+            r#"(module (import "./memory.js" "memory" (memory 1)) (data (i32.const 0) "abc"))"#,
+        )?;
+        assert!(analysis.has_active_segments);
+
+        let analysis =
+            analyze_wat(r#"(module (table 1 funcref) (func $f) (elem (i32.const 0) $f))"#)?;
+        assert!(analysis.has_active_segments);
+
+        let analysis = analyze_wat(
+            r#"(module (memory 1) (table 1 funcref) (func $f) (data "abc") (elem func $f))"#,
+        )?;
+        assert!(!analysis.has_active_segments);
+        Ok(())
+    }
 }
@@ -6,9 +6,22 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::Vc;
 use turbo_tasks_fs::{File, FileContent};
 use turbopack_core::{asset::AssetContent, source::Source, virtual_source::VirtualSource};
-use turbopack_ecmascript::utils::StringifyJs;
-
-use crate::{analysis::analyze, source::WebAssemblySource, wasm_edge_var_name};
+use turbopack_ecmascript::utils::{StringifyJs, is_valid_binding_name};
+
+use crate::{
+    analysis::{WebAssemblyAnalysis, WebAssemblyItemKind, analyze},
+    source::WebAssemblySource,
+    wasm_edge_var_name,
+};
+
+/// Formats a name for use in an import or export specifier.
+fn module_export_name(name: &str) -> String {
+    if is_valid_binding_name(name) {
+        name.to_string()
+    } else {
+        StringifyJs(name).to_string()
+    }
+}
 
 /// Create a javascript loader to instantiate the WebAssembly module with the
 /// necessary imports and exports to be processed by [turbopack_ecmascript].
@@ -17,48 +30,108 @@ pub(crate) async fn instantiating_loader_source(
     source: Vc<WebAssemblySource>,
 ) -> Result<Vc<Box<dyn Source>>> {
     let analysis = analyze(source).await?;
+    let edge_variable = wasm_edge_var_name(Vc::upcast(source)).await?;
+
+    let code: RcStr = instantiating_loader_code(&analysis, &edge_variable)?.into();
+
+    Ok(Vc::upcast(VirtualSource::new(
+        source.ident().path().await?.append("_.loader.mjs")?,
+        AssetContent::file(FileContent::Content(File::from(code)).cell()),
+    )))
+}
 
+/// Generates the code of the instantiating loader.
+///
+/// This follows the WebAssembly ESM integration: the imports of the WebAssembly module are
+/// ES module imports, so they are resolved through the module graph, and its exports are ES
+/// module exports. Exported immutable globals are unwrapped to their value. Mutable globals are
+/// exported as the `WebAssembly.Global` object itself, as an ES module binding can't follow
+/// writes made by the WebAssembly module. Their current value is read and written through
+/// `.value`.
+///
+/// Modules built with `wasm-bindgen --target bundler` import all their functions from a JS glue
+/// module which itself depends on the WebAssembly module. The glue module is imported as a
+/// namespace, so its functions are only read when the module is instantiated, after the glue
+/// module has been evaluated.
+fn instantiating_loader_code(
+    analysis: &WebAssemblyAnalysis,
+    edge_variable: &str,
+) -> Result<String> {
     let mut code = String::new();
 
     let mut imports_obj = "{".to_string();
-    for (path, items) in &analysis.imports {
+    let mut import_count = 0;
+    for (i, module) in analysis.import_modules().into_iter().enumerate() {
+        if analysis.wasm_bindgen_glue.as_deref() == Some(module) {
+            let namespace = format!("__turbopack_wasm_module_{i}__");
+            writeln!(
+                code,
+                "import * as {namespace} from {};",
+                StringifyJs(module)
+            )?;
+            writeln!(imports_obj, "\n    {}: {namespace},", StringifyJs(module))?;
+            continue;
+        }
+
+        let mut specifiers = Vec::new();
+        writeln!(imports_obj, "\n    {}: {{", StringifyJs(module))?;
+        for import in analysis
+            .imports
+            .iter()
+            .filter(|import| import.module == module)
+        {
+            let local = format!("__turbopack_wasm_import_{import_count}__");
+            import_count += 1;
+            specifiers.push(format!("{} as {local}", module_export_name(&import.name)));
+            writeln!(
+                imports_obj,
+                "        {}: {local},",
+                StringifyJs(&import.name)
+            )?;
+        }
+        writeln!(imports_obj, "    }},")?;
         writeln!(
             code,
             "import {{ {} }} from {};",
-            items.join(", "),
-            StringifyJs(path)
+            specifiers.join(", "),
+            StringifyJs(module)
         )?;
-
-        writeln!(imports_obj, "\n    {}: {{", StringifyJs(path))?;
-        for item in items {
-            writeln!(imports_obj, "        {}: {},", StringifyJs(item), item)?;
-        }
-        writeln!(imports_obj, "    }},")?;
     }
     writeln!(imports_obj, "}}")?;
 
-    writeln!(code, "import wasmPath from \"WASM_PATH\";")?;
+    writeln!(code, "import __turbopack_wasm_path__ from \"WASM_PATH\";")?;
 
     writeln!(code)?;
 
     writedoc!(
         code,
         r#"
-            const {{ {exports} }} = await __turbopack_wasm__(wasmPath, () => {edgeVariable}, {imports});
-
-            export {{ {exports} }};
+            const __turbopack_wasm_exports__ = await __turbopack_wasm__(__turbopack_wasm_path__, () => {edgeVariable}, {imports});
         "#,
-        edgeVariable = wasm_edge_var_name(Vc::upcast(source)).await?,
+        edgeVariable = edge_variable,
         imports = imports_obj,
-        exports = analysis.exports.join(", "),
     )?;
+    writeln!(code)?;
 
-    let code: RcStr = code.into();
+    for (i, export) in analysis.exports.iter().enumerate() {
+        let mut value = format!("__turbopack_wasm_exports__[{}]", StringifyJs(&export.name));
+        if export.kind == (WebAssemblyItemKind::Global { mutable: false }) {
+            value.push_str(".value");
+        }
+        if is_valid_binding_name(&export.name) {
+            writeln!(code, "export const {} = {value};", export.name)?;
+        } else {
+            let local = format!("__turbopack_wasm_export_{i}__");
+            writeln!(code, "const {local} = {value};")?;
+            writeln!(
+                code,
+                "export {{ {local} as {} }};",
+                StringifyJs(&export.name)
+            )?;
+        }
+    }
 
-    Ok(Vc::upcast(VirtualSource::new(
-        source.ident().path().await?.append("_.loader.mjs")?,
-        AssetContent::file(FileContent::Content(File::from(code)).cell()),
-    )))
+    Ok(code)
 }
 
 /// Create a javascript loader to compile the WebAssembly module and export it
@@ -84,3 +157,74 @@ pub(crate) async fn compiling_loader_source(
         AssetContent::file(FileContent::Content(File::from(code)).cell()),
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::instantiating_loader_code;
+    use crate::analysis::analyze_bytes;
+
+    fn loader_code(wat: &str) -> Result<String> {
+        instantiating_loader_code(&analyze_bytes(&wat::parse_str(wat)?)?, "edge")
+    }
+
+    #[test]
+    fn test_exports() -> Result<()> {
+        let code = loader_code(
+            r#"(module
+                (global $constant (export "constant") i32 (i32.const 1))
+                (global $counter (export "counter") (mut i32) (i32.const 0))
+                (func (export "my-func"))
+                (func (export "default"))
+            )"#,
+        )?;
+        assert!(
+            code.contains(
+                "export const constant = __turbopack_wasm_exports__[\"constant\"].value;\n"
+            )
+        );
+        // Mutable globals are exported as `WebAssembly.Global`, so changes can be observed.
+        assert!(code.contains("export const counter = __turbopack_wasm_exports__[\"counter\"];\n"));
+        assert!(code.contains(
+            "const __turbopack_wasm_export_2__ = __turbopack_wasm_exports__[\"my-func\"];\nexport \
+             { __turbopack_wasm_export_2__ as \"my-func\" };\n"
+        ));
+        assert!(code.contains("export { __turbopack_wasm_export_3__ as \"default\" };\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_imports() -> Result<()> {
+        let code = loader_code(
+            r#"(module
+                (import "./env.js" "log" (func))
+                (import "./env.js" "class" (func))
+                (import "./memory.js" "memory" (memory 1))
+            )"#,
+        )?;
+        assert!(code.contains(
+            "import { log as __turbopack_wasm_import_0__, \"class\" as \
+             __turbopack_wasm_import_1__ } from \"./env.js\";\n"
+        ));
+        assert!(
+            code.contains(
+                "import { memory as __turbopack_wasm_import_2__ } from \"./memory.js\";\n"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_wasm_bindgen_glue() -> Result<()> {
+        let code = loader_code(
+            r#"(module
+                (import "./lib_bg.js" "__wbg_log_1234" (func))
+                (import "./lib_bg.js" "__wbindgen_throw" (func))
+            )"#,
+        )?;
+        assert!(code.contains("import * as __turbopack_wasm_module_0__ from \"./lib_bg.js\";\n"));
+        assert!(code.contains("\"./lib_bg.js\": __turbopack_wasm_module_0__,"));
+        Ok(())
+    }
+}
@@ -26,6 +26,7 @@ use turbopack_ecmascript::{
 };
 
 use crate::{
+    analysis::analyze,
     loader::{compiling_loader_source, instantiating_loader_source},
     output_asset::WebAssemblyAsset,
     raw::RawWebAssemblyModuleAsset,
@@ -144,11 +145,21 @@ impl Module for WebAssemblyModuleAsset {
     }
 
     #[turbo_tasks::function]
-    fn side_effects(self: Vc<Self>) -> Vc<ModuleSideEffects> {
-        // Both versions of this module have a top level await that instantiates a wasm module
-        // wasm module instantiation can trigger arbitrary side effects from the native start
-        // function
-        ModuleSideEffects::SideEffectful.cell()
+    async fn side_effects(&self) -> Result<Vc<ModuleSideEffects>> {
+        // Compiling a wasm module has no side effects. Instantiating it can trigger arbitrary side
+        // effects from the native start function, and active data and element segments write
+        // into memories and tables, which can be imported, and trap when they are out of
+        // bounds. The side effects of the imported modules are tracked by the module graph.
+        let side_effect_free = self.source.ident().await?.query == "?module" || {
+            let analysis = analyze(*self.source).await?;
+            !analysis.has_start && !analysis.has_active_segments
+        };
+        let side_effects = if side_effect_free {
+            ModuleSideEffects::SideEffectFree
+        } else {
+            ModuleSideEffects::SideEffectful
+        };
+        Ok(side_effects.cell())
     }
 }
 
@@ -9,6 +9,7 @@
 
 pub mod evaluate_context;
 pub mod global_module_ids;
+pub mod module_federation;
 pub mod module_options;
 pub mod transition;
 
@@ -57,7 +58,11 @@ use turbopack_ecmascript::{
     },
     tree_shake::asset::EcmascriptModulePartAsset,
 };
-use turbopack_json::JsonModuleAsset;
+use turbopack_image::{
+    srcset::ImageSrcSetSource,
+    svg::{OptimizedSvgSource, SvgComponentSource},
+};
+use turbopack_json::{JsonModuleAsset, data::DataSource};
 use turbopack_resolve::{
     resolve::resolve_options, resolve_options_context::ResolveOptionsContext,
     typescript::type_resolve,
@@ -244,6 +249,16 @@ async fn apply_module_type(
             }
         }
         ModuleType::Json => ResolvedVc::upcast(JsonModuleAsset::new(*source).to_resolved().await?),
+        ModuleType::Data { format } => {
+            module_asset_context
+                .process(
+                    Vc::upcast(DataSource::new(*source, *format)),
+                    ReferenceType::Undefined,
+                )
+                .module()
+                .to_resolved()
+                .await?
+        }
         ModuleType::Raw => ResolvedVc::upcast(RawModule::new(*source).to_resolved().await?),
         ModuleType::NodeAddon => {
             ResolvedVc::upcast(NodeAddonModule::new(*source).to_resolved().await?)
@@ -278,6 +293,51 @@ async fn apply_module_type(
         ModuleType::InlinedBytesJs => {
             ResolvedVc::upcast(InlinedBytesJsModule::new(*source).to_resolved().await?)
         }
+        ModuleType::ImageSrcSet { tag } => {
+            let image = ImageSrcSetSource::new(*source).to_resolved().await?;
+            let inner_assets = image
+                .variants()
+                .await?
+                .iter()
+                .enumerate()
+                .map(async |(i, &variant)| {
+                    Ok((
+                        RcStr::from(format!("VARIANT_{i}")),
+                        ResolvedVc::upcast(
+                            StaticUrlJsModule::new(Vc::upcast(*variant), tag.clone())
+                                .to_resolved()
+                                .await?,
+                        ),
+                    ))
+                })
+                .try_join()
+                .await?
+                .into_iter()
+                .collect();
+            module_asset_context
+                .process(
+                    Vc::upcast(*image),
+                    ReferenceType::Internal(ResolvedVc::cell(inner_assets)),
+                )
+                .module()
+                .to_resolved()
+                .await?
+        }
+        ModuleType::OptimizedSvg { tag } => ResolvedVc::upcast(
+            StaticUrlJsModule::new(Vc::upcast(OptimizedSvgSource::new(*source)), tag.clone())
+                .to_resolved()
+                .await?,
+        ),
+        ModuleType::SvgComponent => {
+            module_asset_context
+                .process(
+                    Vc::upcast(SvgComponentSource::new(*source)),
+                    ReferenceType::Undefined,
+                )
+                .module()
+                .to_resolved()
+                .await?
+        }
         ModuleType::WebAssembly { source_ty } => ResolvedVc::upcast(
             WebAssemblyModuleAsset::new(
                 WebAssemblySource::new(*source, *source_ty),
@@ -0,0 +1,299 @@
+//! Wires the module federation modules of [turbopack_ecmascript::container] into a module asset
+//! context.
+//!
+//! Modules exposed by remote containers are imported with the request prefix of their remote, e.g.
+//! `app2/Button` imports the module `./Button` of the remote `app2`. Shared modules are imported
+//! with their share key as the request. The import map created by
+//! [insert_module_federation_aliases] maps these requests to virtual sources, which the module
+//! rules from [module_federation_module_rules] turn into remote and shared modules.
+
+use anyhow::{Context, Result};
+use bincode::{Decode, Encode};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, NonLocalValue, ResolvedVc, TryJoinIterExt, Vc, trace::TraceRawVcs};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    chunk::ChunkableModule,
+    context::AssetContext,
+    file_source::FileSource,
+    module::Module,
+    reference_type::ReferenceType,
+    resolve::{
+        ModulePart, ResolveResult,
+        options::{
+            ImportMap, ImportMapResult, ImportMapping, ImportMappingReplacement,
+            ReplacedImportMapping,
+        },
+        parse::Request,
+        pattern::Pattern,
+    },
+    source::Source,
+    virtual_source::VirtualSource,
+};
+use turbopack_ecmascript::{
+    EcmascriptInputTransforms,
+    container::{
+        entry::container_entry_module,
+        remote::{RemoteModule, remotes_fs_root},
+        shared::{ConsumeSharedModule, SharedModuleConfig, shared_fs_root},
+    },
+};
+
+use crate::{
+    ModuleAssetContext,
+    module_options::{CustomModuleType, ModuleRule, ModuleRuleEffect, ModuleType, RuleCondition},
+};
+
+/// A container of another bundle, whose entry script defines the global variable `name`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub struct RemoteContainer {
+    pub name: RcStr,
+    /// The URL of the entry script of the container, see [RemoteModule].
+    pub url: RcStr,
+}
+
+/// A module which is shared with other bundles, imported with its share key as the request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, TraceRawVcs, NonLocalValue, Encode, Decode)]
+pub struct SharedModule {
+    pub config: SharedModuleConfig,
+    /// The module of this bundle, which is used when no other bundle provides a suitable version.
+    /// The container provides it to other bundles when `config.version` is set.
+    pub fallback: FileSystemPath,
+}
+
+/// How a bundle takes part in module federation. The chunking context of the bundle must have
+/// module federation enabled.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+pub struct ModuleFederationOptions {
+    /// The name of the container exposing modules of this bundle, see
+    /// [module_federation_container_entry].
+    pub name: Option<RcStr>,
+    /// The modules exposed by the container, by their public name, e.g. `./Button`.
+    #[bincode(with = "turbo_bincode::indexmap")]
+    pub exposes: FxIndexMap<RcStr, FileSystemPath>,
+    /// The containers of other bundles, by the request prefix their modules are imported with.
+    #[bincode(with = "turbo_bincode::indexmap")]
+    pub remotes: FxIndexMap<RcStr, RemoteContainer>,
+    pub shared: Vec<SharedModule>,
+}
+
+fn virtual_source(path: FileSystemPath) -> Vc<VirtualSource> {
+    VirtualSource::new(
+        path,
+        AssetContent::file(FileContent::Content(File::from("")).cell()),
+    )
+}
+
+/// Maps requests to the modules of remote containers and to shared modules.
+pub async fn insert_module_federation_aliases(
+    import_map: &mut ImportMap,
+    options: Vc<ModuleFederationOptions>,
+) -> Result<()> {
+    let options = options.await?;
+    for (prefix, remote) in &options.remotes {
+        import_map.insert_wildcard_alias(
+            format!("{prefix}/"),
+            ImportMapping::Dynamic(ResolvedVc::upcast(
+                RemoteModuleReplacement::new(remote.name.clone())
+                    .to_resolved()
+                    .await?,
+            ))
+            .resolved_cell(),
+        );
+    }
+    let shared_root = shared_fs_root().owned().await?;
+    for shared in &options.shared {
+        let share_key = &shared.config.share_key;
+        let source = virtual_source(shared_root.join(share_key)?)
+            .to_resolved()
+            .await?;
+        import_map.insert_exact_alias(
+            share_key.clone(),
+            ImportMapping::Direct(ResolveResult::source(ResolvedVc::upcast(source)))
+                .resolved_cell(),
+        );
+    }
+    Ok(())
+}
+
+/// Maps the remainder of a request like `app2/Button` to the path of the remote module
+/// `./Button`.
+#[turbo_tasks::value]
+struct RemoteModuleReplacement {
+    container_name: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteModuleReplacement {
+    #[turbo_tasks::function]
+    fn new(container_name: RcStr) -> Vc<Self> {
+        RemoteModuleReplacement { container_name }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMappingReplacement for RemoteModuleReplacement {
+    #[turbo_tasks::function]
+    async fn replace(&self, capture: Vc<Pattern>) -> Result<Vc<ReplacedImportMapping>> {
+        // Containers can't list their exposed modules, so dynamic requests can't be resolved.
+        let Some(exposed) = capture.await?.as_constant_string().cloned() else {
+            return Ok(ReplacedImportMapping::Empty.cell());
+        };
+        let path = remotes_fs_root()
+            .await?
+            .join(&self.container_name)?
+            .join(&exposed)?;
+        let source = virtual_source(path).to_resolved().await?;
+        Ok(ReplacedImportMapping::Direct(ResolveResult::source(ResolvedVc::upcast(source))).cell())
+    }
+
+    #[turbo_tasks::function]
+    fn result(&self, _lookup_path: FileSystemPath, _request: Vc<Request>) -> Vc<ImportMapResult> {
+        ImportMapResult::NoEntry.cell()
+    }
+}
+
+/// The module rules turning the sources aliased by [insert_module_federation_aliases] into
+/// modules.
+pub async fn module_federation_module_rules(
+    options: ResolvedVc<ModuleFederationOptions>,
+) -> Result<Vec<ModuleRule>> {
+    Ok(vec![ModuleRule::new_all(
+        RuleCondition::any(vec![
+            RuleCondition::ResourcePathInExactDirectory(remotes_fs_root().owned().await?),
+            RuleCondition::ResourcePathInExactDirectory(shared_fs_root().owned().await?),
+        ]),
+        vec![ModuleRuleEffect::ModuleType(ModuleType::Custom(
+            ResolvedVc::upcast(ModuleFederationModuleType { options }.resolved_cell()),
+        ))],
+    )])
+}
+
+#[turbo_tasks::value]
+struct ModuleFederationModuleType {
+    options: ResolvedVc<ModuleFederationOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl CustomModuleType for ModuleFederationModuleType {
+    #[turbo_tasks::function]
+    async fn create_module(
+        &self,
+        source: Vc<Box<dyn Source>>,
+        module_asset_context: Vc<ModuleAssetContext>,
+        _part: Option<ModulePart>,
+    ) -> Result<Vc<Box<dyn Module>>> {
+        let options = self.options.await?;
+        let path = source.ident().path().await?;
+
+        if let Some(remote_path) = remotes_fs_root().await?.get_path_to(&path) {
+            let (container_name, exposed) = remote_path
+                .split_once('/')
+                .with_context(|| format!("Invalid remote module path {remote_path}"))?;
+            let remote = options
+                .remotes
+                .values()
+                .find(|remote| remote.name == container_name)
+                .with_context(|| format!("Unknown remote container {container_name}"))?;
+            return Ok(Vc::upcast(RemoteModule::new(
+                remote.name.clone(),
+                remote.url.clone(),
+                format!("./{exposed}").into(),
+            )));
+        }
+
+        let shared_root = shared_fs_root().await?;
+        let share_key = shared_root
+            .get_path_to(&path)
+            .with_context(|| format!("Unexpected module federation source {}", path.path))?;
+        let shared = options
+            .shared
+            .iter()
+            .find(|shared| shared.config.share_key == share_key)
+            .with_context(|| format!("Unknown shared module {share_key}"))?;
+        let fallback =
+            shared_fallback_module(Vc::upcast(module_asset_context), shared.fallback.clone())
+                .await?;
+        Ok(Vc::upcast(ConsumeSharedModule::new(
+            *fallback,
+            shared.config.clone(),
+        )))
+    }
+
+    #[turbo_tasks::function]
+    fn extend_ecmascript_transforms(
+        self: Vc<Self>,
+        _preprocess: Vc<EcmascriptInputTransforms>,
+        _main: Vc<EcmascriptInputTransforms>,
+        _postprocess: Vc<EcmascriptInputTransforms>,
+    ) -> Vc<Box<dyn CustomModuleType>> {
+        // Remote and shared modules have no source code to transform.
+        Vc::upcast(self)
+    }
+}
+
+async fn shared_fallback_module(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    path: FileSystemPath,
+) -> Result<ResolvedVc<Box<dyn ChunkableModule>>> {
+    let module = asset_context
+        .process(
+            Vc::upcast(FileSource::new(path.clone())),
+            ReferenceType::Undefined,
+        )
+        .module()
+        .to_resolved()
+        .await?;
+    ResolvedVc::try_sidecast(module)
+        .with_context(|| format!("The shared module {} can't be placed in a chunk", path.path))
+}
+
+/// Creates the entry module of the container named `options.name`, see
+/// [container_entry_module]. Shared modules with a version are provided to other bundles.
+#[turbo_tasks::function]
+pub async fn module_federation_container_entry(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    path: FileSystemPath,
+    options: Vc<ModuleFederationOptions>,
+) -> Result<Vc<Box<dyn Module>>> {
+    let options = options.await?;
+    let name = options
+        .name
+        .clone()
+        .context("Module federation containers need a name")?;
+    let exposes = options
+        .exposes
+        .iter()
+        .map(async |(exposed, path)| {
+            let module = asset_context
+                .process(
+                    Vc::upcast(FileSource::new(path.clone())),
+                    ReferenceType::Undefined,
+                )
+                .module()
+                .to_resolved()
+                .await?;
+            Ok((exposed.clone(), module))
+        })
+        .try_join()
+        .await?;
+    let shared = options
+        .shared
+        .iter()
+        .filter(|shared| shared.config.version.is_some())
+        .map(async |shared| {
+            let module = shared_fallback_module(asset_context, shared.fallback.clone()).await?;
+            Ok((shared.config.clone(), ResolvedVc::upcast(module)))
+        })
+        .try_join()
+        .await?;
+    Ok(container_entry_module(
+        asset_context,
+        path,
+        name,
+        Vc::cell(exposes.into_iter().collect()),
+        Vc::cell(shared),
+    ))
+}
@@ -28,6 +28,7 @@ use turbopack_css::CssModuleAssetType;
 use turbopack_ecmascript::{
     EcmascriptInputTransform, EcmascriptInputTransforms, EcmascriptOptions, SpecifiedModuleType,
 };
+use turbopack_json::data::DataFormat;
 use turbopack_mdx::MdxTransform;
 use turbopack_node::{
     execution_context::ExecutionContext,
@@ -252,6 +253,8 @@ impl ModuleOptions {
                     ..
                 },
             ref static_url_tag,
+            enable_svg_optimization,
+            enable_svg_components,
             ref enable_postcss_transform,
             ref enable_webpack_loaders,
             environment,
@@ -527,6 +530,25 @@ impl ModuleOptions {
             ),
         ]);
 
+        if enable_svg_components && enable_jsx.is_some() {
+            rules.push(ModuleRule::new(
+                RuleCondition::all(vec![
+                    RuleCondition::ResourcePathEndsWith(".svg".to_string()),
+                    RuleCondition::ResourceQueryEquals("?component".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::SvgComponent)],
+            ));
+        }
+
+        if enable_svg_optimization {
+            rules.push(ModuleRule::new(
+                RuleCondition::ResourcePathEndsWith(".svg".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::OptimizedSvg {
+                    tag: static_url_tag.clone(),
+                })],
+            ));
+        }
+
         if enable_import_as_bytes {
             rules.push(ModuleRule::new(
                 RuleCondition::ReferenceType(ReferenceType::EcmaScriptModules(
@@ -545,6 +567,30 @@ impl ModuleOptions {
                 ]),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Json)],
             ),
+            ModuleRule::new_all(
+                RuleCondition::any(vec![
+                    RuleCondition::ResourcePathEndsWith(".json5".to_string()),
+                    RuleCondition::ResourcePathEndsWith(".jsonc".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Data {
+                    format: DataFormat::Json5,
+                })],
+            ),
+            ModuleRule::new_all(
+                RuleCondition::any(vec![
+                    RuleCondition::ResourcePathEndsWith(".yaml".to_string()),
+                    RuleCondition::ResourcePathEndsWith(".yml".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Data {
+                    format: DataFormat::Yaml,
+                })],
+            ),
+            ModuleRule::new_all(
+                RuleCondition::ResourcePathEndsWith(".toml".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Data {
+                    format: DataFormat::Toml,
+                })],
+            ),
             ModuleRule::new_all(
                 RuleCondition::any(vec![
                     RuleCondition::ResourcePathEndsWith(".js".to_string()),
@@ -620,6 +666,21 @@ impl ModuleOptions {
                     source_ty: WebAssemblySourceType::Text,
                 })],
             ),
+            ModuleRule::new(
+                RuleCondition::all(vec![
+                    RuleCondition::any(vec![
+                        RuleCondition::ResourcePathEndsWith(".avif".to_string()),
+                        RuleCondition::ResourcePathEndsWith(".jpg".to_string()),
+                        RuleCondition::ResourcePathEndsWith(".jpeg".to_string()),
+                        RuleCondition::ResourcePathEndsWith(".png".to_string()),
+                        RuleCondition::ResourcePathEndsWith(".webp".to_string()),
+                    ]),
+                    RuleCondition::ResourceQueryContains("srcset=".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::ImageSrcSet {
+                    tag: static_url_tag.clone(),
+                })],
+            ),
             ModuleRule::new(
                 RuleCondition::any(vec![
                     RuleCondition::ResourcePathEndsWith(".apng".to_string()),
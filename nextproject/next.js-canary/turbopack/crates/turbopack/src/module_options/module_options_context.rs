@@ -212,6 +212,12 @@ pub struct ModuleOptionsContext {
 
     pub static_url_tag: Option<RcStr>,
 
+    /// Minify the markup of SVG images that are referenced by their URL.
+    pub enable_svg_optimization: bool,
+    /// Turn SVG images imported with a `?component` query into React components. This requires
+    /// the JSX transform to be enabled.
+    pub enable_svg_components: bool,
+
     /// Generate (non-emitted) output assets for static assets and externals, to facilitate
     /// generating a list of all non-bundled files that will be required at runtime.
     pub enable_externals_tracing: Option<ResolvedVc<ExternalsTracingOptions>>,
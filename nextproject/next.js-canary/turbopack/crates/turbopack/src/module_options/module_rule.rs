@@ -11,6 +11,7 @@ use turbopack_core::{
 };
 use turbopack_css::CssModuleAssetType;
 use turbopack_ecmascript::{EcmascriptInputTransforms, EcmascriptOptions};
+use turbopack_json::data::DataFormat;
 use turbopack_wasm::source::WebAssemblySourceType;
 
 use crate::module_options::{CustomModuleType, RuleCondition, match_mode::MatchMode};
@@ -134,6 +135,10 @@ pub enum ModuleType {
         options: ResolvedVc<EcmascriptOptions>,
     },
     Json,
+    /// A JSON5, YAML or TOML data file, which is turned into an ES module.
+    Data {
+        format: DataFormat,
+    },
     Raw,
     NodeAddon,
     CssModule,
@@ -150,6 +155,19 @@ pub enum ModuleType {
         tag: Option<RcStr>,
     },
     InlinedBytesJs,
+    /// An image imported with a `srcset` query, which is exported as a set of resized and
+    /// re-encoded variants.
+    ImageSrcSet {
+        /// The tag that is passed to ChunkingContext::asset_url
+        tag: Option<RcStr>,
+    },
+    /// An SVG image that is minified before it's emitted and referenced by its URL.
+    OptimizedSvg {
+        /// The tag that is passed to ChunkingContext::asset_url
+        tag: Option<RcStr>,
+    },
+    /// An SVG image that is converted into a JSX module exporting a React component.
+    SvgComponent,
     WebAssembly {
         source_ty: WebAssemblySourceType,
     },
@@ -164,6 +182,7 @@ impl Display for ModuleType {
             ModuleType::TypescriptDeclaration { .. } => write!(f, "TypescriptDeclaration"),
             ModuleType::EcmascriptExtensionless { .. } => write!(f, "EcmascriptExtensionless"),
             ModuleType::Json => write!(f, "Json"),
+            ModuleType::Data { format } => write!(f, "Data({format})"),
             ModuleType::Raw => write!(f, "Raw"),
             ModuleType::NodeAddon => write!(f, "NodeAddon"),
             ModuleType::CssModule => write!(f, "CssModule"),
@@ -171,6 +190,9 @@ impl Display for ModuleType {
             ModuleType::StaticUrlJs { .. } => write!(f, "StaticUrlJs"),
             ModuleType::StaticUrlCss { .. } => write!(f, "StaticUrlCss"),
             ModuleType::InlinedBytesJs => write!(f, "InlinedBytesJs"),
+            ModuleType::ImageSrcSet { .. } => write!(f, "ImageSrcSet"),
+            ModuleType::OptimizedSvg { .. } => write!(f, "OptimizedSvg"),
+            ModuleType::SvgComponent => write!(f, "SvgComponent"),
             ModuleType::WebAssembly { .. } => write!(f, "WebAssembly"),
             ModuleType::Custom(_) => write!(f, "Custom"),
         }
@@ -215,9 +237,22 @@ impl ModuleType {
             "raw" => ModuleType::Raw,
             "node" => ModuleType::NodeAddon,
             "bytes" => ModuleType::InlinedBytesJs,
+            "image-srcset" => ModuleType::ImageSrcSet { tag: None },
+            "svg" => ModuleType::OptimizedSvg { tag: None },
+            "svg-component" => ModuleType::SvgComponent,
+            "json5" => ModuleType::Data {
+                format: DataFormat::Json5,
+            },
+            "yaml" => ModuleType::Data {
+                format: DataFormat::Yaml,
+            },
+            "toml" => ModuleType::Data {
+                format: DataFormat::Toml,
+            },
             _ => bail!(
                 "Unknown module type: {type_str:?}. Valid types are: asset, ecmascript, \
-                 typescript, css, css-module, wasm, raw, node, bytes"
+                 typescript, css, css-module, wasm, raw, node, bytes, image-srcset, svg, \
+                 svg-component, json5, yaml, toml"
             ),
         })
     }